@@ -23,6 +23,10 @@ pub struct ProgressValues {
     pub read_rows: usize,
     pub read_bytes: usize,
     pub total_rows_to_read: usize,
+    /// The partitions the sources are done with.
+    pub read_partitions: usize,
+    /// The partitions bound to the sources.
+    pub total_partitions: usize,
 }
 
 #[derive(Debug)]
@@ -30,6 +34,8 @@ pub struct Progress {
     read_rows: AtomicUsize,
     read_bytes: AtomicUsize,
     total_rows_to_read: AtomicUsize,
+    read_partitions: AtomicUsize,
+    total_partitions: AtomicUsize,
 }
 
 impl Progress {
@@ -38,6 +44,8 @@ impl Progress {
             read_rows: AtomicUsize::new(0),
             read_bytes: AtomicUsize::new(0),
             total_rows_to_read: AtomicUsize::new(0),
+            read_partitions: AtomicUsize::new(0),
+            total_partitions: AtomicUsize::new(0),
         }
     }
 
@@ -48,16 +56,24 @@ impl Progress {
             .fetch_add(progress_values.read_bytes, Ordering::Relaxed);
         self.total_rows_to_read
             .fetch_add(progress_values.total_rows_to_read, Ordering::Relaxed);
+        self.read_partitions
+            .fetch_add(progress_values.read_partitions, Ordering::Relaxed);
+        self.total_partitions
+            .fetch_add(progress_values.total_partitions, Ordering::Relaxed);
     }
 
     pub fn get_values(&self) -> ProgressValues {
         let read_rows = self.read_rows.load(Ordering::Relaxed) as usize;
         let read_bytes = self.read_bytes.load(Ordering::Relaxed) as usize;
         let total_rows_to_read = self.total_rows_to_read.load(Ordering::Relaxed) as usize;
+        let read_partitions = self.read_partitions.load(Ordering::Relaxed);
+        let total_partitions = self.total_partitions.load(Ordering::Relaxed);
         ProgressValues {
             read_rows,
             read_bytes,
             total_rows_to_read,
+            read_partitions,
+            total_partitions,
         }
     }
 
@@ -65,16 +81,22 @@ impl Progress {
         self.read_rows.store(0, Ordering::Relaxed);
         self.read_bytes.store(0, Ordering::Relaxed);
         self.total_rows_to_read.store(0, Ordering::Relaxed);
+        self.read_partitions.store(0, Ordering::Relaxed);
+        self.total_partitions.store(0, Ordering::Relaxed);
     }
 
     pub fn get_and_reset(&self) -> ProgressValues {
         let read_rows = self.read_rows.fetch_and(0, Ordering::Relaxed) as usize;
         let read_bytes = self.read_bytes.fetch_and(0, Ordering::Relaxed) as usize;
         let total_rows_to_read = self.total_rows_to_read.fetch_and(0, Ordering::Relaxed) as usize;
+        let read_partitions = self.read_partitions.fetch_and(0, Ordering::Relaxed);
+        let total_partitions = self.total_partitions.fetch_and(0, Ordering::Relaxed);
         ProgressValues {
             read_rows,
             read_bytes,
             total_rows_to_read,
+            read_partitions,
+            total_partitions,
         }
     }
 
@@ -83,6 +105,16 @@ impl Progress {
             .fetch_add(total_rows, Ordering::Relaxed);
     }
 
+    pub fn add_total_partitions(&self, partitions: usize) {
+        self.total_partitions
+            .fetch_add(partitions, Ordering::Relaxed);
+    }
+
+    pub fn incr_read_partitions(&self, partitions: usize) {
+        self.read_partitions
+            .fetch_add(partitions, Ordering::Relaxed);
+    }
+
     // Placeholder for default callback init.
     pub fn default_callback(_: &Progress) {}
 }
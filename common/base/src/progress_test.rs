@@ -23,12 +23,16 @@ fn test_progress() -> Result<()> {
         read_rows: 2,
         read_bytes: 10,
         total_rows_to_read: 10,
+        read_partitions: 1,
+        total_partitions: 4,
     };
 
     progress.incr(&values);
 
     assert_eq!(2, progress.get_values().read_rows);
     assert_eq!(10, progress.get_values().read_bytes);
+    assert_eq!(1, progress.get_values().read_partitions);
+    assert_eq!(4, progress.get_values().total_partitions);
     progress.reset();
 
     assert_eq!(0, progress.get_values().read_rows);
@@ -0,0 +1,110 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+/// The object metadata key the codec of a stored object is recorded under.
+/// An object without it is not compressed.
+pub const COMPRESSION_METADATA_KEY: &str = "databend-compression";
+
+/// The codec the data parts are compressed with before being stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Lz4,
+    Zstd,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::None
+    }
+}
+
+impl FromStr for Compression {
+    type Err = ErrorCode;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "" | "none" => Ok(Compression::None),
+            "lz4" => Ok(Compression::Lz4),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(ErrorCode::UnknownCompressionName(format!(
+                "unknown compression {}, supported compressions are none | lz4 | zstd",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compression::None => write!(f, "none"),
+            Compression::Lz4 => write!(f, "lz4"),
+            Compression::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+impl Compression {
+    /// The codec recorded in the metadata of a stored object.
+    pub fn from_metadata(metadata: Option<&HashMap<String, String>>) -> Result<Self> {
+        match metadata.and_then(|m| m.get(COMPRESSION_METADATA_KEY)) {
+            None => Ok(Compression::None),
+            Some(name) => Compression::from_str(name),
+        }
+    }
+
+    /// The metadata to record on an object compressed with this codec.
+    pub fn to_metadata(&self) -> Option<HashMap<String, String>> {
+        match self {
+            Compression::None => None,
+            codec => {
+                let mut metadata = HashMap::new();
+                metadata.insert(COMPRESSION_METADATA_KEY.to_string(), codec.to_string());
+                Some(metadata)
+            }
+        }
+    }
+
+    pub fn compress(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data),
+            Compression::Lz4 => lz4::block::compress(&data, None, true).map_err(|e| {
+                ErrorCode::BadBytes(format!("Cannot compress with lz4, cause: {}", e))
+            }),
+            Compression::Zstd => zstd::stream::encode_all(data.as_slice(), 0).map_err(|e| {
+                ErrorCode::BadBytes(format!("Cannot compress with zstd, cause: {}", e))
+            }),
+        }
+    }
+
+    pub fn decompress(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data),
+            Compression::Lz4 => lz4::block::decompress(&data, None).map_err(|e| {
+                ErrorCode::BadBytes(format!("Cannot decompress with lz4, cause: {}", e))
+            }),
+            Compression::Zstd => zstd::stream::decode_all(data.as_slice()).map_err(|e| {
+                ErrorCode::BadBytes(format!("Cannot decompress with zstd, cause: {}", e))
+            }),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::str::FromStr;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::Compression;
+use crate::COMPRESSION_METADATA_KEY;
+
+#[test]
+fn test_compression_from_str() -> Result<()> {
+    assert_eq!(Compression::None, Compression::from_str("")?);
+    assert_eq!(Compression::None, Compression::from_str("none")?);
+    assert_eq!(Compression::Lz4, Compression::from_str("LZ4")?);
+    assert_eq!(Compression::Zstd, Compression::from_str("zstd")?);
+
+    let e = Compression::from_str("gzip").unwrap_err();
+    assert_eq!(ErrorCode::UnknownCompressionName("").code(), e.code());
+    Ok(())
+}
+
+#[test]
+fn test_compression_round_trip() -> Result<()> {
+    let data = (0..10000u32)
+        .flat_map(|i| (i % 100).to_le_bytes())
+        .collect::<Vec<_>>();
+
+    for codec in [Compression::None, Compression::Lz4, Compression::Zstd] {
+        let compressed = codec.compress(data.clone())?;
+        if codec != Compression::None {
+            assert!(compressed.len() < data.len(), "{} does not compress", codec);
+        }
+
+        // The codec is recovered from the metadata recorded with the object.
+        let metadata = codec.to_metadata();
+        let recorded = Compression::from_metadata(metadata.as_ref())?;
+        assert_eq!(codec, recorded);
+        assert_eq!(data, recorded.decompress(compressed)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_compression_metadata() -> Result<()> {
+    assert_eq!(None, Compression::None.to_metadata());
+    assert_eq!(
+        Some(&"lz4".to_string()),
+        Compression::Lz4
+            .to_metadata()
+            .unwrap()
+            .get(COMPRESSION_METADATA_KEY)
+    );
+    // An object written before the compression was configured.
+    assert_eq!(Compression::None, Compression::from_metadata(None)?);
+    Ok(())
+}
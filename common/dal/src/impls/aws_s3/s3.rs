@@ -20,6 +20,7 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use futures::Stream;
 use futures::StreamExt;
+use futures::TryStreamExt;
 use rusoto_core::credential::StaticProvider;
 use rusoto_core::ByteStream;
 use rusoto_core::HttpClient;
@@ -30,6 +31,7 @@ use rusoto_s3::S3Client;
 use rusoto_s3::S3 as RusotoS3;
 
 use crate::Bytes;
+use crate::Compression;
 use crate::DataAccessor;
 use crate::InputStream;
 use crate::S3InputStream;
@@ -38,13 +40,25 @@ use crate::SeekableReader;
 pub struct S3 {
     client: S3Client,
     bucket: String,
+    /// The codec the objects are written with,
+    /// the objects are read with the codec recorded in their metadata.
+    compression: Compression,
 }
 
 impl S3 {
     #[allow(dead_code)]
     pub fn new(region: Region, bucket: String) -> Self {
         let client = S3Client::new(region);
-        S3 { client, bucket }
+        S3 {
+            client,
+            bucket,
+            compression: Compression::None,
+        }
+    }
+
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
     }
 
     /// build S3 dal with aws credentials
@@ -78,6 +92,7 @@ impl S3 {
         Ok(S3 {
             client,
             bucket: bucket.to_owned(),
+            compression: Compression::None,
         })
     }
 
@@ -85,11 +100,13 @@ impl S3 {
         &self,
         path: &str,
         input_stream: ByteStream,
+        compression: Compression,
     ) -> common_exception::Result<()> {
         let req = PutObjectRequest {
             key: path.to_string(),
             bucket: self.bucket.to_string(),
             body: Some(input_stream),
+            metadata: compression.to_metadata(),
             ..Default::default()
         };
         self.client
@@ -134,18 +151,21 @@ impl DataAccessor for S3 {
             .get_object(req)
             .await
             .map_err(|e| ErrorCode::DALTransportError(e.to_string()))?;
+        let compression = Compression::from_metadata(output.metadata.as_ref())?;
         match output.body {
             Some(stream) => {
                 let mut res = vec![];
                 stream.into_async_read().read_to_end(&mut res).await?;
-                Ok(res)
+                compression.decompress(res)
             }
             None => Ok(Vec::new()),
         }
     }
 
     async fn put(&self, path: &str, content: Vec<u8>) -> common_exception::Result<()> {
-        self.put_byte_stream(path, ByteStream::from(content)).await
+        let content = self.compression.compress(content)?;
+        self.put_byte_stream(path, ByteStream::from(content), self.compression)
+            .await
     }
 
     async fn put_stream(
@@ -159,8 +179,18 @@ impl DataAccessor for S3 {
         >,
         stream_len: usize,
     ) -> common_exception::Result<()> {
+        if self.compression != Compression::None {
+            // The whole object is compressed at once, the compressed length is unknown in advance.
+            let chunks = input_stream.try_collect::<Vec<_>>().await?;
+            return self.put(path, chunks.concat()).await;
+        }
+
         let s = input_stream.map(|bytes| bytes.map(|b| bytes::Bytes::copy_from_slice(&b)));
-        self.put_byte_stream(path, ByteStream::new_with_size(s, stream_len))
-            .await
+        self.put_byte_stream(
+            path,
+            ByteStream::new_with_size(s, stream_len),
+            Compression::None,
+        )
+        .await
     }
 }
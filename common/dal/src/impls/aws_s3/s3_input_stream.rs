@@ -21,6 +21,7 @@ use std::task::Context;
 use std::task::Poll;
 
 use bytes::BufMut;
+use common_base::tokio::io::AsyncReadExt;
 use common_base::tokio::io::ErrorKind;
 use futures::ready;
 use futures::stream::Fuse;
@@ -34,13 +35,25 @@ use rusoto_s3::S3Client;
 use rusoto_s3::StreamingBody;
 use rusoto_s3::S3;
 
-type StreamLenFuture = Pin<Box<dyn Future<Output = Result<i64, Error>> + Send>>;
+use crate::Compression;
+
+type StreamLenFuture = Pin<Box<dyn Future<Output = Result<(i64, Compression), Error>> + Send>>;
+type DecompressFuture = Pin<Box<dyn Future<Output = Result<Vec<u8>, Error>> + Send>>;
+
+enum Body {
+    Raw(Fuse<StreamingBody>),
+    Decompressed(Vec<u8>),
+}
 
 enum State {
     Bare,
-    GettingBody(Pin<Box<dyn Future<Output = Result<Fuse<StreamingBody>, Error>> + Send>>),
+    GettingBody(Pin<Box<dyn Future<Output = Result<Body, Error>> + Send>>),
     GotBody(Fuse<StreamingBody>),
     Seeking(StreamLenFuture),
+    // A compressed object is loaded and decompressed as a whole,
+    // the reads and seeks are then served from memory.
+    Decompressing(DecompressFuture),
+    Decompressed(Vec<u8>),
 }
 
 pub struct S3InputStream {
@@ -81,6 +94,7 @@ impl futures::AsyncRead for S3InputStream {
             let empty = { self.buffer.is_empty() };
             match &mut self.state {
                 State::Bare => {
+                    let from_start = self.cursor_pos == 0;
                     let req = GetObjectRequest {
                         range: Some(format!("bytes={}-", self.cursor_pos)),
                         key: self.key.clone(),
@@ -88,14 +102,29 @@ impl futures::AsyncRead for S3InputStream {
                         ..Default::default()
                     };
                     let client = self.client.clone();
+                    let (bucket, key) = (self.bucket.clone(), self.key.clone());
                     let resp = async move {
                         let reply = client
                             .get_object(req)
                             .await
                             .map_err(|e| Error::new(ErrorKind::Other, e))?;
+                        let compression = Compression::from_metadata(reply.metadata.as_ref())
+                            .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+                        if compression != Compression::None {
+                            // A compressed object is decompressed as a whole:
+                            // the body read from the start is the whole object,
+                            // a body read from an offset is not of any use.
+                            let data = match from_start {
+                                true => decompress_body(reply.body, compression).await?,
+                                false => {
+                                    load_decompressed(client, bucket, key, compression).await?
+                                }
+                            };
+                            return Ok(Body::Decompressed(data));
+                        }
                         reply
                             .body
-                            .map(|s| s.fuse())
+                            .map(|s| Body::Raw(s.fuse()))
                             .ok_or_else(|| Error::new(ErrorKind::Other, "empty stream"))
                     };
                     self.state = State::GettingBody(resp.boxed());
@@ -103,9 +132,13 @@ impl futures::AsyncRead for S3InputStream {
                 State::GettingBody(resp) => {
                     let resp = Pin::new(resp);
                     match ready!(resp.poll(cx)) {
-                        Ok(v) => {
+                        Ok(Body::Raw(v)) => {
                             self.state = State::GotBody(v);
                         }
+                        Ok(Body::Decompressed(data)) => {
+                            self.stream_len = Some(data.len() as u64);
+                            self.state = State::Decompressed(data);
+                        }
                         Err(e) => return Poll::Ready(Err(Error::new(ErrorKind::Other, e))),
                     }
                 }
@@ -130,7 +163,8 @@ impl futures::AsyncRead for S3InputStream {
                         }
                     }
                 }
-                State::Seeking(_f) => {
+                State::Decompressed(_) => return self.read_decompressed(buf),
+                State::Seeking(_) | State::Decompressing(_) => {
                     // read while seeking is NOT allowed
                     return Poll::Ready(Err(Error::new(
                         ErrorKind::Other,
@@ -163,11 +197,29 @@ impl S3InputStream {
             None => loop {
                 match &mut self.state {
                     State::Seeking(f) => match ready!(Pin::new(f).poll(cx)) {
-                        Ok(v) => {
+                        Ok((v, Compression::None)) => {
                             let len = v as u64;
                             self.stream_len = Some(len);
                             return Poll::Ready(self.seek_with_stream_len(cx, pos, len));
                         }
+                        Ok((_, compression)) => {
+                            let load = load_decompressed(
+                                self.client.clone(),
+                                self.bucket.clone(),
+                                self.key.clone(),
+                                compression,
+                            );
+                            self.state = State::Decompressing(load.boxed());
+                        }
+                        Err(e) => return Poll::Ready(Err(e)),
+                    },
+                    State::Decompressing(f) => match ready!(Pin::new(f).poll(cx)) {
+                        Ok(data) => {
+                            let len = data.len() as u64;
+                            self.stream_len = Some(len);
+                            self.state = State::Decompressed(data);
+                            return Poll::Ready(self.seek_with_stream_len(cx, pos, len));
+                        }
                         Err(e) => return Poll::Ready(Err(e)),
                     },
                     State::Bare => {
@@ -184,13 +236,18 @@ impl S3InputStream {
                                 .head_object(head_req)
                                 .await
                                 .map_err(|e| Error::new(ErrorKind::Other, e))?;
-                            result.content_length.ok_or_else(|| {
+                            let compression = Compression::from_metadata(result.metadata.as_ref())
+                                .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+                            let len = result.content_length.ok_or_else(|| {
                                 Error::new(ErrorKind::Other, "expects content-length")
-                            })
+                            })?;
+                            Ok((len, compression))
                         };
                         self.state = State::Seeking(res.boxed());
                     }
-                    State::GettingBody(_) | State::GotBody(_) => self.state = State::Bare,
+                    State::GettingBody(_) | State::GotBody(_) | State::Decompressed(_) => {
+                        self.state = State::Bare
+                    }
                 };
             },
         }
@@ -232,7 +289,7 @@ impl S3InputStream {
                 "invalid seeking operation",
             ));
         }
-        if self.cursor_pos != new_pos {
+        if self.cursor_pos != new_pos && !matches!(self.state, State::Decompressed(_)) {
             // stop pending read
             self.state = State::Bare;
         }
@@ -240,6 +297,20 @@ impl S3InputStream {
         Ok(self.cursor_pos)
     }
 
+    fn read_decompressed(mut self: Pin<&mut Self>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        let data = match &this.state {
+            State::Decompressed(data) => data,
+            _ => unreachable!("read_decompressed is only called in state Decompressed"),
+        };
+
+        let start = std::cmp::min(this.cursor_pos as usize, data.len());
+        let available = std::cmp::min(buf.len(), data.len() - start);
+        buf[..available].copy_from_slice(&data[start..start + available]);
+        this.cursor_pos += available as u64;
+        Poll::Ready(Ok(available))
+    }
+
     fn do_read(mut self: Pin<&mut Self>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
         let available = std::cmp::min(buf.remaining_mut(), self.buffer.len());
         let bytes = self.buffer.split_to(available);
@@ -258,3 +329,37 @@ impl S3InputStream {
         Poll::Ready(Ok(available))
     }
 }
+
+// Load a compressed object as a whole and decompress it.
+async fn load_decompressed(
+    client: S3Client,
+    bucket: String,
+    key: String,
+    compression: Compression,
+) -> Result<Vec<u8>, Error> {
+    let req = GetObjectRequest {
+        key,
+        bucket,
+        ..Default::default()
+    };
+    let reply = client
+        .get_object(req)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e))?;
+
+    decompress_body(reply.body, compression).await
+}
+
+// Read a fetched body of a compressed object to the end and decompress it.
+async fn decompress_body(
+    body: Option<StreamingBody>,
+    compression: Compression,
+) -> Result<Vec<u8>, Error> {
+    let mut data = vec![];
+    if let Some(body) = body {
+        body.into_async_read().read_to_end(&mut data).await?;
+    }
+    compression
+        .decompress(data)
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))
+}
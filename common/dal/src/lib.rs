@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub use compression::Compression;
+pub use compression::COMPRESSION_METADATA_KEY;
 pub use data_accessor::read_obj;
 pub use data_accessor::AsyncSeekableReader;
 pub use data_accessor::Bytes;
@@ -24,9 +26,12 @@ pub use impls::aws_s3::S3;
 pub use impls::local::Local;
 pub use schemes::StorageScheme;
 
+mod compression;
 mod data_accessor;
 mod impls;
 mod schemes;
 
+#[cfg(test)]
+mod compression_test;
 #[cfg(test)]
 mod schemes_test;
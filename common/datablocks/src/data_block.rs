@@ -30,27 +30,33 @@ use common_exception::Result;
 
 use crate::pretty_format_blocks;
 
+/// The (min, max) of every column of a block, in the order of the columns.
+pub type BlockMinMax = Vec<(DataValue, DataValue)>;
+
 #[derive(Clone)]
 pub struct DataBlock {
     schema: DataSchemaRef,
     columns: Vec<DataColumn>,
+    // Attached by the transforms collecting statistics, it is dropped by any block derived from this one.
+    min_max: Option<Arc<BlockMinMax>>,
 }
 
 impl DataBlock {
     pub fn create(schema: DataSchemaRef, columns: Vec<DataColumn>) -> Self {
-        DataBlock { schema, columns }
+        DataBlock {
+            schema,
+            columns,
+            min_max: None,
+        }
     }
 
     pub fn create_by_array(schema: DataSchemaRef, arrays: Vec<Series>) -> Self {
         let columns = arrays.into_iter().map(DataColumn::Array).collect();
-        DataBlock { schema, columns }
+        DataBlock::create(schema, columns)
     }
 
     pub fn empty() -> Self {
-        DataBlock {
-            schema: Arc::new(DataSchema::empty()),
-            columns: vec![],
-        }
+        DataBlock::create(Arc::new(DataSchema::empty()), vec![])
     }
 
     pub fn empty_with_schema(schema: DataSchemaRef) -> Self {
@@ -60,7 +66,7 @@ impl DataBlock {
             let array: ArrayRef = Arc::from(array);
             columns.push(DataColumn::Array(array.into_series()))
         }
-        DataBlock { schema, columns }
+        DataBlock::create(schema, columns)
     }
 
     pub fn is_empty(&self) -> bool {
@@ -96,6 +102,16 @@ impl DataBlock {
         &self.columns
     }
 
+    /// The (min, max) of every column, None if no transform has collected them.
+    pub fn min_max(&self) -> Option<&BlockMinMax> {
+        self.min_max.as_deref()
+    }
+
+    pub fn with_min_max(mut self, min_max: BlockMinMax) -> Self {
+        self.min_max = Some(Arc::new(min_max));
+        self
+    }
+
     pub fn try_column_by_name(&self, name: &str) -> Result<&DataColumn> {
         if name == "*" {
             Ok(&self.columns[0])
@@ -0,0 +1,47 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::columns::DataColumn;
+use common_datavalues::DataValue;
+
+use crate::BlockMinMax;
+use crate::DataBlock;
+
+impl DataBlock {
+    /// Compute the (min, max) of every column, the nulls are ignored.
+    ///
+    /// A column without a natural order, e.g. a list, gets the null of its type as both min and max.
+    pub fn compute_min_max(block: &DataBlock) -> BlockMinMax {
+        block
+            .columns()
+            .iter()
+            .map(|column| match column {
+                DataColumn::Array(series) => match (series.min(), series.max()) {
+                    (Ok(min), Ok(max)) => (min, max),
+                    _ => {
+                        let null = DataValue::from(series.data_type());
+                        (null.clone(), null)
+                    }
+                },
+                DataColumn::Constant(v, _) => (v.clone(), v.clone()),
+            })
+            .collect()
+    }
+
+    /// Attach the (min, max) of every column to the block, for the downstream operators.
+    pub fn attach_min_max(block: DataBlock) -> DataBlock {
+        let min_max = DataBlock::compute_min_max(&block);
+        block.with_min_max(min_max)
+    }
+}
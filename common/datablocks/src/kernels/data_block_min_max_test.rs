@@ -0,0 +1,66 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::prelude::*;
+use common_exception::Result;
+
+use crate::*;
+
+#[test]
+fn test_data_block_min_max() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("b", DataType::Float64, true),
+        DataField::new("c", DataType::String, false),
+        DataField::new("d", DataType::Int8, false),
+    ]);
+
+    let raw = DataBlock::create(schema, vec![
+        Series::new(vec![3i64, -1, 5, 2]).into(),
+        Series::new(vec![Some(1.5f64), None, Some(-2.), Some(0.)]).into(),
+        Series::new(vec!["x", "abc", "y", "b"]).into(),
+        DataColumn::Constant(DataValue::Int8(Some(7)), 4),
+    ]);
+    assert!(raw.min_max().is_none());
+
+    let block = DataBlock::attach_min_max(raw);
+    let expected = vec![
+        (DataValue::Int64(Some(-1)), DataValue::Int64(Some(5))),
+        (DataValue::Float64(Some(-2.)), DataValue::Float64(Some(1.5))),
+        (
+            DataValue::String(Some(b"abc".to_vec())),
+            DataValue::String(Some(b"y".to_vec())),
+        ),
+        (DataValue::Int8(Some(7)), DataValue::Int8(Some(7))),
+    ];
+    assert_eq!(Some(&expected), block.min_max());
+
+    // A block derived from it does not carry the statistics of its source.
+    let sliced = DataBlock::slice_block(&block, 0, 2);
+    assert!(sliced.min_max().is_none());
+    assert_eq!(
+        vec![
+            (DataValue::Int64(Some(-1)), DataValue::Int64(Some(3))),
+            (DataValue::Float64(Some(1.5)), DataValue::Float64(Some(1.5))),
+            (
+                DataValue::String(Some(b"abc".to_vec())),
+                DataValue::String(Some(b"x".to_vec())),
+            ),
+            (DataValue::Int8(Some(7)), DataValue::Int8(Some(7))),
+        ],
+        DataBlock::compute_min_max(&sliced)
+    );
+
+    Ok(())
+}
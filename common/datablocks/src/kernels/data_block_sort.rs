@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
 use std::iter::once;
 use std::sync::Arc;
 
@@ -143,6 +144,73 @@ impl DataBlock {
         Ok(DataBlock::create(lhs.schema().clone(), columns))
     }
 
+    /// Split a block sorted by `sort_columns_descriptions` by the rows of `bounds`,
+    /// which are sorted the same and have at least the sort columns of the block.
+    ///
+    /// It returns `bounds.num_rows() + 1` sorted blocks, block `i` holds the rows before bound `i`
+    /// and not before bound `i - 1`, so that the blocks of a range, split from many sorted blocks,
+    /// are merged independently of the other ranges.
+    pub fn split_sorted_block(
+        block: &DataBlock,
+        bounds: &DataBlock,
+        sort_columns_descriptions: &[SortColumnDescription],
+    ) -> Result<Vec<DataBlock>> {
+        let sort_arrays = sort_columns_descriptions
+            .iter()
+            .map(|f| {
+                let column = block.try_column_by_name(&f.column_name)?.to_array()?;
+                let bound = bounds.try_column_by_name(&f.column_name)?.to_array()?;
+                Ok(vec![column.get_array_ref(), bound.get_array_ref()])
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let sort_dyn_arrays = sort_arrays
+            .iter()
+            .map(|f| vec![f[0].as_ref(), f[1].as_ref()])
+            .collect::<Vec<_>>();
+
+        let sort_options = sort_columns_descriptions
+            .iter()
+            .map(|f| arrow_sort::SortOptions {
+                descending: !f.asc,
+                nulls_first: f.nulls_first,
+            })
+            .collect::<Vec<_>>();
+
+        let sort_options_with_array = sort_dyn_arrays
+            .iter()
+            .zip(sort_options.iter())
+            .map(|(s, opt)| {
+                let pairs: (&[&dyn Array], &SortOptions) = (s, opt);
+                pairs
+            })
+            .collect::<Vec<_>>();
+
+        let comparator = build_comparator(&sort_options_with_array)?;
+
+        let num_rows = block.num_rows();
+        let mut blocks = Vec::with_capacity(bounds.num_rows() + 1);
+        let mut start = 0;
+        for bound in 0..bounds.num_rows() {
+            // Binary search the first row not before the bound.
+            let (mut lo, mut hi) = (start, num_rows);
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if comparator(0, mid, 1, bound) == Ordering::Less {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+
+            blocks.push(DataBlock::slice_block(block, start, lo - start));
+            start = lo;
+        }
+        blocks.push(DataBlock::slice_block(block, start, num_rows - start));
+
+        Ok(blocks)
+    }
+
     pub fn take_arrays_by_slices(
         arrays: &[&dyn Array],
         slices: &[MergeSlice],
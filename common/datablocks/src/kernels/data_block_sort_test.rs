@@ -117,3 +117,43 @@ fn test_data_block_merge_sort() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_data_block_split_sorted_block() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("b", DataType::String, false),
+    ]);
+
+    // Sorted by a desc.
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Series::new(vec![9, 7, 7, 5, 3, 1]),
+        Series::new(vec!["b1", "b2", "b3", "b4", "b5", "b6"]),
+    ]);
+    let options = vec![SortColumnDescription {
+        column_name: "a".to_owned(),
+        asc: false,
+        nulls_first: false,
+    }];
+
+    // The rows equal to a bound go to the range after it.
+    let bounds = DataBlock::create_by_array(
+        DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]),
+        vec![Series::new(vec![7, 4, 0])],
+    );
+    let ranges = DataBlock::split_sorted_block(&block, &bounds, &options)?;
+    assert_eq!(4, ranges.len());
+
+    let rows = ranges
+        .iter()
+        .map(|range| {
+            (0..range.num_rows())
+                .map(|row| range.column(0).try_get(row)?.as_i64())
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(vec![vec![9], vec![7, 7, 5], vec![3, 1], vec![]], rows);
+    assert!(ranges.iter().all(|range| range.schema() == &schema));
+
+    Ok(())
+}
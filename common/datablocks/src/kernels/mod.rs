@@ -21,6 +21,8 @@ mod data_block_group_by_hash_test;
 #[cfg(test)]
 mod data_block_group_by_test;
 #[cfg(test)]
+mod data_block_min_max_test;
+#[cfg(test)]
 mod data_block_scatter_test;
 #[cfg(test)]
 mod data_block_slice_test;
@@ -33,6 +35,7 @@ mod data_block_concat;
 mod data_block_filter;
 mod data_block_group_by;
 mod data_block_group_by_hash;
+mod data_block_min_max;
 mod data_block_scatter;
 mod data_block_slice;
 mod data_block_sort;
@@ -21,6 +21,7 @@ mod data_block;
 mod data_block_debug;
 mod kernels;
 
+pub use data_block::BlockMinMax;
 pub use data_block::DataBlock;
 pub use data_block_debug::*;
 pub use kernels::*;
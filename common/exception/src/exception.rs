@@ -172,6 +172,13 @@ build_exceptions! {
     UnexpectedError(54),
     DateTimeParseError(55),
     BadPredicateRows(56),
+    IllegalTransactionState(57),
+    QueryTimeout(58),
+    ReadOnlySession(59),
+    TooManyJoinRows(60),
+    SchemaMismatch(61),
+    PipelineBufferFull(62),
+    CannotWriteFile(63),
 
     // uncategorized
     UnexpectedResponseType(600),
@@ -198,6 +205,10 @@ build_exceptions! {
     MetaServiceShutdown(2202),
     // meta service is unavailable for now.
     MetaServiceUnavailable(2203),
+    // meta service does not respond in time.
+    MetaServiceTimeout(2204),
+    // meta service node is not the leader and does not know a leader to forward a write to.
+    MetaServiceNotLeader(2205),
 
     // config errors
 
@@ -211,6 +222,7 @@ build_exceptions! {
 
     ConcurrentSnapshotInstall(2404),
     IllegalSnapshot(2405),
+    MetaStoreVersionSkew(2406),
 
     // KVSrv server error
 
@@ -248,6 +260,11 @@ build_exceptions! {
     NamespaceNodeAlreadyExists(4009),
     NamespaceIllegalNodeFormat(4010),
 
+    // table schema error.
+    ColumnAlreadyExists(4011),
+    TableVersionMismatch(4012),
+    DatabaseNotEmpty(4013),
+
     // storage-api error codes
     IllegalScanPlan(5000),
     ReadFileError(5001),
@@ -260,6 +277,7 @@ build_exceptions! {
     // DAL error
     DALTransportError(7000),
     UnknownStorageSchemeName(7001),
+    UnknownCompressionName(7002),
 
 
     // datasource error
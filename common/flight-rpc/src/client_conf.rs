@@ -31,6 +31,12 @@ pub struct FlightClientConf {
     pub username: String,
     pub password: String,
     pub tls_conf: Option<FlightClientTlsConfig>,
+
+    /// Max number of connections a client holds. 0 is treated as 1.
+    pub pool_size: usize,
+
+    /// Seconds a request is allowed to take. 0 is to use the default timeout of the client.
+    pub timeout_secs: u64,
 }
 
 impl FlightClientConf {
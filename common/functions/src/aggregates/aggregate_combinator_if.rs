@@ -154,6 +154,14 @@ impl AggregateFunction for AggregateIfCombinator {
     fn merge_result(&self, place: StateAddr) -> Result<DataValue> {
         self.nested.merge_result(place)
     }
+
+    fn need_manual_drop_state(&self) -> bool {
+        self.nested.need_manual_drop_state()
+    }
+
+    unsafe fn drop_state(&self, place: StateAddr) {
+        self.nested.drop_state(place)
+    }
 }
 
 impl fmt::Display for AggregateIfCombinator {
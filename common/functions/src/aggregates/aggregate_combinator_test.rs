@@ -141,6 +141,13 @@ fn test_aggregate_combinator_function() -> Result<()> {
                 t.name
             );
             assert_eq!(t.display, format!("{:}", func), "{}", t.name);
+
+            if func.need_manual_drop_state() {
+                unsafe {
+                    func.drop_state(addr.into());
+                    func.drop_state(addr2.into());
+                }
+            }
             Ok(())
         };
 
@@ -151,6 +158,28 @@ fn test_aggregate_combinator_function() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_aggregate_combinator_need_manual_drop_state() -> Result<()> {
+    let factory = AggregateFunctionFactory::instance();
+    let arg = DataField::new("a", DataType::Int64, false);
+
+    for (func_name, need) in [
+        ("countdistinct", true),
+        ("sumdistinct", true),
+        ("uniq", true),
+        ("countif", false),
+        ("sum", false),
+    ] {
+        let args = match func_name {
+            "countif" => vec![arg.clone(), DataField::new("b", DataType::Boolean, false)],
+            _ => vec![arg.clone()],
+        };
+        let func = factory.get(func_name, vec![], args)?;
+        assert_eq!(need, func.need_manual_drop_state(), "{}", func_name);
+    }
+    Ok(())
+}
+
 #[test]
 fn test_aggregate_combinator_function_on_empty_data() -> Result<()> {
     struct Test {
@@ -59,4 +59,17 @@ pub trait AggregateFunction: fmt::Display + Sync + Send {
 
     // TODO append the value into the column builder
     fn merge_result(&self, _place: StateAddr) -> Result<DataValue>;
+
+    // whether the state owns memory out of the arena, which has to be released by `drop_state`
+    fn need_manual_drop_state(&self) -> bool {
+        false
+    }
+
+    /// Release the memory the state owns out of the arena, e.g. the set of a distinct state.
+    /// The arena frees its own memory without running the destructors of the states.
+    ///
+    /// # Safety
+    ///
+    /// The place must be initialized by `init_state`, and must not be used after it is dropped.
+    unsafe fn drop_state(&self, _place: StateAddr) {}
 }
@@ -21,4 +21,5 @@ pub use namespace::NamespaceMgr;
 pub use user::user_api::AuthType;
 pub use user::user_api::UserInfo;
 pub use user::user_api::UserMgrApi;
+pub use user::user_api::UserSetting;
 pub use user::user_mgr::UserMgr;
@@ -44,6 +44,22 @@ impl UserInfo {
     }
 }
 
+/// A setting persisted for a user, applied to the sessions the user opens.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct UserSetting {
+    pub name: String,
+    pub value: String,
+}
+
+impl UserSetting {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        UserSetting {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
 pub trait UserMgrApi: Sync + Send {
     fn add_user(&self, user_info: UserInfo) -> Result<u64>;
 
@@ -60,6 +76,12 @@ pub trait UserMgrApi: Sync + Send {
     ) -> Result<Option<u64>>;
 
     fn drop_user(&self, username: String, seq: Option<u64>) -> Result<()>;
+
+    /// The persisted settings of the user, empty if none has been persisted.
+    fn get_user_settings(&self, username: String) -> Result<Vec<UserSetting>>;
+
+    /// Persist a setting for the user, replacing the previous value of the same name.
+    fn set_user_setting(&self, username: String, setting: UserSetting) -> Result<u64>;
 }
 
 impl TryFrom<Vec<u8>> for UserInfo {
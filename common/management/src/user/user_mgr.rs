@@ -31,12 +31,16 @@ use common_meta_types::UpsertKVActionReply;
 use super::user_api::AuthType;
 use crate::user::user_api::UserInfo;
 use crate::user::user_api::UserMgrApi;
+use crate::user::user_api::UserSetting;
 
 pub static USER_API_KEY_PREFIX: &str = "__fd_users";
+pub static USER_SETTINGS_API_KEY_PREFIX: &str = "__fd_user_settings";
 
 pub struct UserMgr {
     kv_api: Arc<dyn KVApi>,
     user_prefix: String,
+    // Kept apart from the users, so listing the users doesn't list the settings.
+    setting_prefix: String,
 
     rt: Arc<Runtime>,
     rpc_time_out: Option<Duration>,
@@ -49,6 +53,7 @@ impl UserMgr {
         UserMgr {
             kv_api,
             user_prefix: format!("{}/{}", USER_API_KEY_PREFIX, tenant),
+            setting_prefix: format!("{}/{}", USER_SETTINGS_API_KEY_PREFIX, tenant),
             rt: Arc::new(rt),
             // TODO(bh): add config.
             rpc_time_out: Some(Duration::from_secs(5)),
@@ -182,4 +187,59 @@ impl UserMgrApi for UserMgr {
             Err(ErrorCode::UnknownUser(format!("unknown user {}", username)))
         }
     }
+
+    fn get_user_settings(&self, username: String) -> Result<Vec<UserSetting>> {
+        Ok(self.get_user_settings_with_seq(&username)?.1)
+    }
+
+    fn set_user_setting(&self, username: String, setting: UserSetting) -> Result<u64> {
+        let (seq, mut settings) = self.get_user_settings_with_seq(&username)?;
+        match settings.iter_mut().find(|s| s.name == setting.name) {
+            Some(s) => s.value = setting.value,
+            None => settings.push(setting),
+        }
+
+        // The settings are rewritten as a whole, the seq guards against a concurrent update.
+        let key = format!("{}/{}", self.setting_prefix, username);
+        let value = serde_json::to_vec(&settings)?;
+        let kv_api = self.kv_api.clone();
+        let res = self.rt.block_on(
+            async move {
+                kv_api
+                    .upsert_kv(&key, MatchSeq::Exact(seq), Some(value), None)
+                    .await
+            },
+            self.rpc_time_out,
+        )??;
+
+        match res.result {
+            Some((s, _)) => Ok(s),
+            None => Err(ErrorCode::UnknownException(format!(
+                "Settings of user {} changed concurrently, seq not match {}",
+                username, seq
+            ))),
+        }
+    }
+}
+
+impl UserMgr {
+    // The settings of the user and their seq, the seq is 0 if none has been persisted.
+    fn get_user_settings_with_seq(&self, username: &str) -> Result<SeqValue<Vec<UserSetting>>> {
+        let key = format!("{}/{}", self.setting_prefix, username);
+        let kv_api = self.kv_api.clone();
+        let res = self
+            .rt
+            .block_on(async move { kv_api.get_kv(&key).await }, self.rpc_time_out)??;
+
+        match res.result {
+            None => Ok((0, vec![])),
+            Some((seq, value)) => {
+                let settings = serde_json::from_slice::<Vec<UserSetting>>(&value.value)
+                    .map_err_to_code(ErrorCode::IllegalUserInfoFormat, || {
+                        format!("Cannot deserialize the settings of user {}", username)
+                    })?;
+                Ok((seq, settings))
+            }
+        }
+    }
 }
@@ -30,6 +30,7 @@ use mockall::*;
 use crate::user::user_api::AuthType;
 use crate::user::user_api::UserInfo;
 use crate::user::user_api::UserMgrApi;
+use crate::user::user_api::UserSetting;
 use crate::UserMgr;
 
 // and mock!
@@ -685,3 +686,108 @@ mod update {
         Ok(())
     }
 }
+
+mod settings {
+    use common_meta_types::KVValue;
+
+    use super::*;
+
+    #[test]
+    fn test_get_user_settings_not_persisted() -> common_exception::Result<()> {
+        let test_key = "__fd_user_settings/tenant1/name";
+
+        let mut kv = MockKV::new();
+        kv.expect_get_kv()
+            .with(predicate::function(move |v| v == test_key))
+            .times(1)
+            .return_once(|_k| Ok(GetKVActionReply { result: None }));
+
+        let kv = Arc::new(kv);
+        let user_mgr = UserMgr::new(kv, "tenant1");
+        let res = user_mgr.get_user_settings("name".to_string())?;
+        assert!(res.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_user_setting_replace() -> common_exception::Result<()> {
+        let test_key = "__fd_user_settings/tenant1/name";
+
+        let prev_value = serde_json::to_vec(&vec![
+            UserSetting::new("max_threads", "8"),
+            UserSetting::new("max_block_size", "100"),
+        ])?;
+        let new_value = serde_json::to_vec(&vec![
+            UserSetting::new("max_threads", "2"),
+            UserSetting::new("max_block_size", "100"),
+        ])?;
+
+        let mut kv = MockKV::new();
+        kv.expect_get_kv()
+            .with(predicate::function(move |v| v == test_key))
+            .times(1)
+            .return_once(move |_k| {
+                Ok(GetKVActionReply {
+                    result: Some((3, KVValue {
+                        meta: None,
+                        value: prev_value,
+                    })),
+                })
+            });
+
+        // The settings are rewritten as a whole, guarded by the seq read.
+        kv.expect_upsert_kv()
+            .with(
+                predicate::function(move |v| v == test_key),
+                predicate::eq(MatchSeq::Exact(3)),
+                predicate::eq(Some(new_value)),
+                predicate::eq(None),
+            )
+            .times(1)
+            .return_once(|_, _, _, _meta| {
+                Ok(UpsertKVActionReply {
+                    prev: None,
+                    result: Some((4, KVValue {
+                        meta: None,
+                        value: vec![],
+                    })),
+                })
+            });
+
+        let kv = Arc::new(kv);
+        let user_mgr = UserMgr::new(kv, "tenant1");
+        let res =
+            user_mgr.set_user_setting("name".to_string(), UserSetting::new("max_threads", "2"))?;
+        assert_eq!(4, res);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_user_setting_seq_mismatch() -> common_exception::Result<()> {
+        let mut kv = MockKV::new();
+        kv.expect_get_kv()
+            .times(1)
+            .return_once(|_k| Ok(GetKVActionReply { result: None }));
+        kv.expect_upsert_kv()
+            .with(
+                predicate::always(),
+                predicate::eq(MatchSeq::Exact(0)),
+                predicate::always(),
+                predicate::eq(None),
+            )
+            .times(1)
+            .return_once(|_, _, _, _meta| {
+                Ok(UpsertKVActionReply {
+                    prev: None,
+                    result: None,
+                })
+            });
+
+        let kv = Arc::new(kv);
+        let user_mgr = UserMgr::new(kv, "tenant1");
+        let res =
+            user_mgr.set_user_setting("name".to_string(), UserSetting::new("max_threads", "2"));
+        assert!(res.is_err());
+        Ok(())
+    }
+}
@@ -13,8 +13,14 @@
 //  limitations under the License.
 //
 
+#[cfg(test)]
+mod meta_api_error_test;
+
 mod kv_api;
 mod meta_api;
+mod meta_api_error;
 
 pub use kv_api::KVApi;
 pub use meta_api::MetaApi;
+pub use meta_api_error::MetaApiError;
+pub use meta_api_error::MetaApiResult;
@@ -15,45 +15,127 @@
 
 use std::sync::Arc;
 
-use common_exception::Result;
+use common_meta_types::BatchItemReply;
+use common_meta_types::BatchMode;
+use common_meta_types::CatalogSnapshot;
 use common_meta_types::CreateDatabaseReply;
 use common_meta_types::CreateTableReply;
 use common_meta_types::DatabaseInfo;
+use common_meta_types::ListDatabasesReply;
 use common_meta_types::MetaId;
 use common_meta_types::MetaVersion;
+use common_meta_types::PingReply;
 use common_meta_types::TableInfo;
+use common_meta_types::TableStatistics;
+use common_meta_types::UpsertDatabaseReply;
+use common_planners::AddColumnPlan;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
 use common_planners::DropDatabasePlan;
 use common_planners::DropTablePlan;
+use common_planners::RenameTablePlan;
+use common_planners::TruncateTablePlan;
 
+use crate::MetaApiResult;
+
+/// The errors are classified into `MetaApiError`, see it for what a caller can tell from an error.
 #[async_trait::async_trait]
 pub trait MetaApi: Send + Sync {
     // database
 
-    async fn create_database(&self, plan: CreateDatabasePlan) -> Result<CreateDatabaseReply>;
+    async fn create_database(&self, plan: CreateDatabasePlan)
+        -> MetaApiResult<CreateDatabaseReply>;
+
+    /// Create the database if absent, otherwise return the existing one.
+    /// `if_not_exists` in the plan is ignored.
+    async fn upsert_database(&self, plan: CreateDatabasePlan)
+        -> MetaApiResult<UpsertDatabaseReply>;
+
+    async fn drop_database(&self, plan: DropDatabasePlan) -> MetaApiResult<()>;
+
+    async fn get_database(&self, db: &str) -> MetaApiResult<Arc<DatabaseInfo>>;
+
+    async fn get_databases(&self) -> MetaApiResult<Vec<Arc<DatabaseInfo>>>;
 
-    async fn drop_database(&self, plan: DropDatabasePlan) -> Result<()>;
+    /// Check if a database exists, without fetching its info.
+    async fn database_exists(&self, db: &str) -> MetaApiResult<bool>;
 
-    async fn get_database(&self, db: &str) -> Result<Arc<DatabaseInfo>>;
+    /// List databases ordered by name, starting after `cursor`, at most `limit` databases a page.
+    async fn list_databases(
+        &self,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> MetaApiResult<ListDatabasesReply>;
 
-    async fn get_databases(&self) -> Result<Vec<Arc<DatabaseInfo>>>;
+    /// All the databases with their tables in one round trip.
+    /// They are read at a single point of the meta state, a table is never returned without its database.
+    async fn get_catalog_snapshot(&self) -> MetaApiResult<CatalogSnapshot>;
 
     // table
 
-    async fn create_table(&self, plan: CreateTablePlan) -> Result<CreateTableReply>;
+    async fn create_table(&self, plan: CreateTablePlan) -> MetaApiResult<CreateTableReply>;
+
+    /// Create tables in one meta operation, the replies are in the same order as the plans.
+    /// An existing table fails unless `if_not_exists` is set in its plan.
+    ///
+    /// With `BatchMode::AbortOnError` none of the tables is created if any of them fails,
+    /// and the error is returned.
+    /// With `BatchMode::ContinueOnError` the other tables are still created,
+    /// the reply of every table tells whether it is created or why it failed.
+    async fn create_tables(
+        &self,
+        plans: Vec<CreateTablePlan>,
+        mode: BatchMode,
+    ) -> MetaApiResult<Vec<BatchItemReply<CreateTableReply>>>;
+
+    async fn drop_table(&self, plan: DropTablePlan) -> MetaApiResult<()>;
+
+    async fn rename_table(&self, plan: RenameTablePlan) -> MetaApiResult<()>;
 
-    async fn drop_table(&self, plan: DropTablePlan) -> Result<()>;
+    async fn alter_table_add_column(&self, plan: AddColumnPlan) -> MetaApiResult<()>;
 
-    async fn get_table(&self, db: &str, table: &str) -> Result<Arc<TableInfo>>;
+    async fn truncate_table(&self, plan: TruncateTablePlan) -> MetaApiResult<()>;
 
-    async fn get_tables(&self, db: &str) -> Result<Vec<Arc<TableInfo>>>;
+    async fn get_table(&self, db: &str, table: &str) -> MetaApiResult<Arc<TableInfo>>;
+
+    async fn get_tables(&self, db: &str) -> MetaApiResult<Vec<Arc<TableInfo>>>;
+
+    /// Get the tables whose name starts with `prefix`, filtered on the meta server,
+    /// e.g., for `SHOW TABLES LIKE 'foo%'`. An empty prefix gets all the tables like `get_tables`.
+    async fn get_tables_with_prefix(
+        &self,
+        db: &str,
+        prefix: &str,
+    ) -> MetaApiResult<Vec<Arc<TableInfo>>>;
+
+    /// Statistics accumulated from the data parts of a table.
+    async fn get_table_statistics(&self, db: &str, table: &str) -> MetaApiResult<TableStatistics>;
+
+    /// Check if a table exists, without fetching its info.
+    /// A table in an absent database does not exist.
+    async fn table_exists(&self, db: &str, table: &str) -> MetaApiResult<bool>;
 
     async fn get_table_by_id(
         &self,
         table_id: MetaId,
         table_version: Option<MetaVersion>,
-    ) -> Result<Arc<TableInfo>>;
+    ) -> MetaApiResult<Arc<TableInfo>>;
+
+    /// Get tables by `(table_id, table_version)` in one round trip, the result is in the same order as `ids`.
+    /// It fails if any of the tables is not found.
+    async fn get_tables_by_ids(
+        &self,
+        ids: Vec<(MetaId, Option<MetaVersion>)>,
+    ) -> MetaApiResult<Vec<Arc<TableInfo>>>;
+
+    /// List the known versions of a table in ascending order, the last one is the current version.
+    async fn list_table_versions(&self, table_id: MetaId) -> MetaApiResult<Vec<MetaVersion>>;
+
+    // health
+
+    /// A cheap liveness probe, it touches no data.
+    /// It succeeds as long as the meta node is up, even if it is read-only or catching up.
+    async fn ping(&self) -> MetaApiResult<PingReply>;
 
     fn name(&self) -> String;
 }
@@ -0,0 +1,168 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::fmt;
+
+use common_exception::ErrorCode;
+use common_meta_types::actual_table_version;
+use common_meta_types::MetaVersion;
+
+pub type MetaApiResult<T> = std::result::Result<T, MetaApiError>;
+
+/// The error of a `MetaApi` call, classified by what a caller may want to branch on.
+///
+/// Every variant keeps the `ErrorCode` it is built from, converting it back to `ErrorCode` loses nothing.
+#[derive(Debug)]
+pub enum MetaApiError {
+    /// The database or table named `name` already exists.
+    AlreadyExists { name: String, source: ErrorCode },
+
+    /// The database or table named `name` does not exist.
+    /// `name` is empty if the call does not look it up by name.
+    NotFound { name: String, source: ErrorCode },
+
+    /// The table is not of the version the call expects.
+    /// A version is `None` if the client does not know it,
+    /// `actual` is only known if the error is built by `table_version_mismatch`.
+    VersionMismatch {
+        expected: Option<MetaVersion>,
+        actual: Option<MetaVersion>,
+        source: ErrorCode,
+    },
+
+    /// The meta service is unreachable or does not respond in time.
+    Transport(ErrorCode),
+
+    /// Any other error, e.g., an invalid argument.
+    Other(ErrorCode),
+}
+
+impl MetaApiError {
+    /// Classify the error of a call on database `db`.
+    pub fn of_database(error: ErrorCode, db: &str) -> Self {
+        Self::classify(error, db, "", None)
+    }
+
+    /// Classify the error of a call on `table` in database `db`,
+    /// which expects the table to be of `expected_version` if it is some.
+    pub fn of_table(
+        error: ErrorCode,
+        db: &str,
+        table: &str,
+        expected_version: Option<MetaVersion>,
+    ) -> Self {
+        Self::classify(error, db, table, expected_version)
+    }
+
+    fn classify(
+        error: ErrorCode,
+        db: &str,
+        table: &str,
+        expected_version: Option<MetaVersion>,
+    ) -> Self {
+        let code = error.code();
+        let name = |n: &str| n.to_string();
+
+        if code == ErrorCode::DatabaseAlreadyExists("").code() {
+            MetaApiError::AlreadyExists {
+                name: name(db),
+                source: error,
+            }
+        } else if code == ErrorCode::TableAlreadyExists("").code() {
+            MetaApiError::AlreadyExists {
+                name: name(table),
+                source: error,
+            }
+        } else if code == ErrorCode::UnknownDatabase("").code() {
+            MetaApiError::NotFound {
+                name: name(db),
+                source: error,
+            }
+        } else if code == ErrorCode::UnknownTable("").code() {
+            MetaApiError::NotFound {
+                name: name(table),
+                source: error,
+            }
+        } else if code == ErrorCode::TableVersionMismatch("").code() {
+            MetaApiError::VersionMismatch {
+                expected: expected_version,
+                actual: actual_table_version(&error),
+                source: error,
+            }
+        } else if Self::is_transport_code(code) {
+            MetaApiError::Transport(error)
+        } else {
+            MetaApiError::Other(error)
+        }
+    }
+
+    fn is_transport_code(code: u16) -> bool {
+        [
+            ErrorCode::CannotConnectNode("").code(),
+            ErrorCode::Timeout("").code(),
+            ErrorCode::MetaServiceError("").code(),
+            ErrorCode::MetaServiceShutdown("").code(),
+            ErrorCode::MetaServiceUnavailable("").code(),
+            ErrorCode::MetaServiceTimeout("").code(),
+            ErrorCode::MetaServiceNotLeader("").code(),
+        ]
+        .contains(&code)
+    }
+
+    /// The `ErrorCode` this error is built from.
+    pub fn error_code(&self) -> &ErrorCode {
+        match self {
+            MetaApiError::AlreadyExists { source, .. } => source,
+            MetaApiError::NotFound { source, .. } => source,
+            MetaApiError::VersionMismatch { source, .. } => source,
+            MetaApiError::Transport(source) => source,
+            MetaApiError::Other(source) => source,
+        }
+    }
+
+    pub fn code(&self) -> u16 {
+        self.error_code().code()
+    }
+
+    pub fn message(&self) -> String {
+        self.error_code().message()
+    }
+}
+
+/// Classify an error without knowing the names the call is about.
+impl From<ErrorCode> for MetaApiError {
+    fn from(error: ErrorCode) -> Self {
+        Self::classify(error, "", "", None)
+    }
+}
+
+impl From<MetaApiError> for ErrorCode {
+    fn from(error: MetaApiError) -> Self {
+        match error {
+            MetaApiError::AlreadyExists { source, .. } => source,
+            MetaApiError::NotFound { source, .. } => source,
+            MetaApiError::VersionMismatch { source, .. } => source,
+            MetaApiError::Transport(source) => source,
+            MetaApiError::Other(source) => source,
+        }
+    }
+}
+
+impl fmt::Display for MetaApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.error_code(), f)
+    }
+}
+
+impl std::error::Error for MetaApiError {}
@@ -0,0 +1,88 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use common_exception::ErrorCode;
+use common_meta_types::table_version_mismatch;
+
+use crate::MetaApiError;
+
+#[test]
+fn test_meta_api_error_classify() {
+    let e = MetaApiError::of_table(
+        ErrorCode::TableAlreadyExists("t1 exists"),
+        "db1",
+        "t1",
+        None,
+    );
+    match e {
+        MetaApiError::AlreadyExists { ref name, .. } => assert_eq!("t1", name),
+        _ => panic!("unexpected: {:?}", e),
+    }
+
+    let e = MetaApiError::of_table(ErrorCode::UnknownDatabase("no db1"), "db1", "t1", None);
+    match e {
+        MetaApiError::NotFound { ref name, .. } => assert_eq!("db1", name),
+        _ => panic!("unexpected: {:?}", e),
+    }
+
+    let e = MetaApiError::of_table(
+        ErrorCode::TableVersionMismatch("changed"),
+        "db1",
+        "t1",
+        Some(3),
+    );
+    match e {
+        MetaApiError::VersionMismatch {
+            expected, actual, ..
+        } => {
+            assert_eq!(Some(3), expected);
+            assert_eq!(None, actual);
+        }
+        _ => panic!("unexpected: {:?}", e),
+    }
+
+    // The actual version is read from an error built by `table_version_mismatch`.
+    let e = MetaApiError::of_table(
+        table_version_mismatch("drop table t1", Some(3), 5),
+        "db1",
+        "t1",
+        Some(3),
+    );
+    match e {
+        MetaApiError::VersionMismatch {
+            expected, actual, ..
+        } => {
+            assert_eq!(Some(3), expected);
+            assert_eq!(Some(5), actual);
+        }
+        _ => panic!("unexpected: {:?}", e),
+    }
+
+    let e = MetaApiError::of_database(ErrorCode::MetaServiceTimeout("slow"), "db1");
+    assert!(matches!(e, MetaApiError::Transport(_)));
+
+    let e = MetaApiError::of_database(ErrorCode::BadArguments("bad"), "db1");
+    assert!(matches!(e, MetaApiError::Other(_)));
+}
+
+#[test]
+fn test_meta_api_error_to_error_code() {
+    let e = MetaApiError::of_database(ErrorCode::DatabaseAlreadyExists("db1 exists"), "db1");
+    assert_eq!(ErrorCode::DatabaseAlreadyExists("").code(), e.code());
+    assert_eq!("db1 exists", e.message());
+
+    let e: ErrorCode = e.into();
+    assert_eq!(ErrorCode::DatabaseAlreadyExists("").code(), e.code());
+    assert_eq!("db1 exists", e.message());
+}
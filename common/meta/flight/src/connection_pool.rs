@@ -0,0 +1,130 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::sync::Arc;
+
+use common_base::tokio::sync::OwnedSemaphorePermit;
+use common_base::tokio::sync::Semaphore;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::Mutex;
+
+/// A pool of at most `max_size` connections.
+///
+/// Every action borrows a connection exclusively until its response is read,
+/// thus at most `max_size` actions are in flight at the same time.
+/// An idle connection is reused, a new one is only built when there is no idle connection.
+/// A pool of size 1 behaves the same as a single shared connection.
+pub(crate) struct ConnectionPool<C> {
+    max_size: usize,
+    idle: Mutex<Vec<C>>,
+    permits: Arc<Semaphore>,
+}
+
+impl<C> ConnectionPool<C> {
+    /// Create a pool holding at most `max_size` connections. A size of 0 means the number of CPUs.
+    pub fn create(max_size: usize) -> Self {
+        let max_size = match max_size {
+            0 => num_cpus::get(),
+            n => n,
+        };
+
+        ConnectionPool {
+            max_size,
+            idle: Mutex::new(Vec::with_capacity(max_size)),
+            permits: Arc::new(Semaphore::new(max_size)),
+        }
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    pub fn idle_size(&self) -> usize {
+        self.idle.lock().len()
+    }
+
+    /// Add an already built connection to the pool, e.g., the one built for handshake.
+    /// It is dropped if the pool is full.
+    pub fn put_idle(&self, conn: C) {
+        let mut idle = self.idle.lock();
+        if idle.len() < self.max_size {
+            idle.push(conn);
+        }
+    }
+
+    /// Drop all the idle connections, e.g., when they point to a node that no longer serves writes.
+    pub fn clear_idle(&self) {
+        self.idle.lock().clear();
+    }
+
+    /// Borrow a connection, waiting for one to be returned if all of them are in use.
+    /// `connect` is called to build a new one if there is no idle connection.
+    pub async fn acquire<F, Fut>(self: &Arc<Self>, connect: F) -> Result<PooledConnection<C>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<C>>,
+    {
+        let permit =
+            self.permits.clone().acquire_owned().await.map_err(|e| {
+                ErrorCode::LogicalError(format!("connection pool is closed: {}", e))
+            })?;
+
+        let idle = self.idle.lock().pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => connect().await?,
+        };
+
+        Ok(PooledConnection {
+            pool: self.clone(),
+            conn: Some(conn),
+            _permit: permit,
+        })
+    }
+}
+
+/// A connection borrowed from a `ConnectionPool`. It is returned to the pool when dropped.
+///
+/// It does not borrow the pool, so that it can be kept with a response stream until the stream is read.
+pub(crate) struct PooledConnection<C> {
+    pool: Arc<ConnectionPool<C>>,
+    conn: Option<C>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl<C> Deref for PooledConnection<C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_ref().unwrap()
+    }
+}
+
+impl<C> DerefMut for PooledConnection<C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_mut().unwrap()
+    }
+}
+
+impl<C> Drop for PooledConnection<C> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.idle.lock().push(conn);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::tokio;
+use common_base::tokio::sync::Barrier;
+use common_exception::ErrorCode;
+use futures::StreamExt;
+
+use crate::connection_pool::ConnectionPool;
+
+/// Builds connections identified by a sequence number.
+struct Connector {
+    built: AtomicU32,
+}
+
+impl Connector {
+    fn create() -> Connector {
+        Connector {
+            built: AtomicU32::new(0),
+        }
+    }
+
+    async fn connect(&self) -> common_exception::Result<u32> {
+        Ok(self.built.fetch_add(1, Ordering::SeqCst))
+    }
+
+    fn built(&self) -> u32 {
+        self.built.load(Ordering::SeqCst)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_connection_pool_reuse() -> anyhow::Result<()> {
+    let connector = Connector::create();
+    let pool = Arc::new(ConnectionPool::create(2));
+
+    {
+        let c = pool.acquire(|| connector.connect()).await?;
+        assert_eq!(0, *c);
+    }
+    assert_eq!(1, pool.idle_size());
+
+    // The idle connection is reused.
+    {
+        let c = pool.acquire(|| connector.connect()).await?;
+        assert_eq!(0, *c);
+        assert_eq!(0, pool.idle_size());
+
+        // No idle connection, build a new one.
+        let c2 = pool.acquire(|| connector.connect()).await?;
+        assert_eq!(1, *c2);
+    }
+    assert_eq!(2, pool.idle_size());
+    assert_eq!(2, connector.built());
+
+    // A full pool drops extra idle connections.
+    pool.put_idle(100);
+    assert_eq!(2, pool.idle_size());
+
+    // Reconnecting drops the idle connections, a new one is built on the next acquire.
+    pool.clear_idle();
+    assert_eq!(0, pool.idle_size());
+    {
+        let c = pool.acquire(|| connector.connect()).await?;
+        assert_eq!(2, *c);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_connection_pool_connect_error() -> anyhow::Result<()> {
+    let pool = Arc::new(ConnectionPool::<u32>::create(1));
+
+    let res = pool
+        .acquire(|| async { Err(ErrorCode::CannotConnectNode("refused")) })
+        .await;
+    assert!(res.is_err());
+
+    // The permit is released on error.
+    let c = pool.acquire(|| async { Ok(5) }).await?;
+    assert_eq!(5, *c);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_connection_pool_concurrent() -> anyhow::Result<()> {
+    // 4 actions hold their connections at the same time:
+    // every one of them waits on the barrier until all of them have got a connection.
+
+    let connector = Arc::new(Connector::create());
+    let pool = Arc::new(ConnectionPool::create(4));
+    let barrier = Arc::new(Barrier::new(4));
+
+    let mut handles = vec![];
+    for _ in 0..4 {
+        let connector = connector.clone();
+        let pool = pool.clone();
+        let barrier = barrier.clone();
+
+        let h = tokio::spawn(async move {
+            let c = pool.acquire(|| connector.connect()).await?;
+            barrier.wait().await;
+            Ok::<u32, ErrorCode>(*c)
+        });
+        handles.push(h);
+    }
+
+    let fu = futures::future::join_all(handles);
+    let res = tokio::time::timeout(Duration::from_secs(5), fu).await?;
+
+    let mut got = res
+        .into_iter()
+        .map(|r| r.unwrap())
+        .collect::<common_exception::Result<Vec<_>>>()?;
+    got.sort_unstable();
+
+    assert_eq!(vec![0, 1, 2, 3], got);
+    assert_eq!(4, pool.idle_size());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_connection_pool_size_one() -> anyhow::Result<()> {
+    // With size 1, the second action waits until the first returns its connection.
+
+    let connector = Arc::new(Connector::create());
+    let pool = Arc::new(ConnectionPool::create(1));
+
+    let c = pool.acquire(|| connector.connect()).await?;
+
+    let mut second = {
+        let connector = connector.clone();
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            let c = pool.acquire(|| connector.connect()).await?;
+            Ok::<u32, ErrorCode>(*c)
+        })
+    };
+
+    let waited = tokio::time::timeout(Duration::from_millis(100), &mut second).await;
+    assert!(waited.is_err(), "blocked until the connection is returned");
+
+    drop(c);
+
+    let got = second.await??;
+    assert_eq!(0, got, "the only connection is reused");
+    assert_eq!(1, connector.built());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_connection_pool_lease_in_stream() -> anyhow::Result<()> {
+    // A connection kept by a reply stream is returned only when the stream is dropped.
+
+    let connector = Arc::new(Connector::create());
+    let pool = Arc::new(ConnectionPool::create(1));
+
+    let c = pool.acquire(|| connector.connect()).await?;
+    let stream = futures::stream::iter(vec![1, 2]).map(move |x| x + *c);
+    let stream = stream.boxed();
+    assert_eq!(0, pool.idle_size());
+
+    let mut second = {
+        let connector = connector.clone();
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            let c = pool.acquire(|| connector.connect()).await?;
+            Ok::<u32, ErrorCode>(*c)
+        })
+    };
+
+    let waited = tokio::time::timeout(Duration::from_millis(100), &mut second).await;
+    assert!(waited.is_err(), "blocked until the stream is dropped");
+
+    let got = stream.collect::<Vec<_>>().await;
+    assert_eq!(vec![1, 2], got);
+
+    let got = second.await??;
+    assert_eq!(0, got, "the only connection is reused");
+    assert_eq!(1, connector.built());
+
+    Ok(())
+}
+
+#[test]
+fn test_connection_pool_default_size() {
+    let pool = ConnectionPool::<u32>::create(0);
+    assert_eq!(num_cpus::get(), pool.max_size());
+
+    let pool = ConnectionPool::<u32>::create(3);
+    assert_eq!(3, pool.max_size());
+}
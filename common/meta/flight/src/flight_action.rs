@@ -18,22 +18,32 @@ use std::sync::Arc;
 
 use common_arrow::arrow_flight::Action;
 use common_exception::ErrorCode;
+use common_meta_types::BatchItemReply;
+use common_meta_types::BatchMode;
+use common_meta_types::CatalogSnapshot;
 use common_meta_types::CreateDatabaseReply;
 use common_meta_types::CreateTableReply;
 use common_meta_types::DatabaseInfo;
 use common_meta_types::GetKVActionReply;
 use common_meta_types::KVMeta;
+use common_meta_types::ListDatabasesReply;
 use common_meta_types::MGetKVActionReply;
 use common_meta_types::MatchSeq;
 use common_meta_types::MetaId;
 use common_meta_types::MetaVersion;
+use common_meta_types::PingReply;
 use common_meta_types::PrefixListReply;
 use common_meta_types::TableInfo;
+use common_meta_types::TableStatistics;
+use common_meta_types::UpsertDatabaseReply;
 use common_meta_types::UpsertKVActionReply;
+use common_planners::AddColumnPlan;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
 use common_planners::DropDatabasePlan;
 use common_planners::DropTablePlan;
+use common_planners::RenameTablePlan;
+use common_planners::TruncateTablePlan;
 use prost::Message;
 use tonic::Request;
 
@@ -63,14 +73,28 @@ macro_rules! action_declare {
 pub enum MetaFlightAction {
     // database meta
     CreateDatabase(CreateDatabaseAction),
+    UpsertDatabase(UpsertDatabaseAction),
     GetDatabase(GetDatabaseAction),
+    DatabaseExists(DatabaseExistsAction),
     DropDatabase(DropDatabaseAction),
     CreateTable(CreateTableAction),
+    CreateTables(CreateTablesAction),
     DropTable(DropTableAction),
+    RenameTable(RenameTableAction),
+    AddColumn(AddColumnAction),
+    TruncateTable(TruncateTableAction),
     GetTable(GetTableAction),
+    TableExists(TableExistsAction),
+    GetTableStatistics(GetTableStatisticsAction),
     GetTableExt(GetTableExtReq),
+    GetTablesByIds(GetTablesByIdsAction),
+    ListTableVersions(ListTableVersionsAction),
     GetTables(GetTablesAction),
+    GetTablesWithPrefix(GetTablesWithPrefixAction),
+    GetTablesChunked(GetTablesChunkedAction),
     GetDatabases(GetDatabasesAction),
+    ListDatabases(ListDatabasesAction),
+    GetCatalogSnapshot(GetCatalogSnapshotAction),
 
     // general purpose kv
     UpsertKV(UpsertKVAction),
@@ -78,6 +102,9 @@ pub enum MetaFlightAction {
     GetKV(GetKVAction),
     MGetKV(MGetKVAction),
     PrefixListKV(PrefixListReq),
+
+    // health
+    Ping(PingAction),
 }
 
 /// Try convert tonic::Request<Action> to DoActionAction.
@@ -198,6 +225,17 @@ action_declare!(
     MetaFlightAction::CreateDatabase
 );
 
+// - upsert database
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct UpsertDatabaseAction {
+    pub plan: CreateDatabasePlan,
+}
+action_declare!(
+    UpsertDatabaseAction,
+    UpsertDatabaseReply,
+    MetaFlightAction::UpsertDatabase
+);
+
 // - get database
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct GetDatabaseAction {
@@ -209,6 +247,13 @@ action_declare!(
     MetaFlightAction::GetDatabase
 );
 
+// - database exists
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct DatabaseExistsAction {
+    pub db: String,
+}
+action_declare!(DatabaseExistsAction, bool, MetaFlightAction::DatabaseExists);
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct DropDatabaseAction {
     pub plan: DropDatabasePlan,
@@ -227,6 +272,18 @@ action_declare!(
     MetaFlightAction::CreateTable
 );
 
+// - create tables in batch
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct CreateTablesAction {
+    pub plans: Vec<CreateTablePlan>,
+    pub mode: BatchMode,
+}
+action_declare!(
+    CreateTablesAction,
+    Vec<BatchItemReply<CreateTableReply>>,
+    MetaFlightAction::CreateTables
+);
+
 // - drop table
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct DropTableAction {
@@ -234,6 +291,27 @@ pub struct DropTableAction {
 }
 action_declare!(DropTableAction, (), MetaFlightAction::DropTable);
 
+// - rename table
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct RenameTableAction {
+    pub plan: RenameTablePlan,
+}
+action_declare!(RenameTableAction, (), MetaFlightAction::RenameTable);
+
+// - add column
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct AddColumnAction {
+    pub plan: AddColumnPlan,
+}
+action_declare!(AddColumnAction, (), MetaFlightAction::AddColumn);
+
+// - truncate table
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct TruncateTableAction {
+    pub plan: TruncateTablePlan,
+}
+action_declare!(TruncateTableAction, (), MetaFlightAction::TruncateTable);
+
 // - get table
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct GetTableAction {
@@ -243,6 +321,15 @@ pub struct GetTableAction {
 
 action_declare!(GetTableAction, Arc<TableInfo>, MetaFlightAction::GetTable);
 
+// - table exists
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct TableExistsAction {
+    pub db: String,
+    pub table: String,
+}
+
+action_declare!(TableExistsAction, bool, MetaFlightAction::TableExists);
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct GetTableExtReq {
     pub tbl_id: MetaId,
@@ -254,6 +341,40 @@ action_declare!(
     MetaFlightAction::GetTableExt
 );
 
+// - get tables by ids
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct GetTablesByIdsAction {
+    pub ids: Vec<(MetaId, Option<MetaVersion>)>,
+}
+action_declare!(
+    GetTablesByIdsAction,
+    Vec<Arc<TableInfo>>,
+    MetaFlightAction::GetTablesByIds
+);
+
+// - get table statistics
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct GetTableStatisticsAction {
+    pub db: String,
+    pub table: String,
+}
+action_declare!(
+    GetTableStatisticsAction,
+    TableStatistics,
+    MetaFlightAction::GetTableStatistics
+);
+
+// - list table versions
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ListTableVersionsAction {
+    pub tbl_id: MetaId,
+}
+action_declare!(
+    ListTableVersionsAction,
+    Vec<MetaVersion>,
+    MetaFlightAction::ListTableVersions
+);
+
 // - get tables
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct GetTablesAction {
@@ -266,6 +387,36 @@ action_declare!(
     MetaFlightAction::GetTables
 );
 
+// - get tables with a name prefix
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct GetTablesWithPrefixAction {
+    pub db: String,
+    pub prefix: String,
+}
+
+action_declare!(
+    GetTablesWithPrefixAction,
+    Vec<Arc<TableInfo>>,
+    MetaFlightAction::GetTablesWithPrefix
+);
+
+// - get tables in chunks
+
+/// Same as `GetTablesAction`, except that the tables are replied in a stream of messages,
+/// each of which holds at most `chunk_size` tables.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct GetTablesChunkedAction {
+    pub db: String,
+    /// The max number of tables in a message. 0 is treated as 1.
+    pub chunk_size: u64,
+}
+
+action_declare!(
+    GetTablesChunkedAction,
+    Vec<Arc<TableInfo>>,
+    MetaFlightAction::GetTablesChunked
+);
+
 // -get databases
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
@@ -276,3 +427,38 @@ action_declare!(
     Vec<Arc<DatabaseInfo>>,
     MetaFlightAction::GetDatabases
 );
+
+// - list databases by page
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct ListDatabasesAction {
+    /// List the databases whose name is greater than the cursor.
+    pub cursor: Option<String>,
+    /// The max number of databases in a page.
+    pub limit: u64,
+}
+
+action_declare!(
+    ListDatabasesAction,
+    ListDatabasesReply,
+    MetaFlightAction::ListDatabases
+);
+
+// - get all databases with their tables
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct GetCatalogSnapshotAction;
+
+action_declare!(
+    GetCatalogSnapshotAction,
+    CatalogSnapshot,
+    MetaFlightAction::GetCatalogSnapshot
+);
+
+// == health actions ==
+// - ping, it does not touch any data
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct PingAction;
+
+action_declare!(PingAction, PingReply, MetaFlightAction::Ping);
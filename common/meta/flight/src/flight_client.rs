@@ -13,18 +13,24 @@
 // limitations under the License.
 
 use std::convert::TryInto;
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
 
+use common_arrow::arrow_flight;
 use common_arrow::arrow_flight::flight_service_client::FlightServiceClient;
 use common_arrow::arrow_flight::Action;
 use common_arrow::arrow_flight::BasicAuth;
 use common_arrow::arrow_flight::HandshakeRequest;
+use common_base::tokio;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_flight_rpc::ConnectionFactory;
 use common_flight_rpc::FlightClientTlsConfig;
 use common_tracing::tracing;
 use futures::stream;
+use futures::stream::BoxStream;
 use futures::StreamExt;
 use prost::Message;
 use serde::de::DeserializeOwned;
@@ -33,30 +39,66 @@ use tonic::metadata::MetadataValue;
 use tonic::service::Interceptor;
 use tonic::transport::Channel;
 use tonic::Request;
+use tonic::Status;
+use tonic::Streaming;
 
+use crate::connection_pool::ConnectionPool;
+use crate::connection_pool::PooledConnection;
 use crate::flight_action::MetaFlightAction;
 use crate::flight_action::RequestFor;
 use crate::flight_client_conf::MetaFlightClientConf;
+use crate::RetryPolicy;
+
+type FlightClient = FlightServiceClient<InterceptedService<Channel, AuthInterceptor>>;
+type ActionReply = Streaming<arrow_flight::Result>;
+
+/// The default max number of connections to metasrv a client holds, 0 means the number of CPUs,
+/// so that concurrent actions do not wait for each other by default.
+pub const DEFAULT_POOL_SIZE: usize = 0;
+
+/// The default time an action is allowed to take before failing with `MetaServiceTimeout`.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
 pub struct MetaFlightClient {
     #[allow(dead_code)]
     token: Vec<u8>,
     pub(crate) timeout: Duration,
-    pub(crate) client: FlightServiceClient<InterceptedService<Channel, AuthInterceptor>>,
+
+    /// Every action borrows a connection from the pool and returns it once its reply is read,
+    /// so that concurrent actions do not wait for each other, up to the pool size.
+    pool: Arc<ConnectionPool<FlightClient>>,
+
+    /// How the read-only actions are retried on transport errors.
+    /// Mutating actions are never retried on transport errors, to avoid applying them twice,
+    /// they are only retried once after reconnecting if the meta service replies it is not the leader.
+    pub(crate) retry_policy: RetryPolicy,
+
+    // To reconnect when retrying.
+    addr: String,
+    username: String,
+    password: String,
+    tls_conf: Option<FlightClientTlsConfig>,
 }
 
 const AUTH_TOKEN_KEY: &str = "auth-token-bin";
 
 impl MetaFlightClient {
     pub async fn try_new(conf: &MetaFlightClientConf) -> Result<MetaFlightClient> {
-        Self::with_tls_conf(
+        let mut client = Self::with_pool_size(
             &conf.meta_service_config.address,
             &conf.meta_service_config.username,
             &conf.meta_service_config.password,
             conf.meta_service_config.tls_conf.clone(),
+            conf.meta_service_config.pool_size,
         )
-        .await
+        .await?;
+
+        if conf.meta_service_config.timeout_secs > 0 {
+            client.set_timeout(Duration::from_secs(conf.meta_service_config.timeout_secs));
+        }
+
+        Ok(client)
     }
 
     pub fn sync_try_new(conf: &MetaFlightClientConf) -> Result<MetaFlightClient> {
@@ -76,9 +118,63 @@ impl MetaFlightClient {
         password: &str,
         conf: Option<FlightClientTlsConfig>,
     ) -> Result<Self> {
-        // TODO configuration
-        let timeout = Duration::from_secs(60);
+        Self::with_pool_size(addr, username, password, conf, DEFAULT_POOL_SIZE).await
+    }
+
+    /// Create a client holding at most `pool_size` connections to metasrv.
+    /// A size of 0 means the number of CPUs.
+    #[tracing::instrument(level = "debug", skip(password))]
+    pub async fn with_pool_size(
+        addr: &str,
+        username: &str,
+        password: &str,
+        conf: Option<FlightClientTlsConfig>,
+        pool_size: usize,
+    ) -> Result<Self> {
+        let timeout = DEFAULT_TIMEOUT;
+
+        // Connect once to fail early on a bad address or bad credentials.
+        let (token, client) =
+            MetaFlightClient::connect(addr, username, password, conf.clone(), timeout).await?;
+
+        let pool = ConnectionPool::create(pool_size);
+        pool.put_idle(client);
+
+        let rx = Self {
+            token,
+            timeout,
+            pool: Arc::new(pool),
+            retry_policy: RetryPolicy::default(),
+            addr: addr.to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            tls_conf: conf,
+        };
+        Ok(rx)
+    }
+
+    /// Set the deadline of every action, including waiting for a connection and retrying.
+    /// An action not finished in time fails with `MetaServiceTimeout`.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
 
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    pub fn pool_size(&self) -> usize {
+        self.pool.max_size()
+    }
+
+    #[tracing::instrument(level = "debug", skip(password, conf))]
+    async fn connect(
+        addr: &str,
+        username: &str,
+        password: &str,
+        conf: Option<FlightClientTlsConfig>,
+        timeout: Duration,
+    ) -> Result<(Vec<u8>, FlightClient)> {
         let res = ConnectionFactory::create_flight_channel(addr, Some(timeout), conf);
 
         tracing::debug!("connecting to {}, res: {:?}", addr, res);
@@ -93,16 +189,35 @@ impl MetaFlightClient {
             FlightServiceClient::with_interceptor(channel, AuthInterceptor { token })
         };
 
-        let rx = Self {
-            token,
-            timeout,
-            client,
-        };
-        Ok(rx)
+        Ok((token, client))
     }
 
-    pub fn set_timeout(&mut self, timeout: Duration) {
-        self.timeout = timeout;
+    /// Drop all the idle connections and connect again, e.g., after the meta leader changes.
+    ///
+    /// The address is resolved again when connecting, thus a DNS name or a load balancer in front of
+    /// the meta service is able to direct the new connection to the new leader.
+    /// A connection borrowed by an in-flight action is not affected, it is returned to the pool when done.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn reconnect(&self) -> Result<()> {
+        let client = self.new_connection().await?;
+
+        self.pool.clear_idle();
+        self.pool.put_idle(client);
+        Ok(())
+    }
+
+    /// Build a new connection with the same address and credentials.
+    async fn new_connection(&self) -> Result<FlightClient> {
+        let (_token, client) = MetaFlightClient::connect(
+            &self.addr,
+            &self.username,
+            &self.password,
+            self.tls_conf.clone(),
+            self.timeout,
+        )
+        .await?;
+
+        Ok(client)
     }
 
     /// Handshake.
@@ -136,6 +251,10 @@ impl MetaFlightClient {
         Ok(token)
     }
 
+    /// Send an action without retrying on transport errors.
+    ///
+    /// If the meta service replies it is not the leader, nothing is applied:
+    /// it reconnects and sends the action once more.
     #[tracing::instrument(level = "debug", skip(self, v))]
     pub(crate) async fn do_action<T, R>(&self, v: T) -> Result<R>
     where
@@ -144,13 +263,56 @@ impl MetaFlightClient {
         R: DeserializeOwned,
     {
         let act: MetaFlightAction = v.into();
-        let req: Request<Action> = (&act).try_into()?;
-        let mut req = common_tracing::inject_span_to_tonic_request(req);
+        let act = &act;
+        let no_retry = &RetryPolicy::no_retry();
 
-        req.set_timeout(self.timeout);
+        with_not_leader_retry(
+            move || self.send_with_retry(act, no_retry),
+            || self.reconnect(),
+        )
+        .await
+    }
+
+    /// Send a read-only action, reconnect and retry it on transport errors.
+    #[tracing::instrument(level = "debug", skip(self, v))]
+    pub(crate) async fn do_read_action<T, R>(&self, v: T) -> Result<R>
+    where
+        T: RequestFor<Reply = R>,
+        T: Into<MetaFlightAction>,
+        R: DeserializeOwned,
+    {
+        let act: MetaFlightAction = v.into();
+        self.send_with_retry(&act, &self.retry_policy).await
+    }
 
-        let mut stream = self.client.clone().do_action(req).await?.into_inner();
-        match stream.message().await? {
+    async fn send_with_retry<R>(
+        &self,
+        act: &MetaFlightAction,
+        retry_policy: &RetryPolicy,
+    ) -> Result<R>
+    where
+        R: DeserializeOwned,
+    {
+        let req: Request<Action> = act.try_into()?;
+        let action = req.into_inner();
+
+        let resp = with_deadline(self.timeout, act, async {
+            let resp = retry_policy
+                .run(|attempt| {
+                    let action = action.clone();
+                    async move {
+                        // The connection is returned to the pool only after the reply is read.
+                        let (_client, mut stream) = self.send_action(action, attempt).await?;
+                        stream.message().await
+                    }
+                })
+                .await
+                .map_err(status_to_error_code)?;
+            Ok(resp)
+        })
+        .await?;
+
+        match resp {
             None => Err(ErrorCode::EmptyData(format!(
                 "Can not receive data from dfs flight server, action: {:?}",
                 act
@@ -161,6 +323,122 @@ impl MetaFlightClient {
             }
         }
     }
+
+    /// Send a read-only action whose reply is a stream of messages.
+    ///
+    /// It reconnects and retries on transport errors until the stream is established.
+    /// An error in the middle of the stream is yielded as the last item and is not retried,
+    /// since the items before it have already been consumed.
+    #[tracing::instrument(level = "debug", skip(self, v))]
+    pub(crate) async fn do_stream_read_action<T, R>(
+        &self,
+        v: T,
+    ) -> Result<BoxStream<'static, Result<R>>>
+    where
+        T: RequestFor<Reply = R>,
+        T: Into<MetaFlightAction>,
+        R: DeserializeOwned + Send + 'static,
+    {
+        let act: MetaFlightAction = v.into();
+        let req: Request<Action> = (&act).try_into()?;
+        let action = req.into_inner();
+
+        let (client, stream) = with_deadline(self.timeout, &act, async {
+            self.retry_policy
+                .run(|attempt| self.send_action(action.clone(), attempt))
+                .await
+                .map_err(status_to_error_code)
+        })
+        .await?;
+
+        // The stream keeps the connection, it is returned to the pool once the stream is dropped.
+        let stream = stream.map(move |resp| {
+            let _client = &client;
+            let resp = resp.map_err(status_to_error_code)?;
+            let v = serde_json::from_slice::<R>(&resp.body)?;
+            Ok(v)
+        });
+        Ok(stream.boxed())
+    }
+
+    /// Send an action with a connection borrowed from the pool, the `attempt`-th time.
+    ///
+    /// The connection is returned with the reply stream, the caller must keep it until the reply is read,
+    /// otherwise another action may be sent on it while the reply is still in flight.
+    async fn send_action(
+        &self,
+        action: Action,
+        attempt: u32,
+    ) -> std::result::Result<(PooledConnection<FlightClient>, ActionReply), Status> {
+        let mut client = self
+            .pool
+            .acquire(|| self.new_connection())
+            .await
+            .map_err(|e| Status::unavailable(e.to_string()))?;
+
+        if attempt > 0 {
+            // The borrowed connection may be broken, replace it.
+            *client = self
+                .new_connection()
+                .await
+                .map_err(|e| Status::unavailable(e.to_string()))?;
+        }
+
+        let req = Request::new(action);
+        let mut req = common_tracing::inject_span_to_tonic_request(req);
+        req.set_timeout(self.timeout);
+
+        let stream = client.do_action(req).await?.into_inner();
+        Ok((client, stream))
+    }
+}
+
+/// A status of code `Unknown` carries the error the meta service replies,
+/// any other status is a failure to talk to the meta service.
+fn status_to_error_code(status: Status) -> ErrorCode {
+    match status.code() {
+        tonic::Code::Unknown => ErrorCode::from(status),
+        _ => ErrorCode::MetaServiceUnavailable(status.to_string()),
+    }
+}
+
+/// Run a mutating action, if the meta service replies it is not the leader,
+/// call `reconnect` and run it once more.
+///
+/// A not-leader reply means the action is not applied, thus running it again never applies it twice.
+pub(crate) async fn with_not_leader_retry<T, F, Fut, C, CFut>(mut op: F, reconnect: C) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    C: FnOnce() -> CFut,
+    CFut: Future<Output = Result<()>>,
+{
+    match op().await {
+        Err(e) if e.code() == ErrorCode::MetaServiceNotLeader("").code() => {
+            tracing::warn!("meta service is not the leader: {}, reconnect and retry", e);
+            reconnect().await?;
+            op().await
+        }
+        res => res,
+    }
+}
+
+/// Run an action, fails with `MetaServiceTimeout` if it does not finish in `timeout`.
+pub(crate) async fn with_deadline<T, Fut>(
+    timeout: Duration,
+    act: &impl Debug,
+    fu: Fut,
+) -> Result<T>
+where
+    Fut: Future<Output = Result<T>>,
+{
+    match tokio::time::timeout(timeout, fu).await {
+        Ok(res) => res,
+        Err(_elapsed) => Err(ErrorCode::MetaServiceTimeout(format!(
+            "meta service does not respond in {:?}, action: {:?}",
+            timeout, act
+        ))),
+    }
 }
 
 #[derive(Clone)]
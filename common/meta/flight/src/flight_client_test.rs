@@ -0,0 +1,163 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use common_base::tokio;
+use common_exception::ErrorCode;
+
+use crate::flight_client::with_deadline;
+use crate::flight_client::with_not_leader_retry;
+
+/// A meta service connection that rejects writes until it is reconnected to the new leader.
+struct FailoverService {
+    to_leader: AtomicBool,
+    writes: AtomicU32,
+    reconnects: AtomicU32,
+}
+
+impl FailoverService {
+    fn create(to_leader: bool) -> FailoverService {
+        FailoverService {
+            to_leader: AtomicBool::new(to_leader),
+            writes: AtomicU32::new(0),
+            reconnects: AtomicU32::new(0),
+        }
+    }
+
+    async fn write(&self) -> common_exception::Result<u64> {
+        self.writes.fetch_add(1, Ordering::SeqCst);
+        if self.to_leader.load(Ordering::SeqCst) {
+            Ok(42)
+        } else {
+            Err(ErrorCode::MetaServiceNotLeader("no leader to write"))
+        }
+    }
+
+    async fn reconnect(&self) -> common_exception::Result<()> {
+        self.reconnects.fetch_add(1, Ordering::SeqCst);
+        self.to_leader.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_with_deadline_timeout() -> anyhow::Result<()> {
+    // A meta service that never responds.
+
+    let timeout = Duration::from_millis(200);
+    let start = Instant::now();
+
+    let res = with_deadline::<(), _>(timeout, &"get_table", futures::future::pending()).await;
+
+    let elapsed = start.elapsed();
+    let err = res.unwrap_err();
+    assert_eq!(ErrorCode::MetaServiceTimeout("").code(), err.code());
+    assert!(err.message().contains("get_table"), "{}", err.message());
+    assert!(elapsed >= timeout, "{:?}", elapsed);
+    assert!(
+        elapsed < timeout * 5,
+        "returns near the deadline: {:?}",
+        elapsed
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_with_deadline_in_time() -> anyhow::Result<()> {
+    let timeout = Duration::from_secs(5);
+
+    let res = with_deadline(timeout, &"get_table", async { Ok(3) }).await?;
+    assert_eq!(3, res);
+
+    let res = with_deadline::<(), _>(timeout, &"get_table", async {
+        Err(ErrorCode::UnknownTable("t1"))
+    })
+    .await;
+    assert_eq!(ErrorCode::UnknownTable("").code(), res.unwrap_err().code());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_with_not_leader_retry() -> anyhow::Result<()> {
+    // Connected to the leader, no reconnect.
+    let svc = FailoverService::create(true);
+    let res = with_not_leader_retry(|| svc.write(), || svc.reconnect()).await?;
+    assert_eq!(42, res);
+    assert_eq!(1, svc.writes.load(Ordering::SeqCst));
+    assert_eq!(0, svc.reconnects.load(Ordering::SeqCst));
+
+    // The leader changed: reconnect and retry once.
+    let svc = FailoverService::create(false);
+    let res = with_not_leader_retry(|| svc.write(), || svc.reconnect()).await?;
+    assert_eq!(42, res);
+    assert_eq!(2, svc.writes.load(Ordering::SeqCst));
+    assert_eq!(1, svc.reconnects.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_with_not_leader_retry_once() -> anyhow::Result<()> {
+    // Still not the leader after reconnecting: give up after one retry.
+    let writes = AtomicU32::new(0);
+    let res = with_not_leader_retry::<(), _, _, _, _>(
+        || {
+            writes.fetch_add(1, Ordering::SeqCst);
+            async { Err(ErrorCode::MetaServiceNotLeader("no leader to write")) }
+        },
+        || async { Ok(()) },
+    )
+    .await;
+    assert_eq!(
+        ErrorCode::MetaServiceNotLeader("").code(),
+        res.unwrap_err().code()
+    );
+    assert_eq!(2, writes.load(Ordering::SeqCst));
+
+    // Any other error is not retried, the action may have been applied.
+    let writes = AtomicU32::new(0);
+    let res = with_not_leader_retry::<(), _, _, _, _>(
+        || {
+            writes.fetch_add(1, Ordering::SeqCst);
+            async { Err(ErrorCode::MetaServiceUnavailable("connection reset")) }
+        },
+        || async { Ok(()) },
+    )
+    .await;
+    assert_eq!(
+        ErrorCode::MetaServiceUnavailable("").code(),
+        res.unwrap_err().code()
+    );
+    assert_eq!(1, writes.load(Ordering::SeqCst));
+
+    // A failed reconnect fails the action.
+    let res = with_not_leader_retry::<(), _, _, _, _>(
+        || async { Err(ErrorCode::MetaServiceNotLeader("no leader to write")) },
+        || async { Err(ErrorCode::CannotConnectNode("refused")) },
+    )
+    .await;
+    assert_eq!(
+        ErrorCode::CannotConnectNode("").code(),
+        res.unwrap_err().code()
+    );
+
+    Ok(())
+}
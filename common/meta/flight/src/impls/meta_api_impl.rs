@@ -16,27 +16,80 @@
 use std::sync::Arc;
 
 use common_meta_api::MetaApi;
+use common_meta_api::MetaApiError;
+use common_meta_api::MetaApiResult;
+use common_meta_types::BatchItemReply;
+use common_meta_types::BatchMode;
+use common_meta_types::CatalogSnapshot;
 use common_meta_types::CreateDatabaseReply;
 use common_meta_types::CreateTableReply;
 use common_meta_types::DatabaseInfo;
+use common_meta_types::ListDatabasesReply;
 use common_meta_types::MetaId;
 use common_meta_types::MetaVersion;
+use common_meta_types::PingReply;
 use common_meta_types::TableInfo;
+use common_meta_types::TableStatistics;
+use common_meta_types::UpsertDatabaseReply;
+use common_planners::AddColumnPlan;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
 use common_planners::DropDatabasePlan;
 use common_planners::DropTablePlan;
+use common_planners::RenameTablePlan;
+use common_planners::TruncateTablePlan;
+use futures::stream::BoxStream;
+use futures::StreamExt;
 
+use crate::AddColumnAction;
 use crate::CreateDatabaseAction;
 use crate::CreateTableAction;
+use crate::CreateTablesAction;
+use crate::DatabaseExistsAction;
 use crate::DropDatabaseAction;
 use crate::DropTableAction;
+use crate::GetCatalogSnapshotAction;
 use crate::GetDatabaseAction;
 use crate::GetDatabasesAction;
 use crate::GetTableAction;
 use crate::GetTableExtReq;
+use crate::GetTableStatisticsAction;
 use crate::GetTablesAction;
+use crate::GetTablesByIdsAction;
+use crate::GetTablesChunkedAction;
+use crate::GetTablesWithPrefixAction;
+use crate::ListDatabasesAction;
+use crate::ListTableVersionsAction;
 use crate::MetaFlightClient;
+use crate::PingAction;
+use crate::RenameTableAction;
+use crate::TableExistsAction;
+use crate::TruncateTableAction;
+use crate::UpsertDatabaseAction;
+
+impl MetaFlightClient {
+    /// Get the tables of a database in chunks of at most `chunk_size` tables.
+    ///
+    /// Unlike `get_tables()`, the caller is able to process a chunk before the following ones
+    /// arrive, and neither end has to hold the whole reply in one message.
+    pub async fn get_tables_chunked(
+        &self,
+        db: &str,
+        chunk_size: u64,
+    ) -> MetaApiResult<BoxStream<'static, MetaApiResult<Vec<Arc<TableInfo>>>>> {
+        let stream = self
+            .do_stream_read_action(GetTablesChunkedAction {
+                db: db.to_string(),
+                chunk_size,
+            })
+            .await
+            .map_err(|e| MetaApiError::of_database(e, db))?;
+
+        let db = db.to_string();
+        let stream = stream.map(move |chunk| chunk.map_err(|e| MetaApiError::of_database(e, &db)));
+        Ok(stream.boxed())
+    }
+}
 
 #[async_trait::async_trait]
 impl MetaApi for MetaFlightClient {
@@ -44,60 +97,197 @@ impl MetaApi for MetaFlightClient {
     async fn create_database(
         &self,
         plan: CreateDatabasePlan,
-    ) -> common_exception::Result<CreateDatabaseReply> {
-        self.do_action(CreateDatabaseAction { plan }).await
+    ) -> MetaApiResult<CreateDatabaseReply> {
+        let db = plan.db.clone();
+        self.do_action(CreateDatabaseAction { plan })
+            .await
+            .map_err(|e| MetaApiError::of_database(e, &db))
+    }
+
+    /// Upsert database call.
+    async fn upsert_database(
+        &self,
+        plan: CreateDatabasePlan,
+    ) -> MetaApiResult<UpsertDatabaseReply> {
+        let db = plan.db.clone();
+        self.do_action(UpsertDatabaseAction { plan })
+            .await
+            .map_err(|e| MetaApiError::of_database(e, &db))
     }
 
     /// Drop database call.
-    async fn drop_database(&self, plan: DropDatabasePlan) -> common_exception::Result<()> {
-        self.do_action(DropDatabaseAction { plan }).await
+    async fn drop_database(&self, plan: DropDatabasePlan) -> MetaApiResult<()> {
+        let db = plan.db.clone();
+        self.do_action(DropDatabaseAction { plan })
+            .await
+            .map_err(|e| MetaApiError::of_database(e, &db))
     }
 
-    async fn get_database(&self, db: &str) -> common_exception::Result<Arc<DatabaseInfo>> {
+    async fn get_database(&self, db: &str) -> MetaApiResult<Arc<DatabaseInfo>> {
         let x = self
-            .do_action(GetDatabaseAction { db: db.to_string() })
-            .await?;
+            .do_read_action(GetDatabaseAction { db: db.to_string() })
+            .await
+            .map_err(|e| MetaApiError::of_database(e, db))?;
 
         Ok(Arc::new(x))
     }
 
-    async fn get_databases(&self) -> common_exception::Result<Vec<Arc<DatabaseInfo>>> {
-        self.do_action(GetDatabasesAction {}).await
+    async fn get_databases(&self) -> MetaApiResult<Vec<Arc<DatabaseInfo>>> {
+        Ok(self.do_read_action(GetDatabasesAction {}).await?)
+    }
+
+    async fn database_exists(&self, db: &str) -> MetaApiResult<bool> {
+        self.do_read_action(DatabaseExistsAction { db: db.to_string() })
+            .await
+            .map_err(|e| MetaApiError::of_database(e, db))
+    }
+
+    async fn list_databases(
+        &self,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> MetaApiResult<ListDatabasesReply> {
+        Ok(self
+            .do_read_action(ListDatabasesAction { cursor, limit })
+            .await?)
+    }
+
+    async fn get_catalog_snapshot(&self) -> MetaApiResult<CatalogSnapshot> {
+        Ok(self.do_read_action(GetCatalogSnapshotAction {}).await?)
     }
 
     /// Create table call.
-    async fn create_table(
+    async fn create_table(&self, plan: CreateTablePlan) -> MetaApiResult<CreateTableReply> {
+        let (db, table) = (plan.db.clone(), plan.table.clone());
+        self.do_action(CreateTableAction { plan })
+            .await
+            .map_err(|e| MetaApiError::of_table(e, &db, &table, None))
+    }
+
+    /// Create tables in batch call.
+    /// The error of a single table in `ContinueOnError` mode is in its reply, not classified.
+    async fn create_tables(
         &self,
-        plan: CreateTablePlan,
-    ) -> common_exception::Result<CreateTableReply> {
-        self.do_action(CreateTableAction { plan }).await
+        plans: Vec<CreateTablePlan>,
+        mode: BatchMode,
+    ) -> MetaApiResult<Vec<BatchItemReply<CreateTableReply>>> {
+        Ok(self.do_action(CreateTablesAction { plans, mode }).await?)
     }
 
     /// Drop table call.
-    async fn drop_table(&self, plan: DropTablePlan) -> common_exception::Result<()> {
-        self.do_action(DropTableAction { plan }).await
+    async fn drop_table(&self, plan: DropTablePlan) -> MetaApiResult<()> {
+        let (db, table) = (plan.db.clone(), plan.table.clone());
+        let expected_version = plan.expected_version;
+        self.do_action(DropTableAction { plan })
+            .await
+            .map_err(|e| MetaApiError::of_table(e, &db, &table, expected_version))
+    }
+
+    /// Rename table call.
+    async fn rename_table(&self, plan: RenameTablePlan) -> MetaApiResult<()> {
+        let (db, table) = (plan.db.clone(), plan.table.clone());
+        let new_table = plan.new_table.clone();
+        self.do_action(RenameTableAction { plan })
+            .await
+            .map_err(|e| match MetaApiError::of_table(e, &db, &table, None) {
+                // It is the new name that already exists.
+                MetaApiError::AlreadyExists { source, .. } => MetaApiError::AlreadyExists {
+                    name: new_table,
+                    source,
+                },
+                e => e,
+            })
+    }
+
+    /// Add a column to table.
+    async fn alter_table_add_column(&self, plan: AddColumnPlan) -> MetaApiResult<()> {
+        let (db, table) = (plan.db.clone(), plan.table.clone());
+        self.do_action(AddColumnAction { plan })
+            .await
+            .map_err(|e| MetaApiError::of_table(e, &db, &table, None))
+    }
+
+    /// Truncate table call.
+    async fn truncate_table(&self, plan: TruncateTablePlan) -> MetaApiResult<()> {
+        let (db, table) = (plan.db.clone(), plan.table.clone());
+        self.do_action(TruncateTableAction { plan })
+            .await
+            .map_err(|e| MetaApiError::of_table(e, &db, &table, None))
     }
 
     /// Get table.
-    async fn get_table(&self, db: &str, table: &str) -> common_exception::Result<Arc<TableInfo>> {
-        self.do_action(GetTableAction {
+    async fn get_table(&self, db: &str, table: &str) -> MetaApiResult<Arc<TableInfo>> {
+        self.do_read_action(GetTableAction {
             db: db.to_string(),
             table: table.to_string(),
         })
         .await
+        .map_err(|e| MetaApiError::of_table(e, db, table, None))
     }
 
     /// Get tables.
-    async fn get_tables(&self, db: &str) -> common_exception::Result<Vec<Arc<TableInfo>>> {
-        self.do_action(GetTablesAction { db: db.to_string() }).await
+    async fn get_tables(&self, db: &str) -> MetaApiResult<Vec<Arc<TableInfo>>> {
+        self.do_read_action(GetTablesAction { db: db.to_string() })
+            .await
+            .map_err(|e| MetaApiError::of_database(e, db))
+    }
+
+    async fn get_tables_with_prefix(
+        &self,
+        db: &str,
+        prefix: &str,
+    ) -> MetaApiResult<Vec<Arc<TableInfo>>> {
+        self.do_read_action(GetTablesWithPrefixAction {
+            db: db.to_string(),
+            prefix: prefix.to_string(),
+        })
+        .await
+        .map_err(|e| MetaApiError::of_database(e, db))
+    }
+
+    async fn get_table_statistics(&self, db: &str, table: &str) -> MetaApiResult<TableStatistics> {
+        self.do_read_action(GetTableStatisticsAction {
+            db: db.to_string(),
+            table: table.to_string(),
+        })
+        .await
+        .map_err(|e| MetaApiError::of_table(e, db, table, None))
+    }
+
+    async fn table_exists(&self, db: &str, table: &str) -> MetaApiResult<bool> {
+        self.do_read_action(TableExistsAction {
+            db: db.to_string(),
+            table: table.to_string(),
+        })
+        .await
+        .map_err(|e| MetaApiError::of_table(e, db, table, None))
     }
 
     async fn get_table_by_id(
         &self,
         tbl_id: MetaId,
         tbl_ver: Option<MetaVersion>,
-    ) -> common_exception::Result<Arc<TableInfo>> {
-        self.do_action(GetTableExtReq { tbl_id, tbl_ver }).await
+    ) -> MetaApiResult<Arc<TableInfo>> {
+        Ok(self
+            .do_read_action(GetTableExtReq { tbl_id, tbl_ver })
+            .await?)
+    }
+
+    async fn get_tables_by_ids(
+        &self,
+        ids: Vec<(MetaId, Option<MetaVersion>)>,
+    ) -> MetaApiResult<Vec<Arc<TableInfo>>> {
+        Ok(self.do_read_action(GetTablesByIdsAction { ids }).await?)
+    }
+
+    async fn list_table_versions(&self, tbl_id: MetaId) -> MetaApiResult<Vec<MetaVersion>> {
+        Ok(self
+            .do_read_action(ListTableVersionsAction { tbl_id })
+            .await?)
+    }
+
+    async fn ping(&self) -> MetaApiResult<PingReply> {
+        Ok(self.do_read_action(PingAction {}).await?)
     }
 
     fn name(&self) -> String {
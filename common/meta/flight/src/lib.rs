@@ -12,16 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(test)]
+mod connection_pool_test;
+#[cfg(test)]
+mod flight_client_test;
+#[cfg(test)]
+mod retry_policy_test;
+
+mod connection_pool;
 mod flight_client;
 #[macro_use]
 mod flight_action;
 mod flight_client_conf;
+mod retry_policy;
 
 pub mod impls;
 
 pub use flight_action::*;
 pub use flight_client::MetaFlightClient;
+pub use flight_client::DEFAULT_POOL_SIZE;
+pub use flight_client::DEFAULT_TIMEOUT;
 pub use flight_client_conf::MetaFlightClientConf;
+pub use retry_policy::RetryPolicy;
 
 // ProtoBuf generated files.
 #[allow(clippy::all)]
@@ -0,0 +1,81 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::time::Duration;
+
+use common_base::tokio;
+use common_tracing::tracing;
+use tonic::Code;
+use tonic::Status;
+
+/// Defines how an idempotent action is retried when the connection to meta service fails.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Max number of retries after the first attempt. 0 disables retrying.
+    pub max_retries: u32,
+
+    /// Time to wait before reconnecting and retrying.
+    pub interval: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            interval: Duration::from_millis(200),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn no_retry() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            interval: Duration::from_millis(0),
+        }
+    }
+
+    /// Run `op` until it returns anything but a transport error, or the retries are used up.
+    ///
+    /// `op` is called with the number of the attempt, starting from 0,
+    /// thus it is able to reconnect before retrying.
+    pub async fn run<T, F, Fut>(&self, mut op: F) -> std::result::Result<T, Status>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = std::result::Result<T, Status>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match op(attempt).await {
+                Err(status) if attempt < self.max_retries && is_transport_error(&status) => {
+                    tracing::warn!("attempt {} failed: {}, retrying", attempt, status);
+                    attempt += 1;
+                    tokio::time::sleep(self.interval).await;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+/// An error returned by meta service is always serialized into the details of a `Code::Unknown` status.
+/// Any other status is caused by the transport.
+pub fn is_transport_error(status: &Status) -> bool {
+    match status.code() {
+        Code::Unknown => status.details().is_empty(),
+        Code::Unavailable | Code::Cancelled | Code::DeadlineExceeded => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use common_base::tokio;
+use common_exception::ErrorCode;
+use tonic::Status;
+
+use crate::retry_policy::is_transport_error;
+use crate::RetryPolicy;
+
+/// A transport that fails with a transport error for the first `failures` calls.
+struct FlakyTransport {
+    failures: u32,
+    calls: AtomicU32,
+}
+
+impl FlakyTransport {
+    fn create(failures: u32) -> FlakyTransport {
+        FlakyTransport {
+            failures,
+            calls: AtomicU32::new(0),
+        }
+    }
+
+    async fn call(&self) -> std::result::Result<u64, Status> {
+        let n = self.calls.fetch_add(1, Ordering::SeqCst);
+        if n < self.failures {
+            Err(Status::unavailable("connection reset"))
+        } else {
+            Ok(42)
+        }
+    }
+}
+
+fn policy(max_retries: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_retries,
+        interval: Duration::from_millis(1),
+    }
+}
+
+#[test]
+fn test_is_transport_error() -> anyhow::Result<()> {
+    assert!(is_transport_error(&Status::unavailable("")));
+    assert!(is_transport_error(&Status::unknown("transport error")));
+    assert!(!is_transport_error(&Status::from(ErrorCode::UnknownTable(
+        "t1"
+    ))));
+    assert!(!is_transport_error(&Status::invalid_argument("")));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_policy_retries_until_success() -> anyhow::Result<()> {
+    let transport = FlakyTransport::create(2);
+    let reconnects = AtomicU32::new(0);
+
+    let res = policy(3)
+        .run(|attempt| {
+            if attempt > 0 {
+                reconnects.fetch_add(1, Ordering::SeqCst);
+            }
+            transport.call()
+        })
+        .await;
+
+    assert_eq!(42, res?);
+    assert_eq!(3, transport.calls.load(Ordering::SeqCst));
+    assert_eq!(2, reconnects.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_policy_gives_up() -> anyhow::Result<()> {
+    let transport = FlakyTransport::create(5);
+
+    let res = policy(2).run(|_| transport.call()).await;
+
+    assert!(res.is_err());
+    assert_eq!(3, transport.calls.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_policy_no_retry() -> anyhow::Result<()> {
+    // Mutating actions are sent with no retry, to never apply twice.
+    let transport = FlakyTransport::create(1);
+
+    let res = RetryPolicy::no_retry().run(|_| transport.call()).await;
+
+    assert!(res.is_err());
+    assert_eq!(1, transport.calls.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_policy_does_not_retry_service_error() -> anyhow::Result<()> {
+    let calls = AtomicU32::new(0);
+
+    let res: std::result::Result<(), Status> = policy(3)
+        .run(|_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(Status::from(ErrorCode::UnknownTable("t1"))) }
+        })
+        .await;
+
+    assert_eq!(25, ErrorCode::from(res.unwrap_err()).code());
+    assert_eq!(1, calls.load(Ordering::SeqCst));
+
+    Ok(())
+}
@@ -13,6 +13,10 @@
 // limitations under the License.
 
 use common_exception::ErrorCode;
+use common_meta_sled_store::sled;
+use common_meta_sled_store::SledDbOptions;
+use common_meta_sled_store::SledTree;
+use common_meta_sled_store::ENCRYPTION_KEY_SIZE;
 use common_meta_types::NodeId;
 use serde::Deserialize;
 use serde::Serialize;
@@ -22,6 +26,9 @@ pub const KVSRV_API_HOST: &str = "KVSRV_API_HOST";
 pub const KVSRV_API_PORT: &str = "KVSRV_API_PORT";
 pub const KVSRV_RAFT_DIR: &str = "KVSRV_RAFT_DIR";
 pub const KVSRV_NO_SYNC: &str = "KVSRV_NO_SYNC";
+pub const KVSRV_SLED_FLUSH_EVERY_MS: &str = "KVSRV_SLED_FLUSH_EVERY_MS";
+pub const KVSRV_SLED_CACHE_CAPACITY: &str = "KVSRV_SLED_CACHE_CAPACITY";
+pub const KVSRV_SLED_ENCRYPTION_KEY_FILE: &str = "KVSRV_SLED_ENCRYPTION_KEY_FILE";
 pub const KVSRV_SNAPSHOT_LOGS_SINCE_LAST: &str = "KVSRV_SNAPSHOT_LOGS_SINCE_LAST";
 pub const KVSRV_HEARTBEAT_INTERVAL: &str = "KVSRV_HEARTBEAT_INTERVAL";
 pub const KVSRV_INSTALL_SNAPSHOT_TIMEOUT: &str = "KVSRV_INSTALL_SNAPSHOT_TIMEOUT";
@@ -69,6 +76,34 @@ pub struct RaftConfig {
     )]
     pub no_sync: bool,
 
+    #[structopt(
+    long,
+    env = KVSRV_SLED_FLUSH_EVERY_MS,
+    default_value = "100",
+    help = concat!("The interval in milli seconds at which sled flushes the buffered writes to disk in background.",
+    " With --no-sync, it bounds the data lost during a crash to the writes of the last interval.",
+    " A larger value makes writes cheaper but loses more on a crash. 0 disables the background flush.")
+    )]
+    pub sled_flush_every_ms: u64,
+
+    #[structopt(
+        long,
+        env = KVSRV_SLED_CACHE_CAPACITY,
+        default_value = "0",
+        help = "The size in bytes of the sled page cache. 0 means the sled default(1GB)."
+    )]
+    pub sled_cache_capacity: u64,
+
+    #[structopt(
+    long,
+    env = KVSRV_SLED_ENCRYPTION_KEY_FILE,
+    default_value = "",
+    help = concat!("The file of the hex encoded 32-byte key to encrypt the persisted meta with AES-256-GCM.",
+    " Empty disables encryption. Meta persisted before encryption is enabled is encrypted when it is opened.",
+    " The key is never persisted with the meta: losing it loses the meta.")
+    )]
+    pub sled_encryption_key_file: String,
+
     // raft config
     #[structopt(
         long,
@@ -148,6 +183,56 @@ impl RaftConfig {
         !self.no_sync
     }
 
+    /// Returns the options to open the sled::Db with.
+    pub fn sled_db_options(&self) -> SledDbOptions {
+        SledDbOptions {
+            cache_capacity: match self.sled_cache_capacity {
+                0 => None,
+                bytes => Some(bytes),
+            },
+            flush_every_ms: match self.sled_flush_every_ms {
+                0 => None,
+                ms => Some(ms),
+            },
+            use_compression: false,
+        }
+    }
+
+    /// Returns the key to encrypt the persisted meta with, loaded from `sled_encryption_key_file`.
+    /// None if encryption is not enabled.
+    pub fn sled_encryption_key(&self) -> common_exception::Result<Option<Vec<u8>>> {
+        if self.sled_encryption_key_file.is_empty() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&self.sled_encryption_key_file).map_err(|e| {
+            ErrorCode::InvalidConfig(format!(
+                "can not read --sled-encryption-key-file {}: {}",
+                self.sled_encryption_key_file, e
+            ))
+        })?;
+
+        let hex = content.trim();
+        let invalid = || {
+            ErrorCode::InvalidConfig(format!(
+                "--sled-encryption-key-file {} must contain {} hex encoded bytes",
+                self.sled_encryption_key_file, ENCRYPTION_KEY_SIZE
+            ))
+        };
+
+        if hex.len() != ENCRYPTION_KEY_SIZE * 2 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+
+        let mut key = Vec::with_capacity(ENCRYPTION_KEY_SIZE);
+        for i in (0..hex.len()).step_by(2) {
+            let b = u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid())?;
+            key.push(b);
+        }
+
+        Ok(Some(key))
+    }
+
     pub fn check(&self) -> common_exception::Result<()> {
         if self.boot && self.single {
             return Err(ErrorCode::InvalidConfig(
@@ -155,9 +240,29 @@ impl RaftConfig {
             ));
         }
 
+        self.sled_encryption_key()?;
+
         Ok(())
     }
 
+    /// Open a sled tree of the meta, and encrypt it if `sled_encryption_key_file` is set.
+    ///
+    /// The plaintext values persisted before encryption is enabled are encrypted in place.
+    pub fn open_sled_tree(
+        &self,
+        db: &sled::Db,
+        tree_name: &str,
+    ) -> common_exception::Result<SledTree> {
+        let mut tree = SledTree::open(db, tree_name, self.is_sync())?;
+
+        if let Some(key) = self.sled_encryption_key()? {
+            tree.enable_encryption(&key, true)?;
+            tree.encrypt_plaintext_values()?;
+        }
+
+        Ok(tree)
+    }
+
     /// Create a unique sled::Tree name by prepending a unique prefix.
     /// So that multiple instance that depends on a sled::Tree can be used in one process.
     /// sled does not allow to open multiple `sled::Db` in one process.
@@ -0,0 +1,61 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::RaftConfig;
+
+#[test]
+fn test_sled_db_options() -> anyhow::Result<()> {
+    let mut conf = RaftConfig::empty();
+    assert_eq!(100, conf.sled_flush_every_ms);
+    assert_eq!(0, conf.sled_cache_capacity);
+
+    let options = conf.sled_db_options();
+    assert_eq!(Some(100), options.flush_every_ms);
+    assert_eq!(None, options.cache_capacity);
+    assert!(!options.use_compression);
+
+    conf.sled_flush_every_ms = 0;
+    conf.sled_cache_capacity = 64 * 1024 * 1024;
+    let options = conf.sled_db_options();
+    assert_eq!(None, options.flush_every_ms);
+    assert_eq!(Some(64 * 1024 * 1024), options.cache_capacity);
+    Ok(())
+}
+
+#[test]
+fn test_sled_encryption_key() -> anyhow::Result<()> {
+    let mut conf = RaftConfig::empty();
+    assert_eq!(None, conf.sled_encryption_key()?);
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("key");
+    conf.sled_encryption_key_file = path.to_string_lossy().to_string();
+
+    // Missing file
+    assert!(conf.check().is_err());
+
+    std::fs::write(&path, format!("{}\n", "0a".repeat(32)))?;
+    assert_eq!(Some(vec![0x0au8; 32]), conf.sled_encryption_key()?);
+    conf.check()?;
+
+    // Too short
+    std::fs::write(&path, "0a".repeat(16))?;
+    assert!(conf.sled_encryption_key().is_err());
+
+    // Not hex
+    std::fs::write(&path, "zz".repeat(32))?;
+    assert!(conf.sled_encryption_key().is_err());
+
+    Ok(())
+}
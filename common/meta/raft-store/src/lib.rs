@@ -22,5 +22,7 @@ pub mod state_machine;
 #[cfg(test)]
 mod testing;
 
+#[cfg(test)]
+mod config_test;
 #[cfg(test)]
 mod raft_types_test;
@@ -38,7 +38,7 @@ impl RaftLog {
     #[tracing::instrument(level = "info", skip(db))]
     pub async fn open(db: &sled::Db, config: &RaftConfig) -> common_exception::Result<RaftLog> {
         let tree_name = config.tree_name(TREE_RAFT_LOG);
-        let inner = SledTree::open(db, &tree_name, config.is_sync())?;
+        let inner = config.open_sled_tree(db, &tree_name)?;
         let rl = RaftLog { inner };
         Ok(rl)
     }
@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Once;
+
 use async_raft::raft::Entry;
 use common_meta_sled_store::SeqNum;
 use common_meta_sled_store::SledKeySpace;
+use common_meta_sled_store::SledKeySpaceRegistry;
 use common_meta_types::KVValue;
 use common_meta_types::LogEntry;
 use common_meta_types::LogIndex;
@@ -93,3 +96,21 @@ impl SledKeySpace for Sequences {
     type K = String;
     type V = SeqNum;
 }
+
+/// Panics if any two of the key spaces above share a `NAME` or a `PREFIX`.
+/// It runs once, when the meta store is opened.
+pub fn check_key_spaces() {
+    static CHECK: Once = Once::new();
+
+    CHECK.call_once(|| {
+        SledKeySpaceRegistry::new()
+            .register::<Logs>()
+            .register::<Nodes>()
+            .register::<StateMachineMeta>()
+            .register::<RaftStateKV>()
+            .register::<Files>()
+            .register::<GenericKV>()
+            .register::<Sequences>()
+            .assert_unique();
+    });
+}
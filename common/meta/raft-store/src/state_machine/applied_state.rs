@@ -52,6 +52,12 @@ pub enum AppliedState {
         result: Option<Table>,
     },
 
+    /// The tables before and after applying every item of a batch, in the order of the batch.
+    Tables {
+        prev: Vec<Option<Table>>,
+        result: Vec<Option<Table>>,
+    },
+
     KV {
         prev: Option<SeqValue<KVValue>>,
         result: Option<SeqValue<KVValue>>,
@@ -120,6 +126,15 @@ impl From<(Option<Table>, Option<Table>)> for AppliedState {
     }
 }
 
+impl From<(Vec<Option<Table>>, Vec<Option<Table>>)> for AppliedState {
+    fn from(v: (Vec<Option<Table>>, Vec<Option<Table>>)) -> Self {
+        AppliedState::Tables {
+            prev: v.0,
+            result: v.1,
+        }
+    }
+}
+
 impl From<(Option<SeqValue<KVValue>>, Option<SeqValue<KVValue>>)> for AppliedState {
     fn from(v: (Option<SeqValue<KVValue>>, Option<SeqValue<KVValue>>)) -> Self {
         AppliedState::KV {
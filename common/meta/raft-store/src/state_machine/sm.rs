@@ -14,6 +14,7 @@
 
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
@@ -26,6 +27,7 @@ use common_meta_sled_store::get_sled_db;
 use common_meta_sled_store::sled;
 use common_meta_sled_store::AsKeySpace;
 use common_meta_sled_store::SledTree;
+use common_meta_types::BatchMode;
 use common_meta_types::Cmd;
 use common_meta_types::Database;
 use common_meta_types::KVMeta;
@@ -213,7 +215,7 @@ impl StateMachine {
 
         let tree_name = StateMachine::tree_name(config, sm_id);
 
-        let sm_tree = SledTree::open(&db, &tree_name, config.is_sync())?;
+        let sm_tree = config.open_sled_tree(&db, &tree_name)?;
 
         let sm = StateMachine {
             _config: config.clone(),
@@ -257,7 +259,7 @@ impl StateMachine {
     pub fn snapshot(
         &self,
     ) -> common_exception::Result<(
-        impl Iterator<Item = sled::Result<(IVec, IVec)>>,
+        impl Iterator<Item = common_exception::Result<(IVec, IVec)>>,
         LogId,
         MembershipConfig,
         String,
@@ -278,18 +280,18 @@ impl StateMachine {
             last_applied.term, last_applied.index, snapshot_idx
         );
 
-        Ok((self.sm_tree.tree.iter(), last_applied, mem, snapshot_id))
+        Ok((self.sm_tree.export(), last_applied, mem, snapshot_id))
     }
 
     /// Serialize a snapshot for transport.
     /// This step does not require a lock, since sled::Tree::iter() creates a consistent view on a tree
     /// no matter if there are other writes applied to the tree.
     pub fn serialize_snapshot(
-        view: impl Iterator<Item = sled::Result<(IVec, IVec)>>,
+        view: impl Iterator<Item = common_exception::Result<(IVec, IVec)>>,
     ) -> common_exception::Result<Vec<u8>> {
         let mut kvs = Vec::new();
         for rkv in view {
-            let (k, v) = rkv.map_err_to_code(ErrorCode::MetaStoreDamaged, || "taking snapshot")?;
+            let (k, v) = rkv?;
             kvs.push(vec![k.to_vec(), v.to_vec()]);
         }
         let snap = SerializableSnapshot { kvs };
@@ -443,9 +445,22 @@ impl StateMachine {
                 }
             }
 
-            Cmd::DropDatabase { ref name } => {
+            Cmd::DropDatabase {
+                ref name,
+                ref cascade,
+            } => {
+                // - If the database is absent, returns (None, None).
+                // - If the database has tables and it is not a cascade drop, returns (prev, prev) and nothing changes.
+                // - Otherwise the database and all of its tables are removed, returns (prev, None).
                 let prev = self.databases.get(name).cloned();
-                if prev.is_some() {
+                if let Some(db) = prev.as_ref() {
+                    if !db.tables.is_empty() && !*cascade {
+                        return Ok((prev.clone(), prev).into());
+                    }
+
+                    for tbl_id in db.tables.values() {
+                        self.tables.remove(tbl_id);
+                    }
                     self.databases.remove(name);
                     self.incr_seq(SEQ_DATABASE_META_ID).await?;
                     tracing::debug!("applied DropDatabase: {}", name);
@@ -478,6 +493,8 @@ impl StateMachine {
                         table_engine: table.table_engine.clone(),
                         table_options: table.table_options.clone(),
                         parts: table.parts.clone(),
+                        version: 0,
+                        schema_history: Default::default(),
                     };
                     self.incr_seq(SEQ_DATABASE_META_ID).await?;
                     db.tables.insert(table_name.clone(), table.table_id);
@@ -489,15 +506,106 @@ impl StateMachine {
                 }
             }
 
+            Cmd::CreateTables {
+                ref tables,
+                ref mode,
+            } => {
+                if *mode == BatchMode::AbortOnError {
+                    let mut prev = Vec::with_capacity(tables.len());
+                    let mut creating = HashSet::new();
+                    let mut conflict = false;
+
+                    // Check the whole batch before creating any table.
+                    for t in tables.iter() {
+                        let db = self.databases.get(&t.db_name);
+                        let p = db
+                            .and_then(|db| db.tables.get(&t.table_name))
+                            .and_then(|tbl_id| self.tables.get(tbl_id))
+                            .cloned();
+
+                        let duplicated = !creating.insert((&t.db_name, &t.table_name));
+                        if db.is_none() || (!t.if_not_exists && (p.is_some() || duplicated)) {
+                            conflict = true;
+                        }
+                        prev.push(p);
+                    }
+
+                    if conflict {
+                        let result = vec![None; tables.len()];
+                        return Ok((prev, result).into());
+                    }
+                }
+
+                // With ContinueOnError a table that can not be created is skipped:
+                // its result is None and the other tables are still created.
+                let mut prev = Vec::with_capacity(tables.len());
+                let mut result = Vec::with_capacity(tables.len());
+                let mut created = false;
+                for t in tables.iter() {
+                    let mut db = match self.databases.get(&t.db_name) {
+                        Some(db) => db.to_owned(),
+                        None => {
+                            prev.push(None);
+                            result.push(None);
+                            continue;
+                        }
+                    };
+
+                    if let Some(table_id) = db.tables.get(&t.table_name) {
+                        let p = self.tables.get(table_id).cloned();
+                        result.push(if t.if_not_exists { p.clone() } else { None });
+                        prev.push(p);
+                        continue;
+                    }
+
+                    prev.push(None);
+
+                    let table = Table {
+                        table_id: self.incr_seq(SEQ_TABLE_ID).await?,
+                        table_name: t.table_name.to_string(),
+                        database_id: db.database_id,
+                        db_name: t.db_name.to_string(),
+                        schema: t.table.schema.clone(),
+                        table_engine: t.table.table_engine.clone(),
+                        table_options: t.table.table_options.clone(),
+                        parts: t.table.parts.clone(),
+                        version: 0,
+                        schema_history: Default::default(),
+                    };
+                    db.tables.insert(t.table_name.clone(), table.table_id);
+                    self.databases.insert(t.db_name.clone(), db);
+                    self.tables.insert(table.table_id, table.clone());
+                    result.push(Some(table));
+                    created = true;
+                }
+
+                if created {
+                    self.incr_seq(SEQ_DATABASE_META_ID).await?;
+                }
+                tracing::debug!("applied CreateTables: {} tables", tables.len());
+
+                Ok((prev, result).into())
+            }
+
             Cmd::DropTable {
                 ref db_name,
                 ref table_name,
                 if_exists: _,
+                ref expected_version,
             } => {
                 let db = self.databases.get_mut(db_name).unwrap();
                 let tbl_id = db.tables.get(table_name);
                 if let Some(tbl_id) = tbl_id {
                     let tbl_id = tbl_id.to_owned();
+
+                    // - If the version does not match, the table is left unchanged: returns (prev, prev).
+                    if let Some(expected_version) = expected_version {
+                        let prev = self.tables.get(&tbl_id).cloned();
+                        if prev.as_ref().map(|t| t.version) != Some(*expected_version) {
+                            return Ok((prev.clone(), prev).into());
+                        }
+                    }
+
                     db.tables.remove(table_name);
                     let prev = self.tables.remove(&tbl_id);
                     self.incr_seq(SEQ_DATABASE_META_ID).await?;
@@ -508,6 +616,120 @@ impl StateMachine {
                 }
             }
 
+            Cmd::RenameTable {
+                ref db_name,
+                ref table_name,
+                ref new_db_name,
+                ref new_table_name,
+            } => {
+                // - If the table is absent, returns (None, None).
+                // - If the new name is taken or the new db is absent, returns (prev, prev) and nothing changes.
+                let tbl_id = self
+                    .databases
+                    .get(db_name)
+                    .and_then(|db| db.tables.get(table_name))
+                    .cloned();
+
+                let tbl_id = match tbl_id {
+                    Some(tbl_id) => tbl_id,
+                    None => return Ok((None::<Table>, None::<Table>).into()),
+                };
+
+                let prev = self.tables.get(&tbl_id).cloned();
+                let new_db_id = match self.databases.get(new_db_name) {
+                    Some(new_db) if !new_db.tables.contains_key(new_table_name) => {
+                        new_db.database_id
+                    }
+                    _ => return Ok((prev.clone(), prev).into()),
+                };
+
+                if let Some(db) = self.databases.get_mut(db_name) {
+                    db.tables.remove(table_name);
+                }
+                if let Some(new_db) = self.databases.get_mut(new_db_name) {
+                    new_db.tables.insert(new_table_name.clone(), tbl_id);
+                }
+
+                let table = self.tables.get_mut(&tbl_id).map(|table| {
+                    table.table_name = new_table_name.clone();
+                    table.db_name = new_db_name.clone();
+                    table.database_id = new_db_id;
+                    table.clone()
+                });
+                self.incr_seq(SEQ_DATABASE_META_ID).await?;
+                tracing::debug!(
+                    "applied RenameTable: {}-{}=>{}-{}",
+                    db_name,
+                    table_name,
+                    new_db_name,
+                    new_table_name
+                );
+
+                Ok((prev, table).into())
+            }
+
+            Cmd::TruncateTable {
+                ref db_name,
+                ref table_name,
+            } => {
+                let tbl_id = self
+                    .databases
+                    .get(db_name)
+                    .and_then(|db| db.tables.get(table_name))
+                    .cloned();
+
+                let prev = tbl_id.and_then(|tbl_id| self.tables.get(&tbl_id).cloned());
+                let mut table = match prev {
+                    Some(ref prev) => prev.clone(),
+                    None => return Ok((None::<Table>, None::<Table>).into()),
+                };
+
+                table
+                    .schema_history
+                    .insert(table.version, table.schema.clone());
+                table.parts.clear();
+                table.version += 1;
+
+                self.tables.insert(table.table_id, table.clone());
+                self.incr_seq(SEQ_DATABASE_META_ID).await?;
+                tracing::debug!("applied TruncateTable: {}-{}", db_name, table_name);
+
+                Ok((prev, Some(table)).into())
+            }
+
+            Cmd::UpdateTableSchema {
+                ref table_id,
+                ref table_version,
+                ref schema,
+            } => {
+                // - If the table is absent, returns (None, None).
+                // - If the version does not match, returns (prev, prev) and nothing changes.
+                let prev = self.tables.get(table_id).cloned();
+                let mut table = match prev {
+                    None => return Ok((None::<Table>, None::<Table>).into()),
+                    Some(ref prev) if prev.version != *table_version => {
+                        return Ok((Some(prev.clone()), Some(prev.clone())).into());
+                    }
+                    Some(ref prev) => prev.clone(),
+                };
+
+                table
+                    .schema_history
+                    .insert(table.version, table.schema.clone());
+                table.schema = schema.clone();
+                table.version += 1;
+
+                self.tables.insert(*table_id, table.clone());
+                self.incr_seq(SEQ_DATABASE_META_ID).await?;
+                tracing::debug!(
+                    "applied UpdateTableSchema: {}, version:{}",
+                    table_id,
+                    table.version
+                );
+
+                Ok((prev, Some(table)).into())
+            }
+
             Cmd::UpsertKV {
                 ref key,
                 ref seq,
@@ -21,7 +21,9 @@ use async_raft::raft::EntryPayload;
 use async_raft::raft::MembershipConfig;
 use async_raft::LogId;
 use common_base::tokio;
+use common_meta_types::BatchMode;
 use common_meta_types::Cmd;
+use common_meta_types::CreateTableEntry;
 use common_meta_types::Database;
 use common_meta_types::KVMeta;
 use common_meta_types::KVValue;
@@ -31,8 +33,10 @@ use common_meta_types::Node;
 use common_meta_types::Operation;
 use common_meta_types::SeqValue;
 use common_meta_types::Slot;
+use common_meta_types::Table;
 use common_tracing::tracing;
 use maplit::btreeset;
+use maplit::hashset;
 use pretty_assertions::assert_eq;
 
 use crate::init_raft_store_ut;
@@ -284,6 +288,566 @@ async fn test_state_machine_apply_add_database() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_state_machine_apply_rename_table() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_raft_store_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_raft_test_context();
+    let mut m = StateMachine::open(&tc.raft_config, 1).await?;
+
+    for db_name in ["foo", "bar"] {
+        m.apply_cmd(&Cmd::CreateDatabase {
+            name: db_name.to_string(),
+            if_not_exists: false,
+            db: Default::default(),
+        })
+        .await?;
+    }
+
+    for table_name in ["t1", "t3"] {
+        m.apply_cmd(&Cmd::CreateTable {
+            db_name: "foo".to_string(),
+            table_name: table_name.to_string(),
+            if_not_exists: false,
+            table: Default::default(),
+        })
+        .await?;
+    }
+
+    let tbl_id = *m.get_database("foo").unwrap().tables.get("t1").unwrap();
+
+    tracing::info!("--- rename to another database");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::RenameTable {
+                db_name: "foo".to_string(),
+                table_name: "t1".to_string(),
+                new_db_name: "bar".to_string(),
+                new_table_name: "t2".to_string(),
+            })
+            .await?;
+
+        let bar_id = m.get_database("bar").unwrap().database_id;
+        match resp {
+            AppliedState::Table {
+                prev: Some(prev),
+                result: Some(result),
+            } => {
+                assert_eq!("t1", prev.table_name);
+                assert_eq!(
+                    Table {
+                        table_id: tbl_id,
+                        table_name: "t2".to_string(),
+                        database_id: bar_id,
+                        db_name: "bar".to_string(),
+                        ..prev
+                    },
+                    result
+                );
+            }
+            _ => panic!("expect renamed table, got: {:?}", resp),
+        }
+
+        assert!(!m.get_database("foo").unwrap().tables.contains_key("t1"));
+        assert_eq!(
+            Some(&tbl_id),
+            m.get_database("bar").unwrap().tables.get("t2")
+        );
+        assert_eq!("t2", m.get_table(&tbl_id).unwrap().table_name);
+    }
+
+    tracing::info!("--- rename absent table");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::RenameTable {
+                db_name: "foo".to_string(),
+                table_name: "t1".to_string(),
+                new_db_name: "foo".to_string(),
+                new_table_name: "t4".to_string(),
+            })
+            .await?;
+        assert_eq!(
+            AppliedState::Table {
+                prev: None,
+                result: None
+            },
+            resp
+        );
+    }
+
+    tracing::info!("--- rename to an existing table");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::RenameTable {
+                db_name: "bar".to_string(),
+                table_name: "t2".to_string(),
+                new_db_name: "foo".to_string(),
+                new_table_name: "t3".to_string(),
+            })
+            .await?;
+        let prev = m.get_table(&tbl_id);
+        assert_eq!(
+            AppliedState::Table {
+                prev: prev.clone(),
+                result: prev
+            },
+            resp
+        );
+        assert_eq!(
+            Some(&tbl_id),
+            m.get_database("bar").unwrap().tables.get("t2")
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_state_machine_apply_update_table_schema() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_raft_store_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_raft_test_context();
+    let mut m = StateMachine::open(&tc.raft_config, 1).await?;
+
+    m.apply_cmd(&Cmd::CreateDatabase {
+        name: "foo".to_string(),
+        if_not_exists: false,
+        db: Default::default(),
+    })
+    .await?;
+
+    m.apply_cmd(&Cmd::CreateTable {
+        db_name: "foo".to_string(),
+        table_name: "t1".to_string(),
+        if_not_exists: false,
+        table: Table {
+            schema: b"v0".to_vec(),
+            ..Default::default()
+        },
+    })
+    .await?;
+
+    let tbl_id = *m.get_database("foo").unwrap().tables.get("t1").unwrap();
+    assert_eq!(0, m.get_table(&tbl_id).unwrap().version);
+
+    tracing::info!("--- update schema with matched version");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::UpdateTableSchema {
+                table_id: tbl_id,
+                table_version: 0,
+                schema: b"v1".to_vec(),
+            })
+            .await?;
+
+        let table = m.get_table(&tbl_id).unwrap();
+        assert_eq!(1, table.version);
+        assert_eq!(b"v1".to_vec(), table.schema);
+        assert_eq!(Some(&b"v0".to_vec()), table.schema_history.get(&0));
+
+        match resp {
+            AppliedState::Table {
+                prev: Some(prev),
+                result: Some(result),
+            } => {
+                assert_eq!(0, prev.version);
+                assert_eq!(table, result);
+            }
+            _ => panic!("expect updated table, got: {:?}", resp),
+        }
+    }
+
+    tracing::info!("--- update schema with mismatched version");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::UpdateTableSchema {
+                table_id: tbl_id,
+                table_version: 0,
+                schema: b"v2".to_vec(),
+            })
+            .await?;
+
+        let table = m.get_table(&tbl_id);
+        assert_eq!(
+            AppliedState::Table {
+                prev: table.clone(),
+                result: table.clone()
+            },
+            resp
+        );
+        assert_eq!(1, table.unwrap().version);
+    }
+
+    tracing::info!("--- update schema of absent table");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::UpdateTableSchema {
+                table_id: tbl_id + 100,
+                table_version: 0,
+                schema: b"v2".to_vec(),
+            })
+            .await?;
+        assert_eq!(
+            AppliedState::Table {
+                prev: None,
+                result: None
+            },
+            resp
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_state_machine_apply_create_tables() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_raft_store_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_raft_test_context();
+    let mut m = StateMachine::open(&tc.raft_config, 1).await?;
+
+    m.apply_cmd(&Cmd::CreateDatabase {
+        name: "foo".to_string(),
+        if_not_exists: false,
+        db: Default::default(),
+    })
+    .await?;
+
+    let entry = |db: &str, table: &str, if_not_exists: bool| CreateTableEntry {
+        db_name: db.to_string(),
+        table_name: table.to_string(),
+        if_not_exists,
+        table: Default::default(),
+    };
+
+    tracing::info!("--- create tables");
+    let t1 = {
+        let resp = m
+            .apply_cmd(&Cmd::CreateTables {
+                tables: vec![entry("foo", "t1", false), entry("foo", "t2", false)],
+                mode: BatchMode::AbortOnError,
+            })
+            .await?;
+
+        match resp {
+            AppliedState::Tables { prev, result } => {
+                assert_eq!(vec![None, None], prev);
+                assert_eq!(2, result.len());
+                assert_eq!("t1", result[0].as_ref().unwrap().table_name);
+                assert_eq!("t2", result[1].as_ref().unwrap().table_name);
+                result[0].clone().unwrap()
+            }
+            _ => panic!("expect Tables, got: {:?}", resp),
+        }
+    };
+
+    tracing::info!("--- a conflicting table aborts the batch");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::CreateTables {
+                tables: vec![entry("foo", "t3", false), entry("foo", "t1", false)],
+                mode: BatchMode::AbortOnError,
+            })
+            .await?;
+
+        assert_eq!(
+            AppliedState::Tables {
+                prev: vec![None, Some(t1.clone())],
+                result: vec![None, None],
+            },
+            resp
+        );
+        assert!(m.get_database("foo").unwrap().tables.get("t3").is_none());
+    }
+
+    tracing::info!("--- an absent database aborts the batch");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::CreateTables {
+                tables: vec![entry("foo", "t3", false), entry("bar", "t1", false)],
+                mode: BatchMode::AbortOnError,
+            })
+            .await?;
+
+        assert_eq!(
+            AppliedState::Tables {
+                prev: vec![None, None],
+                result: vec![None, None],
+            },
+            resp
+        );
+        assert!(m.get_database("foo").unwrap().tables.get("t3").is_none());
+    }
+
+    tracing::info!("--- an existing table with if_not_exists is kept");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::CreateTables {
+                tables: vec![entry("foo", "t3", false), entry("foo", "t1", true)],
+                mode: BatchMode::AbortOnError,
+            })
+            .await?;
+
+        match resp {
+            AppliedState::Tables { prev, result } => {
+                assert_eq!(vec![None, Some(t1.clone())], prev);
+                assert_eq!("t3", result[0].as_ref().unwrap().table_name);
+                assert_eq!(Some(t1.clone()), result[1]);
+            }
+            _ => panic!("expect Tables, got: {:?}", resp),
+        }
+    }
+
+    tracing::info!("--- continue on error skips the failing tables");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::CreateTables {
+                tables: vec![
+                    entry("foo", "t4", false),
+                    entry("foo", "t1", false),
+                    entry("bar", "t5", false),
+                    entry("foo", "t5", false),
+                ],
+                mode: BatchMode::ContinueOnError,
+            })
+            .await?;
+
+        match resp {
+            AppliedState::Tables { prev, result } => {
+                assert_eq!(vec![None, Some(t1), None, None], prev);
+                assert_eq!("t4", result[0].as_ref().unwrap().table_name);
+                assert_eq!(None, result[1]);
+                assert_eq!(None, result[2]);
+                assert_eq!("t5", result[3].as_ref().unwrap().table_name);
+            }
+            _ => panic!("expect Tables, got: {:?}", resp),
+        }
+        let db = m.get_database("foo").unwrap();
+        assert!(db.tables.get("t4").is_some());
+        assert!(db.tables.get("t5").is_some());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_state_machine_apply_drop_table_with_version() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_raft_store_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_raft_test_context();
+    let mut m = StateMachine::open(&tc.raft_config, 1).await?;
+
+    m.apply_cmd(&Cmd::CreateDatabase {
+        name: "foo".to_string(),
+        if_not_exists: false,
+        db: Default::default(),
+    })
+    .await?;
+
+    m.apply_cmd(&Cmd::CreateTable {
+        db_name: "foo".to_string(),
+        table_name: "t1".to_string(),
+        if_not_exists: false,
+        table: Default::default(),
+    })
+    .await?;
+
+    let tbl_id = *m.get_database("foo").unwrap().tables.get("t1").unwrap();
+    let table = m.get_table(&tbl_id).unwrap();
+
+    tracing::info!("--- drop with a mismatched version");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::DropTable {
+                db_name: "foo".to_string(),
+                table_name: "t1".to_string(),
+                if_exists: false,
+                expected_version: Some(1),
+            })
+            .await?;
+        assert_eq!(
+            AppliedState::Table {
+                prev: Some(table.clone()),
+                result: Some(table.clone()),
+            },
+            resp
+        );
+        assert!(m.get_table(&tbl_id).is_some());
+    }
+
+    tracing::info!("--- drop with the current version");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::DropTable {
+                db_name: "foo".to_string(),
+                table_name: "t1".to_string(),
+                if_exists: false,
+                expected_version: Some(0),
+            })
+            .await?;
+        assert_eq!(
+            AppliedState::Table {
+                prev: Some(table),
+                result: None,
+            },
+            resp
+        );
+        assert!(m.get_table(&tbl_id).is_none());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_state_machine_apply_drop_database_cascade() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_raft_store_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_raft_test_context();
+    let mut m = StateMachine::open(&tc.raft_config, 1).await?;
+
+    m.apply_cmd(&Cmd::CreateDatabase {
+        name: "foo".to_string(),
+        if_not_exists: false,
+        db: Default::default(),
+    })
+    .await?;
+
+    for table_name in ["t1", "t2"] {
+        m.apply_cmd(&Cmd::CreateTable {
+            db_name: "foo".to_string(),
+            table_name: table_name.to_string(),
+            if_not_exists: false,
+            table: Default::default(),
+        })
+        .await?;
+    }
+
+    let db = m.get_database("foo").unwrap();
+    let tbl_ids = db.tables.values().cloned().collect::<Vec<_>>();
+
+    tracing::info!("--- drop without cascade leaves the database unchanged");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::DropDatabase {
+                name: "foo".to_string(),
+                cascade: false,
+            })
+            .await?;
+        assert_eq!(
+            AppliedState::DataBase {
+                prev: Some(db.clone()),
+                result: Some(db.clone()),
+            },
+            resp
+        );
+        assert!(m.get_database("foo").is_some());
+        assert!(tbl_ids.iter().all(|id| m.get_table(id).is_some()));
+    }
+
+    tracing::info!("--- drop with cascade removes the tables too");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::DropDatabase {
+                name: "foo".to_string(),
+                cascade: true,
+            })
+            .await?;
+        assert_eq!(
+            AppliedState::DataBase {
+                prev: Some(db),
+                result: None,
+            },
+            resp
+        );
+        assert!(m.get_database("foo").is_none());
+        assert!(tbl_ids.iter().all(|id| m.get_table(id).is_none()));
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_state_machine_apply_truncate_table() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_raft_store_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_raft_test_context();
+    let mut m = StateMachine::open(&tc.raft_config, 1).await?;
+
+    m.apply_cmd(&Cmd::CreateDatabase {
+        name: "foo".to_string(),
+        if_not_exists: false,
+        db: Default::default(),
+    })
+    .await?;
+
+    m.apply_cmd(&Cmd::CreateTable {
+        db_name: "foo".to_string(),
+        table_name: "t1".to_string(),
+        if_not_exists: false,
+        table: Table {
+            schema: b"schema".to_vec(),
+            parts: hashset! {"part-1".to_string(), "part-2".to_string()},
+            ..Default::default()
+        },
+    })
+    .await?;
+
+    let tbl_id = *m.get_database("foo").unwrap().tables.get("t1").unwrap();
+    assert_eq!(2, m.get_table(&tbl_id).unwrap().parts.len());
+
+    tracing::info!("--- truncate table");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::TruncateTable {
+                db_name: "foo".to_string(),
+                table_name: "t1".to_string(),
+            })
+            .await?;
+
+        let table = m.get_table(&tbl_id).unwrap();
+        assert!(table.parts.is_empty());
+        assert_eq!(b"schema".to_vec(), table.schema);
+        assert_eq!(tbl_id, table.table_id);
+        assert_eq!(1, table.version);
+
+        match resp {
+            AppliedState::Table {
+                prev: Some(prev),
+                result: Some(result),
+            } => {
+                assert_eq!(2, prev.parts.len());
+                assert_eq!(table, result);
+            }
+            _ => panic!("expect truncated table, got: {:?}", resp),
+        }
+    }
+
+    tracing::info!("--- truncate absent table");
+    {
+        let resp = m
+            .apply_cmd(&Cmd::TruncateTable {
+                db_name: "foo".to_string(),
+                table_name: "t2".to_string(),
+            })
+            .await?;
+        assert_eq!(
+            AppliedState::Table {
+                prev: None,
+                result: None
+            },
+            resp
+        );
+    }
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_state_machine_apply_non_dup_generic_kv_upsert_get() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_raft_store_ut!();
@@ -741,3 +1305,54 @@ async fn test_state_machine_snapshot() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_state_machine_encrypted_snapshot() -> anyhow::Result<()> {
+    // - Take a snapshot of an encrypted state machine.
+    // - Install it into the next state machine, whose key is derived from another tree name.
+    // - Both read the same plaintext, but neither stores it.
+
+    let (_log_guards, ut_span) = init_raft_store_ut!();
+    let _ent = ut_span.enter();
+
+    let mut tc = new_raft_test_context();
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("key");
+    std::fs::write(&path, "0a".repeat(32))?;
+    tc.raft_config.sled_encryption_key_file = path.to_string_lossy().to_string();
+
+    let mut sm = StateMachine::open(&tc.raft_config, 0).await?;
+
+    let (logs, want) = snapshot_logs();
+    for l in logs.iter() {
+        sm.apply(l).await?;
+    }
+
+    let (it, _last, _mem, _id) = sm.snapshot()?;
+    let data = StateMachine::serialize_snapshot(it)?;
+    let snap: SerializableSnapshot = serde_json::from_slice(&data)?;
+    assert_eq!(want, pretty_snapshot(&snap.kvs));
+
+    let new_sm = StateMachine::open(&tc.raft_config, 1).await?;
+    new_sm.sm_tree.import(snap.kvs.into_iter().map(|mut x| {
+        let v = x.pop().unwrap();
+        let k = x.pop().unwrap();
+        (k, v)
+    }))?;
+
+    let (it, _last, _mem, _id) = new_sm.snapshot()?;
+    assert_eq!(want, pretty_snapshot_iter(it));
+    assert_eq!(sm.get_last_applied()?, new_sm.get_last_applied()?);
+
+    for m in [&sm, &new_sm] {
+        let exported = m.sm_tree.export().collect::<Result<Vec<_>, _>>()?;
+        let stored = m.sm_tree.tree.iter().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(exported.len(), stored.len());
+        for ((k1, plain), (k2, sealed)) in exported.iter().zip(stored.iter()) {
+            assert_eq!(k1, k2);
+            assert_ne!(plain, sealed);
+        }
+    }
+
+    Ok(())
+}
@@ -128,7 +128,9 @@ pub fn pretty_snapshot(snap: &[SnapshotKeyValue]) -> Vec<String> {
     res
 }
 
-pub fn pretty_snapshot_iter(snap: impl Iterator<Item = sled::Result<(IVec, IVec)>>) -> Vec<String> {
+pub fn pretty_snapshot_iter(
+    snap: impl Iterator<Item = common_exception::Result<(IVec, IVec)>>,
+) -> Vec<String> {
     let mut res = vec![];
     for kv in snap {
         let (k, v) = kv.unwrap();
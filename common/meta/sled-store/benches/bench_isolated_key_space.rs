@@ -0,0 +1,108 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_meta_sled_store::get_sled_db;
+use common_meta_sled_store::init_temp_sled_db;
+use common_meta_sled_store::IsolatedKeySpace;
+use common_meta_sled_store::SledKeySpace;
+use common_meta_sled_store::SledTree;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+
+/// A key space written by many tasks at once.
+struct Hot {}
+impl SledKeySpace for Hot {
+    const PREFIX: u8 = 1;
+    const NAME: &'static str = "hot";
+    type K = String;
+    type V = String;
+}
+
+/// A key space scanned while `Hot` is written.
+struct Cold {}
+impl SledKeySpace for Cold {
+    const PREFIX: u8 = 2;
+    const NAME: &'static str = "cold";
+    type K = String;
+    type V = String;
+}
+
+const WRITERS: usize = 4;
+const READERS: usize = 4;
+const KEYS: usize = 100;
+
+async fn write_hot_scan_cold(tree: &SledTree) {
+    let mut handles = vec![];
+
+    for w in 0..WRITERS {
+        let tree = tree.clone();
+        handles.push(tokio::spawn(async move {
+            for i in 0..KEYS {
+                let k = format!("{}-{}", w, i);
+                tree.insert::<Hot>(&k, &k).await.unwrap();
+            }
+        }));
+    }
+
+    for _ in 0..READERS {
+        let tree = tree.clone();
+        handles.push(tokio::spawn(async move {
+            for _ in 0..KEYS / 10 {
+                tree.range_values::<Cold, _>(..).unwrap();
+            }
+        }));
+    }
+
+    for h in handles {
+        h.await.unwrap();
+    }
+}
+
+// Compare a hot key space sharing the tree with the other key spaces, and isolated in its own tree.
+fn criterion_benchmark_isolated_key_space(c: &mut Criterion) {
+    init_temp_sled_db(tempfile::tempdir().unwrap());
+    let db = get_sled_db();
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(WRITERS + READERS)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    for (name, isolate) in [
+        ("shared hot key space", false),
+        ("isolated hot key space", true),
+    ] {
+        let isolated = match isolate {
+            true => vec![IsolatedKeySpace::of::<Hot>()],
+            false => vec![],
+        };
+        let tree =
+            SledTree::open_isolated(&db, format!("bench-{}", isolate), false, &isolated).unwrap();
+
+        rt.block_on(async {
+            for i in 0..1000 {
+                let k = format!("{:04}", i);
+                tree.insert::<Cold>(&k, &k).await.unwrap();
+            }
+        });
+
+        c.bench_function(name, |b| b.iter(|| rt.block_on(write_hot_scan_cold(&tree))));
+    }
+}
+
+criterion_group!(benches, criterion_benchmark_isolated_key_space);
+criterion_main!(benches);
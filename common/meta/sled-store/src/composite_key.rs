@@ -0,0 +1,80 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use byteorder::BigEndian;
+use byteorder::ByteOrder;
+use common_exception::ErrorCode;
+use serde::Deserialize;
+use serde::Serialize;
+use sled::IVec;
+
+use crate::SledOrderedSerde;
+
+/// A key of two u64 components, e.g. `(db_id, table_id)`.
+///
+/// It is serialized as the two components in BigEndian, one after another, 16 bytes in total.
+/// Since both components are of fixed width, the serialized keys sort the same as the tuples:
+/// first by the first component, then by the second. E.g. `(1, 256)` sorts before `(2, 0)`.
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct CompositeKey(pub u64, pub u64);
+
+impl CompositeKey {
+    /// The range of all keys with the first component equal to `first`, e.g. all tables of a db.
+    pub fn prefix_range(first: u64) -> RangeInclusive<CompositeKey> {
+        CompositeKey(first, 0)..=CompositeKey(first, u64::MAX)
+    }
+}
+
+impl fmt::Display for CompositeKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.0, self.1)
+    }
+}
+
+impl From<(u64, u64)> for CompositeKey {
+    fn from(t: (u64, u64)) -> Self {
+        CompositeKey(t.0, t.1)
+    }
+}
+
+impl SledOrderedSerde for CompositeKey {
+    fn ser(&self) -> Result<IVec, ErrorCode> {
+        let mut buf = vec![0; 16];
+
+        BigEndian::write_u64(&mut buf[..8], self.0);
+        BigEndian::write_u64(&mut buf[8..], self.1);
+        Ok(buf.into())
+    }
+
+    fn de<V: AsRef<[u8]>>(v: V) -> Result<Self, ErrorCode>
+    where Self: Sized {
+        let b = v.as_ref();
+        if b.len() != 16 {
+            return Err(ErrorCode::MetaStoreDamaged(format!(
+                "invalid composite key length: {}, expect: 16",
+                b.len()
+            )));
+        }
+
+        Ok(CompositeKey(
+            BigEndian::read_u64(&b[..8]),
+            BigEndian::read_u64(&b[8..]),
+        ))
+    }
+}
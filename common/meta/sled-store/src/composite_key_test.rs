@@ -0,0 +1,71 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::CompositeKey;
+use crate::SledOrderedSerde;
+
+#[test]
+fn test_composite_key_ser_de() -> anyhow::Result<()> {
+    let k = CompositeKey(1, 256);
+
+    let b = k.ser()?;
+    assert_eq!(
+        &[0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0],
+        b.as_ref()
+    );
+    assert_eq!(k, CompositeKey::de(&b)?);
+
+    // A key of wrong length is an error instead of a panic.
+    assert!(CompositeKey::de(&[1, 2, 3]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_composite_key_order() -> anyhow::Result<()> {
+    // Sorted as tuples, including across the component boundaries.
+    let keys = vec![
+        CompositeKey(0, u64::MAX),
+        CompositeKey(1, 0),
+        CompositeKey(1, 255),
+        CompositeKey(1, 256),
+        CompositeKey(2, 0),
+        CompositeKey(256, 1),
+        CompositeKey(u64::MAX, 0),
+    ];
+
+    for w in keys.windows(2) {
+        assert!(w[0] < w[1]);
+        assert!(
+            w[0].ser()? < w[1].ser()?,
+            "{} should sort before {} after serialized",
+            w[0],
+            w[1]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_composite_key_prefix_range() -> anyhow::Result<()> {
+    let r = CompositeKey::prefix_range(1);
+
+    assert!(!r.contains(&CompositeKey(0, u64::MAX)));
+    assert!(r.contains(&CompositeKey(1, 0)));
+    assert!(r.contains(&CompositeKey(1, u64::MAX)));
+    assert!(!r.contains(&CompositeKey(2, 0)));
+
+    Ok(())
+}
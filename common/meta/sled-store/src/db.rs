@@ -22,11 +22,54 @@ use std::sync::Mutex;
 use lazy_static::lazy_static;
 use tempfile::TempDir;
 
+/// The options to open the global sled::Db with.
+/// They are db wide: every SledTree opened in the db shares them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SledDbOptions {
+    /// The size in bytes of the page cache, None to use the sled default(1GB).
+    pub cache_capacity: Option<u64>,
+
+    /// Buffered writes are flushed every this many milli seconds in background,
+    /// None to flush only when explicitly asked.
+    pub flush_every_ms: Option<u64>,
+
+    /// Whether to compress the data with zstd.
+    /// sled is built with the `compression` feature for it.
+    pub use_compression: bool,
+}
+
+impl Default for SledDbOptions {
+    /// The sled defaults.
+    fn default() -> Self {
+        SledDbOptions {
+            cache_capacity: None,
+            flush_every_ms: Some(500),
+            use_compression: false,
+        }
+    }
+}
+
+impl SledDbOptions {
+    pub(crate) fn open(&self, path: String) -> sled::Db {
+        let mut config = sled::Config::default()
+            .path(path)
+            .flush_every_ms(self.flush_every_ms)
+            .use_compression(self.use_compression);
+
+        if let Some(cache_capacity) = self.cache_capacity {
+            config = config.cache_capacity(cache_capacity);
+        }
+
+        config.open().expect("open global sled::Db")
+    }
+}
+
 pub(crate) struct GlobalSledDb {
     /// When opening a db on a temp dir, the temp dir guard must be held.
     #[allow(dead_code)]
     pub(crate) temp_dir: Option<TempDir>,
     pub(crate) db: sled::Db,
+    pub(crate) options: SledDbOptions,
 }
 
 lazy_static! {
@@ -43,13 +86,17 @@ pub fn init_temp_sled_db(temp_dir: TempDir) {
 
     let path = temp_dir.path().to_str().unwrap().to_string();
 
+    let options = SledDbOptions::default();
     *g = Some(GlobalSledDb {
         temp_dir: Some(temp_dir),
-        db: sled::open(path).expect("open global sled::Db"),
+        db: options.open(path),
+        options,
     });
 }
 
-pub fn init_sled_db(path: String) {
+/// Open the db at `path` with `options`.
+/// It is a no-op if the db is already opened, the options of the first call win.
+pub fn init_sled_db(path: String, options: SledDbOptions) {
     let mut g = GLOBAL_SLED.as_ref().lock().unwrap();
 
     if g.is_some() {
@@ -58,7 +105,8 @@ pub fn init_sled_db(path: String) {
 
     *g = Some(GlobalSledDb {
         temp_dir: None,
-        db: sled::open(path).expect("open global sled::Db"),
+        db: options.open(path),
+        options,
     });
 }
 
@@ -74,3 +122,9 @@ pub fn get_sled_db() -> sled::Db {
 
     panic!("init_sled_db() or init_temp_sled_db() has to be called before using get_sled_db()");
 }
+
+/// Returns the options the global sled::Db is opened with, None if it is not opened yet.
+pub fn get_sled_db_options() -> Option<SledDbOptions> {
+    let guard = GLOBAL_SLED.as_ref().lock().unwrap();
+    guard.as_ref().map(|g| g.options.clone())
+}
@@ -0,0 +1,278 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export every tree of a sled::Db into a single stream, and restore a db from it.
+//!
+//! The format is self-describing, every record starts with a one byte tag:
+//!
+//! ```text
+//! header:   MAGIC, version: u32
+//! tree:     'T', name_len: u32, name
+//! kv:       'K', key_len: u32, key, value_len: u32, value
+//! end:      'E'
+//! ```
+//!
+//! All integers are BigEndian. The kv records following a tree record belong to that tree.
+
+use std::io::Read;
+use std::io::Write;
+
+use byteorder::BigEndian;
+use byteorder::ReadBytesExt;
+use byteorder::WriteBytesExt;
+use common_exception::ErrorCode;
+use common_exception::ToErrorCode;
+use common_tracing::tracing;
+
+const MAGIC: &[u8; 8] = b"SLEDDUMP";
+const VERSION: u32 = 1;
+
+const TAG_TREE: u8 = b'T';
+const TAG_KV: u8 = b'K';
+const TAG_END: u8 = b'E';
+
+/// The most bytes of a record allocated before they are read.
+const MAX_PREALLOCATED_BYTES: usize = 64 * 1024;
+
+/// The bytes of the kvs written to a tree in one batch when importing.
+const IMPORT_BATCH_BYTES: usize = 4 * 1024 * 1024;
+
+/// Write every tree of `db`, including the default one, to `w`.
+///
+/// Trees are written in the order of their names and the kvs in a tree in key order,
+/// thus exporting the same data always produces the same bytes.
+pub fn export_db(db: &sled::Db, w: &mut impl Write) -> common_exception::Result<()> {
+    w.write_all(MAGIC)?;
+    w.write_u32::<BigEndian>(VERSION)?;
+
+    let mut names = db.tree_names();
+    names.sort();
+
+    for name in names.iter() {
+        let tree = db
+            .open_tree(name)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("export_db: open tree: {:?}", name)
+            })?;
+
+        w.write_u8(TAG_TREE)?;
+        write_bytes(w, name)?;
+
+        for item in tree.iter() {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("export_db: read tree: {:?}", name)
+            })?;
+
+            w.write_u8(TAG_KV)?;
+            write_bytes(w, &k)?;
+            write_bytes(w, &v)?;
+        }
+    }
+
+    w.write_u8(TAG_END)?;
+    w.flush()?;
+
+    Ok(())
+}
+
+/// Restore the trees exported by `export_db` into `db`.
+///
+/// It is meant to restore into a fresh db: a tree that already has data is an error.
+/// The kvs of a tree are written in batches of about `IMPORT_BATCH_BYTES`, so the memory used does not
+/// grow with the size of a tree.
+/// If the import fails, the trees it has written are emptied again, and the trees it has created are dropped.
+pub fn import_db(db: &sled::Db, r: &mut impl Read) -> common_exception::Result<()> {
+    import_db_in_batches(db, r, IMPORT_BATCH_BYTES)
+}
+
+pub(crate) fn import_db_in_batches(
+    db: &sled::Db,
+    r: &mut impl Read,
+    batch_bytes: usize,
+) -> common_exception::Result<()> {
+    let existing = db.tree_names();
+    let mut imported = vec![];
+
+    let res = import_trees(db, r, batch_bytes, &mut imported);
+    if res.is_err() {
+        undo_import(db, &existing, &imported);
+    }
+    res
+}
+
+fn import_trees(
+    db: &sled::Db,
+    r: &mut impl Read,
+    batch_bytes: usize,
+    imported: &mut Vec<sled::Tree>,
+) -> common_exception::Result<()> {
+    let mut magic = [0u8; 8];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(ErrorCode::MetaStoreDamaged("import_db: not a sled db dump"));
+    }
+
+    let version = r.read_u32::<BigEndian>()?;
+    if version != VERSION {
+        return Err(ErrorCode::MetaStoreDamaged(format!(
+            "import_db: unsupported dump version: {}, expect: {}",
+            version, VERSION
+        )));
+    }
+
+    let mut current: Option<TreeImport> = None;
+
+    loop {
+        let tag = r.read_u8()?;
+        match tag {
+            TAG_TREE => {
+                if let Some(mut tree_import) = current.take() {
+                    tree_import.apply()?;
+                }
+
+                let name = read_bytes(r)?;
+                let tree = db
+                    .open_tree(&name)
+                    .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                        format!("import_db: open tree: {:?}", name)
+                    })?;
+
+                if !tree.is_empty() {
+                    return Err(ErrorCode::MetaStoreAlreadyExists(format!(
+                        "import_db: tree {:?} is not empty",
+                        String::from_utf8_lossy(&name)
+                    )));
+                }
+
+                imported.push(tree.clone());
+                current = Some(TreeImport::create(tree, batch_bytes));
+            }
+            TAG_KV => {
+                let k = read_bytes(r)?;
+                let v = read_bytes(r)?;
+
+                match current.as_mut() {
+                    None => {
+                        return Err(ErrorCode::MetaStoreDamaged(
+                            "import_db: kv record before any tree record",
+                        ));
+                    }
+                    Some(tree_import) => tree_import.insert(k, v)?,
+                }
+            }
+            TAG_END => {
+                if let Some(mut tree_import) = current.take() {
+                    tree_import.apply()?;
+                }
+                break;
+            }
+            _ => {
+                return Err(ErrorCode::MetaStoreDamaged(format!(
+                    "import_db: unknown record tag: {}",
+                    tag
+                )));
+            }
+        }
+    }
+
+    db.flush()
+        .map_err_to_code(ErrorCode::MetaStoreDamaged, || "import_db: flush")?;
+
+    Ok(())
+}
+
+/// Empty the trees written by a failed import, and drop the ones that did not exist before it.
+/// They were all empty before the import.
+fn undo_import(db: &sled::Db, existing: &[sled::IVec], imported: &[sled::Tree]) {
+    for tree in imported {
+        let name = tree.name();
+        let res = match existing.contains(&name) {
+            true => tree.clear(),
+            false => db.drop_tree(&name).map(|_| ()),
+        };
+
+        if let Err(e) = res {
+            tracing::warn!(
+                "import_db: failed to undo the import of tree {:?}: {}",
+                String::from_utf8_lossy(&name),
+                e
+            );
+        }
+    }
+}
+
+/// The kvs of a tree being imported, written in batches of about `batch_bytes`.
+struct TreeImport {
+    tree: sled::Tree,
+    batch: sled::Batch,
+    batch_bytes: usize,
+    max_batch_bytes: usize,
+}
+
+impl TreeImport {
+    fn create(tree: sled::Tree, max_batch_bytes: usize) -> Self {
+        TreeImport {
+            tree,
+            batch: sled::Batch::default(),
+            batch_bytes: 0,
+            max_batch_bytes,
+        }
+    }
+
+    fn insert(&mut self, k: Vec<u8>, v: Vec<u8>) -> common_exception::Result<()> {
+        self.batch_bytes += k.len() + v.len();
+        self.batch.insert(k, v);
+
+        if self.batch_bytes >= self.max_batch_bytes {
+            self.apply()?;
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self) -> common_exception::Result<()> {
+        let batch = std::mem::take(&mut self.batch);
+        self.batch_bytes = 0;
+
+        self.tree
+            .apply_batch(batch)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("import_db: write tree: {:?}", self.tree.name())
+            })
+    }
+}
+
+fn write_bytes(w: &mut impl Write, b: &[u8]) -> common_exception::Result<()> {
+    let len = u32::try_from(b.len()).map_err(|_| {
+        ErrorCode::MetaStoreDamaged(format!("export_db: {} bytes is too large", b.len()))
+    })?;
+    w.write_u32::<BigEndian>(len)?;
+    w.write_all(b)?;
+    Ok(())
+}
+
+/// The length is not trusted: the buffer grows with the bytes actually read,
+/// so a damaged length fails as a truncated dump instead of allocating up to 4GB.
+fn read_bytes(r: &mut impl Read) -> common_exception::Result<Vec<u8>> {
+    let len = r.read_u32::<BigEndian>()? as usize;
+    let mut buf = Vec::with_capacity(len.min(MAX_PREALLOCATED_BYTES));
+    r.take(len as u64).read_to_end(&mut buf)?;
+    if buf.len() != len {
+        return Err(ErrorCode::MetaStoreDamaged(format!(
+            "import_db: truncated record, expect {} bytes, but got {}",
+            len,
+            buf.len()
+        )));
+    }
+    Ok(buf)
+}
@@ -0,0 +1,162 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::db_export::import_db_in_batches;
+use crate::export_db;
+use crate::import_db;
+
+/// Open a fresh temp db. Only sync operations are used on it, see the notes in `db.rs`.
+fn temp_db() -> anyhow::Result<sled::Db> {
+    let db = sled::Config::new().temporary(true).open()?;
+    Ok(db)
+}
+
+/// Collect all trees and their kvs, sorted by tree name.
+fn dump(db: &sled::Db) -> anyhow::Result<Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>> {
+    let mut names = db.tree_names();
+    names.sort();
+
+    let mut res = vec![];
+    for name in names {
+        let tree = db.open_tree(&name)?;
+        let mut kvs = vec![];
+        for item in tree.iter() {
+            let (k, v) = item?;
+            kvs.push((k.to_vec(), v.to_vec()));
+        }
+        res.push((name.to_vec(), kvs));
+    }
+    Ok(res)
+}
+
+#[test]
+fn test_export_import_db() -> anyhow::Result<()> {
+    let src = temp_db()?;
+
+    src.insert("default-key", "default-value")?;
+
+    let t1 = src.open_tree("t1")?;
+    t1.insert(&[1u8, 2], "a")?;
+    t1.insert(&[1u8], vec![0u8, 255])?;
+
+    let t2 = src.open_tree("t2")?;
+    t2.insert("x", "")?;
+
+    // An empty tree is restored too.
+    src.open_tree("t3")?;
+
+    let mut buf = vec![];
+    export_db(&src, &mut buf)?;
+
+    let dst = temp_db()?;
+    import_db(&dst, &mut buf.as_slice())?;
+
+    assert_eq!(dump(&src)?, dump(&dst)?);
+    assert!(dump(&dst)?.iter().any(|(name, _)| name == b"t3"));
+
+    // Exporting the restored db produces the same bytes.
+    let mut buf2 = vec![];
+    export_db(&dst, &mut buf2)?;
+    assert_eq!(buf, buf2);
+
+    Ok(())
+}
+
+#[test]
+fn test_import_db_in_batches() -> anyhow::Result<()> {
+    let src = temp_db()?;
+    let t1 = src.open_tree("t1")?;
+    for i in 0..100u32 {
+        t1.insert(i.to_be_bytes(), "v")?;
+    }
+
+    let mut buf = vec![];
+    export_db(&src, &mut buf)?;
+
+    // Every few kvs are written in a batch.
+    let dst = temp_db()?;
+    import_db_in_batches(&dst, &mut buf.as_slice(), 16)?;
+    assert_eq!(dump(&src)?, dump(&dst)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_import_db_errors() -> anyhow::Result<()> {
+    let src = temp_db()?;
+    src.open_tree("t1")?.insert("k", "v")?;
+
+    let mut buf = vec![];
+    export_db(&src, &mut buf)?;
+
+    // Not a dump.
+    {
+        let dst = temp_db()?;
+        let res = import_db(&dst, &mut &b"not a dump at all"[..]);
+        assert!(res.is_err());
+    }
+
+    // Truncated.
+    {
+        let dst = temp_db()?;
+        let res = import_db(&dst, &mut &buf[..buf.len() - 2]);
+        assert!(res.is_err());
+    }
+
+    // A damaged length larger than the rest of the dump.
+    {
+        let dst = temp_db()?;
+        let mut damaged = b"SLEDDUMP".to_vec();
+        damaged.extend_from_slice(&1u32.to_be_bytes());
+        damaged.push(b'T');
+        damaged.extend_from_slice(&u32::MAX.to_be_bytes());
+        damaged.extend_from_slice(b"t1");
+        let err = import_db(&dst, &mut damaged.as_slice()).unwrap_err();
+        assert!(err.message().contains("truncated"), "{}", err.message());
+    }
+
+    // Not a fresh db, the tree with data is left as it is.
+    {
+        let dst = temp_db()?;
+        dst.open_tree("t1")?.insert("k2", "v2")?;
+        let res = import_db(&dst, &mut buf.as_slice());
+        let err = res.unwrap_err();
+        assert!(err.message().contains("is not empty"), "{}", err.message());
+        assert_eq!(1, dst.open_tree("t1")?.len());
+    }
+
+    // A failed import is undone: the written trees are emptied, the created trees are dropped.
+    {
+        let src = temp_db()?;
+        src.insert("default-key", "default-value")?;
+        src.open_tree("t1")?.insert("k", "v")?;
+        src.open_tree("t2")?.insert("k", "v")?;
+
+        let mut buf = vec![];
+        export_db(&src, &mut buf)?;
+
+        let dst = temp_db()?;
+        let mut names = dst.tree_names();
+        let res = import_db_in_batches(&dst, &mut &buf[..buf.len() - 2], 1);
+        assert!(res.is_err());
+
+        let mut got = dst.tree_names();
+        got.sort();
+        names.sort();
+        assert_eq!(names, got);
+        assert!(dst.is_empty());
+    }
+
+    Ok(())
+}
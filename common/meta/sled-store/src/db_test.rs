@@ -0,0 +1,34 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::SledDbOptions;
+
+#[test]
+fn test_sled_db_options_use_compression() -> anyhow::Result<()> {
+    // Only sync operations are used on this extra db, see the notes in `db.rs`.
+    let dir = tempfile::tempdir()?;
+    let options = SledDbOptions {
+        use_compression: true,
+        ..Default::default()
+    };
+    let path = dir.path().to_str().unwrap().to_string();
+    let db = options.open(path);
+
+    let tree = db.open_tree("t")?;
+    tree.insert("a", vec![1u8; 1024])?;
+    tree.flush()?;
+    assert_eq!(Some(vec![1u8; 1024].into()), tree.get("a")?);
+
+    Ok(())
+}
@@ -15,30 +15,73 @@
 //! sled_store implement a key-value like store backed by sled::Tree.
 //!
 //! It is used by raft for log and state machine storage.
+pub use composite_key::CompositeKey;
 pub use db::get_sled_db;
+pub use db::get_sled_db_options;
 pub use db::init_sled_db;
 pub use db::init_temp_sled_db;
+pub use db::SledDbOptions;
+pub use db_export::export_db;
+pub use db_export::import_db;
 pub use kv::KVMeta;
 pub use kv::KVValue;
 pub use seq_num::SeqNum;
 pub use seq_value::SeqValue;
 pub use sled;
+pub use sled_cipher::ENCRYPTION_KEY_SIZE;
+pub use sled_format::VALUE_FORMAT_VERSION;
 pub use sled_key_space::SledKeySpace;
+pub use sled_key_space_registry::SledKeySpaceRegistry;
+pub use sled_metrics::METRIC_SLED_TREE_APPEND;
+pub use sled_metrics::METRIC_SLED_TREE_FLUSH;
+pub use sled_metrics::METRIC_SLED_TREE_FLUSH_DURATION;
+pub use sled_metrics::METRIC_SLED_TREE_GET;
+pub use sled_metrics::METRIC_SLED_TREE_INSERT;
+pub use sled_metrics::METRIC_SLED_TREE_RANGE_REMOVE;
+pub use sled_metrics::METRIC_SLED_TREE_RANGE_SCAN;
+pub use sled_metrics::METRIC_SLED_TREE_REMOVE;
+pub use sled_range_delete::MultiRangeDelete;
 pub use sled_serde::SledOrderedSerde;
 pub use sled_serde::SledRangeSerde;
 pub use sled_serde::SledSerde;
 pub use sled_tree::AsKeySpace;
+pub use sled_tree::IsolatedKeySpace;
 pub use sled_tree::SledTree;
 pub use sled_tree::SledValueToKey;
+pub use sled_tree::ALLOW_UNPREFIXED_TREE_NAME_ENV;
+pub use sled_tree::DEFAULT_APPEND_CHUNK_SIZE;
+pub use sled_tree::DEFAULT_STREAM_YIELD_INTERVAL;
+pub use sled_tree::TEST_TREE_NAME_PREFIX;
 
+mod composite_key;
 mod db;
+mod db_export;
 mod kv;
 mod seq_num;
 mod seq_value;
+mod sled_checksum;
+mod sled_cipher;
+mod sled_flusher;
+mod sled_format;
 mod sled_key_space;
+mod sled_key_space_registry;
+mod sled_metrics;
+mod sled_range_delete;
+mod sled_read_cache;
 mod sled_serde;
 mod sled_tree;
+mod sled_ttl;
 
+#[cfg(test)]
+mod composite_key_test;
+#[cfg(test)]
+mod db_export_test;
+#[cfg(test)]
+mod db_test;
+#[cfg(test)]
+mod sled_key_space_registry_test;
+#[cfg(test)]
+mod sled_serde_test;
 #[cfg(test)]
 mod sled_tree_test;
 #[cfg(test)]
@@ -0,0 +1,119 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use sled::IVec;
+
+use crate::sled_format::split_format_version;
+use crate::sled_format::with_format_version;
+use crate::SledKeySpace;
+
+/// The first byte of a checksummed value.
+/// A value serialized by `SledSerde` is json and never starts with it.
+const CHECKSUM_MARKER: u8 = 0xFF;
+
+/// The size of the marker and the CRC32 in front of a checksummed value.
+const CHECKSUM_HEADER_SIZE: usize = 5;
+
+/// Optional CRC32 checksum of the values of a SledTree, to detect the values damaged on disk.
+///
+/// A checksummed value is `CHECKSUM_MARKER`, then the big-endian CRC32 of the serialized value, then the serialized value.
+/// A checksummed value is always verified when read, whether or not checksum is enabled for writing.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ValueChecksum {
+    /// Whether to checksum the values written.
+    pub(crate) enabled: bool,
+    /// Whether to accept a value without checksum when checksum is enabled,
+    /// i.e., a value written before checksum is enabled.
+    pub(crate) accept_unchecked: bool,
+}
+
+impl ValueChecksum {
+    /// Serialize a value with the format version, and prepend its checksum if enabled.
+    pub(crate) fn serialize_value<KV: SledKeySpace>(&self, v: &KV::V) -> Result<IVec, ErrorCode> {
+        let payload = with_format_version(&KV::serialize_value(v)?);
+        if !self.enabled {
+            return Ok(payload.into());
+        }
+
+        let mut buf = Vec::with_capacity(CHECKSUM_HEADER_SIZE + payload.len());
+        buf.push(CHECKSUM_MARKER);
+        buf.extend_from_slice(&crc32fast::hash(&payload).to_be_bytes());
+        buf.extend_from_slice(&payload);
+        Ok(buf.into())
+    }
+
+    /// Verify the checksum of a value if it has one, check its format version, and deserialize it.
+    /// The expiry in front of the value must be already split and the value decrypted.
+    /// `tree` and the serialized key `k` are only used to tell which value is damaged.
+    pub(crate) fn deserialize_value<KV: SledKeySpace>(
+        &self,
+        tree: &str,
+        k: &[u8],
+        v: &[u8],
+    ) -> Result<KV::V, ErrorCode> {
+        if v.first() != Some(&CHECKSUM_MARKER) {
+            if self.enabled && !self.accept_unchecked {
+                return Err(ErrorCode::MetaStoreDamaged(format!(
+                    "value without checksum, tree: {}, key: {}",
+                    tree,
+                    Self::key_name::<KV>(k)
+                )));
+            }
+            return Self::deserialize_versioned::<KV>(tree, k, v);
+        }
+
+        if v.len() < CHECKSUM_HEADER_SIZE {
+            return Err(ErrorCode::MetaStoreDamaged(format!(
+                "truncated checksum header, tree: {}, key: {}",
+                tree,
+                Self::key_name::<KV>(k)
+            )));
+        }
+
+        let mut expected = [0u8; 4];
+        expected.copy_from_slice(&v[1..CHECKSUM_HEADER_SIZE]);
+        let expected = u32::from_be_bytes(expected);
+
+        let payload = &v[CHECKSUM_HEADER_SIZE..];
+        let actual = crc32fast::hash(payload);
+        if actual != expected {
+            return Err(ErrorCode::MetaStoreDamaged(format!(
+                "checksum mismatch, tree: {}, key: {}, expected: {:08x}, actual: {:08x}",
+                tree,
+                Self::key_name::<KV>(k),
+                expected,
+                actual
+            )));
+        }
+
+        Self::deserialize_versioned::<KV>(tree, k, payload)
+    }
+
+    fn deserialize_versioned<KV: SledKeySpace>(
+        tree: &str,
+        k: &[u8],
+        v: &[u8],
+    ) -> Result<KV::V, ErrorCode> {
+        let v = split_format_version(tree, || Self::key_name::<KV>(k), v)?;
+        KV::deserialize_value(v)
+    }
+
+    pub(crate) fn key_name<KV: SledKeySpace>(k: &[u8]) -> String {
+        match KV::deserialize_key(k) {
+            Ok(key) => format!("{}:{}", KV::NAME, key),
+            Err(_) => format!("{}:{:?}", KV::NAME, k),
+        }
+    }
+}
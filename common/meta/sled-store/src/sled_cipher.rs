@@ -0,0 +1,180 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use ring::aead;
+use ring::hkdf;
+use ring::rand::SecureRandom;
+use ring::rand::SystemRandom;
+use sled::IVec;
+
+/// The first byte of an encrypted value.
+/// Neither a json value, a checksummed value nor a value with an expiry starts with it.
+const ENCRYPTED_MARKER: u8 = 0xFD;
+
+/// The size of the marker and the nonce in front of an encrypted value.
+const ENCRYPTED_HEADER_SIZE: usize = 1 + aead::NONCE_LEN;
+
+/// The size of the master key to derive the key of every tree from.
+pub const ENCRYPTION_KEY_SIZE: usize = 32;
+
+/// The salt to derive the key of a tree from the master key.
+const KEY_DERIVATION_SALT: &[u8] = b"databend-meta-sled-tree";
+
+/// Optional AES-256-GCM encryption of the values of a SledTree, to keep the meta data encrypted at rest.
+///
+/// The key of a tree is derived from the master key and the tree name, the keys are never stored.
+/// An encrypted value is `ENCRYPTED_MARKER`, then a random nonce, then the sealed value and its tag.
+/// The serialized key of a value is authenticated along with it, so that a value can not be moved to another key.
+/// Encryption is applied after checksum and before expiry, an expiry stays readable without the key.
+#[derive(Clone)]
+pub(crate) struct ValueCipher {
+    key: Arc<aead::LessSafeKey>,
+    /// Whether to accept a plaintext value, i.e., written before encryption is enabled.
+    pub(crate) accept_plaintext: bool,
+}
+
+impl fmt::Debug for ValueCipher {
+    // Never print the key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValueCipher")
+            .field("accept_plaintext", &self.accept_plaintext)
+            .finish()
+    }
+}
+
+impl ValueCipher {
+    /// Derive the key of tree `tree` from `master_key`.
+    pub(crate) fn create(
+        master_key: &[u8],
+        tree: &str,
+        accept_plaintext: bool,
+    ) -> Result<ValueCipher, ErrorCode> {
+        if master_key.len() != ENCRYPTION_KEY_SIZE {
+            return Err(ErrorCode::InvalidConfig(format!(
+                "encryption key of tree {} must be {} bytes, got: {}",
+                tree,
+                ENCRYPTION_KEY_SIZE,
+                master_key.len()
+            )));
+        }
+
+        let info = [tree.as_bytes()];
+        let okm = hkdf::Salt::new(hkdf::HKDF_SHA256, KEY_DERIVATION_SALT)
+            .extract(master_key)
+            .expand(&info, &aead::AES_256_GCM)
+            .map_err(|_| ErrorCode::LogicalError("derive encryption key"))?;
+        let key = aead::LessSafeKey::new(aead::UnboundKey::from(okm));
+
+        Ok(ValueCipher {
+            key: Arc::new(key),
+            accept_plaintext,
+        })
+    }
+
+    /// Encrypt a serialized value stored with the serialized key `k`.
+    pub(crate) fn encrypt(&self, k: &[u8], v: &[u8]) -> Result<IVec, ErrorCode> {
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| ErrorCode::LogicalError("generate encryption nonce"))?;
+
+        let mut sealed = v.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(k),
+                &mut sealed,
+            )
+            .map_err(|_| ErrorCode::LogicalError("encrypt value"))?;
+
+        let mut buf = Vec::with_capacity(ENCRYPTED_HEADER_SIZE + sealed.len());
+        buf.push(ENCRYPTED_MARKER);
+        buf.extend_from_slice(&nonce);
+        buf.extend_from_slice(&sealed);
+        Ok(buf.into())
+    }
+
+    /// Decrypt a value stored with the serialized key `k`, the expiry in front of it must be already split.
+    ///
+    /// `cipher` is None if encryption is not enabled, then an encrypted value is rejected.
+    /// `tree` and `key_name` are only used to tell which value fails.
+    pub(crate) fn decrypt<'a>(
+        cipher: Option<&ValueCipher>,
+        tree: &str,
+        key_name: impl Fn() -> String,
+        k: &[u8],
+        v: &'a [u8],
+    ) -> Result<Cow<'a, [u8]>, ErrorCode> {
+        let cipher = match (cipher, is_encrypted(v)) {
+            (Some(c), true) => c,
+            (None, false) => return Ok(Cow::Borrowed(v)),
+            (Some(c), false) if c.accept_plaintext => return Ok(Cow::Borrowed(v)),
+            (Some(_), false) => {
+                return Err(ErrorCode::MetaStoreDamaged(format!(
+                    "plaintext value, tree: {}, key: {}",
+                    tree,
+                    key_name()
+                )))
+            }
+            (None, true) => {
+                return Err(ErrorCode::MetaStoreDamaged(format!(
+                    "encrypted value but encryption is not enabled, tree: {}, key: {}",
+                    tree,
+                    key_name()
+                )))
+            }
+        };
+
+        if v.len() < ENCRYPTED_HEADER_SIZE {
+            return Err(ErrorCode::MetaStoreDamaged(format!(
+                "truncated encryption header, tree: {}, key: {}",
+                tree,
+                key_name()
+            )));
+        }
+
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        nonce.copy_from_slice(&v[1..ENCRYPTED_HEADER_SIZE]);
+
+        let mut sealed = v[ENCRYPTED_HEADER_SIZE..].to_vec();
+        let plain = cipher
+            .key
+            .open_in_place(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(k),
+                &mut sealed,
+            )
+            .map_err(|_| {
+                ErrorCode::MetaStoreDamaged(format!(
+                    "can not decrypt value, wrong key or damaged value, tree: {}, key: {}",
+                    tree,
+                    key_name()
+                ))
+            })?;
+        let len = plain.len();
+        sealed.truncate(len);
+
+        Ok(Cow::Owned(sealed))
+    }
+}
+
+/// Whether a value, with the expiry in front of it split, is encrypted.
+pub(crate) fn is_encrypted(v: &[u8]) -> bool {
+    v.first() == Some(&ENCRYPTED_MARKER)
+}
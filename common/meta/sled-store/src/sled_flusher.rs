@@ -0,0 +1,176 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use common_base::tokio::sync::Notify;
+use common_exception::ErrorCode;
+use common_exception::ToErrorCode;
+
+use crate::sled_metrics::record_flush;
+
+/// Coalesces the flushes of concurrent writers to a sled::Tree, a.k.a. group commit.
+///
+/// A writer registers its intent to flush after its write is applied.
+/// At most one flush runs at a time, it covers every intent registered before it starts.
+/// The writers arriving during a flush wait for it, then one of them starts the next flush for all of them.
+/// A writer returns only when a flush that started after its intent is done,
+/// thus its write is durable just as if it flushed by itself.
+pub(crate) struct CoalescingFlusher {
+    state: Mutex<FlushState>,
+    flushed: Notify,
+}
+
+#[derive(Default)]
+struct FlushState {
+    /// The number of intents ever registered, the last registered intent.
+    requested: u64,
+    /// All the intents up to this one are durable.
+    durable: u64,
+    /// The intents up to this one are covered by a failed flush, with its error message.
+    failed: Option<(u64, String)>,
+    /// Whether a flush is running.
+    flushing: bool,
+    /// The number of flushes done.
+    flushes: u64,
+}
+
+impl CoalescingFlusher {
+    pub(crate) fn create() -> CoalescingFlusher {
+        CoalescingFlusher {
+            state: Mutex::new(FlushState::default()),
+            flushed: Notify::new(),
+        }
+    }
+
+    /// Returns when all the writes to `tree` applied before this call are flushed to disk.
+    pub(crate) async fn flush(
+        &self,
+        tree_name: &str,
+        tree: &sled::Tree,
+    ) -> common_exception::Result<()> {
+        let intent = {
+            let mut state = self.state.lock().unwrap();
+            state.requested += 1;
+            state.requested
+        };
+
+        loop {
+            let flushed = {
+                let mut state = self.state.lock().unwrap();
+
+                if state.durable >= intent {
+                    return Ok(());
+                }
+                if let Some((failed, msg)) = &state.failed {
+                    if *failed >= intent {
+                        return Err(ErrorCode::MetaStoreDamaged(format!(
+                            "flush sled-tree: {}",
+                            msg
+                        )));
+                    }
+                }
+
+                if !state.flushing {
+                    state.flushing = true;
+                    state.requested
+                } else {
+                    // Register before releasing the lock, to not miss the notification of the running flush.
+                    let notified = self.flushed.notified();
+                    drop(state);
+                    notified.await;
+                    continue;
+                }
+            };
+
+            return self.flush_upto(tree_name, tree, flushed).await;
+        }
+    }
+
+    /// Returns the number of flushes done.
+    pub(crate) fn flushes(&self) -> u64 {
+        self.state.lock().unwrap().flushes
+    }
+
+    async fn flush_upto(
+        &self,
+        tree_name: &str,
+        tree: &sled::Tree,
+        upto: u64,
+    ) -> common_exception::Result<()> {
+        // Let the waiters go on even if this future is dropped before the flush is done.
+        let mut running = RunningFlush {
+            flusher: self,
+            upto,
+            result: None,
+        };
+
+        let start = Instant::now();
+        let res = tree
+            .flush_async()
+            .await
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || "flush sled-tree");
+        record_flush(tree_name, start.elapsed());
+
+        running.result = Some(res.as_ref().map(|_| ()).map_err(|e| e.message()));
+        drop(running);
+
+        res.map(|_| ())
+    }
+}
+
+impl fmt::Debug for CoalescingFlusher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("CoalescingFlusher")
+            .field("requested", &state.requested)
+            .field("durable", &state.durable)
+            .field("flushing", &state.flushing)
+            .field("flushes", &state.flushes)
+            .finish()
+    }
+}
+
+/// Publishes the result of a running flush and wakes up the waiters when dropped.
+struct RunningFlush<'a> {
+    flusher: &'a CoalescingFlusher,
+    upto: u64,
+    /// None if the flush is cancelled.
+    result: Option<Result<(), String>>,
+}
+
+impl<'a> Drop for RunningFlush<'a> {
+    fn drop(&mut self) {
+        {
+            let mut state = self.flusher.state.lock().unwrap();
+            state.flushing = false;
+
+            match self.result.take() {
+                Some(Ok(())) => {
+                    state.flushes += 1;
+                    state.durable = state.durable.max(self.upto);
+                }
+                Some(Err(msg)) => {
+                    state.flushes += 1;
+                    state.failed = Some((self.upto, msg));
+                }
+                // A waiter starts the next flush.
+                None => {}
+            }
+        }
+        self.flusher.flushed.notify_waiters();
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+
+/// The first byte of a value with a format version.
+/// A value serialized by `SledSerde` is json and never starts with it.
+const FORMAT_VERSION_MARKER: u8 = 0xFC;
+
+/// The version of the format of the values written by this build.
+/// Bump it when the serialization of a value changes incompatibly,
+/// so that an older build fails with `MetaStoreVersionSkew` instead of taking the value as damaged.
+///
+/// A value without a format version is written before it is introduced, i.e. version 0,
+/// which is serialized the same as version 1.
+pub const VALUE_FORMAT_VERSION: u8 = 1;
+
+/// Prepend the format version of this build to a serialized value.
+///
+/// A value with a format version is `FORMAT_VERSION_MARKER`, then the version, then the serialized value.
+pub(crate) fn with_format_version(payload: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(2 + payload.len());
+    buf.push(FORMAT_VERSION_MARKER);
+    buf.push(VALUE_FORMAT_VERSION);
+    buf.extend_from_slice(payload);
+    buf
+}
+
+/// Split the format version from a value, and return the serialized value
+/// if it is of a version this build can read.
+/// `tree` and `key_name` are only used to tell which value fails.
+pub(crate) fn split_format_version<'a>(
+    tree: &str,
+    key_name: impl FnOnce() -> String,
+    v: &'a [u8],
+) -> Result<&'a [u8], ErrorCode> {
+    if v.first() != Some(&FORMAT_VERSION_MARKER) {
+        return Ok(v);
+    }
+
+    let version = match v.get(1) {
+        Some(version) => *version,
+        None => {
+            return Err(ErrorCode::MetaStoreDamaged(format!(
+                "truncated format version, tree: {}, key: {}",
+                tree,
+                key_name()
+            )))
+        }
+    };
+
+    if version > VALUE_FORMAT_VERSION {
+        return Err(ErrorCode::MetaStoreVersionSkew(format!(
+            "value format version {} is newer than {}, the newest this build can read, \
+            upgrade this node to read it, tree: {}, key: {}",
+            version,
+            VALUE_FORMAT_VERSION,
+            tree,
+            key_name()
+        )));
+    }
+
+    Ok(&v[2..])
+}
@@ -27,6 +27,12 @@ use crate::SledSerde;
 
 /// Defines a key space in sled::Tree that has its own key value type.
 /// And a prefix that is used to distinguish keys from different spaces in a SledTree.
+///
+/// A serialized key is always stored in full, i.e., `PREFIX` followed by the serialized user key,
+/// even if the keys of a key space share a longer prefix, such as the db id of table keys.
+/// sled already stores the keys in a node with their common prefix stripped,
+/// and export, import, checksums and isolated key spaces all rely on a raw key telling its key space
+/// and user key by itself, without per tree state.
 pub trait SledKeySpace {
     /// Prefix is a unique u8 that is prepended before the serialized key, to identify a namespace in sled::Tree.
     const PREFIX: u8;
@@ -68,6 +74,9 @@ pub trait SledKeySpace {
     }
 
     /// Convert range of user key to range of sled::IVec for query.
+    ///
+    /// A range with its start greater than its end is an error instead of an empty range,
+    /// it is most likely a bug of the caller. An unbounded side is never checked.
     fn serialize_range<R>(range: &R) -> Result<(Bound<IVec>, Bound<IVec>), ErrorCode>
     where R: RangeBounds<Self::K> {
         let s = range.start_bound();
@@ -76,6 +85,22 @@ pub trait SledKeySpace {
         let s = Self::serialize_bound(s, "left")?;
         let e = Self::serialize_bound(e, "right")?;
 
+        // The serialized keys preserve the order of the user keys.
+        if let (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) = (range.start_bound(), range.end_bound())
+        {
+            if Self::serialize_key(start)? > Self::serialize_key(end)? {
+                return Err(ErrorCode::BadArguments(format!(
+                    "invalid range of {}: start {:?} is greater than end {:?}",
+                    Self::NAME,
+                    range.start_bound(),
+                    range.end_bound()
+                )));
+            }
+        }
+
         Ok((s, e))
     }
 
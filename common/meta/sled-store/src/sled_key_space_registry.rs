@@ -0,0 +1,74 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use common_exception::ErrorCode;
+
+use crate::SledKeySpace;
+
+/// Collects the key spaces stored in a sled::Tree, to check they do not collide.
+///
+/// Two key spaces sharing a `NAME` or a `PREFIX` silently read and overwrite each other's data,
+/// e.g., a key space copied from another without changing its constants.
+#[derive(Debug, Default)]
+pub struct SledKeySpaceRegistry {
+    // (PREFIX, NAME, type name) of every registered key space.
+    spaces: Vec<(u8, &'static str, &'static str)>,
+}
+
+impl SledKeySpaceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<KV: SledKeySpace>(&mut self) -> &mut Self {
+        self.spaces
+            .push((KV::PREFIX, KV::NAME, std::any::type_name::<KV>()));
+        self
+    }
+
+    /// Returns an error listing every collision if any two key spaces share a `NAME` or a `PREFIX`.
+    pub fn check(&self) -> Result<(), ErrorCode> {
+        let mut by_name = BTreeMap::<&str, Vec<&str>>::new();
+        let mut by_prefix = BTreeMap::<u8, Vec<&str>>::new();
+        for (prefix, name, type_name) in self.spaces.iter() {
+            by_name.entry(name).or_default().push(type_name);
+            by_prefix.entry(*prefix).or_default().push(type_name);
+        }
+
+        let mut collisions = vec![];
+        for (name, types) in by_name.iter().filter(|(_, t)| t.len() > 1) {
+            collisions.push(format!("NAME {:?}: {}", name, types.join(", ")));
+        }
+        for (prefix, types) in by_prefix.iter().filter(|(_, t)| t.len() > 1) {
+            collisions.push(format!("PREFIX {}: {}", prefix, types.join(", ")));
+        }
+
+        match collisions.is_empty() {
+            true => Ok(()),
+            false => Err(ErrorCode::MetaStoreDamaged(format!(
+                "sled key spaces collide, {}",
+                collisions.join("; ")
+            ))),
+        }
+    }
+
+    /// Panics if any two key spaces share a `NAME` or a `PREFIX`.
+    pub fn assert_unique(&self) {
+        if let Err(e) = self.check() {
+            panic!("{}", e.message());
+        }
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use common_meta_types::LogIndex;
+use common_meta_types::Node;
+use common_meta_types::NodeId;
+
+use crate::testing::fake_key_spaces::Logs;
+use crate::testing::fake_key_spaces::Nodes;
+use crate::SledKeySpace;
+use crate::SledKeySpaceRegistry;
+
+/// A key space copied from `Logs` without changing its NAME.
+struct CopiedLogs {}
+impl SledKeySpace for CopiedLogs {
+    const PREFIX: u8 = 100;
+    const NAME: &'static str = "log";
+    type K = LogIndex;
+    type V = Node;
+}
+
+/// A key space reusing the PREFIX of `Nodes`.
+struct CopiedNodes {}
+impl SledKeySpace for CopiedNodes {
+    const PREFIX: u8 = 2;
+    const NAME: &'static str = "copied-node";
+    type K = NodeId;
+    type V = Node;
+}
+
+#[test]
+fn test_key_space_registry_unique() -> anyhow::Result<()> {
+    SledKeySpaceRegistry::new()
+        .register::<Logs>()
+        .register::<Nodes>()
+        .check()?;
+    Ok(())
+}
+
+#[test]
+fn test_key_space_registry_collision() -> anyhow::Result<()> {
+    let mut registry = SledKeySpaceRegistry::new();
+    registry
+        .register::<Logs>()
+        .register::<Nodes>()
+        .register::<CopiedLogs>()
+        .register::<CopiedNodes>();
+
+    let err = registry.check().unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreDamaged("").code(), err.code());
+
+    let msg = err.message();
+    assert!(msg.contains("NAME \"log\""), "{}", msg);
+    assert!(msg.contains("PREFIX 2"), "{}", msg);
+    assert!(msg.contains("CopiedLogs"), "{}", msg);
+    assert!(msg.contains("CopiedNodes"), "{}", msg);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "sled key spaces collide, NAME \"log\"")]
+fn test_key_space_registry_assert_unique_panics() {
+    SledKeySpaceRegistry::new()
+        .register::<Logs>()
+        .register::<CopiedLogs>()
+        .assert_unique();
+}
@@ -0,0 +1,39 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metrics of sled tree operations.
+//! They are labeled by tree name and key space, never by key, to keep the cardinality bounded.
+
+use std::time::Duration;
+
+use metrics::counter;
+use metrics::histogram;
+
+pub static METRIC_SLED_TREE_GET: &str = "sled_tree.get";
+pub static METRIC_SLED_TREE_INSERT: &str = "sled_tree.insert";
+pub static METRIC_SLED_TREE_REMOVE: &str = "sled_tree.remove";
+pub static METRIC_SLED_TREE_RANGE_REMOVE: &str = "sled_tree.range_remove";
+pub static METRIC_SLED_TREE_RANGE_SCAN: &str = "sled_tree.range_scan";
+pub static METRIC_SLED_TREE_APPEND: &str = "sled_tree.append";
+pub static METRIC_SLED_TREE_FLUSH: &str = "sled_tree.flush";
+pub static METRIC_SLED_TREE_FLUSH_DURATION: &str = "sled_tree.flush_duration";
+
+pub(crate) fn incr_op(metric: &'static str, tree: &str, key_space: &'static str) {
+    counter!(metric, 1, "tree" => tree.to_string(), "key_space" => key_space);
+}
+
+pub(crate) fn record_flush(tree: &str, duration: Duration) {
+    counter!(METRIC_SLED_TREE_FLUSH, 1, "tree" => tree.to_string());
+    histogram!(METRIC_SLED_TREE_FLUSH_DURATION, duration, "tree" => tree.to_string());
+}
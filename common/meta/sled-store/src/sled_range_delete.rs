@@ -0,0 +1,69 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Bound;
+use std::ops::RangeBounds;
+
+use common_exception::ErrorCode;
+use sled::IVec;
+
+use crate::SledKeySpace;
+
+/// Ranges of keys in possibly different key spaces, to be deleted together by `SledTree::multi_range_delete`.
+#[derive(Debug, Default)]
+pub struct MultiRangeDelete {
+    pub(crate) ranges: Vec<RangeDelete>,
+}
+
+#[derive(Debug)]
+pub(crate) struct RangeDelete {
+    pub(crate) key_space: &'static str,
+    /// The prefix of the key space, to find the tree storing it.
+    pub(crate) prefix: u8,
+    /// The user key range, for error messages.
+    pub(crate) message: String,
+    pub(crate) range: (Bound<IVec>, Bound<IVec>),
+}
+
+impl MultiRangeDelete {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a range of key space `KV` to delete.
+    /// An invalid range fails here, before anything is deleted.
+    pub fn add<KV, R>(&mut self, range: R) -> Result<&mut Self, ErrorCode>
+    where
+        KV: SledKeySpace,
+        R: RangeBounds<KV::K>,
+    {
+        let sled_range = KV::serialize_range(&range)?;
+
+        self.ranges.push(RangeDelete {
+            key_space: KV::NAME,
+            prefix: KV::PREFIX,
+            message: format!("[{:?}, {:?}]", range.start_bound(), range.end_bound()),
+            range: sled_range,
+        });
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
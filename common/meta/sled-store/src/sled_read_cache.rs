@@ -0,0 +1,88 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use common_cache::Cache;
+use common_cache::LruCache;
+use sled::IVec;
+
+/// A read-through LRU cache of a SledTree, keyed by the serialized key.
+///
+/// An absent key is cached too, as a `None`.
+/// A writer must hold the lock from writing sled to invalidating the written keys,
+/// so that a reader never puts back a value older than the write.
+pub(crate) struct ReadCache {
+    capacity: u64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    lru: Mutex<LruCache<IVec, Option<IVec>>>,
+}
+
+impl ReadCache {
+    pub(crate) fn create(capacity: u64) -> ReadCache {
+        ReadCache {
+            capacity,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            lru: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, LruCache<IVec, Option<IVec>>> {
+        self.lru.lock().unwrap()
+    }
+
+    /// Get the cached value, or load and cache it with `load` if it is not cached.
+    pub(crate) fn get_or_load<E>(
+        &self,
+        key: IVec,
+        load: impl FnOnce(&IVec) -> Result<Option<IVec>, E>,
+    ) -> Result<Option<IVec>, E> {
+        let mut lru = self.lock();
+
+        if let Some(v) = lru.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(v.clone());
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let v = load(&key)?;
+        lru.put(key, v.clone());
+        Ok(v)
+    }
+
+    /// Returns the number of `(hits, misses)`.
+    pub(crate) fn stats(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+}
+
+impl fmt::Debug for ReadCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (hits, misses) = self.stats();
+        f.debug_struct("ReadCache")
+            .field("capacity", &self.capacity)
+            .field("hits", &hits)
+            .field("misses", &misses)
+            .finish()
+    }
+}
@@ -135,6 +135,44 @@ impl SledOrderedSerde for u64 {
     }
 }
 
+/// Signed integers are serialized with the sign bit flipped, in BigEndian.
+///
+/// Plain BigEndian bytes sort negative numbers after positive ones, e.g., -1 is `ff..ff`.
+/// Flipping the sign bit maps `i64::MIN..=i64::MAX` to `0..=u64::MAX` in the same order.
+macro_rules! impl_signed_ordered_serde {
+    ($t: ty, $u: ty, $write: ident, $read: ident) => {
+        impl SledOrderedSerde for $t {
+            fn ser(&self) -> Result<IVec, ErrorCode> {
+                let mut buf = vec![0; size_of_val(self)];
+
+                let flipped = (*self as $u) ^ (1 << (<$u>::BITS - 1));
+                BigEndian::$write(&mut buf, flipped);
+                Ok(buf.into())
+            }
+
+            fn de<V: AsRef<[u8]>>(v: V) -> Result<Self, ErrorCode>
+            where Self: Sized {
+                let b = v.as_ref();
+                let size = std::mem::size_of::<$t>();
+                if b.len() != size {
+                    return Err(ErrorCode::MetaStoreDamaged(format!(
+                        "invalid {} key length: {}, expect: {}",
+                        stringify!($t),
+                        b.len(),
+                        size
+                    )));
+                }
+
+                let flipped = BigEndian::$read(b);
+                Ok((flipped ^ (1 << (<$u>::BITS - 1))) as $t)
+            }
+        }
+    };
+}
+
+impl_signed_ordered_serde!(i32, u32, write_u32, read_u32);
+impl_signed_ordered_serde!(i64, u64, write_u64, read_u64);
+
 /// For LogId to be able to stored in sled::Tree as a key.
 impl SledOrderedSerde for String {
     fn ser(&self) -> Result<IVec, ErrorCode> {
@@ -0,0 +1,52 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::SledOrderedSerde;
+
+#[test]
+fn test_signed_key_ser_de() -> anyhow::Result<()> {
+    for k in [i64::MIN, -256, -1, 0, 1, 256, i64::MAX] {
+        assert_eq!(k, i64::de(k.ser()?)?);
+    }
+    for k in [i32::MIN, -1, 0, 1, i32::MAX] {
+        assert_eq!(k, i32::de(k.ser()?)?);
+    }
+
+    assert_eq!(&[0x7f, 0xff, 0xff, 0xff], (-1i32).ser()?.as_ref());
+    assert_eq!(&[0x80, 0, 0, 0], 0i32.ser()?.as_ref());
+
+    // A key of wrong length is an error instead of a panic.
+    assert!(i64::de(&[1, 2, 3]).is_err());
+    assert!(i32::de(&[1, 2, 3, 4, 5, 6, 7, 8]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_signed_key_order() -> anyhow::Result<()> {
+    assert!((-1i64).ser()? < 0i64.ser()?);
+    assert!((-1i32).ser()? < 0i32.ser()?);
+
+    let keys = vec![i64::MIN, -257, -256, -1, 0, 1, 255, 256, i64::MAX];
+    for w in keys.windows(2) {
+        assert!(
+            w[0].ser()? < w[1].ser()?,
+            "{} should sort before {} after serialized",
+            w[0],
+            w[1]
+        );
+    }
+
+    Ok(())
+}
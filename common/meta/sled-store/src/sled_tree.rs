@@ -12,17 +12,63 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::fmt::Display;
 use std::marker::PhantomData;
 use std::ops::Bound;
 use std::ops::RangeBounds;
+use std::sync::Arc;
+use std::sync::MutexGuard;
+use std::time::Duration;
 
+use common_base::tokio;
+use common_cache::Cache;
+use common_cache::LruCache;
 use common_exception::ErrorCode;
 use common_exception::ToErrorCode;
 use common_tracing::tracing;
-
+use futures::Stream;
+use sled::transaction::ConflictableTransactionError;
+use sled::transaction::TransactionError;
+use sled::IVec;
+use sled::Transactional;
+
+use crate::get_sled_db_options;
+use crate::sled_checksum::ValueChecksum;
+use crate::sled_cipher::is_encrypted;
+use crate::sled_cipher::ValueCipher;
+use crate::sled_flusher::CoalescingFlusher;
+use crate::sled_metrics::incr_op;
+use crate::sled_metrics::METRIC_SLED_TREE_APPEND;
+use crate::sled_metrics::METRIC_SLED_TREE_GET;
+use crate::sled_metrics::METRIC_SLED_TREE_INSERT;
+use crate::sled_metrics::METRIC_SLED_TREE_RANGE_REMOVE;
+use crate::sled_metrics::METRIC_SLED_TREE_RANGE_SCAN;
+use crate::sled_metrics::METRIC_SLED_TREE_REMOVE;
+use crate::sled_read_cache::ReadCache;
+use crate::sled_ttl::is_expired;
+use crate::sled_ttl::now_millis;
+use crate::sled_ttl::split_expiry;
+use crate::sled_ttl::with_expire_at;
+use crate::sled_ttl::with_expiry;
+use crate::MultiRangeDelete;
+use crate::SledDbOptions;
 use crate::SledKeySpace;
 
+/// The default number of items a stream returns before yielding to the async runtime.
+pub const DEFAULT_STREAM_YIELD_INTERVAL: usize = 256;
+
+/// The default number of key-values `append_chunked` writes in one batch.
+pub const DEFAULT_APPEND_CHUNK_SIZE: usize = 10_000;
+
+/// In unit tests every tree name opened by `SledTree::open` must start with this prefix,
+/// so that a test never opens a real tree by accident, nor collides with another test.
+pub const TEST_TREE_NAME_PREFIX: &str = "test-";
+
+/// Set this env var to `1` to turn off the `TEST_TREE_NAME_PREFIX` check for the whole test process.
+pub const ALLOW_UNPREFIXED_TREE_NAME_ENV: &str = "SLED_TREE_ALLOW_UNPREFIXED_NAME";
+
 /// Extract key from a value of sled tree that includes its key.
 pub trait SledValueToKey<K> {
     fn to_key(&self) -> K;
@@ -44,22 +90,108 @@ pub struct SledTree {
     /// See: https://github.com/drmingdrmer/sledtest/blob/500929ab0b89afe547143a38fde6fe85d88f1f80/src/ben_sync.rs
     sync: bool,
 
+    /// A stream returned by `range_get_stream` yields to the async runtime every this many items,
+    /// so that a large scan does not starve other tasks on the same thread.
+    /// 0 disables yielding.
+    stream_yield_interval: usize,
+
+    /// The options of the global sled::Db, for observability.
+    /// None if the global one is not opened.
+    db_options: Option<SledDbOptions>,
+
+    /// An optional LRU cache `get` reads through, shared by the clones of this SledTree.
+    /// The writes through this SledTree invalidate it, writing `tree` directly does not.
+    read_cache: Option<Arc<ReadCache>>,
+
+    /// Whether to checksum the values written, and whether to accept a value without checksum.
+    checksum: ValueChecksum,
+
+    /// The TTL of the values written without an explicit one, by key space prefix.
+    default_ttls: BTreeMap<u8, Duration>,
+
+    /// Encrypts the values written and decrypts the values read, None if encryption is not enabled.
+    cipher: Option<ValueCipher>,
+
+    /// The dedicated trees of the key spaces isolated by `open_isolated`, by key space prefix.
+    /// The other key spaces are stored in `tree`.
+    isolated: BTreeMap<u8, sled::Tree>,
+
+    /// Coalesces the flushes of concurrent writes, shared by the clones of this SledTree.
+    flusher: Arc<CoalescingFlusher>,
+
     pub tree: sled::Tree,
 }
 
+/// A key space stored in a dedicated sled::Tree, see `SledTree::open_isolated`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IsolatedKeySpace {
+    prefix: u8,
+    name: &'static str,
+}
+
+impl IsolatedKeySpace {
+    pub fn of<KV: SledKeySpace>() -> Self {
+        IsolatedKeySpace {
+            prefix: KV::PREFIX,
+            name: KV::NAME,
+        }
+    }
+}
+
 impl SledTree {
     /// Open SledTree
+    ///
+    /// In unit tests the name must start with `TEST_TREE_NAME_PREFIX`,
+    /// unless the env var `ALLOW_UNPREFIXED_TREE_NAME_ENV` is `1`.
+    /// A test that opens a real tree name on purpose should use `open_any_name` instead.
     pub fn open<N: AsRef<[u8]> + Display>(
         db: &sled::Db,
         tree_name: N,
         sync: bool,
     ) -> common_exception::Result<Self> {
-        // During testing, every tree name must be unique.
-        if cfg!(test) {
-            let x = tree_name.as_ref();
-            let x = &x[0..5];
-            assert_eq!(x, b"test-");
+        if cfg!(test) && !Self::unprefixed_name_allowed() {
+            assert!(
+                tree_name
+                    .as_ref()
+                    .starts_with(TEST_TREE_NAME_PREFIX.as_bytes()),
+                "tree name in unit test must start with {:?}, got: {}; use SledTree::open_any_name() or set {}=1 to open it on purpose",
+                TEST_TREE_NAME_PREFIX,
+                tree_name,
+                ALLOW_UNPREFIXED_TREE_NAME_ENV
+            );
         }
+
+        Self::open_any_name(db, tree_name, sync)
+    }
+
+    /// Open SledTree with every key space in `isolated` stored in a dedicated sled::Tree `<name>/<KV::NAME>`
+    /// instead of the tree shared by the others, so that a hot key space does not contend for the locks
+    /// and the cache of the shared tree.
+    ///
+    /// The entries of an isolated key space already in the shared tree are moved to its dedicated tree.
+    /// Every SledTree of the same tree must be opened with the same isolated key spaces,
+    /// otherwise one reads the shared tree while the other writes the dedicated one.
+    /// The API is the same for an isolated key space.
+    pub fn open_isolated<N: AsRef<[u8]> + Display>(
+        db: &sled::Db,
+        tree_name: N,
+        sync: bool,
+        isolated: &[IsolatedKeySpace],
+    ) -> common_exception::Result<Self> {
+        let mut tree = Self::open(db, tree_name, sync)?;
+        for key_space in isolated {
+            tree.isolate(db, key_space)?;
+        }
+        Ok(tree)
+    }
+
+    /// Open SledTree without checking the name, even in unit tests.
+    /// It is up to the caller to keep the trees of different tests apart.
+    pub fn open_any_name<N: AsRef<[u8]> + Display>(
+        db: &sled::Db,
+        tree_name: N,
+        sync: bool,
+    ) -> common_exception::Result<Self> {
         let t = db
             .open_tree(&tree_name)
             .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
@@ -71,11 +203,450 @@ impl SledTree {
         let rl = SledTree {
             name: format!("{}", tree_name),
             sync,
+            stream_yield_interval: DEFAULT_STREAM_YIELD_INTERVAL,
+            db_options: get_sled_db_options(),
+            read_cache: None,
+            checksum: ValueChecksum::default(),
+            default_ttls: BTreeMap::new(),
+            cipher: None,
+            isolated: BTreeMap::new(),
+            flusher: Arc::new(CoalescingFlusher::create()),
             tree: t,
         };
         Ok(rl)
     }
 
+    fn unprefixed_name_allowed() -> bool {
+        std::env::var(ALLOW_UNPREFIXED_TREE_NAME_ENV)
+            .map(|v| v == "1")
+            .unwrap_or(false)
+    }
+
+    /// Set the number of items a stream returns before yielding to the async runtime.
+    pub fn set_stream_yield_interval(&mut self, interval: usize) {
+        self.stream_yield_interval = interval;
+    }
+
+    /// Prepend a CRC32 checksum to every value written from now on, and fail a read of a damaged value.
+    ///
+    /// With `accept_unchecked`, a value without checksum, i.e., written before checksum is enabled, is still accepted.
+    /// It is meant for the migration window of an existing tree, until all its values are rewritten.
+    /// A value with checksum is always verified, whether or not checksum is enabled.
+    pub fn enable_checksum(&mut self, accept_unchecked: bool) {
+        self.checksum = ValueChecksum {
+            enabled: true,
+            accept_unchecked,
+        };
+    }
+
+    /// Expire every value of key space `KV` written from now on `ttl` after it is written,
+    /// unless it is written with an explicit TTL by `insert_with_ttl`.
+    ///
+    /// An expired value is invisible to the reads, and is removed by `remove_expired`.
+    /// It is meant for a key space used as a cache.
+    pub fn set_default_ttl<KV: SledKeySpace>(&mut self, ttl: Duration) {
+        self.default_ttls.insert(KV::PREFIX, ttl);
+    }
+
+    /// Encrypt every value written from now on with AES-256-GCM, and decrypt the values read.
+    ///
+    /// The key of this tree is derived from the 32-byte `master_key` and the tree name, neither is stored in the tree.
+    /// A value that can not be decrypted, e.g., with a wrong key, fails the read with `MetaStoreDamaged`.
+    ///
+    /// With `accept_plaintext`, a plaintext value, i.e., written before encryption is enabled, is still accepted.
+    /// It is meant for the migration window of an existing tree, until `encrypt_plaintext_values` rewrites it.
+    pub fn enable_encryption(
+        &mut self,
+        master_key: &[u8],
+        accept_plaintext: bool,
+    ) -> common_exception::Result<()> {
+        self.cipher = Some(ValueCipher::create(
+            master_key,
+            &self.name,
+            accept_plaintext,
+        )?);
+        Ok(())
+    }
+
+    /// Encrypt in place every plaintext value of the tree, keeping its expiry, and stop accepting plaintext values.
+    ///
+    /// Returns the number of values encrypted. Encryption must be enabled by `enable_encryption`.
+    ///
+    /// It requires exclusive access to the tree like `compact`: a value written by another `SledTree` of the same tree
+    /// during the migration may be left in plaintext, and it will be rejected afterwards.
+    pub fn encrypt_plaintext_values(&mut self) -> common_exception::Result<usize> {
+        let cipher = match &self.cipher {
+            Some(c) => c.clone(),
+            None => {
+                return Err(ErrorCode::LogicalError(format!(
+                    "encrypt_plaintext_values: encryption is not enabled on tree: {}",
+                    self.name
+                )))
+            }
+        };
+
+        let mut n = 0;
+        for tree in self.trees() {
+            n += self.encrypt_tree(&cipher, tree)?;
+        }
+
+        if let Some(c) = &self.read_cache {
+            c.lock().clear();
+        }
+
+        if let Some(c) = &mut self.cipher {
+            c.accept_plaintext = false;
+        }
+
+        tracing::info!("encrypted {} plaintext values of SledTree {}", n, self.name);
+
+        Ok(n)
+    }
+
+    fn encrypt_tree(
+        &self,
+        cipher: &ValueCipher,
+        tree: &sled::Tree,
+    ) -> common_exception::Result<usize> {
+        let mut batch = sled::Batch::default();
+        let mut n = 0;
+
+        for item in tree.iter() {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("encrypt_plaintext_values: read: {}", self.name)
+            })?;
+
+            let (expire_at, plain) = split_expiry(&v).map_err(|e| {
+                ErrorCode::MetaStoreDamaged(format!("{}, tree: {}", e.message(), self.name))
+            })?;
+            if is_encrypted(plain) {
+                continue;
+            }
+
+            let sealed = cipher.encrypt(&k, plain)?;
+            let sealed = match expire_at {
+                Some(expire_at) => with_expire_at(&sealed, expire_at),
+                None => sealed,
+            };
+
+            batch.insert(k, sealed);
+            n += 1;
+        }
+
+        tree.apply_batch(batch)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("encrypt_plaintext_values: write: {}", self.name)
+            })?;
+        tree.flush()
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("encrypt_plaintext_values: flush: {}", self.name)
+            })?;
+
+        Ok(n)
+    }
+
+    /// Iterate a consistent view of the raw key-values of the tree, with the values decrypted,
+    /// e.g., to build a snapshot that is installed into a tree with another name or another key by `import`.
+    ///
+    /// The key-values of the dedicated trees of the isolated key spaces follow the ones of the shared tree,
+    /// the view of every tree is taken at once.
+    ///
+    /// The expiry and the checksum of a value are kept as is.
+    pub fn export(&self) -> impl Iterator<Item = common_exception::Result<(IVec, IVec)>> {
+        let cipher = self.cipher.clone();
+        let name = self.name.clone();
+
+        let iters = self.trees().map(|tree| tree.iter()).collect::<Vec<_>>();
+
+        iters.into_iter().flatten().map(move |item| {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("export: read: {}", name)
+            })?;
+
+            let (expire_at, sealed) = split_expiry(&v).map_err(|e| {
+                ErrorCode::MetaStoreDamaged(format!("{}, tree: {}", e.message(), name))
+            })?;
+            if !is_encrypted(sealed) {
+                return Ok((k, v));
+            }
+
+            let key_name = || format!("{:?}", k);
+            let plain = ValueCipher::decrypt(cipher.as_ref(), &name, key_name, &k, sealed)?;
+            let plain = match expire_at {
+                Some(expire_at) => with_expire_at(&plain, expire_at),
+                None => IVec::from(plain.as_ref()),
+            };
+            Ok((k, plain))
+        })
+    }
+
+    /// Insert the raw key-values exported by `export`, encrypted if encryption is enabled on this tree.
+    ///
+    /// Returns the number of key-values inserted. They are not flushed.
+    pub fn import(
+        &self,
+        kvs: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>,
+    ) -> common_exception::Result<usize> {
+        let mut n = 0;
+
+        for (k, v) in kvs {
+            let v = match &self.cipher {
+                None => IVec::from(v),
+                Some(cipher) => {
+                    let (expire_at, plain) = split_expiry(&v).map_err(|e| {
+                        ErrorCode::MetaStoreDamaged(format!("{}, tree: {}", e.message(), self.name))
+                    })?;
+                    if is_encrypted(plain) {
+                        return Err(ErrorCode::MetaStoreDamaged(format!(
+                            "import: encrypted value, tree: {}, key: {:?}",
+                            self.name, k
+                        )));
+                    }
+
+                    let sealed = cipher.encrypt(&k, plain)?;
+                    match expire_at {
+                        Some(expire_at) => with_expire_at(&sealed, expire_at),
+                        None => sealed,
+                    }
+                }
+            };
+
+            let prefix = k.first().copied().unwrap_or_default();
+            self.tree_by_prefix(prefix)
+                .insert(&k, v)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("import: write: {}", self.name)
+                })?;
+            n += 1;
+        }
+
+        if let Some(c) = &self.read_cache {
+            c.lock().clear();
+        }
+
+        Ok(n)
+    }
+
+    /// Move the entries of an isolated key space from the shared tree to its dedicated tree `<name>/<KV::NAME>`.
+    fn isolate(
+        &mut self,
+        db: &sled::Db,
+        key_space: &IsolatedKeySpace,
+    ) -> common_exception::Result<()> {
+        let name = format!("{}/{}", self.name, key_space.name);
+        let dedicated = db
+            .open_tree(&name)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("open_isolated: open tree: {}", name)
+            })?;
+
+        // Copy first then remove, a crash in between leaves a copy in the shared tree, which is moved again.
+        let mut copy = sled::Batch::default();
+        let mut remove = sled::Batch::default();
+        let mut n = 0;
+
+        for item in self.tree.scan_prefix([key_space.prefix]) {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("open_isolated: read: {}:{}", self.name, key_space.name)
+            })?;
+            copy.insert(k.clone(), v);
+            remove.remove(k);
+            n += 1;
+        }
+
+        if n > 0 {
+            dedicated
+                .apply_batch(copy)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("open_isolated: write: {}", name)
+                })?;
+            dedicated
+                .flush()
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("open_isolated: flush: {}", name)
+                })?;
+
+            self.tree
+                .apply_batch(remove)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("open_isolated: remove: {}:{}", self.name, key_space.name)
+                })?;
+            self.tree
+                .flush()
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("open_isolated: flush: {}", self.name)
+                })?;
+
+            tracing::info!(
+                "moved {} entries of {} to the dedicated tree {}",
+                n,
+                key_space.name,
+                name
+            );
+        }
+
+        self.isolated.insert(key_space.prefix, dedicated);
+        Ok(())
+    }
+
+    /// The tree storing key space `KV`: its dedicated tree if it is isolated, otherwise the shared one.
+    fn tree_of<KV: SledKeySpace>(&self) -> &sled::Tree {
+        self.tree_by_prefix(KV::PREFIX)
+    }
+
+    fn tree_by_prefix(&self, prefix: u8) -> &sled::Tree {
+        self.isolated.get(&prefix).unwrap_or(&self.tree)
+    }
+
+    /// The shared tree and the dedicated trees of the isolated key spaces.
+    fn trees(&self) -> impl Iterator<Item = &sled::Tree> {
+        std::iter::once(&self.tree).chain(self.isolated.values())
+    }
+
+    /// Cache at most `capacity` keys read by `get`.
+    /// It is meant for a tree read much more often than written.
+    pub fn enable_read_cache(&mut self, capacity: u64) {
+        self.read_cache = Some(Arc::new(ReadCache::create(capacity)));
+    }
+
+    /// Returns the number of `(hits, misses)` of the read cache, None if it is not enabled.
+    pub fn read_cache_stats(&self) -> Option<(u64, u64)> {
+        self.read_cache.as_ref().map(|c| c.stats())
+    }
+
+    /// Rewrite all entries of the tree into a fresh tree to reclaim the space left by deleted entries.
+    ///
+    /// Returns the number of bytes the on-disk size of `db` shrinks by, 0 if it does not shrink.
+    /// `db` must be the db this tree is opened in.
+    ///
+    /// It requires exclusive access to the tree: no other `SledTree` or `sled::Tree` of the same tree may be used
+    /// during or after compaction, they must be opened again. This is why it borrows `self` mutably.
+    ///
+    /// The entries are first copied to a temporary tree `<name>.compacting`.
+    /// If the process crashes in the middle, the entries are left in the temporary tree,
+    /// and the next compaction refuses to start until it is recovered by an operator.
+    /// The dedicated trees of the isolated key spaces are compacted the same way, each with its own temporary tree.
+    pub fn compact(&mut self, db: &sled::Db) -> common_exception::Result<u64> {
+        let size_before = db
+            .size_on_disk()
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || "compact: size_on_disk")?;
+
+        // The shared tree and the dedicated trees, by the prefix of the isolated key space.
+        let names = std::iter::once((None, self.name.clone()))
+            .chain(
+                self.isolated
+                    .iter()
+                    .map(|(prefix, tree)| (Some(*prefix), Self::tree_name(tree))),
+            )
+            .collect::<Vec<_>>();
+
+        // Refuse to start before anything is changed, if any of the trees was interrupted.
+        for (_, name) in names.iter() {
+            let tmp_name = format!("{}.compacting", name);
+            let tmp = db
+                .open_tree(&tmp_name)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("compact: open tree: {}", tmp_name)
+                })?;
+
+            if !tmp.is_empty() {
+                return Err(ErrorCode::MetaStoreDamaged(format!(
+                    "compact: {} is not empty, a previous compaction of {} was interrupted",
+                    tmp_name, name
+                )));
+            }
+        }
+
+        for (prefix, name) in names.iter() {
+            match prefix {
+                None => self.tree = Self::compact_tree(db, &self.tree, name)?,
+                Some(prefix) => {
+                    let fresh = Self::compact_tree(db, &self.isolated[prefix], name)?;
+                    self.isolated.insert(*prefix, fresh);
+                }
+            }
+        }
+
+        let size_after = db
+            .size_on_disk()
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || "compact: size_on_disk")?;
+
+        tracing::info!(
+            "compacted SledTree {}: db size on disk: {} -> {}",
+            self.name,
+            size_before,
+            size_after
+        );
+
+        Ok(size_before.saturating_sub(size_after))
+    }
+
+    /// Rewrite the entries of `tree` named `name` into a fresh tree of the same name, and return the fresh one.
+    fn compact_tree(
+        db: &sled::Db,
+        tree: &sled::Tree,
+        name: &str,
+    ) -> common_exception::Result<sled::Tree> {
+        let tmp_name = format!("{}.compacting", name);
+
+        let tmp = db
+            .open_tree(&tmp_name)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("compact: open tree: {}", tmp_name)
+            })?;
+
+        Self::copy_tree(tree, &tmp)?;
+        db.flush()
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || "compact: flush")?;
+
+        db.drop_tree(name)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("compact: drop tree: {}", name)
+            })?;
+
+        let fresh = db
+            .open_tree(name)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("compact: open tree: {}", name)
+            })?;
+
+        Self::copy_tree(&tmp, &fresh)?;
+        db.flush()
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || "compact: flush")?;
+
+        drop(tmp);
+        db.drop_tree(&tmp_name)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("compact: drop tree: {}", tmp_name)
+            })?;
+        db.flush()
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || "compact: flush")?;
+
+        Ok(fresh)
+    }
+
+    fn tree_name(tree: &sled::Tree) -> String {
+        String::from_utf8_lossy(&tree.name()).to_string()
+    }
+
+    fn copy_tree(from: &sled::Tree, to: &sled::Tree) -> common_exception::Result<()> {
+        let mut batch = sled::Batch::default();
+        for item in from.iter() {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || "compact: read")?;
+            batch.insert(k, v);
+        }
+
+        to.apply_batch(batch)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, || "compact: write")?;
+        Ok(())
+    }
+
+    /// Lock the read cache if it is enabled.
+    /// A write holds it until the written keys are invalidated.
+    fn lock_read_cache(&self) -> Option<MutexGuard<'_, LruCache<IVec, Option<IVec>>>> {
+        self.read_cache.as_ref().map(|c| c.lock())
+    }
+
     /// Borrows the SledTree and creates a wrapper with access limited to a specified key space `KV`.
     pub fn key_space<KV: SledKeySpace>(&self) -> AsKeySpace<KV> {
         AsKeySpace::<KV> {
@@ -84,17 +655,17 @@ impl SledTree {
         }
     }
 
-    /// Return true if the tree contains the key.
+    /// Return true if the tree contains the key and it is not expired.
     pub fn contains_key<KV: SledKeySpace>(&self, key: &KV::K) -> common_exception::Result<bool>
     where KV: SledKeySpace {
         let got = self
-            .tree
-            .contains_key(KV::serialize_key(key)?)
+            .tree_of::<KV>()
+            .get(KV::serialize_key(key)?)
             .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
                 format!("contains_key: {}:{}", self.name, key)
             })?;
 
-        Ok(got)
+        Ok(matches!(got, Some(v) if !is_expired(&v, now_millis())))
     }
 
     pub async fn update_and_fetch<KV: SledKeySpace, F>(
@@ -105,25 +676,63 @@ impl SledTree {
     where
         F: FnMut(Option<KV::V>) -> Option<KV::V>,
     {
+        incr_op(METRIC_SLED_TREE_INSERT, &self.name, KV::NAME);
+
         let mes = || format!("update_and_fetch: {}", key);
 
         let k = KV::serialize_key(key)?;
 
-        let res = self
-            .tree
-            .update_and_fetch(k, move |old| {
-                let old = old.map(|o| KV::deserialize_value(o).unwrap());
+        let res = {
+            let mut cache = self.lock_read_cache();
+
+            // The closure can not return an error: keep the stored value on failure,
+            // and report the error once the update is done.
+            // sled may call the closure more than once, only the last call counts.
+            let mut failure = None;
+            let res = self
+                .tree_of::<KV>()
+                .update_and_fetch(&k, |old| {
+                    failure = None;
+
+                    let prev = match old.filter(|o| !is_expired(o, now_millis())) {
+                        None => None,
+                        Some(o) => match self.deserialize_value::<KV>(&k, o) {
+                            Ok(v) => Some(v),
+                            Err(e) => {
+                                failure = Some(e);
+                                return old.map(IVec::from);
+                            }
+                        },
+                    };
+
+                    match f(prev) {
+                        None => None,
+                        Some(new_val) => match self.serialize_value::<KV>(&k, &new_val, None) {
+                            Ok(v) => Some(v),
+                            Err(e) => {
+                                failure = Some(e);
+                                old.map(IVec::from)
+                            }
+                        },
+                    }
+                })
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, mes)?;
+
+            if let Some(c) = cache.as_mut() {
+                c.pop(&k);
+            }
 
-                let new_val = f(old);
-                new_val.map(|new_val| KV::serialize_value(&new_val).unwrap())
-            })
-            .map_err_to_code(ErrorCode::MetaStoreDamaged, mes)?;
+            if let Some(e) = failure {
+                return Err(e);
+            }
+            res
+        };
 
         self.flush_async(true).await?;
 
         let value = match res {
             None => None,
-            Some(v) => Some(KV::deserialize_value(v)?),
+            Some(v) => Some(self.deserialize_value::<KV>(&k, v)?),
         };
 
         Ok(value)
@@ -132,16 +741,72 @@ impl SledTree {
     /// Retrieve the value of key.
     pub fn get<KV: SledKeySpace>(&self, key: &KV::K) -> common_exception::Result<Option<KV::V>>
     where KV: SledKeySpace {
-        let got = self
-            .tree
-            .get(KV::serialize_key(key)?)
-            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
-                format!("get: {}:{}", self.name, key)
-            })?;
+        incr_op(METRIC_SLED_TREE_GET, &self.name, KV::NAME);
+
+        let k = KV::serialize_key(key)?;
+        let load = |k: &IVec| self.tree_of::<KV>().get(k);
+
+        let got = match &self.read_cache {
+            None => load(&k),
+            Some(cache) => cache.get_or_load(k, load),
+        }
+        .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+            format!("get: {}:{}", self.name, key)
+        })?;
 
         let v = match got {
-            None => None,
-            Some(v) => Some(KV::deserialize_value(v)?),
+            Some(v) if !is_expired(&v, now_millis()) => Some(self.deserialize_value::<KV>(&k, v)?),
+            _ => None,
+        };
+
+        Ok(v)
+    }
+
+    /// Retrieve the value of key, tolerating a value that can not be deserialized.
+    ///
+    /// Unlike `get`, a corrupted value does not fail the call:
+    /// `on_corrupt` is called with the raw key and value bytes and `None` is returned.
+    /// It is meant for recovering a tree that is partially damaged.
+    pub fn get_lenient<KV, F>(
+        &self,
+        key: &KV::K,
+        on_corrupt: F,
+    ) -> common_exception::Result<Option<KV::V>>
+    where
+        KV: SledKeySpace,
+        F: FnOnce(&[u8], &[u8]),
+    {
+        incr_op(METRIC_SLED_TREE_GET, &self.name, KV::NAME);
+
+        let k = KV::serialize_key(key)?;
+        let load = |k: &IVec| self.tree_of::<KV>().get(k);
+
+        let got = match &self.read_cache {
+            None => load(&k),
+            Some(cache) => cache.get_or_load(k.clone(), load),
+        }
+        .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+            format!("get_lenient: {}:{}", self.name, key)
+        })?;
+
+        let v = match got {
+            Some(v) if !is_expired(&v, now_millis()) => {
+                match self.deserialize_value::<KV>(&k, &v) {
+                    Ok(x) => Some(x),
+                    Err(e) => {
+                        tracing::warn!(
+                            "skip corrupted value of {}:{}, key: {}, error: {}",
+                            self.name,
+                            KV::NAME,
+                            key,
+                            e
+                        );
+                        on_corrupt(&k, &v);
+                        None
+                    }
+                }
+            }
+            _ => None,
         };
 
         Ok(v)
@@ -152,21 +817,19 @@ impl SledTree {
     where KV: SledKeySpace {
         let range = KV::serialize_range(&(Bound::Unbounded::<KV::K>, Bound::Unbounded::<KV::K>))?;
 
-        let mut it = self.tree.range(range).rev();
-        let last = it.next();
-        let last = match last {
-            None => {
-                return Ok(None);
+        let now = now_millis();
+        for item in self.tree_of::<KV>().range(range).rev() {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || "last")?;
+            if is_expired(&v, now) {
+                continue;
             }
-            Some(res) => res,
-        };
 
-        let last = last.map_err_to_code(ErrorCode::MetaStoreDamaged, || "last")?;
+            let key = KV::deserialize_key(&k)?;
+            let value = self.deserialize_value::<KV>(&k, v)?;
+            return Ok(Some((key, value)));
+        }
 
-        let (k, v) = last;
-        let key = KV::deserialize_key(k)?;
-        let value = KV::deserialize_value(v)?;
-        Ok(Some((key, value)))
+        Ok(None)
     }
 
     #[tracing::instrument(level = "debug", skip(self))]
@@ -178,16 +841,29 @@ impl SledTree {
     where
         KV: SledKeySpace,
     {
-        let removed = self
-            .tree
-            .remove(KV::serialize_key(key)?)
-            .map_err_to_code(ErrorCode::MetaStoreDamaged, || format!("removed: {}", key,))?;
+        incr_op(METRIC_SLED_TREE_REMOVE, &self.name, KV::NAME);
+
+        let k = KV::serialize_key(key)?;
+
+        let removed = {
+            let mut cache = self.lock_read_cache();
+
+            let removed = self
+                .tree_of::<KV>()
+                .remove(&k)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || format!("removed: {}", key,))?;
+
+            if let Some(c) = cache.as_mut() {
+                c.pop(&k);
+            }
+            removed
+        };
 
         self.flush_async(flush).await?;
 
         let removed = match removed {
-            Some(x) => Some(KV::deserialize_value(x)?),
-            None => None,
+            Some(x) if !is_expired(&x, now_millis()) => Some(self.deserialize_value::<KV>(&k, x)?),
+            _ => None,
         };
 
         Ok(removed)
@@ -200,6 +876,8 @@ impl SledTree {
         KV: SledKeySpace,
         R: RangeBounds<KV::K>,
     {
+        incr_op(METRIC_SLED_TREE_RANGE_REMOVE, &self.name, KV::NAME);
+
         let mut batch = sled::Batch::default();
 
         // Convert K range into sled::IVec range
@@ -207,24 +885,201 @@ impl SledTree {
 
         let range_mes = self.range_message::<KV, _>(&range);
 
-        for item in self.tree.range(sled_range) {
-            let (k, _) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
-                format!("range_remove: {}", range_mes,)
-            })?;
-            batch.remove(k);
+        {
+            let mut cache = self.lock_read_cache();
+            let mut keys = vec![];
+
+            for item in self.tree_of::<KV>().range(sled_range) {
+                let (k, _) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("range_remove: {}", range_mes,)
+                })?;
+                batch.remove(k.clone());
+                keys.push(k);
+            }
+
+            self.tree_of::<KV>()
+                .apply_batch(batch)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("batch remove: {}", range_mes,)
+                })?;
+
+            if let Some(c) = cache.as_mut() {
+                for k in keys.iter() {
+                    c.pop(k);
+                }
+            }
         }
 
-        self.tree
-            .apply_batch(batch)
-            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
-                format!("batch remove: {}", range_mes,)
-            })?;
+        self.flush_async(flush).await?;
+
+        Ok(())
+    }
+
+    /// Delete the kvs of all the ranges in `deletes` in one `sled::Batch`, either all or none of them are deleted.
+    ///
+    /// It is meant for a cascading delete across key spaces, e.g., a database record along with the records of its tables,
+    /// which leaves no orphan behind if the process crashes in the middle.
+    #[tracing::instrument(level = "debug", skip(self, deletes))]
+    pub async fn multi_range_delete(
+        &self,
+        deletes: &MultiRangeDelete,
+        flush: bool,
+    ) -> common_exception::Result<()> {
+        // A batch for every tree involved, the shared one or the dedicated one of an isolated key space.
+        let mut batches = BTreeMap::<Option<u8>, sled::Batch>::new();
+
+        {
+            let mut cache = self.lock_read_cache();
+            let mut keys = vec![];
+
+            for delete in deletes.ranges.iter() {
+                incr_op(METRIC_SLED_TREE_RANGE_REMOVE, &self.name, delete.key_space);
+
+                let isolated = self.isolated.get_key_value(&delete.prefix).map(|(p, _)| *p);
+                let batch = batches.entry(isolated).or_default();
+
+                for item in self
+                    .tree_by_prefix(delete.prefix)
+                    .range(delete.range.clone())
+                {
+                    let (k, _) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                        format!(
+                            "multi_range_delete: {}:{}/{}",
+                            self.name, delete.key_space, delete.message
+                        )
+                    })?;
+                    batch.remove(k.clone());
+                    keys.push(k);
+                }
+            }
+
+            let mes = || format!("batch remove: {}: {} ranges", self.name, deletes.len());
+            let tree_of = |isolated: &Option<u8>| match isolated {
+                Some(prefix) => &self.isolated[prefix],
+                None => &self.tree,
+            };
+
+            if batches.len() <= 1 {
+                for (isolated, batch) in batches {
+                    tree_of(&isolated)
+                        .apply_batch(batch)
+                        .map_err_to_code(ErrorCode::MetaStoreDamaged, mes)?;
+                }
+            } else {
+                // The batches of more than one tree are applied in one transaction.
+                let trees = batches.keys().map(tree_of).collect::<Vec<_>>();
+                let batches = batches.into_values().collect::<Vec<_>>();
+
+                trees
+                    .as_slice()
+                    .transaction(|tx_trees| {
+                        for (tx_tree, batch) in tx_trees.iter().zip(batches.iter()) {
+                            tx_tree.apply_batch(batch)?;
+                        }
+                        Ok::<_, ConflictableTransactionError<()>>(())
+                    })
+                    .map_err(|e| ErrorCode::MetaStoreDamaged(format!("{}: {:?}", mes(), e)))?;
+            }
+
+            if let Some(c) = cache.as_mut() {
+                for k in keys.iter() {
+                    c.pop(k);
+                }
+            }
+        }
 
         self.flush_async(flush).await?;
 
         Ok(())
     }
 
+    /// Delete all kvs in key space `KV`, kvs in other key spaces are left intact.
+    ///
+    /// sled can only clear a whole tree, thus it removes every key with prefix `KV::PREFIX` in one batch.
+    /// Keys and values are never deserialized, which makes it faster than `range_remove(..)`.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn clear_key_space<KV>(&self, flush: bool) -> common_exception::Result<()>
+    where KV: SledKeySpace {
+        incr_op(METRIC_SLED_TREE_RANGE_REMOVE, &self.name, KV::NAME);
+
+        let mut batch = sled::Batch::default();
+
+        {
+            let mut cache = self.lock_read_cache();
+            let mut keys = vec![];
+
+            for k in self.tree_of::<KV>().scan_prefix([KV::PREFIX]).keys() {
+                let k = k.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("clear_key_space: {}:{}", self.name, KV::NAME)
+                })?;
+                batch.remove(k.clone());
+                keys.push(k);
+            }
+
+            self.tree_of::<KV>()
+                .apply_batch(batch)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("batch remove: {}:{}", self.name, KV::NAME)
+                })?;
+
+            if let Some(c) = cache.as_mut() {
+                for k in keys.iter() {
+                    c.pop(k);
+                }
+            }
+        }
+
+        self.flush_async(flush).await?;
+
+        Ok(())
+    }
+
+    /// Delete the expired kvs in key space `KV`, kvs in other key spaces are left intact.
+    ///
+    /// An expired kv is already invisible to the reads, this reclaims its space.
+    /// Returns the number of kvs deleted.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn remove_expired<KV>(&self, flush: bool) -> common_exception::Result<usize>
+    where KV: SledKeySpace {
+        incr_op(METRIC_SLED_TREE_RANGE_REMOVE, &self.name, KV::NAME);
+
+        let now = now_millis();
+
+        let removed = {
+            let mut cache = self.lock_read_cache();
+            let mut removed = 0;
+
+            for item in self.tree_of::<KV>().scan_prefix([KV::PREFIX]) {
+                let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("remove_expired: {}:{}", self.name, KV::NAME)
+                })?;
+                if !is_expired(&v, now) {
+                    continue;
+                }
+
+                // The kv may be overridden after it is scanned, it is removed only if it is still the expired one.
+                let cas = self
+                    .tree_of::<KV>()
+                    .compare_and_swap(&k, Some(v), None as Option<IVec>)
+                    .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                        format!("remove_expired: {}:{}", self.name, KV::NAME)
+                    })?;
+
+                if cas.is_ok() {
+                    removed += 1;
+                    if let Some(c) = cache.as_mut() {
+                        c.pop(&k);
+                    }
+                }
+            }
+            removed
+        };
+
+        self.flush_async(flush).await?;
+
+        Ok(removed)
+    }
+
     /// Get keys in `range`
     pub fn range_keys<KV, R>(&self, range: R) -> common_exception::Result<Vec<KV::K>>
     where
@@ -233,14 +1088,51 @@ impl SledTree {
     {
         let mut res = vec![];
 
+        incr_op(METRIC_SLED_TREE_RANGE_SCAN, &self.name, KV::NAME);
+
         let range_mes = self.range_message::<KV, _>(&range);
 
         // Convert K range into sled::IVec range
         let range = KV::serialize_range(&range)?;
-        for item in self.tree.range(range) {
-            let (k, _) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+        let now = now_millis();
+        for item in self.tree_of::<KV>().range(range) {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
                 format!("range_get: {}", range_mes,)
             })?;
+            if is_expired(&v, now) {
+                continue;
+            }
+
+            let key = KV::deserialize_key(k)?;
+            res.push(key);
+        }
+
+        Ok(res)
+    }
+
+    /// Get keys in `range` in descending order.
+    /// The bounds are the same as `range_keys`.
+    pub fn range_keys_rev<KV, R>(&self, range: R) -> common_exception::Result<Vec<KV::K>>
+    where
+        KV: SledKeySpace,
+        R: RangeBounds<KV::K>,
+    {
+        incr_op(METRIC_SLED_TREE_RANGE_SCAN, &self.name, KV::NAME);
+
+        let mut res = vec![];
+
+        let range_mes = self.range_message::<KV, _>(&range);
+
+        // Convert K range into sled::IVec range
+        let range = KV::serialize_range(&range)?;
+        let now = now_millis();
+        for item in self.tree_of::<KV>().range(range).rev() {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("range_get_rev: {}", range_mes,)
+            })?;
+            if is_expired(&v, now) {
+                continue;
+            }
 
             let key = KV::deserialize_key(k)?;
             res.push(key);
@@ -257,23 +1149,84 @@ impl SledTree {
     {
         let mut res = vec![];
 
+        incr_op(METRIC_SLED_TREE_RANGE_SCAN, &self.name, KV::NAME);
+
         let range_mes = self.range_message::<KV, _>(&range);
 
         // Convert K range into sled::IVec range
         let range = KV::serialize_range(&range)?;
-        for item in self.tree.range(range) {
+        let now = now_millis();
+        for item in self.tree_of::<KV>().range(range) {
             let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
                 format!("range_get: {}", range_mes,)
             })?;
+            if is_expired(&v, now) {
+                continue;
+            }
 
-            let key = KV::deserialize_key(k)?;
-            let value = KV::deserialize_value(v)?;
+            let key = KV::deserialize_key(&k)?;
+            let value = self.deserialize_value::<KV>(&k, v)?;
             res.push((key, value));
         }
 
         Ok(res)
     }
 
+    /// Get key-values in `range`, skipping the entries that can not be deserialized.
+    ///
+    /// Unlike `range_kvs`, a corrupted entry does not abort the scan:
+    /// `on_corrupt` is called with its raw key and value bytes and the scan continues.
+    /// It is meant for recovering a tree that is partially damaged.
+    pub fn range_kvs_lenient<KV, R, F>(
+        &self,
+        range: R,
+        mut on_corrupt: F,
+    ) -> common_exception::Result<Vec<(KV::K, KV::V)>>
+    where
+        KV: SledKeySpace,
+        R: RangeBounds<KV::K>,
+        F: FnMut(&[u8], &[u8]),
+    {
+        let mut res = vec![];
+
+        incr_op(METRIC_SLED_TREE_RANGE_SCAN, &self.name, KV::NAME);
+
+        let range_mes = self.range_message::<KV, _>(&range);
+
+        // Convert K range into sled::IVec range
+        let range = KV::serialize_range(&range)?;
+        let now = now_millis();
+        for item in self.tree_of::<KV>().range(range) {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("range_kvs_lenient: {}", range_mes,)
+            })?;
+            if is_expired(&v, now) {
+                continue;
+            }
+
+            let kv = KV::deserialize_key(&k).and_then(|key| {
+                self.deserialize_value::<KV>(&k, &v)
+                    .map(|value| (key, value))
+            });
+
+            match kv {
+                Ok(kv) => res.push(kv),
+                Err(e) => {
+                    tracing::warn!(
+                        "skip corrupted entry of {}:{}, raw key: {:?}, error: {}",
+                        self.name,
+                        KV::NAME,
+                        k,
+                        e
+                    );
+                    on_corrupt(&k, &v);
+                }
+            }
+        }
+
+        Ok(res)
+    }
+
     /// Get key-valuess in `range`
     pub fn range<KV, R>(
         &self,
@@ -285,19 +1238,28 @@ impl SledTree {
         KV: SledKeySpace,
         R: RangeBounds<KV::K>,
     {
+        incr_op(METRIC_SLED_TREE_RANGE_SCAN, &self.name, KV::NAME);
+
         let range_mes = self.range_message::<KV, _>(&range);
 
         // Convert K range into sled::IVec range
         let range = KV::serialize_range(&range)?;
 
-        let it = self.tree.range(range);
+        let checksum = self.checksum;
+        let cipher = self.cipher.clone();
+        let name = self.name.clone();
+
+        let now = now_millis();
+
+        let it = self.tree_of::<KV>().range(range);
+        let it = it.filter(move |item| !matches!(item, Ok((_, v)) if is_expired(v, now)));
         let it = it.map(move |item| {
             let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
                 format!("range_get: {}", range_mes,)
             })?;
 
-            let key = KV::deserialize_key(k)?;
-            let value = KV::deserialize_value(v)?;
+            let key = KV::deserialize_key(&k)?;
+            let value = decode_value::<KV>(&checksum, cipher.as_ref(), &name, &k, v)?;
 
             Ok((key, value))
         });
@@ -308,16 +1270,22 @@ impl SledTree {
     /// Get key-valuess in with the same prefix
     pub fn scan_prefix<KV>(&self, prefix: &KV::K) -> common_exception::Result<Vec<(KV::K, KV::V)>>
     where KV: SledKeySpace {
+        incr_op(METRIC_SLED_TREE_RANGE_SCAN, &self.name, KV::NAME);
+
         let mut res = vec![];
 
         let mes = || format!("scan_prefix: {}", prefix);
 
         let pref = KV::serialize_key(prefix)?;
-        for item in self.tree.scan_prefix(pref) {
+        let now = now_millis();
+        for item in self.tree_of::<KV>().scan_prefix(pref) {
             let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, mes)?;
+            if is_expired(&v, now) {
+                continue;
+            }
 
-            let key = KV::deserialize_key(k)?;
-            let value = KV::deserialize_value(v)?;
+            let key = KV::deserialize_key(&k)?;
+            let value = self.deserialize_value::<KV>(&k, v)?;
             res.push((key, value));
         }
 
@@ -332,44 +1300,240 @@ impl SledTree {
     {
         let mut res = vec![];
 
+        incr_op(METRIC_SLED_TREE_RANGE_SCAN, &self.name, KV::NAME);
+
         let range_mes = self.range_message::<KV, _>(&range);
 
         // Convert K range into sled::IVec range
         let range = KV::serialize_range(&range)?;
+        let now = now_millis();
 
-        for item in self.tree.range(range) {
-            let (_, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+        for item in self.tree_of::<KV>().range(range) {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
                 format!("range_get: {}", range_mes,)
             })?;
+            if is_expired(&v, now) {
+                continue;
+            }
+
+            let ent = self.deserialize_value::<KV>(&k, v)?;
+            res.push(ent);
+        }
+
+        Ok(res)
+    }
+
+    /// Get values of key in `range` in descending key order, e.g., to get the most recent logs.
+    /// The bounds are the same as `range_values`.
+    pub fn range_values_rev<KV, R>(&self, range: R) -> common_exception::Result<Vec<KV::V>>
+    where
+        KV: SledKeySpace,
+        R: RangeBounds<KV::K>,
+    {
+        incr_op(METRIC_SLED_TREE_RANGE_SCAN, &self.name, KV::NAME);
+
+        let mut res = vec![];
+
+        let range_mes = self.range_message::<KV, _>(&range);
+
+        // Convert K range into sled::IVec range
+        let range = KV::serialize_range(&range)?;
+        let now = now_millis();
+
+        for item in self.tree_of::<KV>().range(range).rev() {
+            let (k, v) = item.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("range_get_rev: {}", range_mes,)
+            })?;
+            if is_expired(&v, now) {
+                continue;
+            }
 
-            let ent = KV::deserialize_value(v)?;
+            let ent = self.deserialize_value::<KV>(&k, v)?;
             res.push(ent);
         }
 
         Ok(res)
     }
 
+    /// Get values of key in `range` as a stream.
+    ///
+    /// Unlike `range_values`, it does not load the whole range at once,
+    /// and yields to the async runtime every `stream_yield_interval` items.
+    pub fn range_get_stream<KV, R>(
+        &self,
+        range: R,
+    ) -> common_exception::Result<impl Stream<Item = common_exception::Result<KV::V>>>
+    where
+        KV: SledKeySpace,
+        R: RangeBounds<KV::K>,
+    {
+        incr_op(METRIC_SLED_TREE_RANGE_SCAN, &self.name, KV::NAME);
+
+        let range_mes = self.range_message::<KV, _>(&range);
+        let interval = self.stream_yield_interval;
+
+        // Convert K range into sled::IVec range
+        let range = KV::serialize_range(&range)?;
+        let now = now_millis();
+        let it = self
+            .tree_of::<KV>()
+            .range(range)
+            .filter(move |item| !matches!(item, Ok((_, v)) if is_expired(v, now)));
+
+        let checksum = self.checksum;
+        let cipher = self.cipher.clone();
+        let name = self.name.clone();
+
+        let strm = futures::stream::unfold((it, 0usize), move |(mut it, n)| {
+            let range_mes = range_mes.clone();
+            let name = name.clone();
+            let cipher = cipher.clone();
+            async move {
+                if interval > 0 && n > 0 && n % interval == 0 {
+                    tokio::task::yield_now().await;
+                }
+
+                let item = it.next()?;
+                let res = item
+                    .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                        format!("range_get: {}", range_mes,)
+                    })
+                    .and_then(|(k, v)| {
+                        decode_value::<KV>(&checksum, cipher.as_ref(), &name, &k, v)
+                    });
+
+                Some((res, (it, n + 1)))
+            }
+        });
+
+        Ok(strm)
+    }
+
+    /// Watch the changes of a single key.
+    ///
+    /// The stream emits the new value every time the key is inserted or updated,
+    /// or `None` when it is removed. The changes before this call are not emitted.
+    pub fn watch_key<KV>(
+        &self,
+        key: &KV::K,
+    ) -> common_exception::Result<impl Stream<Item = common_exception::Result<Option<KV::V>>>>
+    where
+        KV: SledKeySpace,
+    {
+        let k = KV::serialize_key(key)?;
+        let subscriber = self.tree_of::<KV>().watch_prefix(k.clone());
+
+        let checksum = self.checksum;
+        let cipher = self.cipher.clone();
+        let name = self.name.clone();
+
+        let strm = futures::stream::unfold(subscriber, move |mut subscriber| {
+            let k = k.clone();
+            let name = name.clone();
+            let cipher = cipher.clone();
+            async move {
+                loop {
+                    let event = (&mut subscriber).await?;
+                    let res = match event {
+                        sled::Event::Insert { key, value } if key == k => {
+                            decode_value::<KV>(&checksum, cipher.as_ref(), &name, &k, value)
+                                .map(Some)
+                        }
+                        sled::Event::Remove { key } if key == k => Ok(None),
+                        // A longer key that has the watched key as its prefix.
+                        _ => continue,
+                    };
+                    return Some((res, subscriber));
+                }
+            }
+        });
+
+        Ok(strm)
+    }
+
     /// Append many key-values into SledTree.
     pub async fn append<KV>(&self, kvs: &[(KV::K, KV::V)]) -> common_exception::Result<()>
     where KV: SledKeySpace {
+        incr_op(METRIC_SLED_TREE_APPEND, &self.name, KV::NAME);
+
         let mut batch = sled::Batch::default();
+        let mut keys = Vec::with_capacity(kvs.len());
 
         for (key, value) in kvs.iter() {
             let k = KV::serialize_key(key)?;
-            let v = KV::serialize_value(value)?;
+            let v = self.serialize_value::<KV>(&k, value, None)?;
 
-            batch.insert(k, v);
+            batch.insert(k.clone(), v);
+            keys.push(k);
         }
 
-        self.tree
-            .apply_batch(batch)
-            .map_err_to_code(ErrorCode::MetaStoreDamaged, || "batch append")?;
+        self.apply_batch(self.tree_of::<KV>(), batch, &keys, || "batch append")?;
 
         self.flush_async(true).await?;
 
         Ok(())
     }
 
+    /// Append many key-values into SledTree, in batches of at most `chunk_size` key-values.
+    ///
+    /// Unlike `append`, a huge import does not build one giant batch: memory is bounded by the chunk size.
+    /// The key-values are not applied atomically as a whole, only every chunk is.
+    /// With `flush_each_chunk`, every applied chunk is flushed, otherwise only the last one is.
+    /// A `chunk_size` of 0 means `DEFAULT_APPEND_CHUNK_SIZE`.
+    ///
+    /// Returns the number of key-values inserted.
+    #[tracing::instrument(level = "debug", skip(self, kvs))]
+    pub async fn append_chunked<KV>(
+        &self,
+        kvs: &[(KV::K, KV::V)],
+        chunk_size: usize,
+        flush_each_chunk: bool,
+    ) -> common_exception::Result<usize>
+    where
+        KV: SledKeySpace,
+    {
+        let chunk_size = if chunk_size == 0 {
+            DEFAULT_APPEND_CHUNK_SIZE
+        } else {
+            chunk_size
+        };
+
+        let mut inserted = 0;
+
+        for chunk in kvs.chunks(chunk_size) {
+            incr_op(METRIC_SLED_TREE_APPEND, &self.name, KV::NAME);
+
+            let mut batch = sled::Batch::default();
+            let mut keys = Vec::with_capacity(chunk.len());
+
+            for (key, value) in chunk.iter() {
+                let k = KV::serialize_key(key)?;
+                let v = self.serialize_value::<KV>(&k, value, None)?;
+
+                batch.insert(k.clone(), v);
+                keys.push(k);
+            }
+
+            self.apply_batch(self.tree_of::<KV>(), batch, &keys, || {
+                "batch append_chunked"
+            })?;
+            self.flush_async(flush_each_chunk).await?;
+
+            inserted += chunk.len();
+            tracing::debug!(
+                "append_chunked {}:{}: {}/{}",
+                self.name,
+                KV::NAME,
+                inserted,
+                kvs.len()
+            );
+        }
+
+        self.flush_async(true).await?;
+
+        Ok(inserted)
+    }
+
     /// Append many values into SledTree.
     /// This could be used in cases the key is included in value and a value should impl trait `IntoKey` to retrieve the key from a value.
     #[tracing::instrument(level = "debug", skip(self, values))]
@@ -378,28 +1542,70 @@ impl SledTree {
         KV: SledKeySpace,
         KV::V: SledValueToKey<KV::K>,
     {
+        incr_op(METRIC_SLED_TREE_APPEND, &self.name, KV::NAME);
+
         let mut batch = sled::Batch::default();
+        let mut keys = Vec::with_capacity(values.len());
 
         for value in values.iter() {
             let key: KV::K = value.to_key();
 
             let k = KV::serialize_key(&key)?;
-            let v = KV::serialize_value(value)?;
+            let v = self.serialize_value::<KV>(&k, value, None)?;
 
-            batch.insert(k, v);
+            batch.insert(k.clone(), v);
+            keys.push(k);
         }
 
-        self.tree
-            .apply_batch(batch)
-            .map_err_to_code(ErrorCode::MetaStoreDamaged, || "batch append_values")?;
+        self.apply_batch(self.tree_of::<KV>(), batch, &keys, || "batch append_values")?;
 
         self.flush_async(true).await?;
 
         Ok(())
     }
 
+    /// Remove many values from SledTree, the key of every value is retrieved with trait `SledValueToKey`.
+    /// It is the counterpart of `append_values`, the keys are removed in one batch.
+    /// Returns the number of the keys that existed, not expired, and are removed.
+    #[tracing::instrument(level = "debug", skip(self, values))]
+    pub async fn remove_values<KV>(&self, values: &[KV::V]) -> common_exception::Result<usize>
+    where
+        KV: SledKeySpace,
+        KV::V: SledValueToKey<KV::K>,
+    {
+        incr_op(METRIC_SLED_TREE_REMOVE, &self.name, KV::NAME);
+
+        let mut keys = BTreeSet::new();
+        for value in values.iter() {
+            let key: KV::K = value.to_key();
+            keys.insert(KV::serialize_key(&key)?);
+        }
+
+        let now = now_millis();
+        let mut batch = sled::Batch::default();
+        let mut removed = 0;
+        for k in keys.iter() {
+            let got = self
+                .tree_of::<KV>()
+                .get(k)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || "batch remove_values")?;
+            if matches!(got, Some(v) if !is_expired(&v, now)) {
+                removed += 1;
+            }
+            batch.remove(k.clone());
+        }
+
+        let keys = keys.into_iter().collect::<Vec<_>>();
+        self.apply_batch(self.tree_of::<KV>(), batch, &keys, || "batch remove_values")?;
+
+        self.flush_async(true).await?;
+
+        Ok(removed)
+    }
+
     /// Insert a single kv.
     /// Returns the last value if it is set.
+    /// It expires after the default TTL of key space `KV` if there is one.
     #[tracing::instrument(level = "debug", skip(self, value))]
     pub async fn insert<KV>(
         &self,
@@ -409,19 +1615,57 @@ impl SledTree {
     where
         KV: SledKeySpace,
     {
+        self.insert_expiring::<KV>(key, value, None).await
+    }
+
+    /// Insert a single kv that expires `ttl` after now, overriding the default TTL of key space `KV`.
+    /// Returns the last value if it is set and not expired.
+    #[tracing::instrument(level = "debug", skip(self, value))]
+    pub async fn insert_with_ttl<KV>(
+        &self,
+        key: &KV::K,
+        value: &KV::V,
+        ttl: Duration,
+    ) -> common_exception::Result<Option<KV::V>>
+    where
+        KV: SledKeySpace,
+    {
+        self.insert_expiring::<KV>(key, value, Some(ttl)).await
+    }
+
+    async fn insert_expiring<KV>(
+        &self,
+        key: &KV::K,
+        value: &KV::V,
+        ttl: Option<Duration>,
+    ) -> common_exception::Result<Option<KV::V>>
+    where
+        KV: SledKeySpace,
+    {
+        incr_op(METRIC_SLED_TREE_INSERT, &self.name, KV::NAME);
+
         let k = KV::serialize_key(key)?;
-        let v = KV::serialize_value(value)?;
+        let v = self.serialize_value::<KV>(&k, value, ttl)?;
 
-        let prev = self
-            .tree
-            .insert(k, v)
-            .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
-                format!("insert_value {}", key)
-            })?;
+        let prev = {
+            let mut cache = self.lock_read_cache();
+
+            let prev = self
+                .tree_of::<KV>()
+                .insert(&k, v)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                    format!("insert_value {}", key)
+                })?;
+
+            if let Some(c) = cache.as_mut() {
+                c.pop(&k);
+            }
+            prev
+        };
 
         let prev = match prev {
-            None => None,
-            Some(x) => Some(KV::deserialize_value(x)?),
+            Some(x) if !is_expired(&x, now_millis()) => Some(self.deserialize_value::<KV>(&k, x)?),
+            _ => None,
         };
 
         self.flush_async(true).await?;
@@ -429,6 +1673,53 @@ impl SledTree {
         Ok(prev)
     }
 
+    /// Insert a single kv only if the key is absent or expired, atomically.
+    /// Returns true if it is inserted, false if the key already exists and nothing is changed.
+    #[tracing::instrument(level = "debug", skip(self, value))]
+    pub async fn insert_if_absent<KV>(
+        &self,
+        key: &KV::K,
+        value: &KV::V,
+    ) -> common_exception::Result<bool>
+    where
+        KV: SledKeySpace,
+    {
+        incr_op(METRIC_SLED_TREE_INSERT, &self.name, KV::NAME);
+
+        let k = KV::serialize_key(key)?;
+        let v = self.serialize_value::<KV>(&k, value, None)?;
+
+        let cas = {
+            let mut cache = self.lock_read_cache();
+
+            let mes = || format!("insert_if_absent: {}:{}", self.name, key);
+
+            // An expired value is taken as absent, it is replaced only if it is not changed meanwhile.
+            let current = self
+                .tree_of::<KV>()
+                .get(&k)
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, mes)?
+                .filter(|x| is_expired(x, now_millis()));
+
+            let cas = self
+                .tree_of::<KV>()
+                .compare_and_swap(&k, current, Some(v))
+                .map_err_to_code(ErrorCode::MetaStoreDamaged, mes)?;
+
+            if let Some(c) = cache.as_mut() {
+                c.pop(&k);
+            }
+            cas
+        };
+
+        let inserted = cas.is_ok();
+        if inserted {
+            self.flush_async(true).await?;
+        }
+
+        Ok(inserted)
+    }
+
     /// Insert a single kv, Retrieve the key from value.
     #[tracing::instrument(level = "debug", skip(self, value))]
     pub async fn insert_value<KV>(&self, value: &KV::V) -> common_exception::Result<Option<KV::V>>
@@ -440,6 +1731,195 @@ impl SledTree {
         self.insert::<KV>(&key, value).await
     }
 
+    /// Move the value of `src_key` in key space `SRC` to `dst_key` in key space `DST`, atomically.
+    ///
+    /// The value is read, written to `DST` and removed from `SRC` in one transaction,
+    /// across the trees if either key space is isolated: nothing is changed if any step fails.
+    /// The value keeps its expiry if it has one, otherwise it gets the default TTL of `DST` if there is one.
+    /// It fails with `MetaStoreNotFound` if `src_key` is absent or expired.
+    /// A move of a key onto itself changes nothing.
+    /// Returns the value moved.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn move_key<SRC, DST>(
+        &self,
+        src_key: &SRC::K,
+        dst_key: &DST::K,
+    ) -> common_exception::Result<SRC::V>
+    where
+        SRC: SledKeySpace,
+        DST: SledKeySpace<V = SRC::V>,
+    {
+        incr_op(METRIC_SLED_TREE_INSERT, &self.name, DST::NAME);
+        incr_op(METRIC_SLED_TREE_REMOVE, &self.name, SRC::NAME);
+
+        let src_k = SRC::serialize_key(src_key)?;
+        let dst_k = DST::serialize_key(dst_key)?;
+
+        let mes = || {
+            format!(
+                "move_key: {}:{}/{} to {}/{}",
+                self.name,
+                SRC::NAME,
+                src_key,
+                DST::NAME,
+                dst_key
+            )
+        };
+
+        let src_tree = self.tree_of::<SRC>();
+        let dst_tree = self.tree_of::<DST>();
+        let trees = if std::ptr::eq(src_tree, dst_tree) {
+            vec![src_tree]
+        } else {
+            vec![src_tree, dst_tree]
+        };
+
+        let moved = {
+            let mut cache = self.lock_read_cache();
+
+            let moved = trees
+                .as_slice()
+                .transaction(|tx_trees| {
+                    let (tx_src, tx_dst) = (&tx_trees[0], &tx_trees[tx_trees.len() - 1]);
+                    let abort = ConflictableTransactionError::Abort;
+
+                    let now = now_millis();
+                    let v = match tx_src.get(&src_k)? {
+                        Some(v) if !is_expired(&v, now) => v,
+                        _ => return Err(abort(ErrorCode::MetaStoreNotFound(mes()))),
+                    };
+
+                    let value = self.deserialize_value::<SRC>(&src_k, &v).map_err(abort)?;
+
+                    // Moving a key onto itself changes nothing, removing the source would delete it.
+                    if src_k == dst_k {
+                        return Ok(value);
+                    }
+
+                    let ttl = match split_expiry(&v).map_err(abort)? {
+                        (Some(expire_at), _) => {
+                            Some(Duration::from_millis(expire_at.saturating_sub(now)))
+                        }
+                        (None, _) => None,
+                    };
+                    let dst_v = self
+                        .serialize_value::<DST>(&dst_k, &value, ttl)
+                        .map_err(abort)?;
+
+                    tx_dst.insert(dst_k.as_ref(), dst_v)?;
+                    tx_src.remove(src_k.as_ref())?;
+                    Ok(value)
+                })
+                .map_err(|e| match e {
+                    TransactionError::Abort(e) => e,
+                    TransactionError::Storage(e) => {
+                        ErrorCode::MetaStoreDamaged(format!("{}: {}", mes(), e))
+                    }
+                })?;
+
+            if let Some(c) = cache.as_mut() {
+                c.pop(&src_k);
+                c.pop(&dst_k);
+            }
+            moved
+        };
+
+        self.flush_async(true).await?;
+
+        Ok(moved)
+    }
+
+    /// Count the entries of every key space in this tree, for diagnosis.
+    ///
+    /// `known` maps a key space prefix to its name, e.g. `(KV::PREFIX, KV::NAME)`.
+    /// It returns `(name, count)` ordered by prefix,
+    /// entries with a prefix not in `known` are counted in an "unknown" bucket, which is the last one.
+    /// It scans the whole tree.
+    pub fn describe(&self, known: &[(u8, &str)]) -> common_exception::Result<Vec<(String, usize)>> {
+        let mut counts = BTreeMap::new();
+        let mut unknown = 0;
+
+        for k in self.trees().flat_map(|t| t.iter().keys()) {
+            let k = k.map_err_to_code(ErrorCode::MetaStoreDamaged, || {
+                format!("describe: {}", self.name)
+            })?;
+
+            match k.first() {
+                Some(prefix) if known.iter().any(|(p, _)| p == prefix) => {
+                    *counts.entry(*prefix).or_insert(0) += 1;
+                }
+                _ => unknown += 1,
+            }
+        }
+
+        let mut res = vec![];
+        for (prefix, count) in counts {
+            let (_, name) = known.iter().find(|(p, _)| *p == prefix).unwrap();
+            res.push((name.to_string(), count));
+        }
+        if unknown > 0 {
+            res.push(("unknown".to_string(), unknown));
+        }
+
+        Ok(res)
+    }
+
+    /// Apply a batch writing `keys` to `tree` and invalidate them in the read cache.
+    fn apply_batch<M, F>(
+        &self,
+        tree: &sled::Tree,
+        batch: sled::Batch,
+        keys: &[IVec],
+        mes: F,
+    ) -> common_exception::Result<()>
+    where
+        M: Display,
+        F: FnOnce() -> M,
+    {
+        let mut cache = self.lock_read_cache();
+
+        tree.apply_batch(batch)
+            .map_err_to_code(ErrorCode::MetaStoreDamaged, mes)?;
+
+        if let Some(c) = cache.as_mut() {
+            for k in keys.iter() {
+                c.pop(k);
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize a value to store with the serialized key `k`, with a checksum if enabled, encrypted if enabled,
+    /// and with an expiry if `ttl` is given or key space `KV` has a default TTL.
+    fn serialize_value<KV: SledKeySpace>(
+        &self,
+        k: &[u8],
+        v: &KV::V,
+        ttl: Option<Duration>,
+    ) -> common_exception::Result<IVec> {
+        let v = self.checksum.serialize_value::<KV>(v)?;
+
+        let v = match &self.cipher {
+            Some(cipher) => cipher.encrypt(k, &v)?,
+            None => v,
+        };
+
+        let v = match ttl.or_else(|| self.default_ttls.get(&KV::PREFIX).copied()) {
+            Some(ttl) => with_expiry(v, ttl),
+            None => v,
+        };
+        Ok(v)
+    }
+
+    /// Decrypt a value stored with the serialized key `k`, verify its checksum, and deserialize it.
+    fn deserialize_value<KV: SledKeySpace>(
+        &self,
+        k: &[u8],
+        v: impl AsRef<[u8]>,
+    ) -> common_exception::Result<KV::V> {
+        decode_value::<KV>(&self.checksum, self.cipher.as_ref(), &self.name, k, v)
+    }
+
     /// Build a string describing the range for a range operation.
     fn range_message<KV, R>(&self, range: &R) -> String
     where
@@ -455,18 +1935,57 @@ impl SledTree {
         )
     }
 
+    /// Flush all dirty data of the tree to disk.
+    ///
+    /// Unlike the flush done by every write, it ignores the `sync` flag and always flushes.
+    /// A caller that writes with `sync=false` uses it as a single durability barrier at the end.
+    ///
+    /// Concurrent flushes are coalesced: one flush makes the writes of all the callers waiting for it durable.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn force_flush(&self) -> common_exception::Result<()> {
+        // sled flushes the whole db, the dedicated trees of the isolated key spaces included.
+        self.flusher.flush(&self.name, &self.tree).await
+    }
+
+    /// The number of flushes actually done through this SledTree and its clones.
+    pub fn flush_count(&self) -> u64 {
+        self.flusher.flushes()
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     async fn flush_async(&self, flush: bool) -> common_exception::Result<()> {
         if flush && self.sync {
-            self.tree
-                .flush_async()
-                .await
-                .map_err_to_code(ErrorCode::MetaStoreDamaged, || "flush sled-tree")?;
+            self.force_flush().await?;
         }
         Ok(())
     }
 }
 
+/// Decode a value stored with the serialized key `k`:
+/// skip its expiry, it is up to the caller to check it, decrypt it if encrypted, verify its checksum and deserialize it.
+/// `tree` is only used to tell which value fails.
+fn decode_value<KV: SledKeySpace>(
+    checksum: &ValueChecksum,
+    cipher: Option<&ValueCipher>,
+    tree: &str,
+    k: &[u8],
+    v: impl AsRef<[u8]>,
+) -> common_exception::Result<KV::V> {
+    let key_name = || ValueChecksum::key_name::<KV>(k);
+
+    let (_expire_at, v) = split_expiry(v.as_ref()).map_err(|e| {
+        ErrorCode::MetaStoreDamaged(format!(
+            "{}, tree: {}, key: {}",
+            e.message(),
+            tree,
+            key_name()
+        ))
+    })?;
+
+    let v = ValueCipher::decrypt(cipher, tree, key_name, k, v)?;
+    checksum.deserialize_value::<KV>(tree, k, &v)
+}
+
 /// It borrows the internal SledTree with access limited to a specified namespace `KV`.
 pub struct AsKeySpace<'a, KV: SledKeySpace> {
     inner: &'a SledTree,
@@ -510,11 +2029,20 @@ impl<'a, KV: SledKeySpace> AsKeySpace<'a, KV> {
         self.inner.range_remove::<KV, R>(range, flush).await
     }
 
+    pub async fn clear(&self, flush: bool) -> common_exception::Result<()> {
+        self.inner.clear_key_space::<KV>(flush).await
+    }
+
     pub fn range_keys<R>(&self, range: R) -> common_exception::Result<Vec<KV::K>>
     where R: RangeBounds<KV::K> {
         self.inner.range_keys::<KV, R>(range)
     }
 
+    pub fn range_keys_rev<R>(&self, range: R) -> common_exception::Result<Vec<KV::K>>
+    where R: RangeBounds<KV::K> {
+        self.inner.range_keys_rev::<KV, R>(range)
+    }
+
     pub fn range<R>(
         &self,
         range: R,
@@ -541,10 +2069,43 @@ impl<'a, KV: SledKeySpace> AsKeySpace<'a, KV> {
         self.inner.range_values::<KV, R>(range)
     }
 
+    pub fn range_values_rev<R>(&self, range: R) -> common_exception::Result<Vec<KV::V>>
+    where R: RangeBounds<KV::K> {
+        self.inner.range_values_rev::<KV, R>(range)
+    }
+
+    pub fn range_get_stream<R>(
+        &self,
+        range: R,
+    ) -> common_exception::Result<impl Stream<Item = common_exception::Result<KV::V>>>
+    where
+        R: RangeBounds<KV::K>,
+    {
+        self.inner.range_get_stream::<KV, R>(range)
+    }
+
+    pub fn watch_key(
+        &self,
+        key: &KV::K,
+    ) -> common_exception::Result<impl Stream<Item = common_exception::Result<Option<KV::V>>>> {
+        self.inner.watch_key::<KV>(key)
+    }
+
     pub async fn append(&self, kvs: &[(KV::K, KV::V)]) -> common_exception::Result<()> {
         self.inner.append::<KV>(kvs).await
     }
 
+    pub async fn append_chunked(
+        &self,
+        kvs: &[(KV::K, KV::V)],
+        chunk_size: usize,
+        flush_each_chunk: bool,
+    ) -> common_exception::Result<usize> {
+        self.inner
+            .append_chunked::<KV>(kvs, chunk_size, flush_each_chunk)
+            .await
+    }
+
     pub async fn append_values(&self, values: &[KV::V]) -> common_exception::Result<()>
     where KV::V: SledValueToKey<KV::K> {
         self.inner.append_values::<KV>(values).await
@@ -558,6 +2119,55 @@ impl<'a, KV: SledKeySpace> AsKeySpace<'a, KV> {
         self.inner.insert::<KV>(key, value).await
     }
 
+    pub fn get_lenient<F>(
+        &self,
+        key: &KV::K,
+        on_corrupt: F,
+    ) -> common_exception::Result<Option<KV::V>>
+    where
+        F: FnOnce(&[u8], &[u8]),
+    {
+        self.inner.get_lenient::<KV, F>(key, on_corrupt)
+    }
+
+    pub fn range_kvs_lenient<R, F>(
+        &self,
+        range: R,
+        on_corrupt: F,
+    ) -> common_exception::Result<Vec<(KV::K, KV::V)>>
+    where
+        R: RangeBounds<KV::K>,
+        F: FnMut(&[u8], &[u8]),
+    {
+        self.inner.range_kvs_lenient::<KV, R, F>(range, on_corrupt)
+    }
+
+    pub async fn remove_values(&self, values: &[KV::V]) -> common_exception::Result<usize>
+    where KV::V: SledValueToKey<KV::K> {
+        self.inner.remove_values::<KV>(values).await
+    }
+
+    pub async fn insert_with_ttl(
+        &self,
+        key: &KV::K,
+        value: &KV::V,
+        ttl: Duration,
+    ) -> common_exception::Result<Option<KV::V>> {
+        self.inner.insert_with_ttl::<KV>(key, value, ttl).await
+    }
+
+    pub async fn remove_expired(&self, flush: bool) -> common_exception::Result<usize> {
+        self.inner.remove_expired::<KV>(flush).await
+    }
+
+    pub async fn insert_if_absent(
+        &self,
+        key: &KV::K,
+        value: &KV::V,
+    ) -> common_exception::Result<bool> {
+        self.inner.insert_if_absent::<KV>(key, value).await
+    }
+
     pub async fn insert_value(&self, value: &KV::V) -> common_exception::Result<Option<KV::V>>
     where KV::V: SledValueToKey<KV::K> {
         self.inner.insert_value::<KV>(value).await
@@ -12,28 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Bound;
+use std::time::Duration;
+
 use async_raft::raft::Entry;
 use async_raft::raft::EntryNormal;
 use async_raft::raft::EntryPayload;
 use common_base::tokio;
 use common_base::GlobalSequence;
+use common_exception::ErrorCode;
 use common_meta_types::Cmd;
 use common_meta_types::KVValue;
 use common_meta_types::LogEntry;
 use common_meta_types::LogId;
 use common_meta_types::LogIndex;
+use common_meta_types::Node;
+use futures::StreamExt;
+use futures::TryStreamExt;
 
 use crate::get_sled_db;
 use crate::testing::fake_key_spaces::Files;
 use crate::testing::fake_key_spaces::GenericKV;
 use crate::testing::fake_key_spaces::Logs;
 use crate::testing::fake_key_spaces::Nodes;
+use crate::testing::fake_key_spaces::SignedKeys;
 use crate::testing::fake_key_spaces::StateMachineMeta;
+use crate::testing::fake_key_spaces::Tables;
 use crate::testing::fake_state_machine_meta::StateMachineMetaKey::Initialized;
 use crate::testing::fake_state_machine_meta::StateMachineMetaKey::LastApplied;
 use crate::testing::fake_state_machine_meta::StateMachineMetaValue;
+use crate::CompositeKey;
+use crate::IsolatedKeySpace;
+use crate::MultiRangeDelete;
+use crate::SledDbOptions;
 use crate::SledKeySpace;
+use crate::SledOrderedSerde;
 use crate::SledTree;
+use crate::DEFAULT_STREAM_YIELD_INTERVAL;
+use crate::VALUE_FORMAT_VERSION;
 
 /// 1. Open a temp sled::Db for all tests.
 /// 2. Initialize a global tracing.
@@ -60,7 +76,39 @@ async fn test_sled_tree_open() -> anyhow::Result<()> {
 
     let tc = new_sled_test_context();
     let db = &tc.db;
-    SledTree::open(db, tc.tree_name, true)?;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    // The db options are observable.
+    let debug = format!("{:?}", tree);
+    assert!(
+        debug.contains(&format!("db_options: Some({:?})", SledDbOptions::default())),
+        "{}",
+        debug
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[should_panic(expected = "tree name in unit test must start with")]
+async fn test_sled_tree_open_unprefixed_name() {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let db = get_sled_db();
+    let _ = SledTree::open(&db, format!("state_machine/{}", next_port()), true);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_open_any_name() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    // A real tree name can be opened on purpose.
+    let db = get_sled_db();
+    let name = format!("state_machine/{}", next_port());
+    let tree = SledTree::open_any_name(&db, &name, true)?;
+    assert_eq!(name, tree.name);
 
     Ok(())
 }
@@ -200,6 +248,405 @@ async fn test_sled_tree_append_values_and_range_get() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_range_get_stream() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let mut tree = SledTree::open(db, tc.tree_name, true)?;
+
+    let logs: Vec<Entry<LogEntry>> = (0..10)
+        .map(|i| Entry {
+            log_id: LogId { term: 1, index: i },
+            payload: EntryPayload::Blank,
+        })
+        .collect();
+
+    tree.append_values::<Logs>(&logs).await?;
+
+    for interval in [0, 1, 3, DEFAULT_STREAM_YIELD_INTERVAL] {
+        tree.set_stream_yield_interval(interval);
+
+        let strm = tree.range_get_stream::<Logs, _>(..)?;
+        let got = strm.try_collect::<Vec<_>>().await?;
+        assert_eq!(logs, got, "yield interval: {}", interval);
+
+        let strm = tree.range_get_stream::<Logs, _>(2..5)?;
+        let got = strm.try_collect::<Vec<_>>().await?;
+        assert_eq!(logs[2..5], got, "yield interval: {}", interval);
+
+        let strm = tree.key_space::<Logs>().range_get_stream(8..)?;
+        let got = strm.try_collect::<Vec<_>>().await?;
+        assert_eq!(logs[8..], got, "yield interval: {}", interval);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_watch_key() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    let strm = tree.key_space::<Files>().watch_key(&"a".to_string())?;
+
+    // Not the watched key, though it has the same prefix.
+    tree.insert::<Files>(&"ab".to_string(), &"0".to_string())
+        .await?;
+    tree.insert::<Files>(&"a".to_string(), &"1".to_string())
+        .await?;
+    tree.insert::<Files>(&"a".to_string(), &"2".to_string())
+        .await?;
+    tree.remove::<Files>(&"a".to_string(), false).await?;
+
+    let got = strm.take(3).try_collect::<Vec<_>>().await?;
+    assert_eq!(
+        vec![Some("1".to_string()), Some("2".to_string()), None],
+        got
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_coalesce_flush() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    let n = 64;
+    let keys = (0..n).map(|i| format!("{:02}", i)).collect::<Vec<_>>();
+
+    // Every insert flushes before returning, the concurrent ones share a flush.
+    let inserts = keys.iter().map(|k| {
+        let tree = tree.clone();
+        async move { tree.insert::<Files>(k, k).await }
+    });
+    futures::future::try_join_all(inserts).await?;
+
+    let flushes = tree.flush_count();
+    assert!(flushes >= 1);
+    assert!(flushes < n, "{} flushes for {} inserts", flushes, n);
+
+    for k in keys.iter() {
+        assert_eq!(Some(k.clone()), tree.get::<Files>(k)?);
+    }
+
+    // A flush after the concurrent ones is not coalesced with them.
+    tree.insert::<Files>(&"a".to_string(), &"1".to_string())
+        .await?;
+    assert_eq!(flushes + 1, tree.flush_count());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_checksum() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let mut tree = SledTree::open(db, tc.tree_name, true)?;
+
+    // A value written before checksum is enabled.
+    tree.insert::<Files>(&"old".to_string(), &"0".to_string())
+        .await?;
+
+    tree.enable_checksum(true);
+    tree.insert::<Files>(&"a".to_string(), &"1".to_string())
+        .await?;
+
+    // Checksummed values and values without checksum are both read during migration.
+    assert_eq!(Some("1".to_string()), tree.get::<Files>(&"a".to_string())?);
+    assert_eq!(
+        Some("0".to_string()),
+        tree.get::<Files>(&"old".to_string())?
+    );
+    assert_eq!(2, tree.range_kvs::<Files, _>(..)?.len());
+
+    // Flip a byte of the stored value.
+    let k = Files::serialize_key(&"a".to_string())?;
+    let mut raw = tree.tree.get(&k)?.unwrap().to_vec();
+    let last = raw.len() - 1;
+    raw[last] ^= 0x01;
+    tree.tree.insert(&k, raw)?;
+
+    let err = tree.get::<Files>(&"a".to_string()).unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreDamaged("").code(), err.code());
+    assert!(
+        err.message().contains("checksum mismatch"),
+        "{}",
+        err.message()
+    );
+    assert!(err.message().contains("files:a"), "{}", err.message());
+
+    let res = tree.range_kvs::<Files, _>(..);
+    assert_eq!(
+        ErrorCode::MetaStoreDamaged("").code(),
+        res.unwrap_err().code()
+    );
+
+    // After migration, a value without checksum is rejected.
+    tree.enable_checksum(false);
+    let err = tree.get::<Files>(&"old".to_string()).unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreDamaged("").code(), err.code());
+    assert!(
+        err.message().contains("without checksum"),
+        "{}",
+        err.message()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_format_version() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    // A value is written with the format version of this build.
+    tree.insert::<Files>(&"a".to_string(), &"1".to_string())
+        .await?;
+    let k = Files::serialize_key(&"a".to_string())?;
+    let raw = tree.tree.get(&k)?.unwrap();
+    assert_eq!(VALUE_FORMAT_VERSION, raw[1]);
+    assert_eq!(Some("1".to_string()), tree.get::<Files>(&"a".to_string())?);
+
+    // A value written before the format version is introduced is still read.
+    let payload = Files::serialize_value(&"0".to_string())?;
+    tree.tree.insert(&k, payload.clone())?;
+    assert_eq!(Some("0".to_string()), tree.get::<Files>(&"a".to_string())?);
+
+    // A value written by a newer build is a version skew, not a damaged value.
+    let mut future = raw[..1].to_vec();
+    future.push(VALUE_FORMAT_VERSION + 1);
+    future.extend_from_slice(&payload);
+    tree.tree.insert(&k, future)?;
+
+    let err = tree.get::<Files>(&"a".to_string()).unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreVersionSkew("").code(), err.code());
+    let message = err.message();
+    assert!(
+        message.contains(&format!(
+            "version {} is newer than {}",
+            VALUE_FORMAT_VERSION + 1,
+            VALUE_FORMAT_VERSION
+        )),
+        "{}",
+        message
+    );
+    assert!(message.contains("files:a"), "{}", message);
+
+    let res = tree.range_values::<Files, _>(..);
+    assert_eq!(
+        ErrorCode::MetaStoreVersionSkew("").code(),
+        res.unwrap_err().code()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_encryption() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let mut tree = SledTree::open(db, &tc.tree_name, true)?;
+
+    let err = tree.enable_encryption(&[1u8; 16], false).unwrap_err();
+    assert_eq!(ErrorCode::InvalidConfig("").code(), err.code());
+
+    // The checksummed value is encrypted, the expiry stays in front of it.
+    tree.enable_checksum(false);
+    tree.enable_encryption(&[1u8; 32], false)?;
+    tree.insert::<Files>(&"a".to_string(), &"secret-1".to_string())
+        .await?;
+    tree.insert_with_ttl::<Files>(
+        &"b".to_string(),
+        &"secret-2".to_string(),
+        Duration::from_secs(3600),
+    )
+    .await?;
+
+    // Nothing in plaintext is stored.
+    for item in tree.tree.iter() {
+        let (_k, v) = item?;
+        assert!(!String::from_utf8_lossy(&v).contains("secret"));
+    }
+
+    assert_eq!(
+        Some("secret-1".to_string()),
+        tree.get::<Files>(&"a".to_string())?
+    );
+    assert_eq!(
+        vec!["secret-1".to_string(), "secret-2".to_string()],
+        tree.range_values::<Files, _>(..)?
+    );
+
+    // Another tree derives another key from the same master key.
+    let k = Files::serialize_key(&"a".to_string())?;
+    let raw = tree.tree.get(&k)?.unwrap();
+    let mut other = SledTree::open(db, format!("{}-other", tc.tree_name), true)?;
+    other.enable_encryption(&[1u8; 32], false)?;
+    other.tree.insert(&k, raw)?;
+    let err = other.get::<Files>(&"a".to_string()).unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreDamaged("").code(), err.code());
+
+    // A wrong key fails cleanly.
+    tree.enable_encryption(&[2u8; 32], false)?;
+    let err = tree.get::<Files>(&"a".to_string()).unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreDamaged("").code(), err.code());
+    assert!(
+        err.message().contains("can not decrypt"),
+        "{}",
+        err.message()
+    );
+    assert!(err.message().contains("files:a"), "{}", err.message());
+
+    // An encrypted value is rejected without a key.
+    let mut plain = SledTree::open(db, &tc.tree_name, true)?;
+    let err = plain.get::<Files>(&"a".to_string()).unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreDamaged("").code(), err.code());
+
+    plain.enable_encryption(&[1u8; 32], false)?;
+    assert_eq!(
+        Some("secret-1".to_string()),
+        plain.get::<Files>(&"a".to_string())?
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_encrypt_plaintext_values() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let mut tree = SledTree::open(db, &tc.tree_name, true)?;
+
+    // Values written before encryption is enabled.
+    tree.insert::<Files>(&"a".to_string(), &"1".to_string())
+        .await?;
+    tree.insert_with_ttl::<Files>(
+        &"b".to_string(),
+        &"2".to_string(),
+        Duration::from_secs(3600),
+    )
+    .await?;
+
+    let err = tree.encrypt_plaintext_values().unwrap_err();
+    assert_eq!(ErrorCode::LogicalError("").code(), err.code());
+
+    tree.enable_encryption(&[1u8; 32], true)?;
+    tree.insert::<Files>(&"c".to_string(), &"3".to_string())
+        .await?;
+
+    // Plaintext and encrypted values are both read during migration.
+    assert_eq!(
+        vec!["1".to_string(), "2".to_string(), "3".to_string()],
+        tree.range_values::<Files, _>(..)?
+    );
+
+    assert_eq!(2, tree.encrypt_plaintext_values()?);
+    assert_eq!(0, tree.encrypt_plaintext_values()?);
+
+    assert_eq!(
+        vec!["1".to_string(), "2".to_string(), "3".to_string()],
+        tree.range_values::<Files, _>(..)?
+    );
+
+    // After migration, a plaintext value is rejected.
+    tree.tree
+        .insert(Files::serialize_key(&"d".to_string())?, "\"4\"".as_bytes())?;
+    let err = tree.get::<Files>(&"d".to_string()).unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreDamaged("").code(), err.code());
+    assert!(err.message().contains("plaintext"), "{}", err.message());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_default_ttl() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let mut tree = SledTree::open(db, tc.tree_name, true)?;
+
+    // The expiry is in front of a checksummed value.
+    tree.enable_checksum(false);
+    tree.set_default_ttl::<Files>(Duration::from_millis(200));
+
+    // Inserted without a TTL into a key space with a default TTL.
+    tree.insert::<Files>(&"a".to_string(), &"1".to_string())
+        .await?;
+    tree.append::<Files>(&[("b".to_string(), "2".to_string())])
+        .await?;
+    // An explicit TTL overrides the default one.
+    tree.insert_with_ttl::<Files>(
+        &"c".to_string(),
+        &"3".to_string(),
+        Duration::from_secs(3600),
+    )
+    .await?;
+    // Another key space without a default TTL never expires.
+    tree.insert::<SignedKeys>(&1, &"x".to_string()).await?;
+
+    assert_eq!(Some("1".to_string()), tree.get::<Files>(&"a".to_string())?);
+    assert_eq!(3, tree.range_kvs::<Files, _>(..)?.len());
+    assert_eq!(0, tree.remove_expired::<Files>(true).await?);
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    assert_eq!(None, tree.get::<Files>(&"a".to_string())?);
+    assert!(!tree.contains_key::<Files>(&"b".to_string())?);
+    assert_eq!(
+        vec![("c".to_string(), "3".to_string())],
+        tree.range_kvs::<Files, _>(..)?
+    );
+    assert_eq!(vec!["c".to_string()], tree.range_keys::<Files, _>(..)?);
+    assert_eq!(Some("x".to_string()), tree.get::<SignedKeys>(&1)?);
+
+    // An expired key is taken as absent.
+    assert!(
+        tree.insert_if_absent::<Files>(&"a".to_string(), &"4".to_string())
+            .await?
+    );
+    assert_eq!(Some("4".to_string()), tree.get::<Files>(&"a".to_string())?);
+
+    // Only the expired "b" is left to sweep.
+    assert_eq!(1, tree.remove_expired::<Files>(true).await?);
+    assert_eq!(
+        None,
+        tree.tree.get(Files::serialize_key(&"b".to_string())?)?
+    );
+    assert_eq!(
+        Some("3".to_string()),
+        tree.key_space::<Files>().get(&"c".to_string())?
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_sled_tree_range_keys() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_sled_ut!();
@@ -467,6 +914,73 @@ async fn test_sled_tree_insert() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_sled_tree_insert_if_absent() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    let claims: Vec<Entry<LogEntry>> = vec![
+        Entry {
+            log_id: LogId { term: 1, index: 5 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 2, index: 5 },
+            payload: EntryPayload::Blank,
+        },
+    ];
+
+    // Two racing claims of the same key, exactly one wins.
+
+    let mut handles = vec![];
+    for claim in claims.iter() {
+        let tree = tree.clone();
+        let claim = claim.clone();
+        handles.push(tokio::spawn(async move {
+            tree.insert_if_absent::<Logs>(&5, &claim).await
+        }));
+    }
+
+    let mut inserted = vec![];
+    for handle in handles {
+        inserted.push(handle.await??);
+    }
+    assert_eq!(1, inserted.iter().filter(|x| **x).count());
+
+    let winner = inserted.iter().position(|x| *x).unwrap();
+    assert_eq!(Some(claims[winner].clone()), tree.get::<Logs>(&5)?);
+
+    // An existing key is never overridden.
+
+    assert!(
+        !tree
+            .insert_if_absent::<Logs>(&5, &claims[1 - winner])
+            .await?
+    );
+    assert_eq!(Some(claims[winner].clone()), tree.get::<Logs>(&5)?);
+
+    // Through the key space wrapper.
+
+    assert!(tree.key_space::<Logs>().get(&6)?.is_none());
+    assert!(
+        tree.key_space::<Logs>()
+            .insert_if_absent(&6, &claims[0])
+            .await?
+    );
+    assert!(
+        !tree
+            .key_space::<Logs>()
+            .insert_if_absent(&6, &claims[1])
+            .await?
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_sled_tree_contains_key() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_sled_ut!();
@@ -529,6 +1043,47 @@ async fn test_sled_tree_update_and_fetch() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_update_and_fetch_damaged() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let mut tree = SledTree::open(db, tc.tree_name, true)?;
+    tree.enable_checksum(true);
+
+    tree.insert::<Files>(&"foo".to_string(), &"1".to_string())
+        .await?;
+
+    // Flip a byte of the stored value.
+    let k = Files::serialize_key(&"foo".to_string())?;
+    let mut raw = tree.tree.get(&k)?.unwrap().to_vec();
+    let last = raw.len() - 1;
+    raw[last] ^= 0x01;
+    tree.tree.insert(&k, raw.clone())?;
+
+    let mut called = false;
+    let res = tree
+        .update_and_fetch::<Files, _>(&"foo".to_string(), |v| {
+            called = true;
+            Some(v.unwrap_or_default() + "1")
+        })
+        .await;
+
+    assert_eq!(
+        ErrorCode::MetaStoreDamaged("").code(),
+        res.unwrap_err().code()
+    );
+    assert!(
+        !called,
+        "the damaged value must not be passed to the update"
+    );
+    assert_eq!(raw, tree.tree.get(&k)?.unwrap().to_vec());
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_sled_tree_get() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_sled_ut!();
@@ -570,19 +1125,16 @@ async fn test_sled_tree_get() -> anyhow::Result<()> {
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
-async fn test_sled_tree_last() -> anyhow::Result<()> {
+async fn test_sled_tree_get_with_read_cache() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_sled_ut!();
     let _ent = ut_span.enter();
 
-    // This test assumes the following order.
-    // To ensure a last() does not returns item from another key space with smaller prefix
-    assert!(Logs::PREFIX < StateMachineMeta::PREFIX);
-
     let tc = new_sled_test_context();
     let db = &tc.db;
-    let tree = SledTree::open(db, tc.tree_name, true)?;
+    let mut tree = SledTree::open(db, tc.tree_name, true)?;
+    assert_eq!(None, tree.read_cache_stats());
 
-    assert!(tree.last::<Logs>()?.is_none());
+    tree.enable_read_cache(16);
 
     let logs: Vec<Entry<LogEntry>> = vec![
         Entry {
@@ -590,23 +1142,96 @@ async fn test_sled_tree_last() -> anyhow::Result<()> {
             payload: EntryPayload::Blank,
         },
         Entry {
-            log_id: LogId { term: 3, index: 4 },
-            payload: EntryPayload::Normal(EntryNormal {
-                data: LogEntry {
-                    txid: None,
-                    cmd: Cmd::IncrSeq {
-                        key: "foo".to_string(),
-                    },
-                },
-            }),
+            log_id: LogId { term: 3, index: 2 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 1, index: 9 },
+            payload: EntryPayload::Blank,
         },
     ];
 
-    tree.append_values::<Logs>(&logs).await?;
-    assert_eq!(None, tree.last::<StateMachineMeta>()?);
+    // An absent key is cached too.
+    assert_eq!(None, tree.get::<Logs>(&2)?);
+    assert_eq!(None, tree.get::<Logs>(&2)?);
+    assert_eq!(Some((1, 1)), tree.read_cache_stats());
 
-    let metas = vec![
-        (
+    // Read heavy: only the first get reads sled.
+    tree.insert::<Logs>(&2, &logs[0]).await?;
+    for _ in 0..10 {
+        assert_eq!(Some(logs[0].clone()), tree.get::<Logs>(&2)?);
+    }
+    assert_eq!(Some((10, 2)), tree.read_cache_stats());
+
+    // Every write invalidates the keys it writes.
+    tree.insert::<Logs>(&2, &logs[1]).await?;
+    assert_eq!(Some(logs[1].clone()), tree.get::<Logs>(&2)?);
+
+    tree.remove::<Logs>(&2, true).await?;
+    assert_eq!(None, tree.get::<Logs>(&2)?);
+
+    tree.append_values::<Logs>(&logs[1..]).await?;
+    assert_eq!(Some(logs[1].clone()), tree.get::<Logs>(&2)?);
+    assert_eq!(Some(logs[2].clone()), tree.get::<Logs>(&9)?);
+
+    tree.range_remove::<Logs, _>(9.., true).await?;
+    assert_eq!(None, tree.get::<Logs>(&9)?);
+
+    tree.update_and_fetch::<Logs, _>(&9, |_| Some(logs[2].clone()))
+        .await?;
+    assert_eq!(Some(logs[2].clone()), tree.get::<Logs>(&9)?);
+
+    tree.clear_key_space::<Logs>(true).await?;
+    assert_eq!(None, tree.get::<Logs>(&2)?);
+    assert_eq!(None, tree.get::<Logs>(&9)?);
+
+    // The cache is shared by clones.
+    let cloned = tree.clone();
+    cloned.insert::<Logs>(&2, &logs[0]).await?;
+    assert_eq!(Some(logs[0].clone()), tree.get::<Logs>(&2)?);
+    assert_eq!(tree.read_cache_stats(), cloned.read_cache_stats());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_last() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    // This test assumes the following order.
+    // To ensure a last() does not returns item from another key space with smaller prefix
+    assert!(Logs::PREFIX < StateMachineMeta::PREFIX);
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    assert!(tree.last::<Logs>()?.is_none());
+
+    let logs: Vec<Entry<LogEntry>> = vec![
+        Entry {
+            log_id: LogId { term: 1, index: 2 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 3, index: 4 },
+            payload: EntryPayload::Normal(EntryNormal {
+                data: LogEntry {
+                    txid: None,
+                    cmd: Cmd::IncrSeq {
+                        key: "foo".to_string(),
+                    },
+                },
+            }),
+        },
+    ];
+
+    tree.append_values::<Logs>(&logs).await?;
+    assert_eq!(None, tree.last::<StateMachineMeta>()?);
+
+    let metas = vec![
+        (
             LastApplied,
             StateMachineMetaValue::LogId(LogId { term: 1, index: 2 }),
         ),
@@ -662,6 +1287,560 @@ async fn test_sled_tree_remove() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_append_chunked() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    // 2.5 chunks.
+    let kvs = (0..250u64)
+        .map(|i| (i, format!("v{}", i)))
+        .map(|(i, v)| (format!("{:03}", i), v))
+        .collect::<Vec<_>>();
+
+    let inserted = tree.append_chunked::<Files>(&kvs, 100, false).await?;
+    assert_eq!(250, inserted);
+    assert_eq!(kvs, tree.range_kvs::<Files, _>(..)?);
+
+    // Default chunk size, every chunk flushed.
+    let inserted = tree
+        .key_space::<Files>()
+        .append_chunked(&kvs[..10], 0, true)
+        .await?;
+    assert_eq!(10, inserted);
+
+    // Nothing to insert.
+    let inserted = tree.append_chunked::<Files>(&[], 100, false).await?;
+    assert_eq!(0, inserted);
+    assert_eq!(250, tree.range_keys::<Files, _>(..)?.len());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_compact() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    // Use a db of its own, the size of the shared one changes with other tests.
+    // The tree does not sync, thus only sync operations are used on the db.
+    let db = sled::Config::new().temporary(true).open()?;
+    let tc = new_sled_test_context();
+    let mut tree = SledTree::open(&db, tc.tree_name, false)?;
+
+    let value = "x".repeat(1024);
+    let mut batch = sled::Batch::default();
+    for i in 0..2000 {
+        batch.insert(
+            Files::serialize_key(&format!("{:05}", i))?,
+            value.as_bytes(),
+        );
+    }
+    tree.tree.apply_batch(batch)?;
+    db.flush()?;
+
+    // Delete most of the entries.
+    tree.range_remove::<Files, _>(.."01800".to_string(), false)
+        .await?;
+    db.flush()?;
+
+    let size_before = db.size_on_disk()?;
+    let reclaimed = tree.compact(&db)?;
+    let size_after = db.size_on_disk()?;
+
+    tracing::info!(
+        "size before: {}, after: {}, reclaimed: {}",
+        size_before,
+        size_after,
+        reclaimed
+    );
+    assert!(size_after <= size_before);
+    assert_eq!(size_before - size_after, reclaimed);
+
+    // Live entries are kept, through the same SledTree.
+    let keys = tree.range_keys::<Files, _>(..)?;
+    assert_eq!(200, keys.len());
+    assert_eq!("01800", keys[0]);
+    assert_eq!(
+        Some(value.clone()),
+        tree.get::<Files>(&"01999".to_string())?
+    );
+
+    // The temporary tree is removed.
+    assert!(!db
+        .tree_names()
+        .iter()
+        .any(|name| name.ends_with(b".compacting")));
+
+    // An interrupted compaction is detected.
+    db.open_tree(format!("{}.compacting", tree.name))?
+        .insert("k", "v")?;
+    assert!(tree.compact(&db).is_err());
+    assert_eq!(200, tree.range_keys::<Files, _>(..)?.len());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_composite_key_range() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+    let tables = tree.key_space::<Tables>();
+
+    for (db_id, table_id) in [(2, 0), (1, 256), (0, 3), (1, 1), (1, 0)] {
+        let k = CompositeKey(db_id, table_id);
+        tables.insert(&k, &k.to_string()).await?;
+    }
+
+    // All tables of db 1, in order.
+    let got = tables.range_keys(CompositeKey::prefix_range(1))?;
+    assert_eq!(
+        vec![CompositeKey(1, 0), CompositeKey(1, 1), CompositeKey(1, 256)],
+        got
+    );
+
+    // All keys, ordered by db_id then table_id.
+    let got = tables.range_keys(..)?;
+    assert_eq!(
+        vec![
+            CompositeKey(0, 3),
+            CompositeKey(1, 0),
+            CompositeKey(1, 1),
+            CompositeKey(1, 256),
+            CompositeKey(2, 0),
+        ],
+        got
+    );
+
+    assert!(tables.range_keys(CompositeKey::prefix_range(3))?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_full_keys_on_disk() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+    let tables = tree.key_space::<Tables>();
+
+    // Keys sharing the db id are still stored in full, the shared prefix is left to sled.
+    for table_id in [0, 1, 256] {
+        let k = CompositeKey(1, table_id);
+        tables.insert(&k, &k.to_string()).await?;
+    }
+
+    let raw_keys = tree.tree.iter().keys().collect::<Result<Vec<_>, _>>()?;
+    let mut want = vec![];
+    for table_id in [0, 1, 256] {
+        let mut k = vec![Tables::PREFIX];
+        k.extend_from_slice(CompositeKey(1, table_id).ser()?.as_ref());
+        want.push(sled::IVec::from(k));
+    }
+    assert_eq!(want, raw_keys);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_multi_range_delete() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    // Files stands for the database records, Tables for the table records keyed by (db_id, table_id).
+    let dbs = tree.key_space::<Files>();
+    let tables = tree.key_space::<Tables>();
+
+    for name in ["db1", "db2"] {
+        dbs.insert(&name.to_string(), &name.to_string()).await?;
+    }
+    for (db_id, table_id) in [(1, 1), (1, 2), (2, 1)] {
+        let k = CompositeKey(db_id, table_id);
+        tables.insert(&k, &k.to_string()).await?;
+    }
+
+    // An invalid range fails before anything is deleted.
+    {
+        let mut deletes = MultiRangeDelete::new();
+        deletes.add::<Files, _>("db1".to_string()..="db1".to_string())?;
+        let res = deletes.add::<Tables, _>(CompositeKey(2, 0)..CompositeKey(1, 0));
+        assert_eq!(ErrorCode::BadArguments("").code(), res.unwrap_err().code());
+
+        assert_eq!(2, dbs.range_keys(..)?.len());
+        assert_eq!(3, tables.range_keys(..)?.len());
+    }
+
+    // Drop db1 along with its tables in one batch.
+    {
+        let mut deletes = MultiRangeDelete::new();
+        deletes
+            .add::<Files, _>("db1".to_string()..="db1".to_string())?
+            .add::<Tables, _>(CompositeKey::prefix_range(1))?;
+        assert_eq!(2, deletes.len());
+
+        tree.multi_range_delete(&deletes, true).await?;
+
+        assert_eq!(vec!["db2".to_string()], dbs.range_keys(..)?);
+        assert_eq!(vec![CompositeKey(2, 1)], tables.range_keys(..)?);
+    }
+
+    // An empty delete changes nothing.
+    {
+        tree.multi_range_delete(&MultiRangeDelete::new(), true)
+            .await?;
+        assert_eq!(1, dbs.range_keys(..)?.len());
+        assert_eq!(1, tables.range_keys(..)?.len());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_move_key() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let mut tree = SledTree::open(db, &tc.tree_name, true)?;
+    tree.enable_checksum(false);
+
+    tree.insert::<Files>(&"staging".to_string(), &"1".to_string())
+        .await?;
+    tree.insert_with_ttl::<Files>(
+        &"expiring".to_string(),
+        &"2".to_string(),
+        Duration::from_secs(3600),
+    )
+    .await?;
+
+    // Moved from one key space to another.
+    let moved = tree
+        .move_key::<Files, SignedKeys>(&"staging".to_string(), &1)
+        .await?;
+    assert_eq!("1".to_string(), moved);
+    assert_eq!(None, tree.get::<Files>(&"staging".to_string())?);
+    assert_eq!(Some("1".to_string()), tree.get::<SignedKeys>(&1)?);
+
+    // The expiry is kept.
+    tree.move_key::<Files, SignedKeys>(&"expiring".to_string(), &2)
+        .await?;
+    let raw = tree.tree.get(SignedKeys::serialize_key(&2)?)?.unwrap();
+    assert_eq!(0xFE, raw[0]);
+    assert_eq!(Some("2".to_string()), tree.get::<SignedKeys>(&2)?);
+
+    // A move onto the same key keeps the value.
+    let moved = tree.move_key::<SignedKeys, SignedKeys>(&1, &1).await?;
+    assert_eq!("1".to_string(), moved);
+    assert_eq!(Some("1".to_string()), tree.get::<SignedKeys>(&1)?);
+
+    // An absent source changes nothing.
+    let err = tree
+        .move_key::<Files, SignedKeys>(&"absent".to_string(), &1)
+        .await
+        .unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreNotFound("").code(), err.code());
+    assert_eq!(Some("1".to_string()), tree.get::<SignedKeys>(&1)?);
+
+    // A failure in the middle of a move, a damaged source value, leaves both the source and the destination intact.
+    tree.insert::<Files>(&"damaged".to_string(), &"3".to_string())
+        .await?;
+    let k = Files::serialize_key(&"damaged".to_string())?;
+    let mut raw = tree.tree.get(&k)?.unwrap().to_vec();
+    let last = raw.len() - 1;
+    raw[last] ^= 0x01;
+    tree.tree.insert(&k, raw.clone())?;
+
+    let err = tree
+        .move_key::<Files, SignedKeys>(&"damaged".to_string(), &1)
+        .await
+        .unwrap_err();
+    assert_eq!(ErrorCode::MetaStoreDamaged("").code(), err.code());
+    assert_eq!(Some(raw.into()), tree.tree.get(&k)?);
+    assert_eq!(Some("1".to_string()), tree.get::<SignedKeys>(&1)?);
+
+    // A move across the shared tree and the dedicated tree of an isolated key space.
+    let mut tree = SledTree::open_isolated(db, &tc.tree_name, true, &[IsolatedKeySpace::of::<
+        SignedKeys,
+    >()])?;
+    tree.enable_checksum(false);
+    tree.insert::<Files>(&"staging".to_string(), &"4".to_string())
+        .await?;
+    tree.move_key::<Files, SignedKeys>(&"staging".to_string(), &4)
+        .await?;
+    assert_eq!(None, tree.get::<Files>(&"staging".to_string())?);
+    assert_eq!(vec![1, 2, 4], tree.range_keys::<SignedKeys, _>(..)?);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_isolate_key_space() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, &tc.tree_name, true)?;
+
+    // Written before Files is isolated.
+    tree.insert::<Files>(&"db1".to_string(), &"1".to_string())
+        .await?;
+    tree.insert::<Tables>(&CompositeKey(1, 1), &"t1".to_string())
+        .await?;
+
+    let mut tree =
+        SledTree::open_isolated(db, &tc.tree_name, true, &[IsolatedKeySpace::of::<Files>()])?;
+
+    // The entries of Files are moved to the dedicated tree, the others stay in the shared tree.
+    let dedicated = db.open_tree(format!("{}/{}", tc.tree_name, Files::NAME))?;
+    assert_eq!(1, dedicated.len());
+    assert_eq!(0, tree.tree.scan_prefix([Files::PREFIX]).count());
+    assert_eq!(1, tree.tree.scan_prefix([Tables::PREFIX]).count());
+
+    // The typed API is the same.
+    let dbs = tree.key_space::<Files>();
+    let tables = tree.key_space::<Tables>();
+
+    assert_eq!(Some("1".to_string()), dbs.get(&"db1".to_string())?);
+    dbs.insert(&"db2".to_string(), &"2".to_string()).await?;
+    tables
+        .insert(&CompositeKey(2, 1), &"t2".to_string())
+        .await?;
+    assert_eq!(2, dedicated.len());
+    assert_eq!(
+        vec!["db1".to_string(), "db2".to_string()],
+        dbs.range_keys(..)?
+    );
+
+    let known = [(Files::PREFIX, Files::NAME), (Tables::PREFIX, Tables::NAME)];
+    assert_eq!(
+        vec![(Files::NAME.to_string(), 2), (Tables::NAME.to_string(), 2)],
+        tree.describe(&known)?
+    );
+
+    // A delete across the shared and the dedicated tree.
+    let mut deletes = MultiRangeDelete::new();
+    deletes
+        .add::<Files, _>("db1".to_string()..="db1".to_string())?
+        .add::<Tables, _>(CompositeKey::prefix_range(1))?;
+    tree.multi_range_delete(&deletes, true).await?;
+
+    assert_eq!(vec!["db2".to_string()], dbs.range_keys(..)?);
+    assert_eq!(vec![CompositeKey(2, 1)], tables.range_keys(..)?);
+
+    // A snapshot of the tree covers the dedicated tree, and is installed into it.
+    let exported = tree.export().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(2, exported.len());
+
+    let other = SledTree::open_isolated(db, format!("{}-other", tc.tree_name), true, &[
+        IsolatedKeySpace::of::<Files>(),
+    ])?;
+    other.import(exported.into_iter().map(|(k, v)| (k.to_vec(), v.to_vec())))?;
+    let other_dedicated = db.open_tree(format!("{}-other/{}", tc.tree_name, Files::NAME))?;
+    assert_eq!(1, other_dedicated.len());
+    assert_eq!(0, other.tree.scan_prefix([Files::PREFIX]).count());
+    assert_eq!(
+        Some("2".to_string()),
+        other.get::<Files>(&"db2".to_string())?
+    );
+
+    dbs.clear(true).await?;
+    assert!(dedicated.is_empty());
+    assert_eq!(1, tables.range_keys(..)?.len());
+
+    // The dedicated tree is compacted too, and is still read through the same SledTree.
+    tree.insert::<Files>(&"db3".to_string(), &"3".to_string())
+        .await?;
+    tree.compact(db)?;
+    assert_eq!(
+        Some("3".to_string()),
+        tree.get::<Files>(&"db3".to_string())?
+    );
+    assert_eq!(1, tree.range_keys::<Tables, _>(..)?.len());
+    assert!(!db
+        .tree_names()
+        .iter()
+        .any(|name| name.ends_with(b".compacting")));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_signed_key_range() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+    let signed = tree.key_space::<SignedKeys>();
+
+    // Inserted out of order, across the sign boundary.
+    for k in [3, -10, 0, i64::MAX, -1, 7, i64::MIN, -5, 1, 5] {
+        signed.insert(&k, &k.to_string()).await?;
+    }
+
+    assert_eq!(
+        vec![i64::MIN, -10, -5, -1, 0, 1, 3, 5, 7, i64::MAX],
+        signed.range_keys(..)?
+    );
+    assert_eq!(vec![-5, -1, 0, 1, 3], signed.range_keys(-5..5)?);
+    assert_eq!(vec!["-5", "-1", "0", "1", "3"], signed.range_values(-5..5)?);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_lenient_read() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name.clone(), true)?;
+
+    let logs: Vec<Entry<LogEntry>> = vec![
+        Entry {
+            log_id: LogId { term: 1, index: 2 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 1, index: 4 },
+            payload: EntryPayload::Blank,
+        },
+    ];
+    tree.append_values::<Logs>(&logs).await?;
+
+    // Corrupt the value at index 3 by writing garbage to the underlying sled tree.
+
+    let corrupt_key = Logs::serialize_key(&3)?;
+    db.open_tree(&tc.tree_name)?
+        .insert(&corrupt_key, "not-an-entry".as_bytes())?;
+
+    tracing::info!("--- get and range abort on the corrupted value");
+    {
+        assert!(tree.get::<Logs>(&3).is_err());
+        assert!(tree.range_kvs::<Logs, _>(..).is_err());
+    }
+
+    tracing::info!("--- get_lenient reports the corrupted value and returns None");
+    {
+        let mut reported = vec![];
+        let got =
+            tree.get_lenient::<Logs, _>(&3, |k, v| reported.push((k.to_vec(), v.to_vec())))?;
+        assert!(got.is_none());
+        assert_eq!(
+            vec![(corrupt_key.to_vec(), b"not-an-entry".to_vec())],
+            reported
+        );
+
+        let got = tree.get_lenient::<Logs, _>(&2, |_, _| panic!("2 is not corrupted"))?;
+        assert_eq!(Some(logs[0].clone()), got);
+    }
+
+    tracing::info!("--- range_kvs_lenient skips the corrupted value and continues");
+    {
+        let mut reported = vec![];
+        let got = tree
+            .key_space::<Logs>()
+            .range_kvs_lenient(.., |k, _| reported.push(k.to_vec()))?;
+        assert_eq!(vec![(2, logs[0].clone()), (4, logs[1].clone())], got);
+        assert_eq!(vec![corrupt_key.to_vec()], reported);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_force_flush() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+
+    let logs: Vec<Entry<LogEntry>> = vec![
+        Entry {
+            log_id: LogId { term: 1, index: 2 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 3, index: 4 },
+            payload: EntryPayload::Blank,
+        },
+    ];
+
+    {
+        let tree = SledTree::open(db, tc.tree_name.clone(), false)?;
+        tree.append_values::<Logs>(&logs).await?;
+        tree.force_flush().await?;
+    }
+
+    // Reopen the tree, the data written without sync survives.
+
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+    assert_eq!(logs, tree.range_values::<Logs, _>(..)?);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_remove_values() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    let logs: Vec<Entry<LogEntry>> = vec![
+        Entry {
+            log_id: LogId { term: 1, index: 2 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 1, index: 5 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 1, index: 9 },
+            payload: EntryPayload::Blank,
+        },
+    ];
+
+    tree.append_values::<Logs>(&logs).await?;
+
+    let removed = tree.remove_values::<Logs>(&logs[..2]).await?;
+    assert_eq!(2, removed);
+    assert_eq!(logs[2..], tree.range_values::<Logs, _>(..)?);
+
+    // Absent or duplicated keys are not counted.
+    let removed = tree
+        .key_space::<Logs>()
+        .remove_values(&[logs[0].clone(), logs[2].clone(), logs[2].clone()])
+        .await?;
+    assert_eq!(1, removed);
+    assert!(tree.range_values::<Logs, _>(..)?.is_empty());
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_sled_tree_range_remove() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_sled_ut!();
@@ -724,6 +1903,52 @@ async fn test_sled_tree_range_remove() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_range_inverted() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    let logs: Vec<Entry<LogEntry>> = vec![
+        Entry {
+            log_id: LogId { term: 1, index: 2 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 1, index: 9 },
+            payload: EntryPayload::Blank,
+        },
+    ];
+
+    tree.append_values::<Logs>(&logs).await?;
+
+    let inverted = (Bound::Included(9), Bound::Excluded(2));
+    let want = "invalid range of log: start Included(9) is greater than end Excluded(2)";
+
+    let res = tree.range_values::<Logs, _>(inverted);
+    assert_eq!(want, res.unwrap_err().message());
+
+    let res = tree.range_keys::<Logs, _>(inverted);
+    assert_eq!(want, res.unwrap_err().message());
+
+    let res = tree.range_remove::<Logs, _>(inverted, true).await;
+    assert_eq!(want, res.unwrap_err().message());
+    assert_eq!(logs, tree.range_values::<Logs, _>(..)?);
+
+    // An empty range is not inverted.
+    let empty = (Bound::Included(2), Bound::Excluded(2));
+    assert!(tree.range_values::<Logs, _>(empty)?.is_empty());
+
+    // Unbounded sides are never checked.
+    assert_eq!(logs[1..], tree.range_values::<Logs, _>(9..)?);
+    assert_eq!(logs[..1], tree.range_values::<Logs, _>(..=2)?);
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_sled_tree_multi_types() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_sled_ut!();
@@ -791,6 +2016,94 @@ async fn test_sled_tree_multi_types() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_clear_key_space() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    let logs: Vec<Entry<LogEntry>> = vec![
+        Entry {
+            log_id: LogId { term: 1, index: 2 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 3, index: 4 },
+            payload: EntryPayload::Blank,
+        },
+    ];
+    tree.append_values::<Logs>(&logs).await?;
+
+    let metas = vec![
+        (
+            LastApplied,
+            StateMachineMetaValue::LogId(LogId { term: 1, index: 2 }),
+        ),
+        (Initialized, StateMachineMetaValue::Bool(true)),
+    ];
+    tree.append::<StateMachineMeta>(&metas).await?;
+
+    tree.clear_key_space::<StateMachineMeta>(true).await?;
+
+    assert!(tree.range_kvs::<StateMachineMeta, _>(..)?.is_empty());
+    assert_eq!(logs, tree.range_values::<Logs, _>(..)?);
+
+    // Clear an empty key space.
+    tree.clear_key_space::<StateMachineMeta>(false).await?;
+    assert_eq!(logs, tree.range_values::<Logs, _>(..)?);
+
+    tree.key_space::<Logs>().clear(true).await?;
+    assert!(tree.range_values::<Logs, _>(..)?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sled_tree_describe() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+
+    let known = [
+        (Logs::PREFIX, Logs::NAME),
+        (StateMachineMeta::PREFIX, StateMachineMeta::NAME),
+        (Nodes::PREFIX, Nodes::NAME),
+    ];
+
+    assert!(tree.describe(&known)?.is_empty());
+
+    let logs: Vec<Entry<LogEntry>> = (0..3)
+        .map(|i| Entry {
+            log_id: LogId { term: 1, index: i },
+            payload: EntryPayload::Blank,
+        })
+        .collect();
+    tree.append_values::<Logs>(&logs).await?;
+    tree.insert::<StateMachineMeta>(&Initialized, &StateMachineMetaValue::Bool(true))
+        .await?;
+
+    // A legacy key with a prefix no key space claims.
+    tree.tree.insert([255u8, 1], vec![1u8])?;
+
+    let got = tree.describe(&known)?;
+    assert_eq!(
+        vec![
+            (Logs::NAME.to_string(), 3),
+            (StateMachineMeta::NAME.to_string(), 1),
+            ("unknown".to_string(), 1),
+        ],
+        got
+    );
+
+    Ok(())
+}
+
 // --- key space test ---
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -984,6 +2297,67 @@ async fn test_as_range_keys() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_as_range_rev() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_sled_ut!();
+    let _ent = ut_span.enter();
+
+    let tc = new_sled_test_context();
+    let db = &tc.db;
+    let tree = SledTree::open(db, tc.tree_name, true)?;
+    let log_tree = tree.key_space::<Logs>();
+
+    let logs: Vec<Entry<LogEntry>> = vec![
+        Entry {
+            log_id: LogId { term: 1, index: 2 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 1, index: 9 },
+            payload: EntryPayload::Blank,
+        },
+        Entry {
+            log_id: LogId { term: 1, index: 10 },
+            payload: EntryPayload::Blank,
+        },
+    ];
+
+    log_tree.append_values(&logs).await?;
+
+    // Other key space must not be seen.
+    tree.insert::<Nodes>(&100, &Node::default()).await?;
+
+    let ranges: Vec<(Bound<LogIndex>, Bound<LogIndex>)> = vec![
+        (Bound::Unbounded, Bound::Unbounded),
+        (Bound::Included(0), Bound::Included(2)),
+        (Bound::Included(0), Bound::Excluded(10)),
+        (Bound::Excluded(2), Bound::Included(10)),
+        (Bound::Excluded(2), Bound::Excluded(10)),
+        (Bound::Included(9), Bound::Unbounded),
+        (Bound::Unbounded, Bound::Excluded(9)),
+        (Bound::Included(11), Bound::Unbounded),
+    ];
+
+    for range in ranges {
+        let mut want = log_tree.range_keys(range)?;
+        want.reverse();
+        assert_eq!(want, log_tree.range_keys_rev(range)?, "{:?}", range);
+
+        let mut want = log_tree.range_values(range)?;
+        want.reverse();
+        assert_eq!(want, log_tree.range_values_rev(range)?, "{:?}", range);
+    }
+
+    // The most recent 2 logs.
+    let got = log_tree.range_values_rev(..)?;
+    assert_eq!(
+        logs[1..].iter().rev().cloned().collect::<Vec<_>>(),
+        got[..2]
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_as_range_kvs() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_sled_ut!();
@@ -0,0 +1,77 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use common_exception::ErrorCode;
+use sled::IVec;
+
+/// The first byte of a value with an expiry.
+/// Neither a json value nor a checksummed value starts with it.
+const EXPIRY_MARKER: u8 = 0xFE;
+
+/// The size of the marker and the expiry in front of a value with an expiry.
+const EXPIRY_HEADER_SIZE: usize = 9;
+
+/// Milliseconds since the epoch, the unit of an expiry.
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Prepend the time a value expires at, `ttl` from now, to a serialized value.
+///
+/// A value with an expiry is `EXPIRY_MARKER`, then the big-endian milliseconds since the epoch
+/// it expires at, then the serialized value, which may be checksummed.
+pub(crate) fn with_expiry(v: IVec, ttl: Duration) -> IVec {
+    let expire_at = now_millis().saturating_add(ttl.as_millis() as u64);
+    with_expire_at(&v, expire_at)
+}
+
+/// Prepend the time a value expires at, in milliseconds since the epoch, to a serialized value.
+pub(crate) fn with_expire_at(v: &[u8], expire_at: u64) -> IVec {
+    let mut buf = Vec::with_capacity(EXPIRY_HEADER_SIZE + v.len());
+    buf.push(EXPIRY_MARKER);
+    buf.extend_from_slice(&expire_at.to_be_bytes());
+    buf.extend_from_slice(v);
+    buf.into()
+}
+
+/// Split a stored value into the time it expires at, if it has one, and the serialized value.
+pub(crate) fn split_expiry(v: &[u8]) -> Result<(Option<u64>, &[u8]), ErrorCode> {
+    if v.first() != Some(&EXPIRY_MARKER) {
+        return Ok((None, v));
+    }
+
+    if v.len() < EXPIRY_HEADER_SIZE {
+        return Err(ErrorCode::MetaStoreDamaged("truncated expiry header"));
+    }
+
+    let mut expire_at = [0u8; 8];
+    expire_at.copy_from_slice(&v[1..EXPIRY_HEADER_SIZE]);
+    Ok((
+        Some(u64::from_be_bytes(expire_at)),
+        &v[EXPIRY_HEADER_SIZE..],
+    ))
+}
+
+/// Whether a stored value is expired at `now`, in milliseconds since the epoch.
+/// A damaged expiry header is not treated as expired, it fails the deserialization instead.
+pub(crate) fn is_expired(v: &[u8], now: u64) -> bool {
+    matches!(split_expiry(v), Ok((Some(expire_at), _)) if expire_at <= now)
+}
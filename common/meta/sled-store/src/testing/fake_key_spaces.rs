@@ -22,6 +22,7 @@ use common_meta_types::SeqValue;
 
 use crate::testing::fake_state_machine_meta::StateMachineMetaKey;
 use crate::testing::fake_state_machine_meta::StateMachineMetaValue;
+use crate::CompositeKey;
 use crate::SledKeySpace;
 use crate::SledSerde;
 
@@ -65,6 +66,15 @@ impl SledKeySpace for Files {
     type V = String;
 }
 
+/// Key-Value Types keyed by `(db_id, table_id)` in sled::Tree:
+pub struct Tables {}
+impl SledKeySpace for Tables {
+    const PREFIX: u8 = 7;
+    const NAME: &'static str = "tables";
+    type K = CompositeKey;
+    type V = String;
+}
+
 /// Key-Value Types for storing general purpose kv in sled::Tree:
 pub struct GenericKV {}
 impl SledKeySpace for GenericKV {
@@ -73,3 +83,12 @@ impl SledKeySpace for GenericKV {
     type K = String;
     type V = SeqValue<KVValue<Vec<u8>>>;
 }
+
+/// Key-Value Types keyed by a signed integer in sled::Tree:
+pub struct SignedKeys {}
+impl SledKeySpace for SignedKeys {
+    const PREFIX: u8 = 8;
+    const NAME: &'static str = "signed-keys";
+    type K = i64;
+    type V = String;
+}
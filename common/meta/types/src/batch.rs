@@ -0,0 +1,65 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// How a multi-item meta operation deals with an item that can not be applied.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchMode {
+    /// All or nothing: a failing item aborts the whole batch and the operation returns the error.
+    AbortOnError,
+    /// Apply every item that can be applied, the failures are returned per item.
+    ContinueOnError,
+}
+
+impl Default for BatchMode {
+    fn default() -> Self {
+        BatchMode::AbortOnError
+    }
+}
+
+/// The reply of one item of a multi-item meta operation, in the order of the items.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum BatchItemReply<T> {
+    Ok(T),
+    Err { code: u16, message: String },
+}
+
+impl<T> BatchItemReply<T> {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, BatchItemReply::Ok(_))
+    }
+
+    pub fn into_result(self) -> Result<T> {
+        match self {
+            BatchItemReply::Ok(v) => Ok(v),
+            BatchItemReply::Err { code, message } => Err(ErrorCode::create(code, message, None)),
+        }
+    }
+}
+
+impl<T> From<Result<T>> for BatchItemReply<T> {
+    fn from(res: Result<T>) -> Self {
+        match res {
+            Ok(v) => BatchItemReply::Ok(v),
+            Err(e) => BatchItemReply::Err {
+                code: e.code(),
+                message: e.message(),
+            },
+        }
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::BatchItemReply;
+use crate::BatchMode;
+
+#[test]
+fn test_batch_item_reply() -> Result<()> {
+    assert_eq!(BatchMode::AbortOnError, BatchMode::default());
+
+    let ok: BatchItemReply<u64> = Ok(3).into();
+    assert!(ok.is_ok());
+    assert_eq!(3, ok.into_result()?);
+
+    let err: BatchItemReply<u64> = Err(ErrorCode::UnknownDatabase("no db")).into();
+    assert!(!err.is_ok());
+    assert_eq!(
+        BatchItemReply::Err {
+            code: 3,
+            message: "no db".to_string()
+        },
+        err
+    );
+
+    let e = err.into_result().unwrap_err();
+    assert_eq!(3, e.code());
+    assert_eq!("no db", e.message());
+
+    Ok(())
+}
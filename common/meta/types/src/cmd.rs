@@ -19,9 +19,12 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::table_info::Table;
+use crate::BatchMode;
 use crate::Database;
 use crate::KVMeta;
 use crate::MatchSeq;
+use crate::MetaId;
+use crate::MetaVersion;
 use crate::Node;
 use crate::Operation;
 
@@ -55,6 +58,10 @@ pub enum Cmd {
         // TODO(ariesdevil): add `seq` for distinguish between the results of the execution of
         // the two commands (failed `add` and successful `delete`)
         name: String,
+        /// Drop the tables of the database along with it.
+        /// Without it, a database that still has tables is left unchanged.
+        #[serde(default)]
+        cascade: bool,
     },
 
     /// Create a table if absent
@@ -67,6 +74,15 @@ pub enum Cmd {
         table: Table,
     },
 
+    /// Create a batch of tables.
+    /// With `BatchMode::AbortOnError` either all of them are created or none of them,
+    /// with `BatchMode::ContinueOnError` every table that can be created is created.
+    CreateTables {
+        tables: Vec<CreateTableEntry>,
+        #[serde(default)]
+        mode: BatchMode,
+    },
+
     /// Drop a table if absent
     DropTable {
         // TODO(ariesdevil): add `seq` for distinguish between the results of the execution of
@@ -74,6 +90,26 @@ pub enum Cmd {
         db_name: String,
         table_name: String,
         if_exists: bool,
+        /// Drop only if the table version matches, `None` to drop unconditionally.
+        expected_version: Option<MetaVersion>,
+    },
+
+    /// Rename a table, the table id is preserved.
+    RenameTable {
+        db_name: String,
+        table_name: String,
+        new_db_name: String,
+        new_table_name: String,
+    },
+
+    /// Remove all the data parts of a table, the schema is preserved and the version is bumped.
+    TruncateTable { db_name: String, table_name: String },
+
+    /// Replace the schema of a table if its version matches `table_version`, the version is bumped.
+    UpdateTableSchema {
+        table_id: MetaId,
+        table_version: MetaVersion,
+        schema: Vec<u8>,
     },
 
     /// Update or insert a general purpose kv store
@@ -94,6 +130,15 @@ pub enum Cmd {
     },
 }
 
+/// One table to create in a `Cmd::CreateTables` batch.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreateTableEntry {
+    pub db_name: String,
+    pub table_name: String,
+    pub if_not_exists: bool,
+    pub table: Table,
+}
+
 impl fmt::Display for Cmd {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -120,8 +165,8 @@ impl fmt::Display for Cmd {
                     name, db, if_not_exists, db.database_engine
                 )
             }
-            Cmd::DropDatabase { name } => {
-                write!(f, "drop_db:{}", name)
+            Cmd::DropDatabase { name, cascade } => {
+                write!(f, "drop_db:{}, cascade:{}", name, cascade)
             }
             Cmd::CreateTable {
                 db_name,
@@ -135,15 +180,55 @@ impl fmt::Display for Cmd {
                     db_name, table_name, table, if_not_exists
                 )
             }
+            Cmd::CreateTables { tables, mode } => {
+                write!(f, "create_tables({:?}):", mode)?;
+                for (i, t) in tables.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}-{}", t.db_name, t.table_name)?;
+                }
+                Ok(())
+            }
             Cmd::DropTable {
                 db_name,
                 table_name,
                 if_exists,
+                expected_version,
+            } => {
+                write!(
+                    f,
+                    "delete_table:{}-{}, if_exists:{}, expected_version:{:?}",
+                    db_name, table_name, if_exists, expected_version
+                )
+            }
+            Cmd::RenameTable {
+                db_name,
+                table_name,
+                new_db_name,
+                new_table_name,
+            } => {
+                write!(
+                    f,
+                    "rename_table:{}-{}=>{}-{}",
+                    db_name, table_name, new_db_name, new_table_name
+                )
+            }
+            Cmd::TruncateTable {
+                db_name,
+                table_name,
+            } => {
+                write!(f, "truncate_table:{}-{}", db_name, table_name)
+            }
+            Cmd::UpdateTableSchema {
+                table_id,
+                table_version,
+                ..
             } => {
                 write!(
                     f,
-                    "delete_table:{}-{}, if_exists:{}",
-                    db_name, table_name, if_exists
+                    "update_table_schema:{}, version:{}",
+                    table_id, table_version
                 )
             }
             Cmd::UpsertKV {
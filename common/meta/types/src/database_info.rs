@@ -13,7 +13,7 @@
 //  limitations under the License.
 //
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Formatter;
 
@@ -24,8 +24,8 @@ pub struct Database {
     /// engine name of db
     pub database_engine: String,
 
-    /// tables belong to this database.
-    pub tables: HashMap<String, u64>,
+    /// tables belong to this database, ordered by name.
+    pub tables: BTreeMap<String, u64>,
 }
 
 impl fmt::Display for Database {
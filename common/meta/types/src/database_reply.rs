@@ -13,7 +13,42 @@
 //  limitations under the License.
 //
 
+use std::sync::Arc;
+
+use crate::DatabaseInfo;
+use crate::TableInfo;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct CreateDatabaseReply {
     pub database_id: u64,
 }
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ListDatabasesReply {
+    /// Databases of this page, ordered by name.
+    pub databases: Vec<Arc<DatabaseInfo>>,
+    /// The cursor to fetch the next page, `None` if there are no more databases.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct UpsertDatabaseReply {
+    pub database: Arc<DatabaseInfo>,
+    /// `true` if the database is created by this call, `false` if it already exists.
+    pub created: bool,
+}
+
+/// All the databases with their tables, read at a single point of the meta state,
+/// thus a table is never listed without its database.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct CatalogSnapshot {
+    /// Databases ordered by name.
+    pub databases: Vec<DatabaseSnapshot>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct DatabaseSnapshot {
+    pub database: Arc<DatabaseInfo>,
+    /// Tables of the database ordered by name.
+    pub tables: Vec<Arc<TableInfo>>,
+}
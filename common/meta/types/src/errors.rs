@@ -12,12 +12,49 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::Display;
+
+use common_exception::ErrorCode;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::MatchSeq;
+use crate::MetaVersion;
+
+/// Followed by the actual version of the table at the end of the message of a `TableVersionMismatch` error.
+const ACTUAL_TABLE_VERSION: &str = "actual version: ";
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum ConflictSeq {
     NotMatch { want: MatchSeq, got: u64 },
 }
+
+/// Build the error of a call `what` on a table that is not of the expected version.
+///
+/// The actual version ends the message, so that `actual_table_version` reads it back
+/// after the error is sent to the client.
+pub fn table_version_mismatch(
+    what: impl Display,
+    expected: Option<MetaVersion>,
+    actual: MetaVersion,
+) -> ErrorCode {
+    let expected = match expected {
+        Some(expected) => expected.to_string(),
+        None => "none".to_string(),
+    };
+    ErrorCode::TableVersionMismatch(format!(
+        "{}: expected version: {}, {}{}",
+        what, expected, ACTUAL_TABLE_VERSION, actual
+    ))
+}
+
+/// The actual version of the table in an error built by `table_version_mismatch`.
+pub fn actual_table_version(error: &ErrorCode) -> Option<MetaVersion> {
+    if error.code() != ErrorCode::TableVersionMismatch("").code() {
+        return None;
+    }
+
+    let message = error.message();
+    let (_, actual) = message.rsplit_once(ACTUAL_TABLE_VERSION)?;
+    actual.parse().ok()
+}
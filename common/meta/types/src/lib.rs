@@ -14,14 +14,19 @@
 
 //! This crate defines data types used in meta data storage service.
 
+#[cfg(test)]
+mod batch_test;
 #[cfg(test)]
 mod cluster_test;
 #[cfg(test)]
 mod match_seq_test;
+#[cfg(test)]
+mod table_info_test;
 
 mod errors;
 mod match_seq;
 
+mod batch;
 mod cluster;
 mod cmd;
 mod database_info;
@@ -29,21 +34,31 @@ mod database_reply;
 mod kv_reply;
 mod log_entry;
 mod operation;
+mod ping_reply;
 mod raft_txid;
 mod raft_types;
 mod table_info;
 mod table_reply;
 
+pub use batch::BatchItemReply;
+pub use batch::BatchMode;
 pub use cluster::Node;
 pub use cluster::NodeInfo;
 pub use cluster::Slot;
 pub use cmd::Cmd;
+pub use cmd::CreateTableEntry;
 pub use common_meta_sled_store::KVMeta;
 pub use common_meta_sled_store::KVValue;
 pub use common_meta_sled_store::SeqValue;
 pub use database_info::Database;
 pub use database_info::DatabaseInfo;
+pub use database_reply::CatalogSnapshot;
 pub use database_reply::CreateDatabaseReply;
+pub use database_reply::DatabaseSnapshot;
+pub use database_reply::ListDatabasesReply;
+pub use database_reply::UpsertDatabaseReply;
+pub use errors::actual_table_version;
+pub use errors::table_version_mismatch;
 pub use errors::ConflictSeq;
 pub use kv_reply::GetKVActionReply;
 pub use kv_reply::MGetKVActionReply;
@@ -55,6 +70,7 @@ pub use match_seq::MatchSeqExt;
 pub use operation::MetaId;
 pub use operation::MetaVersion;
 pub use operation::Operation;
+pub use ping_reply::PingReply;
 pub use raft_txid::RaftTxId;
 pub use raft_types::LogId;
 pub use raft_types::LogIndex;
@@ -62,4 +78,5 @@ pub use raft_types::NodeId;
 pub use raft_types::Term;
 pub use table_info::Table;
 pub use table_info::TableInfo;
+pub use table_info::TableStatistics;
 pub use table_reply::CreateTableReply;
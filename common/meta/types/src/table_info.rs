@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
@@ -46,6 +47,14 @@ pub struct Table {
 
     /// name of parts that belong to this table.
     pub parts: HashSet<String>,
+
+    /// version of this table, it increments on every schema change.
+    #[serde(default)]
+    pub version: MetaVersion,
+
+    /// serialized schemas of the previous versions.
+    #[serde(default)]
+    pub schema_history: BTreeMap<MetaVersion, Vec<u8>>,
 }
 
 impl fmt::Display for Table {
@@ -111,3 +120,14 @@ impl Default for TableInfo {
         }
     }
 }
+
+/// Statistics of the data stored in a table.
+///
+/// The fields that are not tracked yet are zeros.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TableStatistics {
+    pub number_of_rows: u64,
+    pub data_bytes: u64,
+    pub compressed_data_bytes: u64,
+    pub number_of_parts: u64,
+}
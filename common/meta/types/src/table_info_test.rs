@@ -0,0 +1,40 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::exception::Result;
+
+use crate::Table;
+
+#[test]
+fn test_table_deserialize_without_version() -> Result<()> {
+    // Tables stored before the version and the schema history were introduced.
+    let stored = r#"{
+        "table_id": 1,
+        "table_name": "t",
+        "database_id": 2,
+        "db_name": "db",
+        "schema": [],
+        "table_engine": "Memory",
+        "table_options": {},
+        "parts": []
+    }"#;
+
+    let table: Table = serde_json::from_str(stored)?;
+    assert_eq!(1, table.table_id);
+    assert_eq!("t", table.table_name);
+    assert_eq!(0, table.version);
+    assert!(table.schema_history.is_empty());
+
+    Ok(())
+}
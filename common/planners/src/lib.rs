@@ -17,6 +17,8 @@ mod plan_aggregator_test;
 #[cfg(test)]
 mod plan_builder_test;
 #[cfg(test)]
+mod plan_cross_join_test;
+#[cfg(test)]
 mod plan_describe_table_test;
 #[cfg(test)]
 mod plan_display_test;
@@ -31,6 +33,8 @@ mod plan_filter_test;
 #[cfg(test)]
 mod plan_having_test;
 #[cfg(test)]
+mod plan_join_test;
+#[cfg(test)]
 mod plan_limit_test;
 #[cfg(test)]
 mod plan_projection_test;
@@ -41,6 +45,8 @@ mod plan_scan_test;
 #[cfg(test)]
 mod plan_select_test;
 #[cfg(test)]
+mod plan_window_test;
+#[cfg(test)]
 mod test;
 
 mod plan_aggregator_final;
@@ -48,8 +54,10 @@ mod plan_aggregator_partial;
 mod plan_broadcast;
 mod plan_builder;
 mod plan_builder_scan;
+mod plan_cross_join;
 mod plan_database_create;
 mod plan_database_drop;
+mod plan_delete;
 mod plan_describe_table;
 mod plan_display;
 mod plan_display_indent;
@@ -70,6 +78,7 @@ mod plan_extras;
 mod plan_filter;
 mod plan_having;
 mod plan_insert_into;
+mod plan_join;
 mod plan_kill;
 mod plan_limit;
 mod plan_limit_by;
@@ -87,20 +96,26 @@ mod plan_sort;
 mod plan_stage;
 mod plan_statistics;
 mod plan_subqueries_set;
+mod plan_table_add_column;
 mod plan_table_create;
 mod plan_table_drop;
+mod plan_table_rename;
 mod plan_truncate_table;
+mod plan_update;
 mod plan_use_database;
 mod plan_visitor;
+mod plan_window;
 
 pub use plan_aggregator_final::AggregatorFinalPlan;
 pub use plan_aggregator_partial::AggregatorPartialPlan;
 pub use plan_broadcast::BroadcastPlan;
 pub use plan_builder::PlanBuilder;
 pub use plan_builder_scan::TableScanInfo;
+pub use plan_cross_join::CrossJoinPlan;
 pub use plan_database_create::CreateDatabasePlan;
 pub use plan_database_create::DatabaseOptions;
 pub use plan_database_drop::DropDatabasePlan;
+pub use plan_delete::DeletePlan;
 pub use plan_describe_table::DescribeTablePlan;
 pub use plan_empty::EmptyPlan;
 pub use plan_explain::ExplainPlan;
@@ -137,6 +152,8 @@ pub use plan_extras::Extras;
 pub use plan_filter::FilterPlan;
 pub use plan_having::HavingPlan;
 pub use plan_insert_into::InsertIntoPlan;
+pub use plan_join::JoinPlan;
+pub use plan_join::JoinType;
 pub use plan_kill::KillPlan;
 pub use plan_limit::LimitPlan;
 pub use plan_limit_by::LimitByPlan;
@@ -158,9 +175,14 @@ pub use plan_stage::StageKind;
 pub use plan_stage::StagePlan;
 pub use plan_statistics::Statistics;
 pub use plan_subqueries_set::SubQueriesSetPlan;
+pub use plan_table_add_column::AddColumnPlan;
 pub use plan_table_create::CreateTablePlan;
 pub use plan_table_create::TableOptions;
 pub use plan_table_drop::DropTablePlan;
+pub use plan_table_rename::RenameTablePlan;
 pub use plan_truncate_table::TruncateTablePlan;
+pub use plan_update::UpdatePlan;
 pub use plan_use_database::UseDatabasePlan;
 pub use plan_visitor::PlanVisitor;
+pub use plan_window::WindowFunction;
+pub use plan_window::WindowPlan;
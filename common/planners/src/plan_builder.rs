@@ -27,6 +27,7 @@ use crate::plan_subqueries_set::SubQueriesSetPlan;
 use crate::validate_expression;
 use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
+use crate::CrossJoinPlan;
 use crate::EmptyPlan;
 use crate::ExplainPlan;
 use crate::ExplainType;
@@ -34,6 +35,8 @@ use crate::Expression;
 use crate::ExpressionPlan;
 use crate::FilterPlan;
 use crate::HavingPlan;
+use crate::JoinPlan;
+use crate::JoinType;
 use crate::LimitByPlan;
 use crate::LimitPlan;
 use crate::PlanNode;
@@ -41,6 +44,8 @@ use crate::ProjectionPlan;
 use crate::RewriteHelper;
 use crate::SelectPlan;
 use crate::SortPlan;
+use crate::WindowFunction;
+use crate::WindowPlan;
 
 pub enum AggregateMode {
     Partial,
@@ -252,6 +257,52 @@ impl PlanBuilder {
         })))
     }
 
+    /// Apply a ranking function over the partitions of the input
+    pub fn window(
+        &self,
+        func: WindowFunction,
+        column_name: &str,
+        partition_by: &[Expression],
+        order_by: &[Expression],
+    ) -> Result<Self> {
+        Ok(Self::from(&PlanNode::Window(WindowPlan {
+            func,
+            column_name: column_name.to_string(),
+            partition_by: partition_by.to_vec(),
+            order_by: order_by.to_vec(),
+            schema: WindowPlan::window_schema(&self.plan, column_name),
+            input: Arc::new(self.plan.clone()),
+        })))
+    }
+
+    /// The cartesian product of the input and `build`, which is materialized
+    pub fn cross_join(&self, build: &PlanNode) -> Result<Self> {
+        Ok(Self::from(&PlanNode::CrossJoin(CrossJoinPlan {
+            schema: CrossJoinPlan::cross_join_schema(&self.plan, build)?,
+            probe: Arc::new(self.plan.clone()),
+            build: Arc::new(build.clone()),
+        })))
+    }
+
+    /// Join the input with `build` on the equality of the keys, `build` is materialized
+    pub fn join(
+        &self,
+        join_type: JoinType,
+        build: &PlanNode,
+        probe_keys: &[Expression],
+        build_keys: &[Expression],
+    ) -> Result<Self> {
+        JoinPlan::check_keys(&self.plan, build, probe_keys, build_keys)?;
+        Ok(Self::from(&PlanNode::Join(JoinPlan {
+            join_type,
+            probe_keys: probe_keys.to_vec(),
+            build_keys: build_keys.to_vec(),
+            schema: JoinPlan::join_schema(join_type, &self.plan, build)?,
+            probe: Arc::new(self.plan.clone()),
+            build: Arc::new(build.clone()),
+        })))
+    }
+
     pub fn select(&self) -> Result<Self> {
         Ok(Self::from(&PlanNode::Select(SelectPlan {
             input: Arc::new(self.plan.clone()),
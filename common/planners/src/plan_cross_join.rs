@@ -0,0 +1,80 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::PlanNode;
+
+/// The cartesian product of two inputs, for the joins without predicates.
+/// Every row of the probe side is paired with every row of the build side,
+/// the build side is materialized so it should be the smaller one.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct CrossJoinPlan {
+    /// The streamed side, its columns come first
+    pub probe: Arc<PlanNode>,
+    /// The materialized side, its columns come last
+    pub build: Arc<PlanNode>,
+    /// Output data schema
+    pub schema: DataSchemaRef,
+}
+
+impl CrossJoinPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn get_inputs(&self) -> Vec<Arc<PlanNode>> {
+        vec![self.probe.clone(), self.build.clone()]
+    }
+
+    pub fn set_inputs(&mut self, inputs: Vec<&PlanNode>) -> Result<()> {
+        if inputs.len() != 2 {
+            return Err(ErrorCode::BadPlanInputs(format!(
+                "CrossJoinPlan expects 2 inputs, but got {}",
+                inputs.len()
+            )));
+        }
+
+        self.probe = Arc::new(inputs[0].clone());
+        self.build = Arc::new(inputs[1].clone());
+        Ok(())
+    }
+
+    /// The output schema: the probe fields followed by the build fields.
+    /// The columns are looked up by name, so the names must not clash.
+    pub fn cross_join_schema(probe: &PlanNode, build: &PlanNode) -> Result<DataSchemaRef> {
+        let mut fields = probe.schema().fields().clone();
+        let mut names = fields
+            .iter()
+            .map(|field| field.name().clone())
+            .collect::<HashSet<_>>();
+
+        for field in build.schema().fields() {
+            if !names.insert(field.name().clone()) {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Column `{}` appears on both sides of the cross join, alias one of them",
+                    field.name()
+                )));
+            }
+            fields.push(field.clone());
+        }
+        Ok(DataSchemaRefExt::create(fields))
+    }
+}
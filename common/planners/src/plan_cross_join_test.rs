@@ -0,0 +1,59 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::*;
+use common_exception::Result;
+use pretty_assertions::assert_eq;
+
+use crate::*;
+
+#[test]
+fn test_cross_join_plan() -> Result<()> {
+    let probe = PlanBuilder::create(DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::UInt64, false),
+        DataField::new("b", DataType::Utf8, false),
+    ]))
+    .build()?;
+    let build = PlanBuilder::create(DataSchemaRefExt::create(vec![DataField::new(
+        "c",
+        DataType::Int32,
+        true,
+    )]))
+    .build()?;
+
+    let plan = PlanBuilder::from(&probe).cross_join(&build)?.build()?;
+
+    let expect = "CrossJoin: build=[c]";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    assert_eq!(
+        vec!["a", "b", "c"],
+        plan.schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(2, plan.inputs().len());
+
+    // The columns of both sides are looked up by name.
+    let result = PlanBuilder::from(&probe).cross_join(&probe);
+    assert!(result.is_err());
+    assert_eq!(
+        "Code: 6, displayText = Column `a` appears on both sides of the cross join, alias one of them.",
+        result.err().unwrap().to_string()
+    );
+    Ok(())
+}
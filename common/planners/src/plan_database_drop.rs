@@ -21,6 +21,8 @@ use common_datavalues::DataSchemaRef;
 pub struct DropDatabasePlan {
     pub if_exists: bool,
     pub db: String,
+    /// Drop the tables of the database too, otherwise a database with tables can not be dropped.
+    pub cascade: bool,
 }
 
 impl DropDatabasePlan {
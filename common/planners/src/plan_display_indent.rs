@@ -20,10 +20,13 @@ use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
 use crate::CreateDatabasePlan;
 use crate::CreateTablePlan;
+use crate::CrossJoinPlan;
+use crate::DeletePlan;
 use crate::DropDatabasePlan;
 use crate::DropTablePlan;
 use crate::Expression;
 use crate::ExpressionPlan;
+use crate::JoinPlan;
 use crate::LimitPlan;
 use crate::PlanNode;
 use crate::ProjectionPlan;
@@ -31,6 +34,8 @@ use crate::ReadDataSourcePlan;
 use crate::SortPlan;
 use crate::StagePlan;
 use crate::SubQueriesSetPlan;
+use crate::UpdatePlan;
+use crate::WindowPlan;
 
 pub struct PlanNodeIndentFormatDisplay<'a> {
     indent: usize,
@@ -65,6 +70,11 @@ impl<'a> fmt::Display for PlanNodeIndentFormatDisplay<'a> {
             PlanNode::Having(plan) => write!(f, "Having: {:?}", plan.predicate),
             PlanNode::Sort(plan) => Self::format_sort(f, plan),
             PlanNode::Limit(plan) => Self::format_limit(f, plan),
+            PlanNode::Window(plan) => Self::format_window(f, plan),
+            PlanNode::Delete(plan) => Self::format_delete(f, plan),
+            PlanNode::Update(plan) => Self::format_update(f, plan),
+            PlanNode::CrossJoin(plan) => Self::format_cross_join(f, plan),
+            PlanNode::Join(plan) => Self::format_join(f, plan),
             PlanNode::SubQueryExpression(plan) => Self::format_subquery_expr(f, plan),
             PlanNode::ReadSource(plan) => Self::format_read_source(f, plan),
             PlanNode::CreateDatabase(plan) => Self::format_create_database(f, plan),
@@ -191,6 +201,55 @@ impl<'a> PlanNodeIndentFormatDisplay<'a> {
         }
     }
 
+    fn format_window(f: &mut Formatter, plan: &WindowPlan) -> fmt::Result {
+        write!(
+            f,
+            "Window: {}() as {}, partitionBy={:?}, orderBy={:?}",
+            plan.func, plan.column_name, plan.partition_by, plan.order_by
+        )
+    }
+
+    fn format_delete(f: &mut Formatter, plan: &DeletePlan) -> fmt::Result {
+        write!(
+            f,
+            "Delete: {}.{}, predicate={:?}",
+            plan.db, plan.table, plan.predicate
+        )
+    }
+
+    fn format_update(f: &mut Formatter, plan: &UpdatePlan) -> fmt::Result {
+        write!(f, "Update: {}.{}, set=[", plan.db, plan.table)?;
+        for (i, (column, value)) in plan.assignments.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} = {:?}", column, value)?;
+        }
+        write!(f, "], predicate={:?}", plan.predicate)
+    }
+
+    fn format_cross_join(f: &mut Formatter, plan: &CrossJoinPlan) -> fmt::Result {
+        write!(
+            f,
+            "CrossJoin: build=[{}]",
+            plan.build
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    fn format_join(f: &mut Formatter, plan: &JoinPlan) -> fmt::Result {
+        write!(
+            f,
+            "Join: {}, probeKeys={:?}, buildKeys={:?}",
+            plan.join_type, plan.probe_keys, plan.build_keys
+        )
+    }
+
     fn format_subquery_expr(f: &mut Formatter, plan: &SubQueriesSetPlan) -> fmt::Result {
         let mut names = Vec::with_capacity(plan.expressions.len());
         for expression in &plan.expressions {
@@ -223,7 +282,8 @@ impl<'a> PlanNodeIndentFormatDisplay<'a> {
 
     fn format_drop_database(f: &mut Formatter, plan: &DropDatabasePlan) -> fmt::Result {
         write!(f, "Drop database {:},", plan.db)?;
-        write!(f, " if_exists:{:}", plan.if_exists)
+        write!(f, " if_exists:{:},", plan.if_exists)?;
+        write!(f, " cascade:{:}", plan.cascade)
     }
 
     fn format_create_table(f: &mut Formatter, plan: &CreateTablePlan) -> fmt::Result {
@@ -26,6 +26,8 @@ pub enum ExplainType {
     Syntax,
     Graph,
     Pipeline,
+    /// Run the query, and show its pipeline with the counters of the blocks read.
+    Analyze,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
@@ -0,0 +1,138 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::CrossJoinPlan;
+use crate::Expression;
+use crate::PlanNode;
+
+/// The join types supported by the hash join.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum JoinType {
+    /// Only the probe rows with a matching build row.
+    Inner,
+    /// All the probe rows, the unmatched ones with NULLs for the build columns.
+    Left,
+}
+
+impl fmt::Display for JoinType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JoinType::Inner => write!(f, "INNER"),
+            JoinType::Left => write!(f, "LEFT OUTER"),
+        }
+    }
+}
+
+/// Joins two inputs on the equality of their keys, with a hash table over the build side.
+/// The probe side is streamed, the build side is materialized so it should be the smaller one.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct JoinPlan {
+    pub join_type: JoinType,
+    /// The keys evaluated on the probe side
+    pub probe_keys: Vec<Expression>,
+    /// The keys evaluated on the build side, compared with the probe keys pairwise
+    pub build_keys: Vec<Expression>,
+    /// The streamed side, its columns come first
+    pub probe: Arc<PlanNode>,
+    /// The materialized side, its columns come last
+    pub build: Arc<PlanNode>,
+    /// Output data schema
+    pub schema: DataSchemaRef,
+}
+
+impl JoinPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn get_inputs(&self) -> Vec<Arc<PlanNode>> {
+        vec![self.probe.clone(), self.build.clone()]
+    }
+
+    pub fn set_inputs(&mut self, inputs: Vec<&PlanNode>) -> Result<()> {
+        if inputs.len() != 2 {
+            return Err(ErrorCode::BadPlanInputs(format!(
+                "JoinPlan expects 2 inputs, but got {}",
+                inputs.len()
+            )));
+        }
+
+        self.probe = Arc::new(inputs[0].clone());
+        self.build = Arc::new(inputs[1].clone());
+        Ok(())
+    }
+
+    /// The output schema: the probe fields followed by the build fields,
+    /// the build fields are nullable for a left outer join.
+    pub fn join_schema(
+        join_type: JoinType,
+        probe: &PlanNode,
+        build: &PlanNode,
+    ) -> Result<DataSchemaRef> {
+        let schema = CrossJoinPlan::cross_join_schema(probe, build)?;
+        match join_type {
+            JoinType::Inner => Ok(schema),
+            JoinType::Left => {
+                let probe_fields = probe.schema().fields().len();
+                let fields = schema
+                    .fields()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| match index < probe_fields {
+                        true => field.clone(),
+                        false => DataField::new(field.name(), field.data_type().clone(), true),
+                    })
+                    .collect::<Vec<_>>();
+                Ok(DataSchemaRefExt::create(fields))
+            }
+        }
+    }
+
+    /// The keys are compared by their values, so the keys of a pair must have the same type.
+    pub fn check_keys(
+        probe: &PlanNode,
+        build: &PlanNode,
+        probe_keys: &[Expression],
+        build_keys: &[Expression],
+    ) -> Result<()> {
+        if probe_keys.is_empty() || probe_keys.len() != build_keys.len() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Join expects the same non-zero number of keys on both sides, but got {} and {}",
+                probe_keys.len(),
+                build_keys.len()
+            )));
+        }
+
+        for (probe_key, build_key) in probe_keys.iter().zip(build_keys) {
+            let probe_type = probe_key.to_data_type(&probe.schema())?;
+            let build_type = build_key.to_data_type(&build.schema())?;
+            if probe_type != build_type {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Join keys {:?} and {:?} have different types {:?} and {:?}",
+                    probe_key, build_key, probe_type, build_type
+                )));
+            }
+        }
+        Ok(())
+    }
+}
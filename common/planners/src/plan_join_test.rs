@@ -0,0 +1,83 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::*;
+use common_exception::Result;
+use pretty_assertions::assert_eq;
+
+use crate::*;
+
+#[test]
+fn test_join_plan() -> Result<()> {
+    let probe = PlanBuilder::create(DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::UInt64, false),
+        DataField::new("b", DataType::String, false),
+    ]))
+    .build()?;
+    let build = PlanBuilder::create(DataSchemaRefExt::create(vec![
+        DataField::new("c", DataType::UInt64, false),
+        DataField::new("d", DataType::Int32, false),
+    ]))
+    .build()?;
+
+    let inner = PlanBuilder::from(&probe)
+        .join(JoinType::Inner, &build, &[col("a")], &[col("c")])?
+        .build()?;
+    assert_eq!(
+        "Join: INNER, probeKeys=[a], buildKeys=[c]",
+        format!("{:?}", inner)
+    );
+    let nullables = |plan: &PlanNode| {
+        plan.schema()
+            .fields()
+            .iter()
+            .map(|f| (f.name().clone(), f.is_nullable()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        vec![
+            ("a".to_string(), false),
+            ("b".to_string(), false),
+            ("c".to_string(), false),
+            ("d".to_string(), false)
+        ],
+        nullables(&inner)
+    );
+
+    // The build columns of a left outer join are NULL for the unmatched probe rows.
+    let left = PlanBuilder::from(&probe)
+        .join(JoinType::Left, &build, &[col("a")], &[col("c")])?
+        .build()?;
+    assert_eq!(
+        "Join: LEFT OUTER, probeKeys=[a], buildKeys=[c]",
+        format!("{:?}", left)
+    );
+    assert_eq!(
+        vec![
+            ("a".to_string(), false),
+            ("b".to_string(), false),
+            ("c".to_string(), true),
+            ("d".to_string(), true)
+        ],
+        nullables(&left)
+    );
+
+    // The keys of a pair must have the same type.
+    let result = PlanBuilder::from(&probe).join(JoinType::Inner, &build, &[col("a")], &[col("d")]);
+    assert!(result.is_err());
+
+    let result = PlanBuilder::from(&probe).join(JoinType::Inner, &build, &[], &[]);
+    assert!(result.is_err());
+    Ok(())
+}
@@ -24,6 +24,8 @@ use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
 use crate::CreateDatabasePlan;
 use crate::CreateTablePlan;
+use crate::CrossJoinPlan;
+use crate::DeletePlan;
 use crate::DescribeTablePlan;
 use crate::DropDatabasePlan;
 use crate::DropTablePlan;
@@ -33,6 +35,7 @@ use crate::ExpressionPlan;
 use crate::FilterPlan;
 use crate::HavingPlan;
 use crate::InsertIntoPlan;
+use crate::JoinPlan;
 use crate::KillPlan;
 use crate::LimitByPlan;
 use crate::LimitPlan;
@@ -46,7 +49,9 @@ use crate::ShowCreateTablePlan;
 use crate::SortPlan;
 use crate::StagePlan;
 use crate::TruncateTablePlan;
+use crate::UpdatePlan;
 use crate::UseDatabasePlan;
+use crate::WindowPlan;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
 pub enum PlanNode {
@@ -63,6 +68,9 @@ pub enum PlanNode {
     Sort(SortPlan),
     Limit(LimitPlan),
     LimitBy(LimitByPlan),
+    Window(WindowPlan),
+    CrossJoin(CrossJoinPlan),
+    Join(JoinPlan),
     Scan(ScanPlan),
     ReadSource(ReadDataSourcePlan),
     Select(SelectPlan),
@@ -76,6 +84,8 @@ pub enum PlanNode {
     UseDatabase(UseDatabasePlan),
     SetVariable(SettingPlan),
     InsertInto(InsertIntoPlan),
+    Delete(DeletePlan),
+    Update(UpdatePlan),
     ShowCreateTable(ShowCreateTablePlan),
     SubQueryExpression(SubQueriesSetPlan),
     Kill(KillPlan),
@@ -98,6 +108,9 @@ impl PlanNode {
             PlanNode::Having(v) => v.schema(),
             PlanNode::Limit(v) => v.schema(),
             PlanNode::LimitBy(v) => v.schema(),
+            PlanNode::Window(v) => v.schema(),
+            PlanNode::CrossJoin(v) => v.schema(),
+            PlanNode::Join(v) => v.schema(),
             PlanNode::ReadSource(v) => v.schema(),
             PlanNode::Select(v) => v.schema(),
             PlanNode::Explain(v) => v.schema(),
@@ -111,6 +124,8 @@ impl PlanNode {
             PlanNode::Sort(v) => v.schema(),
             PlanNode::UseDatabase(v) => v.schema(),
             PlanNode::InsertInto(v) => v.schema(),
+            PlanNode::Delete(v) => v.schema(),
+            PlanNode::Update(v) => v.schema(),
             PlanNode::ShowCreateTable(v) => v.schema(),
             PlanNode::SubQueryExpression(v) => v.schema(),
             PlanNode::Kill(v) => v.schema(),
@@ -132,6 +147,9 @@ impl PlanNode {
             PlanNode::Having(_) => "HavingPlan",
             PlanNode::Limit(_) => "LimitPlan",
             PlanNode::LimitBy(_) => "LimitByPlan",
+            PlanNode::Window(_) => "WindowPlan",
+            PlanNode::CrossJoin(_) => "CrossJoinPlan",
+            PlanNode::Join(_) => "JoinPlan",
             PlanNode::ReadSource(_) => "ReadSourcePlan",
             PlanNode::Select(_) => "SelectPlan",
             PlanNode::Explain(_) => "ExplainPlan",
@@ -145,6 +163,8 @@ impl PlanNode {
             PlanNode::Sort(_) => "SortPlan",
             PlanNode::UseDatabase(_) => "UseDatabasePlan",
             PlanNode::InsertInto(_) => "InsertIntoPlan",
+            PlanNode::Delete(_) => "DeletePlan",
+            PlanNode::Update(_) => "UpdatePlan",
             PlanNode::ShowCreateTable(_) => "ShowCreateTablePlan",
             PlanNode::SubQueryExpression(_) => "CreateSubQueriesSets",
             PlanNode::Kill(_) => "KillQuery",
@@ -165,6 +185,11 @@ impl PlanNode {
             PlanNode::Explain(v) => vec![v.input.clone()],
             PlanNode::Select(v) => vec![v.input.clone()],
             PlanNode::Sort(v) => vec![v.input.clone()],
+            PlanNode::Window(v) => vec![v.input.clone()],
+            PlanNode::Delete(v) => vec![v.input.clone()],
+            PlanNode::Update(v) => vec![v.input.clone()],
+            PlanNode::CrossJoin(v) => v.get_inputs(),
+            PlanNode::Join(v) => v.get_inputs(),
             PlanNode::SubQueryExpression(v) => v.get_inputs(),
 
             _ => vec![],
@@ -193,6 +218,11 @@ impl PlanNode {
             PlanNode::Explain(v) => v.set_input(inputs[0]),
             PlanNode::Select(v) => v.set_input(inputs[0]),
             PlanNode::Sort(v) => v.set_input(inputs[0]),
+            PlanNode::Window(v) => v.set_input(inputs[0]),
+            PlanNode::Delete(v) => v.set_input(inputs[0]),
+            PlanNode::Update(v) => v.set_input(inputs[0]),
+            PlanNode::CrossJoin(v) => v.set_inputs(inputs)?,
+            PlanNode::Join(v) => v.set_inputs(inputs)?,
             PlanNode::SubQueryExpression(v) => v.set_inputs(inputs),
             _ => {
                 return Err(ErrorCode::UnImplement(format!(
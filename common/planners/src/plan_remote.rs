@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use common_datavalues::DataSchemaRef;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
@@ -21,6 +23,8 @@ pub struct RemotePlan {
     pub stage_id: String,
     pub stream_id: String,
     pub fetch_nodes: Vec<String>,
+    /// The backup nodes of a fetch node, tried in order if the fetch node is unreachable.
+    pub fetch_node_replicas: HashMap<String, Vec<String>>,
 }
 
 impl RemotePlan {
@@ -27,6 +27,8 @@ use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
 use crate::CreateDatabasePlan;
 use crate::CreateTablePlan;
+use crate::CrossJoinPlan;
+use crate::DeletePlan;
 use crate::DescribeTablePlan;
 use crate::DropDatabasePlan;
 use crate::DropTablePlan;
@@ -38,6 +40,7 @@ use crate::Expressions;
 use crate::FilterPlan;
 use crate::HavingPlan;
 use crate::InsertIntoPlan;
+use crate::JoinPlan;
 use crate::KillPlan;
 use crate::LimitByPlan;
 use crate::LimitPlan;
@@ -53,7 +56,9 @@ use crate::ShowCreateTablePlan;
 use crate::SortPlan;
 use crate::StagePlan;
 use crate::TruncateTablePlan;
+use crate::UpdatePlan;
 use crate::UseDatabasePlan;
+use crate::WindowPlan;
 
 /// `PlanRewriter` is a visitor that can help to rewrite `PlanNode`
 /// By default, a `PlanRewriter` will traverse the plan tree in pre-order and return rewritten plan tree.
@@ -85,6 +90,9 @@ pub trait PlanRewriter {
             PlanNode::Sort(plan) => self.rewrite_sort(plan),
             PlanNode::Limit(plan) => self.rewrite_limit(plan),
             PlanNode::LimitBy(plan) => self.rewrite_limit_by(plan),
+            PlanNode::Window(plan) => self.rewrite_window(plan),
+            PlanNode::CrossJoin(plan) => self.rewrite_cross_join(plan),
+            PlanNode::Join(plan) => self.rewrite_join(plan),
             PlanNode::Scan(plan) => self.rewrite_scan(plan),
             PlanNode::ReadSource(plan) => self.rewrite_read_data_source(plan),
             PlanNode::Select(plan) => self.rewrite_select(plan),
@@ -102,6 +110,8 @@ pub trait PlanRewriter {
             PlanNode::DropTable(plan) => self.rewrite_drop_table(plan),
             PlanNode::DropDatabase(plan) => self.rewrite_drop_database(plan),
             PlanNode::InsertInto(plan) => self.rewrite_insert_into(plan),
+            PlanNode::Delete(plan) => self.rewrite_delete(plan),
+            PlanNode::Update(plan) => self.rewrite_update(plan),
             PlanNode::ShowCreateTable(plan) => self.rewrite_show_create_table(plan),
             PlanNode::SubQueryExpression(plan) => self.rewrite_sub_queries_sets(plan),
             PlanNode::TruncateTable(plan) => self.rewrite_truncate_table(plan),
@@ -276,6 +286,38 @@ pub trait PlanRewriter {
             .build()
     }
 
+    fn rewrite_window(&mut self, plan: &WindowPlan) -> Result<PlanNode> {
+        let new_input = self.rewrite_plan_node(plan.input.as_ref())?;
+        let new_partition_by = self.rewrite_exprs(&new_input.schema(), &plan.partition_by)?;
+        let new_order_by = self.rewrite_exprs(&new_input.schema(), &plan.order_by)?;
+        PlanBuilder::from(&new_input)
+            .window(
+                plan.func,
+                &plan.column_name,
+                &new_partition_by,
+                &new_order_by,
+            )?
+            .build()
+    }
+
+    fn rewrite_cross_join(&mut self, plan: &CrossJoinPlan) -> Result<PlanNode> {
+        let new_probe = self.rewrite_plan_node(plan.probe.as_ref())?;
+        let new_build = self.rewrite_plan_node(plan.build.as_ref())?;
+        PlanBuilder::from(&new_probe)
+            .cross_join(&new_build)?
+            .build()
+    }
+
+    fn rewrite_join(&mut self, plan: &JoinPlan) -> Result<PlanNode> {
+        let new_probe = self.rewrite_plan_node(plan.probe.as_ref())?;
+        let new_build = self.rewrite_plan_node(plan.build.as_ref())?;
+        let new_probe_keys = self.rewrite_exprs(&new_probe.schema(), &plan.probe_keys)?;
+        let new_build_keys = self.rewrite_exprs(&new_build.schema(), &plan.build_keys)?;
+        PlanBuilder::from(&new_probe)
+            .join(plan.join_type, &new_build, &new_probe_keys, &new_build_keys)?
+            .build()
+    }
+
     fn rewrite_scan(&mut self, plan: &ScanPlan) -> Result<PlanNode> {
         Ok(PlanNode::Scan(plan.clone()))
     }
@@ -340,6 +382,39 @@ pub trait PlanRewriter {
         Ok(PlanNode::InsertInto(plan.clone()))
     }
 
+    fn rewrite_delete(&mut self, plan: &DeletePlan) -> Result<PlanNode> {
+        let new_input = self.rewrite_plan_node(plan.input.as_ref())?;
+        let new_predicate = self.rewrite_expr(&new_input.schema(), &plan.predicate)?;
+        Ok(PlanNode::Delete(DeletePlan {
+            db: plan.db.clone(),
+            table: plan.table.clone(),
+            predicate: new_predicate,
+            input: Arc::new(new_input),
+        }))
+    }
+
+    fn rewrite_update(&mut self, plan: &UpdatePlan) -> Result<PlanNode> {
+        let new_input = self.rewrite_plan_node(plan.input.as_ref())?;
+        let new_assignments = plan
+            .assignments
+            .iter()
+            .map(|(column, value)| {
+                Ok((
+                    column.clone(),
+                    self.rewrite_expr(&new_input.schema(), value)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let new_predicate = self.rewrite_expr(&new_input.schema(), &plan.predicate)?;
+        Ok(PlanNode::Update(UpdatePlan {
+            db: plan.db.clone(),
+            table: plan.table.clone(),
+            assignments: new_assignments,
+            predicate: new_predicate,
+            input: Arc::new(new_input),
+        }))
+    }
+
     fn rewrite_show_create_table(&mut self, plan: &ShowCreateTablePlan) -> Result<PlanNode> {
         Ok(PlanNode::ShowCreateTable(plan.clone()))
     }
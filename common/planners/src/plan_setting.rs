@@ -26,6 +26,8 @@ pub struct VarValue {
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
 pub struct SettingPlan {
     pub vars: Vec<VarValue>,
+    /// Persist the values for the user, see `SET PERSISTENT`
+    pub persistent: bool,
 }
 
 impl SettingPlan {
@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use common_datavalues::DataSchema;
 use common_datavalues::DataSchemaRef;
+use common_meta_types::MetaVersion;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 pub struct DropTablePlan {
@@ -23,6 +24,8 @@ pub struct DropTablePlan {
     pub db: String,
     /// The table name
     pub table: String,
+    /// Drop the table only if its version is the expected one, `None` to drop it unconditionally.
+    pub expected_version: Option<MetaVersion>,
 }
 
 impl DropTablePlan {
@@ -0,0 +1,46 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_datavalues::DataSchema;
+use common_datavalues::DataSchemaRef;
+
+use crate::Expression;
+use crate::PlanNode;
+
+/// Updates the rows of a table matching the predicate, by rewriting the table with the updated rows.
+/// The rows whose predicate is NULL are kept as they are.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct UpdatePlan {
+    pub db: String,
+    /// The table name
+    pub table: String,
+    /// The columns to update and their new values, evaluated on the rows before the update
+    pub assignments: Vec<(String, Expression)>,
+    /// The rows it is true for are updated
+    pub predicate: Expression,
+    /// The logical plan reading all the rows of the table
+    pub input: Arc<PlanNode>,
+}
+
+impl UpdatePlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        Arc::new(DataSchema::empty())
+    }
+
+    pub fn set_input(&mut self, node: &PlanNode) {
+        self.input = Arc::new(node.clone());
+    }
+}
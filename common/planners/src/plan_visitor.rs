@@ -20,6 +20,8 @@ use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
 use crate::CreateDatabasePlan;
 use crate::CreateTablePlan;
+use crate::CrossJoinPlan;
+use crate::DeletePlan;
 use crate::DescribeTablePlan;
 use crate::DropDatabasePlan;
 use crate::DropTablePlan;
@@ -30,6 +32,7 @@ use crate::ExpressionPlan;
 use crate::FilterPlan;
 use crate::HavingPlan;
 use crate::InsertIntoPlan;
+use crate::JoinPlan;
 use crate::KillPlan;
 use crate::LimitByPlan;
 use crate::LimitPlan;
@@ -44,7 +47,9 @@ use crate::ShowCreateTablePlan;
 use crate::SortPlan;
 use crate::StagePlan;
 use crate::TruncateTablePlan;
+use crate::UpdatePlan;
 use crate::UseDatabasePlan;
+use crate::WindowPlan;
 
 /// `PlanVisitor` implements visitor pattern(reference [syn](https://docs.rs/syn/1.0.72/syn/visit/trait.Visit.html)) for `PlanNode`.
 ///
@@ -98,6 +103,9 @@ pub trait PlanVisitor {
             PlanNode::Sort(plan) => self.visit_sort(plan),
             PlanNode::Limit(plan) => self.visit_limit(plan),
             PlanNode::LimitBy(plan) => self.visit_limit_by(plan),
+            PlanNode::Window(plan) => self.visit_window(plan),
+            PlanNode::CrossJoin(plan) => self.visit_cross_join(plan),
+            PlanNode::Join(plan) => self.visit_join(plan),
             PlanNode::Scan(plan) => self.visit_scan(plan),
             PlanNode::ReadSource(plan) => self.visit_read_data_source(plan),
             PlanNode::Select(plan) => self.visit_select(plan),
@@ -116,6 +124,8 @@ pub trait PlanVisitor {
             PlanNode::Having(plan) => self.visit_having(plan),
             PlanNode::Expression(plan) => self.visit_expression(plan),
             PlanNode::InsertInto(plan) => self.visit_insert_into(plan),
+            PlanNode::Delete(plan) => self.visit_delete(plan),
+            PlanNode::Update(plan) => self.visit_update(plan),
             PlanNode::ShowCreateTable(plan) => self.visit_show_create_table(plan),
             PlanNode::SubQueryExpression(plan) => self.visit_sub_queries_sets(plan),
             PlanNode::Kill(plan) => self.visit_kill_query(plan),
@@ -206,6 +216,18 @@ pub trait PlanVisitor {
         self.visit_exprs(&plan.order_by)
     }
 
+    fn visit_cross_join(&mut self, plan: &CrossJoinPlan) -> Result<()> {
+        self.visit_plan_node(plan.probe.as_ref())?;
+        self.visit_plan_node(plan.build.as_ref())
+    }
+
+    fn visit_join(&mut self, plan: &JoinPlan) -> Result<()> {
+        self.visit_plan_node(plan.probe.as_ref())?;
+        self.visit_plan_node(plan.build.as_ref())?;
+        self.visit_exprs(&plan.probe_keys)?;
+        self.visit_exprs(&plan.build_keys)
+    }
+
     fn visit_limit(&mut self, plan: &LimitPlan) -> Result<()> {
         self.visit_plan_node(plan.input.as_ref())
     }
@@ -214,6 +236,12 @@ pub trait PlanVisitor {
         self.visit_plan_node(plan.input.as_ref())
     }
 
+    fn visit_window(&mut self, plan: &WindowPlan) -> Result<()> {
+        self.visit_plan_node(plan.input.as_ref())?;
+        self.visit_exprs(&plan.partition_by)?;
+        self.visit_exprs(&plan.order_by)
+    }
+
     fn visit_scan(&mut self, _: &ScanPlan) -> Result<()> {
         Ok(())
     }
@@ -262,6 +290,19 @@ pub trait PlanVisitor {
         Ok(())
     }
 
+    fn visit_delete(&mut self, plan: &DeletePlan) -> Result<()> {
+        self.visit_plan_node(plan.input.as_ref())?;
+        self.visit_expr(&plan.predicate)
+    }
+
+    fn visit_update(&mut self, plan: &UpdatePlan) -> Result<()> {
+        self.visit_plan_node(plan.input.as_ref())?;
+        for (_, value) in &plan.assignments {
+            self.visit_expr(value)?;
+        }
+        self.visit_expr(&plan.predicate)
+    }
+
     fn visit_show_create_table(&mut self, _: &ShowCreateTablePlan) -> Result<()> {
         Ok(())
     }
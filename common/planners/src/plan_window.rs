@@ -0,0 +1,98 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
+
+use crate::Expression;
+use crate::PlanNode;
+
+/// The ranking functions supported by the window plan.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum WindowFunction {
+    /// Sequential number of the row within its partition, starting at 1.
+    RowNumber,
+    /// Rank of the row within its partition, with gaps after ties.
+    Rank,
+    /// Rank of the row within its partition, without gaps after ties.
+    DenseRank,
+}
+
+impl fmt::Display for WindowFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WindowFunction::RowNumber => write!(f, "row_number"),
+            WindowFunction::Rank => write!(f, "rank"),
+            WindowFunction::DenseRank => write!(f, "dense_rank"),
+        }
+    }
+}
+
+/// Computes a ranking function over the rows of every partition,
+/// the result is appended to the input as a UInt64 column.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct WindowPlan {
+    /// The ranking function
+    pub func: WindowFunction,
+    /// The name of the result column
+    pub column_name: String,
+    /// The expression to partition on
+    pub partition_by: Vec<Expression>,
+    /// The sort expression to rank on
+    pub order_by: Vec<Expression>,
+    /// The logical plan
+    pub input: Arc<PlanNode>,
+    /// Output data schema
+    pub schema: DataSchemaRef,
+}
+
+impl WindowPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn set_input(&mut self, node: &PlanNode) {
+        self.input = Arc::new(node.clone());
+    }
+
+    /// The output schema: the input fields followed by the ranking column.
+    pub fn window_schema(input: &PlanNode, column_name: &str) -> DataSchemaRef {
+        let mut fields = input.schema().fields().clone();
+        fields.push(DataField::new(column_name, DataType::UInt64, false));
+        DataSchemaRefExt::create(fields)
+    }
+
+    /// The sort expressions the input must be ordered by:
+    /// the partition keys first, then the order keys.
+    pub fn sort_exprs(&self) -> Vec<Expression> {
+        let mut exprs = Vec::with_capacity(self.partition_by.len() + self.order_by.len());
+        for expr in &self.partition_by {
+            exprs.push(match expr {
+                Expression::Sort { .. } => expr.clone(),
+                _ => Expression::Sort {
+                    expr: Box::new(expr.clone()),
+                    asc: true,
+                    nulls_first: false,
+                },
+            });
+        }
+        exprs.extend(self.order_by.iter().cloned());
+        exprs
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::*;
+use common_exception::Result;
+use pretty_assertions::assert_eq;
+
+use crate::*;
+
+#[test]
+fn test_window_plan() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::UInt64, false),
+        DataField::new("b", DataType::UInt64, false),
+    ]);
+
+    let plan = PlanBuilder::create(schema)
+        .window(WindowFunction::DenseRank, "r", &[col("a")], &[sort(
+            "b", false, false,
+        )])?
+        .build()?;
+
+    let expect = "Window: dense_rank() as r, partitionBy=[a], orderBy=[b]";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    assert_eq!(
+        vec!["a", "b", "r"],
+        plan.schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>()
+    );
+
+    if let PlanNode::Window(plan) = plan {
+        assert_eq!(
+            vec![sort("a", true, false), sort("b", false, false)],
+            plan.sort_exprs()
+        );
+    }
+    Ok(())
+}
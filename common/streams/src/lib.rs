@@ -24,6 +24,15 @@ mod stream_skip_test;
 #[cfg(test)]
 mod stream_limit_by_test;
 
+#[cfg(test)]
+mod stream_reblock_test;
+
+#[cfg(test)]
+mod stream_take_test;
+
+#[cfg(test)]
+mod stream_throttle_test;
+
 mod sources;
 mod stream;
 mod stream_abort;
@@ -32,11 +41,14 @@ mod stream_datablock;
 mod stream_limit_by;
 mod stream_parquet;
 mod stream_progress;
+mod stream_reblock;
 mod stream_skip;
 mod stream_sort;
 mod stream_source;
 mod stream_sub_queries;
 mod stream_take;
+mod stream_throttle;
+mod stream_timeout;
 
 pub use sources::*;
 pub use stream::SendableDataBlockStream;
@@ -46,8 +58,12 @@ pub use stream_datablock::DataBlockStream;
 pub use stream_limit_by::LimitByStream;
 pub use stream_parquet::ParquetStream;
 pub use stream_progress::ProgressStream;
+pub use stream_reblock::ReblockStream;
 pub use stream_skip::SkipStream;
 pub use stream_sort::SortStream;
 pub use stream_source::SourceStream;
 pub use stream_sub_queries::SubQueriesStream;
 pub use stream_take::TakeStream;
+pub use stream_throttle::ThrottleLimiter;
+pub use stream_throttle::ThrottleStream;
+pub use stream_timeout::TimeoutStream;
@@ -55,6 +55,8 @@ impl Stream for ProgressStream {
                             read_rows: block.num_rows(),
                             read_bytes: block.memory_size(),
                             total_rows_to_read: 0,
+                            read_partitions: 0,
+                            total_partitions: 0,
                         };
 
                         (this.callback)(&progress_values);
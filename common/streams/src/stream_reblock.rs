@@ -0,0 +1,90 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use futures::ready;
+use futures::Stream;
+use futures::StreamExt;
+
+use crate::SendableDataBlockStream;
+
+/// ReblockStream coalesces the small blocks of the input until they reach
+/// `block_size` rows, the remaining rows are flushed at the end of the input.
+pub struct ReblockStream {
+    input: SendableDataBlockStream,
+    block_size: usize,
+    blocks: Vec<DataBlock>,
+    rows: usize,
+    finished: bool,
+}
+
+impl ReblockStream {
+    pub fn new(input: SendableDataBlockStream, block_size: usize) -> Self {
+        ReblockStream {
+            input,
+            block_size,
+            blocks: vec![],
+            rows: 0,
+            finished: false,
+        }
+    }
+
+    fn flush(&mut self) -> Result<DataBlock> {
+        let blocks = std::mem::take(&mut self.blocks);
+        self.rows = 0;
+        match blocks.len() {
+            1 => Ok(blocks[0].clone()),
+            _ => DataBlock::concat_blocks(&blocks),
+        }
+    }
+}
+
+impl Stream for ReblockStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            match ready!(self.input.poll_next_unpin(ctx)) {
+                Some(Ok(block)) => {
+                    if block.num_rows() == 0 {
+                        continue;
+                    }
+
+                    self.rows += block.num_rows();
+                    self.blocks.push(block);
+                    if self.rows >= self.block_size {
+                        return Poll::Ready(Some(self.flush()));
+                    }
+                }
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => {
+                    self.finished = true;
+                    if self.blocks.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    return Poll::Ready(Some(self.flush()));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_datablocks::*;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use futures::TryStreamExt;
+
+use crate::*;
+
+#[tokio::test]
+async fn test_reblockstream() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![DataField::new("id", DataType::Int32, false)]);
+
+    // 10 blocks with 1 row each.
+    let blocks = (0..10)
+        .map(|i| DataBlock::create_by_array(schema.clone(), vec![Series::new(vec![i as i32])]))
+        .collect::<Vec<_>>();
+
+    let stream = DataBlockStream::create(schema, None, blocks);
+    let result = ReblockStream::new(Box::pin(stream), 4)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    // Two full blocks and the remaining 2 rows flushed at the end.
+    let rows = result.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+    assert_eq!(vec![4, 4, 2], rows);
+
+    let expected = vec![
+        "+----+", "| id |", "+----+", "| 0  |", "| 1  |", "| 2  |", "| 3  |", "| 4  |", "| 5  |",
+        "| 6  |", "| 7  |", "| 8  |", "| 9  |", "+----+",
+    ];
+    assert_blocks_eq(expected, &result);
+
+    Ok(())
+}
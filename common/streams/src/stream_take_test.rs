@@ -0,0 +1,112 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common_base::tokio;
+use common_datablocks::*;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use futures::stream::StreamExt;
+use futures::TryStreamExt;
+
+use crate::*;
+
+/// A stream of `blocks` blocks with 10 rows each, counting the blocks pulled.
+fn counting_stream(blocks: i32, pulled: Arc<AtomicUsize>) -> SendableDataBlockStream {
+    let schema = DataSchemaRefExt::create(vec![DataField::new("id", DataType::Int32, false)]);
+    let blocks = (0..blocks)
+        .map(|i| {
+            let ids = (i * 10..(i + 1) * 10).collect::<Vec<i32>>();
+            DataBlock::create_by_array(schema.clone(), vec![Series::new(ids)])
+        })
+        .collect::<Vec<_>>();
+
+    Box::pin(
+        DataBlockStream::create(schema, None, blocks).inspect(move |_| {
+            pulled.fetch_add(1, Ordering::SeqCst);
+        }),
+    )
+}
+
+#[tokio::test]
+async fn test_takestream() -> Result<()> {
+    struct Test {
+        name: &'static str,
+        limit: usize,
+        offset: usize,
+        rows: usize,
+        pulled: usize,
+    }
+
+    let tests = vec![
+        Test {
+            name: "limit within the first block",
+            limit: 5,
+            offset: 0,
+            rows: 5,
+            pulled: 1,
+        },
+        Test {
+            name: "limit at the block boundary",
+            limit: 20,
+            offset: 0,
+            rows: 20,
+            pulled: 2,
+        },
+        Test {
+            name: "limit with offset",
+            limit: 10,
+            offset: 15,
+            rows: 10,
+            pulled: 3,
+        },
+        Test {
+            name: "offset beyond the input",
+            limit: 10,
+            offset: 1000,
+            rows: 0,
+            pulled: 10,
+        },
+        Test {
+            name: "zero limit",
+            limit: 0,
+            offset: 0,
+            rows: 0,
+            pulled: 0,
+        },
+    ];
+
+    for t in tests {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let input = counting_stream(10, pulled.clone());
+        let input: SendableDataBlockStream = match t.offset {
+            0 => input,
+            offset => Box::pin(SkipStream::new(input, offset)),
+        };
+
+        let result = TakeStream::new(input, t.limit)
+            .try_collect::<Vec<_>>()
+            .await?;
+        let rows: usize = result.iter().map(|block| block.num_rows()).sum();
+
+        assert_eq!(t.rows, rows, "{}", t.name);
+        // The input must not be drained once the limit is reached.
+        assert_eq!(t.pulled, pulled.load(Ordering::SeqCst), "{}", t.name);
+    }
+
+    Ok(())
+}
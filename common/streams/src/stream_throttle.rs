@@ -0,0 +1,175 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+
+use common_base::tokio::time::sleep;
+use common_base::tokio::time::Instant;
+use common_base::tokio::time::Sleep;
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use futures::ready;
+use futures::Stream;
+use futures::StreamExt;
+
+use crate::SendableDataBlockStream;
+
+/// ThrottleStream caps the number of rows and bytes its input produces per second.
+///
+/// Every block takes its rows and bytes out of token buckets refilled at the configured rates.
+/// A block taking more tokens than the buckets hold is held back, and the stream sleeps until
+/// the buckets are refilled, instead of polling the input again.
+pub struct ThrottleStream {
+    input: SendableDataBlockStream,
+    limiter: Arc<ThrottleLimiter>,
+    pending: Option<DataBlock>,
+    delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl ThrottleStream {
+    /// A rate of 0 means no limit on it.
+    pub fn create(
+        input: SendableDataBlockStream,
+        rows_per_second: u64,
+        bytes_per_second: u64,
+    ) -> Self {
+        let limiter = ThrottleLimiter::create(rows_per_second, bytes_per_second);
+        Self::create_with_limiter(input, Arc::new(limiter))
+    }
+
+    /// The streams created with the same `limiter` are capped together, at the rates of the limiter.
+    pub fn create_with_limiter(
+        input: SendableDataBlockStream,
+        limiter: Arc<ThrottleLimiter>,
+    ) -> Self {
+        ThrottleStream {
+            input,
+            limiter,
+            pending: None,
+            delay: None,
+        }
+    }
+}
+
+/// The token buckets of the rows and bytes, shared by the ThrottleStreams created with it.
+pub struct ThrottleLimiter {
+    buckets: Mutex<(Option<TokenBucket>, Option<TokenBucket>)>,
+}
+
+impl ThrottleLimiter {
+    /// A rate of 0 means no limit on it.
+    pub fn create(rows_per_second: u64, bytes_per_second: u64) -> Self {
+        ThrottleLimiter {
+            buckets: Mutex::new((
+                TokenBucket::create(rows_per_second),
+                TokenBucket::create(bytes_per_second),
+            )),
+        }
+    }
+
+    /// Whether neither the rows nor the bytes are limited.
+    pub fn is_unlimited(&self) -> bool {
+        matches!(*self.lock(), (None, None))
+    }
+
+    /// Take the tokens of a block, returns how long to wait before it is allowed to be forwarded.
+    fn take(&self, block: &DataBlock) -> Duration {
+        let now = Instant::now();
+        let mut wait = Duration::ZERO;
+        let mut buckets = self.lock();
+        if let Some(rows) = buckets.0.as_mut() {
+            wait = wait.max(rows.take(block.num_rows() as f64, now));
+        }
+        if let Some(bytes) = buckets.1.as_mut() {
+            wait = wait.max(bytes.take(block.memory_size() as f64, now));
+        }
+        wait
+    }
+
+    fn lock(&self) -> MutexGuard<'_, (Option<TokenBucket>, Option<TokenBucket>)> {
+        self.buckets.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Stream for ThrottleStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(delay) = this.delay.as_mut() {
+                ready!(delay.as_mut().poll(ctx));
+                this.delay = None;
+                return Poll::Ready(this.pending.take().map(Ok));
+            }
+
+            match ready!(this.input.poll_next_unpin(ctx)) {
+                Some(Ok(block)) => {
+                    let wait = this.limiter.take(&block);
+                    if wait.is_zero() {
+                        return Poll::Ready(Some(Ok(block)));
+                    }
+                    this.pending = Some(block);
+                    this.delay = Some(Box::pin(sleep(wait)));
+                }
+                other => return Poll::Ready(other),
+            }
+        }
+    }
+}
+
+/// A token bucket holding at most one second of tokens.
+///
+/// It starts empty, so that the rate is not exceeded by a burst at the beginning.
+/// Taking more tokens than it holds leaves it in debt, which is paid off by the refilling.
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn create(rate: u64) -> Option<Self> {
+        match rate {
+            0 => None,
+            _ => Some(TokenBucket {
+                rate: rate as f64,
+                tokens: 0.0,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    fn take(&mut self, n: f64, now: Instant) -> Duration {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.refilled_at = now;
+
+        self.tokens -= n;
+        match self.tokens < 0.0 {
+            true => Duration::from_secs_f64(-self.tokens / self.rate),
+            false => Duration::ZERO,
+        }
+    }
+}
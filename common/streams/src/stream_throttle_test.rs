@@ -0,0 +1,99 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use common_base::tokio;
+use common_datablocks::*;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use futures::TryStreamExt;
+
+use crate::*;
+
+/// A stream of `blocks` blocks with `rows` rows each.
+fn blocks_stream(blocks: usize, rows: usize) -> SendableDataBlockStream {
+    let schema = DataSchemaRefExt::create(vec![DataField::new("id", DataType::Int64, false)]);
+    let blocks = (0..blocks)
+        .map(|_| {
+            let ids = (0..rows as i64).collect::<Vec<i64>>();
+            DataBlock::create_by_array(schema.clone(), vec![Series::new(ids)])
+        })
+        .collect::<Vec<_>>();
+
+    Box::pin(DataBlockStream::create(schema, None, blocks))
+}
+
+#[tokio::test]
+async fn test_throttle_stream_rows() -> Result<()> {
+    // 5000 rows at 10000 rows/s take at least 0.5 second.
+    let start = Instant::now();
+    let stream = ThrottleStream::create(blocks_stream(10, 500), 10000, 0);
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let elapsed = start.elapsed();
+
+    let rows = result.iter().map(|b| b.num_rows()).sum::<usize>();
+    assert_eq!(5000, rows);
+    assert!(elapsed >= Duration::from_millis(500), "{:?}", elapsed);
+    assert!(rows as f64 / elapsed.as_secs_f64() <= 10000.0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_throttle_stream_bytes() -> Result<()> {
+    // Every block of 1000 Int64 rows is 8000 bytes, 4 of them at 64000 bytes/s take at least 0.5 second.
+    let start = Instant::now();
+    let stream = ThrottleStream::create(blocks_stream(4, 1000), 0, 64000);
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let elapsed = start.elapsed();
+
+    let bytes = result.iter().map(|b| b.memory_size()).sum::<usize>();
+    assert!(bytes >= 32000);
+    assert!(elapsed >= Duration::from_millis(500), "{:?}", elapsed);
+    assert!(bytes as f64 / elapsed.as_secs_f64() <= 64000.0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_throttle_stream_unlimited() -> Result<()> {
+    let start = Instant::now();
+    let stream = ThrottleStream::create(blocks_stream(10, 500), 0, 0);
+    let result = stream.try_collect::<Vec<_>>().await?;
+
+    assert_eq!(10, result.len());
+    assert!(start.elapsed() < Duration::from_millis(500));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_throttle_stream_shared_limiter() -> Result<()> {
+    // 2 streams of 2500 rows sharing 10000 rows/s take at least 0.5 second together.
+    let limiter = Arc::new(ThrottleLimiter::create(10000, 0));
+    let start = Instant::now();
+    let a = ThrottleStream::create_with_limiter(blocks_stream(5, 500), limiter.clone());
+    let b = ThrottleStream::create_with_limiter(blocks_stream(5, 500), limiter);
+    let (a, b) = futures::try_join!(a.try_collect::<Vec<_>>(), b.try_collect::<Vec<_>>())?;
+    let elapsed = start.elapsed();
+
+    let rows = a
+        .iter()
+        .chain(b.iter())
+        .map(|b| b.num_rows())
+        .sum::<usize>();
+    assert_eq!(5000, rows);
+    assert!(elapsed >= Duration::from_millis(500), "{:?}", elapsed);
+    Ok(())
+}
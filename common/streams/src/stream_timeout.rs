@@ -0,0 +1,79 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+
+use common_base::tokio::time::sleep;
+use common_base::tokio::time::Sleep;
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use futures::Stream;
+use futures::StreamExt;
+
+use crate::SendableDataBlockStream;
+
+/// TimeoutStream fails with `QueryTimeout` once `timeout` elapsed since it was created,
+/// `on_timeout` is called at that moment to cancel the producers of the input.
+pub struct TimeoutStream {
+    input: SendableDataBlockStream,
+    timeout: Duration,
+    deadline: Pin<Box<Sleep>>,
+    on_timeout: Option<Box<dyn FnOnce() + Send>>,
+    finished: bool,
+}
+
+impl TimeoutStream {
+    pub fn create(
+        input: SendableDataBlockStream,
+        timeout: Duration,
+        on_timeout: Box<dyn FnOnce() + Send>,
+    ) -> Self {
+        TimeoutStream {
+            input,
+            timeout,
+            deadline: Box::pin(sleep(timeout)),
+            on_timeout: Some(on_timeout),
+            finished: false,
+        }
+    }
+}
+
+impl Stream for TimeoutStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        if self.deadline.as_mut().poll(ctx).is_ready() {
+            self.finished = true;
+            if let Some(on_timeout) = self.on_timeout.take() {
+                on_timeout();
+            }
+
+            return Poll::Ready(Some(Err(ErrorCode::QueryTimeout(format!(
+                "Query exceeded the max execution time of {:?}",
+                self.timeout
+            )))));
+        }
+
+        self.input.poll_next_unpin(ctx)
+    }
+}
@@ -188,9 +188,11 @@ impl FlightService for MetaFlightImpl {
         info!("Receive do_action: {:?}", action);
 
         let s = JsonSer;
-        let body = self.action_handler.execute(action, s).await?;
-        let arrow = arrow_flight::Result { body };
-        let output = futures::stream::once(async { Ok(arrow) });
+        let replies = self.action_handler.execute_stream(action, s).await?;
+        let output = futures::stream::iter(replies.map(|body| {
+            body.map(|body| arrow_flight::Result { body })
+                .map_err(Status::from)
+        }));
         Ok(Response::new(Box::pin(output)))
     }
 
@@ -21,6 +21,9 @@ use serde::Serialize;
 
 use crate::meta_service::MetaNode;
 
+/// The serialized messages of a reply, in the order to send.
+pub type ReplyChunks<R> = Box<dyn Iterator<Item = Result<R, ErrorCode>> + Send + Sync>;
+
 pub trait ReplySerializer {
     type Output;
     fn serialize<T>(&self, v: T) -> Result<Self::Output, ErrorCode>
@@ -63,16 +66,63 @@ impl ActionHandler {
 
             // database
             MetaFlightAction::CreateDatabase(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::UpsertDatabase(a) => s.serialize(self.handle(a).await?),
             MetaFlightAction::GetDatabase(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::DatabaseExists(a) => s.serialize(self.handle(a).await?),
             MetaFlightAction::DropDatabase(a) => s.serialize(self.handle(a).await?),
             MetaFlightAction::GetDatabases(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::ListDatabases(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::GetCatalogSnapshot(a) => s.serialize(self.handle(a).await?),
 
             // table
             MetaFlightAction::CreateTable(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::CreateTables(a) => s.serialize(self.handle(a).await?),
             MetaFlightAction::DropTable(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::RenameTable(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::AddColumn(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::TruncateTable(a) => s.serialize(self.handle(a).await?),
             MetaFlightAction::GetTable(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::TableExists(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::GetTableStatistics(a) => s.serialize(self.handle(a).await?),
             MetaFlightAction::GetTables(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::GetTablesWithPrefix(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::GetTablesChunked(a) => s.serialize(self.handle(a).await?),
             MetaFlightAction::GetTableExt(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::GetTablesByIds(a) => s.serialize(self.handle(a).await?),
+            MetaFlightAction::ListTableVersions(a) => s.serialize(self.handle(a).await?),
+
+            // health
+            MetaFlightAction::Ping(a) => s.serialize(self.handle(a).await?),
+        }
+    }
+
+    /// Execute an action whose reply may be split into more than one message.
+    ///
+    /// The chunks are serialized only when they are iterated,
+    /// so that a large reply is not held twice in memory.
+    /// An action that is not chunked is replied in exactly one message.
+    pub async fn execute_stream<S, R>(
+        &self,
+        action: MetaFlightAction,
+        s: S,
+    ) -> common_exception::Result<ReplyChunks<R>>
+    where
+        S: ReplySerializer<Output = R> + Send + Sync + 'static,
+    {
+        match action {
+            MetaFlightAction::GetTablesChunked(a) => {
+                let chunk_size = a.chunk_size.max(1) as usize;
+                let tables = self.handle(a).await?;
+                let chunks = tables
+                    .chunks(chunk_size)
+                    .map(|c| c.to_vec())
+                    .collect::<Vec<_>>();
+                Ok(Box::new(chunks.into_iter().map(move |c| s.serialize(c))))
+            }
+            _ => {
+                let reply = self.execute(action, s).await?;
+                Ok(Box::new(std::iter::once(Ok(reply))))
+            }
         }
     }
 }
@@ -14,6 +14,7 @@
 //
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
@@ -21,28 +22,57 @@ use common_arrow::arrow::datatypes::Schema as ArrowSchema;
 use common_arrow::arrow::io::ipc::write::common::IpcWriteOptions;
 use common_arrow::arrow_flight::utils::flight_data_from_arrow_schema;
 use common_arrow::arrow_flight::FlightData;
+use common_datavalues::DataSchema;
 use common_exception::ErrorCode;
+use common_meta_flight::AddColumnAction;
 use common_meta_flight::CreateDatabaseAction;
 use common_meta_flight::CreateTableAction;
+use common_meta_flight::CreateTablesAction;
+use common_meta_flight::DatabaseExistsAction;
 use common_meta_flight::DropDatabaseAction;
 use common_meta_flight::DropTableAction;
+use common_meta_flight::GetCatalogSnapshotAction;
 use common_meta_flight::GetDatabaseAction;
 use common_meta_flight::GetDatabasesAction;
 use common_meta_flight::GetTableAction;
 use common_meta_flight::GetTableExtReq;
+use common_meta_flight::GetTableStatisticsAction;
 use common_meta_flight::GetTablesAction;
+use common_meta_flight::GetTablesByIdsAction;
+use common_meta_flight::GetTablesChunkedAction;
+use common_meta_flight::GetTablesWithPrefixAction;
+use common_meta_flight::ListDatabasesAction;
+use common_meta_flight::ListTableVersionsAction;
+use common_meta_flight::RenameTableAction;
+use common_meta_flight::TableExistsAction;
+use common_meta_flight::TruncateTableAction;
+use common_meta_flight::UpsertDatabaseAction;
 use common_meta_raft_store::state_machine::AppliedState;
+use common_meta_types::table_version_mismatch;
+use common_meta_types::BatchItemReply;
+use common_meta_types::BatchMode;
+use common_meta_types::CatalogSnapshot;
 use common_meta_types::Cmd::CreateDatabase;
 use common_meta_types::Cmd::CreateTable;
+use common_meta_types::Cmd::CreateTables;
 use common_meta_types::Cmd::DropDatabase;
 use common_meta_types::Cmd::DropTable;
+use common_meta_types::Cmd::RenameTable;
+use common_meta_types::Cmd::TruncateTable;
+use common_meta_types::Cmd::UpdateTableSchema;
 use common_meta_types::CreateDatabaseReply;
+use common_meta_types::CreateTableEntry;
 use common_meta_types::CreateTableReply;
 use common_meta_types::Database;
 use common_meta_types::DatabaseInfo;
+use common_meta_types::DatabaseSnapshot;
+use common_meta_types::ListDatabasesReply;
 use common_meta_types::LogEntry;
+use common_meta_types::MetaVersion;
 use common_meta_types::Table;
 use common_meta_types::TableInfo;
+use common_meta_types::TableStatistics;
+use common_meta_types::UpsertDatabaseReply;
 use log::info;
 
 use crate::executor::action_handler::RequestHandler;
@@ -103,6 +133,56 @@ impl RequestHandler<CreateDatabaseAction> for ActionHandler {
     }
 }
 
+#[async_trait::async_trait]
+impl RequestHandler<UpsertDatabaseAction> for ActionHandler {
+    async fn handle(
+        &self,
+        act: UpsertDatabaseAction,
+    ) -> common_exception::Result<UpsertDatabaseReply> {
+        let plan = act.plan;
+        let db_name = &plan.db;
+
+        let cr = LogEntry {
+            txid: None,
+            cmd: CreateDatabase {
+                name: db_name.clone(),
+                if_not_exists: true,
+                db: Database {
+                    database_id: 0,
+                    database_engine: plan.engine.clone(),
+                    tables: HashMap::new(),
+                },
+            },
+        };
+
+        let rst = self
+            .meta_node
+            .write(cr)
+            .await
+            .map_err(|e| ErrorCode::MetaNodeInternalError(e.to_string()))?;
+
+        match rst {
+            AppliedState::DataBase { prev, result } => {
+                let created = prev.is_none();
+                let db = prev.or(result).ok_or_else(|| {
+                    ErrorCode::MetaNodeInternalError("database is neither created nor existing")
+                })?;
+
+                Ok(UpsertDatabaseReply {
+                    database: Arc::new(DatabaseInfo {
+                        database_id: db.database_id,
+                        db: db_name.clone(),
+                        engine: db.database_engine,
+                    }),
+                    created,
+                })
+            }
+
+            _ => Err(ErrorCode::MetaNodeInternalError("not a Database result")),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl RequestHandler<GetDatabaseAction> for ActionHandler {
     async fn handle(&self, act: GetDatabaseAction) -> common_exception::Result<DatabaseInfo> {
@@ -123,6 +203,14 @@ impl RequestHandler<GetDatabaseAction> for ActionHandler {
     }
 }
 
+#[async_trait::async_trait]
+impl RequestHandler<DatabaseExistsAction> for ActionHandler {
+    async fn handle(&self, act: DatabaseExistsAction) -> common_exception::Result<bool> {
+        let db = self.meta_node.get_database(&act.db).await;
+        Ok(db.is_some())
+    }
+}
+
 #[async_trait::async_trait]
 impl RequestHandler<DropDatabaseAction> for ActionHandler {
     async fn handle(&self, act: DropDatabaseAction) -> common_exception::Result<()> {
@@ -132,6 +220,7 @@ impl RequestHandler<DropDatabaseAction> for ActionHandler {
             txid: None,
             cmd: DropDatabase {
                 name: db_name.clone(),
+                cascade: act.plan.cascade,
             },
         };
 
@@ -142,6 +231,13 @@ impl RequestHandler<DropDatabaseAction> for ActionHandler {
             .map_err(|e| ErrorCode::MetaNodeInternalError(e.to_string()))?;
 
         match rst {
+            AppliedState::DataBase {
+                prev: Some(_),
+                result: Some(_),
+            } => Err(ErrorCode::DatabaseNotEmpty(format!(
+                "database not empty: {:}, drop it with cascade",
+                db_name
+            ))),
             AppliedState::DataBase { prev, .. } => {
                 if prev.is_some() || if_exists {
                     Ok(())
@@ -180,6 +276,8 @@ impl RequestHandler<CreateTableAction> for ActionHandler {
             table_engine: plan.engine.clone(),
             table_options: plan.options.clone(),
             parts: Default::default(),
+            version: 0,
+            schema_history: Default::default(),
         };
 
         let cr = LogEntry {
@@ -222,12 +320,118 @@ impl RequestHandler<CreateTableAction> for ActionHandler {
     }
 }
 
+#[async_trait::async_trait]
+impl RequestHandler<CreateTablesAction> for ActionHandler {
+    async fn handle(
+        &self,
+        act: CreateTablesAction,
+    ) -> common_exception::Result<Vec<BatchItemReply<CreateTableReply>>> {
+        let plans = act.plans;
+        let mode = act.mode;
+
+        info!("create tables: {} tables, {:?}", plans.len(), mode);
+
+        let mut names = HashSet::new();
+        let mut tables = Vec::with_capacity(plans.len());
+        for plan in plans.iter() {
+            // With ContinueOnError the failing tables are reported by the state machine.
+            if mode == BatchMode::AbortOnError
+                && self.meta_node.get_database(&plan.db).await.is_none()
+            {
+                return Err(ErrorCode::UnknownDatabase(format!(
+                    "create tables: database not found {:}",
+                    plan.db
+                )));
+            }
+            if mode == BatchMode::AbortOnError
+                && !names.insert((&plan.db, &plan.table))
+                && !plan.if_not_exists
+            {
+                return Err(ErrorCode::TableAlreadyExists(format!(
+                    "table exists: {}",
+                    plan.table
+                )));
+            }
+
+            let options = IpcWriteOptions::default();
+            let flight_data = flight_data_from_arrow_schema(&plan.schema.to_arrow(), &options);
+
+            tables.push(CreateTableEntry {
+                db_name: plan.db.clone(),
+                table_name: plan.table.clone(),
+                if_not_exists: plan.if_not_exists,
+                table: Table {
+                    table_id: 0,
+                    table_name: plan.table.clone(),
+                    database_id: 0, // this field is unused during the creation of table
+                    db_name: plan.db.clone(),
+                    schema: flight_data.data_header,
+                    table_engine: plan.engine.clone(),
+                    table_options: plan.options.clone(),
+                    parts: Default::default(),
+                    version: 0,
+                    schema_history: Default::default(),
+                },
+            });
+        }
+
+        let cr = LogEntry {
+            txid: None,
+            cmd: CreateTables { tables, mode },
+        };
+
+        let rst = self
+            .meta_node
+            .write(cr)
+            .await
+            .map_err(|e| ErrorCode::MetaNodeInternalError(e.to_string()))?;
+
+        match rst {
+            AppliedState::Tables { prev, result } => {
+                let mut replies = Vec::with_capacity(plans.len());
+                for ((plan, prev), result) in plans.iter().zip(prev).zip(result) {
+                    let reply = match result {
+                        Some(table) => Ok(CreateTableReply {
+                            table_id: table.table_id,
+                        }),
+                        None if prev.is_some() && !plan.if_not_exists => Err(
+                            ErrorCode::TableAlreadyExists(format!("table exists: {}", plan.table)),
+                        ),
+                        None => Err(ErrorCode::UnknownDatabase(format!(
+                            "create tables: database not found {:}",
+                            plan.db
+                        ))),
+                    };
+                    replies.push(reply);
+                }
+
+                if mode == BatchMode::AbortOnError && replies.iter().any(Result::is_err) {
+                    // Nothing is created: a conflicting table is the cause,
+                    // otherwise a database was dropped meanwhile.
+                    let mut errors = replies
+                        .into_iter()
+                        .filter_map(Result::err)
+                        .collect::<Vec<_>>();
+                    let i = errors
+                        .iter()
+                        .position(|e| e.code() == ErrorCode::TableAlreadyExists("").code())
+                        .unwrap_or(0);
+                    return Err(errors.swap_remove(i));
+                }
+                Ok(replies.into_iter().map(Into::into).collect())
+            }
+            _ => Err(ErrorCode::MetaNodeInternalError("not a Tables result")),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl RequestHandler<DropTableAction> for ActionHandler {
     async fn handle(&self, act: DropTableAction) -> common_exception::Result<()> {
         let db_name = &act.plan.db;
         let table_name = &act.plan.table;
         let if_exists = act.plan.if_exists;
+        let expected_version = act.plan.expected_version;
 
         let cr = LogEntry {
             txid: None,
@@ -235,6 +439,7 @@ impl RequestHandler<DropTableAction> for ActionHandler {
                 db_name: db_name.clone(),
                 table_name: table_name.clone(),
                 if_exists,
+                expected_version,
             },
         };
 
@@ -245,6 +450,14 @@ impl RequestHandler<DropTableAction> for ActionHandler {
             .map_err(|e| ErrorCode::MetaNodeInternalError(e.to_string()))?;
 
         match rst {
+            AppliedState::Table {
+                prev: Some(prev),
+                result: Some(_),
+            } => Err(table_version_mismatch(
+                format_args!("drop table {}", table_name),
+                expected_version,
+                prev.version,
+            )),
             AppliedState::Table { prev, .. } => {
                 if prev.is_some() || if_exists {
                     Ok(())
@@ -260,6 +473,213 @@ impl RequestHandler<DropTableAction> for ActionHandler {
     }
 }
 
+#[async_trait::async_trait]
+impl RequestHandler<RenameTableAction> for ActionHandler {
+    async fn handle(&self, act: RenameTableAction) -> common_exception::Result<()> {
+        let plan = act.plan;
+        let db_name = &plan.db;
+        let table_name = &plan.table;
+        let new_db_name = &plan.new_db;
+        let new_table_name = &plan.new_table;
+
+        if self.meta_node.get_database(new_db_name).await.is_none() {
+            return Err(ErrorCode::UnknownDatabase(format!(
+                "rename table: database not found {:}",
+                new_db_name
+            )));
+        }
+
+        let cr = LogEntry {
+            txid: None,
+            cmd: RenameTable {
+                db_name: db_name.clone(),
+                table_name: table_name.clone(),
+                new_db_name: new_db_name.clone(),
+                new_table_name: new_table_name.clone(),
+            },
+        };
+
+        let rst = self
+            .meta_node
+            .write(cr)
+            .await
+            .map_err(|e| ErrorCode::MetaNodeInternalError(e.to_string()))?;
+
+        match rst {
+            AppliedState::Table { prev: None, .. } => Err(ErrorCode::UnknownTable(format!(
+                "table not found: {:}",
+                table_name
+            ))),
+            AppliedState::Table { prev, result } if prev == result => Err(
+                ErrorCode::TableAlreadyExists(format!("table exists: {}", new_table_name)),
+            ),
+            AppliedState::Table { .. } => Ok(()),
+            _ => Err(ErrorCode::MetaNodeInternalError("not a Table result")),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestHandler<AddColumnAction> for ActionHandler {
+    async fn handle(&self, act: AddColumnAction) -> common_exception::Result<()> {
+        let plan = act.plan;
+        let db_name = &plan.db;
+        let table_name = &plan.table;
+        let field = &plan.field;
+
+        if !field.is_nullable() {
+            return Err(ErrorCode::BadArguments(format!(
+                "add column: column {} must be nullable",
+                field.name()
+            )));
+        }
+
+        let db = self.meta_node.get_database(db_name).await.ok_or_else(|| {
+            ErrorCode::UnknownDatabase(format!("add column: database not found {:}", db_name))
+        })?;
+
+        let table_id = db
+            .tables
+            .get(table_name)
+            .ok_or_else(|| ErrorCode::UnknownTable(format!("table not found: {:}", table_name)))?;
+
+        let table =
+            self.meta_node.get_table(table_id).await.ok_or_else(|| {
+                ErrorCode::UnknownTable(format!("table not found: {:}", table_name))
+            })?;
+
+        let arrow_schema = ArrowSchema::try_from(&FlightData {
+            data_header: table.schema,
+            ..Default::default()
+        })
+        .map_err(|e| ErrorCode::IllegalSchema(format!("invalid schema: {:}", e.to_string())))?;
+        let schema: DataSchema = arrow_schema.into();
+
+        if schema.column_with_name(field.name()).is_some() {
+            return Err(ErrorCode::ColumnAlreadyExists(format!(
+                "column {} already exists in table {}",
+                field.name(),
+                table_name
+            )));
+        }
+
+        let mut fields = schema.fields().clone();
+        fields.push(field.clone());
+        let new_schema = DataSchema::new(fields);
+
+        let options = IpcWriteOptions::default();
+        let flight_data = flight_data_from_arrow_schema(&new_schema.to_arrow(), &options);
+
+        let cr = LogEntry {
+            txid: None,
+            cmd: UpdateTableSchema {
+                table_id: *table_id,
+                table_version: table.version,
+                schema: flight_data.data_header,
+            },
+        };
+
+        let rst = self
+            .meta_node
+            .write(cr)
+            .await
+            .map_err(|e| ErrorCode::MetaNodeInternalError(e.to_string()))?;
+
+        match rst {
+            AppliedState::Table { prev: None, .. } => Err(ErrorCode::UnknownTable(format!(
+                "table not found: {:}",
+                table_name
+            ))),
+            AppliedState::Table {
+                prev: Some(prev),
+                result,
+            } if Some(&prev) == result.as_ref() => Err(table_version_mismatch(
+                format_args!("table {} has been changed concurrently", table_name),
+                Some(table.version),
+                prev.version,
+            )),
+            AppliedState::Table { .. } => Ok(()),
+            _ => Err(ErrorCode::MetaNodeInternalError("not a Table result")),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestHandler<TruncateTableAction> for ActionHandler {
+    async fn handle(&self, act: TruncateTableAction) -> common_exception::Result<()> {
+        let db_name = &act.plan.db;
+        let table_name = &act.plan.table;
+
+        let cr = LogEntry {
+            txid: None,
+            cmd: TruncateTable {
+                db_name: db_name.clone(),
+                table_name: table_name.clone(),
+            },
+        };
+
+        let rst = self
+            .meta_node
+            .write(cr)
+            .await
+            .map_err(|e| ErrorCode::MetaNodeInternalError(e.to_string()))?;
+
+        match rst {
+            AppliedState::Table { prev: None, .. } => Err(ErrorCode::UnknownTable(format!(
+                "table not found: {:}",
+                table_name
+            ))),
+            AppliedState::Table { .. } => Ok(()),
+            _ => Err(ErrorCode::MetaNodeInternalError("not a Table result")),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestHandler<TableExistsAction> for ActionHandler {
+    async fn handle(&self, act: TableExistsAction) -> common_exception::Result<bool> {
+        let db = self.meta_node.get_database(&act.db).await;
+
+        let table_id = db.and_then(|db| db.tables.get(&act.table).cloned());
+        let table = match table_id {
+            Some(id) => self.meta_node.get_table(&id).await,
+            None => None,
+        };
+        Ok(table.is_some())
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestHandler<GetTableStatisticsAction> for ActionHandler {
+    async fn handle(
+        &self,
+        act: GetTableStatisticsAction,
+    ) -> common_exception::Result<TableStatistics> {
+        let db_name = &act.db;
+        let table_name = &act.table;
+
+        let db = self.meta_node.get_database(db_name).await.ok_or_else(|| {
+            ErrorCode::UnknownDatabase(format!("get table: database not found {:}", db_name))
+        })?;
+
+        let table_id = db
+            .tables
+            .get(table_name)
+            .ok_or_else(|| ErrorCode::UnknownTable(format!("table not found: {:}", table_name)))?;
+
+        let table =
+            self.meta_node.get_table(table_id).await.ok_or_else(|| {
+                ErrorCode::UnknownTable(format!("table not found: {:}", table_name))
+            })?;
+
+        // Only the part names are stored for now, rows and bytes are not tracked yet.
+        Ok(TableStatistics {
+            number_of_parts: table.parts.len() as u64,
+            ..Default::default()
+        })
+    }
+}
+
 #[async_trait::async_trait]
 impl RequestHandler<GetTableAction> for ActionHandler {
     async fn handle(&self, act: GetTableAction) -> common_exception::Result<Arc<TableInfo>> {
@@ -289,7 +709,7 @@ impl RequestHandler<GetTableAction> for ActionHandler {
                 let rst = TableInfo {
                     database_id: db.database_id,
                     table_id: table.table_id,
-                    version: 0, // placeholder, not yet implemented in meta service
+                    version: table.version,
                     db: db_name.clone(),
                     name: table_name.clone(),
                     is_local: false,
@@ -312,8 +732,22 @@ impl RequestHandler<GetTableExtReq> for ActionHandler {
         let result = self.meta_node.get_table(&table_id).await;
         match result {
             Some(table) => {
+                // Readers pinned to a previous version see the schema of that version.
+                let (version, schema) = match act.tbl_ver {
+                    Some(ver) if ver != table.version => {
+                        let schema = table.schema_history.get(&ver).ok_or_else(|| {
+                            ErrorCode::UnknownTable(format!(
+                                "table of id {} version {} not found",
+                                table_id, ver
+                            ))
+                        })?;
+                        (ver, schema.clone())
+                    }
+                    _ => (table.version, table.schema),
+                };
+
                 let arrow_schema = ArrowSchema::try_from(&FlightData {
-                    data_header: table.schema,
+                    data_header: schema,
                     ..Default::default()
                 })
                 .map_err(|e| {
@@ -324,7 +758,7 @@ impl RequestHandler<GetTableExtReq> for ActionHandler {
                     table_id: table.table_id,
                     db: table.db_name,
                     name: table.table_name,
-                    version: 0,
+                    version,
                     is_local: false,
                     schema: Arc::new(arrow_schema.into()),
                     engine: table.table_engine.clone(),
@@ -340,6 +774,41 @@ impl RequestHandler<GetTableExtReq> for ActionHandler {
     }
 }
 
+#[async_trait::async_trait]
+impl RequestHandler<GetTablesByIdsAction> for ActionHandler {
+    async fn handle(
+        &self,
+        act: GetTablesByIdsAction,
+    ) -> common_exception::Result<Vec<Arc<TableInfo>>> {
+        let mut tables = Vec::with_capacity(act.ids.len());
+        for (i, (tbl_id, tbl_ver)) in act.ids.into_iter().enumerate() {
+            let table = self
+                .handle(GetTableExtReq { tbl_id, tbl_ver })
+                .await
+                .map_err(|e| e.add_message(format!("get tables by ids: at index {}", i)))?;
+            tables.push(table);
+        }
+        Ok(tables)
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestHandler<ListTableVersionsAction> for ActionHandler {
+    async fn handle(
+        &self,
+        act: ListTableVersionsAction,
+    ) -> common_exception::Result<Vec<MetaVersion>> {
+        let table = self.meta_node.get_table(&act.tbl_id).await.ok_or_else(|| {
+            ErrorCode::UnknownTable(format!("table of id {} not found", act.tbl_id))
+        })?;
+
+        // schema_history is a BTreeMap, the history versions are already sorted.
+        let mut versions = table.schema_history.keys().cloned().collect::<Vec<_>>();
+        versions.push(table.version);
+        Ok(versions)
+    }
+}
+
 #[async_trait::async_trait]
 impl RequestHandler<GetDatabasesAction> for ActionHandler {
     async fn handle(
@@ -360,39 +829,145 @@ impl RequestHandler<GetDatabasesAction> for ActionHandler {
     }
 }
 
+#[async_trait::async_trait]
+impl RequestHandler<ListDatabasesAction> for ActionHandler {
+    async fn handle(
+        &self,
+        req: ListDatabasesAction,
+    ) -> common_exception::Result<ListDatabasesReply> {
+        if req.limit == 0 {
+            return Err(ErrorCode::BadArguments(
+                "list databases: limit must be greater than 0",
+            ));
+        }
+
+        // Databases are ordered by name.
+        let res = self.meta_node.get_databases().await;
+        let mut remaining = res
+            .iter()
+            .filter(|(name, _)| match &req.cursor {
+                Some(cursor) => name > cursor,
+                None => true,
+            })
+            .peekable();
+
+        let databases = remaining
+            .by_ref()
+            .take(req.limit as usize)
+            .map(|(name, db)| {
+                Arc::new(DatabaseInfo {
+                    database_id: db.database_id,
+                    db: name.to_string(),
+                    engine: db.database_engine.to_string(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let next_cursor = match remaining.peek() {
+            Some(_) => databases.last().map(|db| db.db.clone()),
+            None => None,
+        };
+
+        Ok(ListDatabasesReply {
+            databases,
+            next_cursor,
+        })
+    }
+}
+
 #[async_trait::async_trait]
 impl RequestHandler<GetTablesAction> for ActionHandler {
     async fn handle(&self, req: GetTablesAction) -> common_exception::Result<Vec<Arc<TableInfo>>> {
         let res = self.meta_node.get_tables(req.db.as_str()).await?;
-        Ok(res
-            .iter()
-            .try_fold(Vec::new(), |mut acc, (id, name, tbl)| {
-                let arrow_schema = ArrowSchema::try_from(&FlightData {
-                    data_header: tbl.schema.clone(),
-                    ..Default::default()
-                })
-                .map_err(|e| {
-                    ErrorCode::IllegalSchema(format!(
-                        "invalid schema of table id {}, error: {}",
-                        *id,
-                        e.to_string()
-                    ))
-                })?;
+        res.iter()
+            .map(|(id, name, tbl)| to_table_info(&req.db, *id, name, tbl).map(Arc::new))
+            .collect()
+    }
+}
 
-                let tbl_info = TableInfo {
-                    database_id: tbl.database_id,
-                    db: req.db.to_string(),
-                    table_id: *id,
-                    version: 0,
-                    is_local: false,
-                    name: name.to_string(),
-                    schema: Arc::new(arrow_schema.into()),
-                    engine: tbl.table_engine.to_string(),
-                    options: tbl.table_options.clone(),
-                };
+#[async_trait::async_trait]
+impl RequestHandler<GetTablesWithPrefixAction> for ActionHandler {
+    async fn handle(
+        &self,
+        req: GetTablesWithPrefixAction,
+    ) -> common_exception::Result<Vec<Arc<TableInfo>>> {
+        let res = self
+            .meta_node
+            .get_tables_with_prefix(req.db.as_str(), req.prefix.as_str())
+            .await?;
+        res.iter()
+            .map(|(id, name, tbl)| to_table_info(&req.db, *id, name, tbl).map(Arc::new))
+            .collect()
+    }
+}
+
+/// Returns all of the tables, `ActionHandler::execute_stream()` splits them into chunks.
+#[async_trait::async_trait]
+impl RequestHandler<GetTablesChunkedAction> for ActionHandler {
+    async fn handle(
+        &self,
+        req: GetTablesChunkedAction,
+    ) -> common_exception::Result<Vec<Arc<TableInfo>>> {
+        self.handle(GetTablesAction { db: req.db }).await
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestHandler<GetCatalogSnapshotAction> for ActionHandler {
+    async fn handle(
+        &self,
+        _req: GetCatalogSnapshotAction,
+    ) -> common_exception::Result<CatalogSnapshot> {
+        let res = self.meta_node.get_catalog_snapshot().await?;
+
+        let mut databases = Vec::with_capacity(res.len());
+        for (db_name, db, tbls) in res.iter() {
+            let tables = tbls
+                .iter()
+                .map(|(id, name, tbl)| to_table_info(db_name, *id, name, tbl).map(Arc::new))
+                .collect::<common_exception::Result<Vec<_>>>()?;
+
+            databases.push(DatabaseSnapshot {
+                database: Arc::new(DatabaseInfo {
+                    database_id: db.database_id,
+                    db: db_name.to_string(),
+                    engine: db.database_engine.to_string(),
+                }),
+                tables,
+            });
+        }
 
-                acc.push(Arc::new(tbl_info));
-                Ok::<_, ErrorCode>(acc)
-            })?)
+        Ok(CatalogSnapshot { databases })
     }
 }
+
+fn to_table_info(
+    db_name: &str,
+    id: u64,
+    name: &str,
+    tbl: &Table,
+) -> common_exception::Result<TableInfo> {
+    let arrow_schema = ArrowSchema::try_from(&FlightData {
+        data_header: tbl.schema.clone(),
+        ..Default::default()
+    })
+    .map_err(|e| {
+        ErrorCode::IllegalSchema(format!(
+            "invalid schema of table id {}, error: {}",
+            id,
+            e.to_string()
+        ))
+    })?;
+
+    Ok(TableInfo {
+        database_id: tbl.database_id,
+        db: db_name.to_string(),
+        table_id: id,
+        version: tbl.version,
+        is_local: false,
+        name: name.to_string(),
+        schema: Arc::new(arrow_schema.into()),
+        engine: tbl.table_engine.to_string(),
+        options: tbl.table_options.clone(),
+    })
+}
@@ -15,6 +15,8 @@
 mod action_handler;
 mod kv_handlers;
 mod meta_handlers;
+mod ping_handler;
 
 pub use action_handler::ActionHandler;
+pub use action_handler::ReplyChunks;
 pub use action_handler::ReplySerializer;
@@ -0,0 +1,26 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_meta_flight::PingAction;
+use common_meta_types::PingReply;
+
+use crate::executor::action_handler::RequestHandler;
+use crate::executor::ActionHandler;
+
+#[async_trait::async_trait]
+impl RequestHandler<PingAction> for ActionHandler {
+    async fn handle(&self, _req: PingAction) -> common_exception::Result<PingReply> {
+        Ok(self.meta_node.ping())
+    }
+}
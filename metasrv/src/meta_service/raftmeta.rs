@@ -17,6 +17,7 @@ use std::collections::HashSet;
 use std::io::Cursor;
 use std::ops::Bound;
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_raft::async_trait::async_trait;
 use async_raft::config::Config;
@@ -43,6 +44,7 @@ use common_exception::prelude::ErrorCode;
 use common_exception::prelude::ToErrorCode;
 use common_meta_raft_store::config::RaftConfig;
 use common_meta_raft_store::log::RaftLog;
+use common_meta_raft_store::sled_key_spaces::check_key_spaces;
 use common_meta_raft_store::state::RaftState;
 use common_meta_raft_store::state_machine::AppliedState;
 use common_meta_raft_store::state_machine::SerializableSnapshot;
@@ -55,6 +57,7 @@ use common_meta_types::KVValue;
 use common_meta_types::LogEntry;
 use common_meta_types::Node;
 use common_meta_types::NodeId;
+use common_meta_types::PingReply;
 use common_meta_types::SeqValue;
 use common_meta_types::Table;
 use common_tracing::tracing;
@@ -155,6 +158,7 @@ impl MetaRaftStore {
         open: Option<()>,
         create: Option<()>,
     ) -> common_exception::Result<MetaRaftStore> {
+        check_key_spaces();
         let db = get_sled_db();
 
         let raft_state = RaftState::open_create(&db, config, open, create).await?;
@@ -226,14 +230,13 @@ impl MetaRaftStore {
             snap.kvs.len()
         );
 
+        // The values are plaintext in a snapshot, they are encrypted with the key of the new tree if enabled.
+        let nkvs = new_sm.sm_tree.import(snap.kvs.into_iter().map(|mut x| {
+            let v = x.pop().unwrap_or_default();
+            let k = x.pop().unwrap_or_default();
+            (k, v)
+        }))?;
         let tree = &new_sm.sm_tree.tree;
-        let nkvs = snap.kvs.len();
-        for x in snap.kvs.into_iter() {
-            let k = &x[0];
-            let v = &x[1];
-            tree.insert(k, v.clone())
-                .map_err_to_code(ErrorCode::MetaStoreDamaged, || "fail to insert snapshot")?;
-        }
 
         tracing::info!(
             "installed state machine from snapshot, no_kvs: {} last_applied: {}",
@@ -552,6 +555,7 @@ pub struct MetaNode {
     pub running_tx: watch::Sender<()>,
     pub running_rx: watch::Receiver<()>,
     pub join_handles: Mutex<Vec<JoinHandle<common_exception::Result<()>>>>,
+    pub started_at: Instant,
 }
 
 impl MetaRaftStore {
@@ -633,6 +637,7 @@ impl MetaNodeBuilder {
             running_tx: tx,
             running_rx: rx,
             join_handles: Mutex::new(Vec::new()),
+            started_at: Instant::now(),
         });
 
         if self.monitor_metrics {
@@ -1021,13 +1026,26 @@ impl MetaNode {
     pub async fn get_tables(
         &self,
         db_name: &str,
+    ) -> common_exception::Result<Vec<(u64, String, Table)>> {
+        self.get_tables_with_prefix(db_name, "").await
+    }
+
+    /// Get the tables of a database whose name starts with `prefix`, ordered by name,
+    /// from local meta state machine.
+    /// Only the prefix range of the table names of the database is scanned.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_tables_with_prefix(
+        &self,
+        db_name: &str,
+        prefix: &str,
     ) -> common_exception::Result<Vec<(u64, String, Table)>> {
         // inconsistent get: from local state machine
         let sm = self.sto.state_machine.read().await;
         if let Some(db) = sm.get_database(db_name) {
             let tbls = db
                 .tables
-                .iter()
+                .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+                .take_while(|(tbl_name, _)| tbl_name.starts_with(prefix))
                 .try_fold(Vec::new(), |mut acc, (tbl_name, tbl_id)| {
                     let tbl = sm.tables.get(tbl_id).ok_or_else(|| {
                         ErrorCode::IllegalMetaState(format!(" table of id {}, not found", tbl_id))
@@ -1044,6 +1062,31 @@ impl MetaNode {
         }
     }
 
+    /// Get all the databases with their tables ordered by name, from local meta state machine.
+    /// They are read under one lock of the state machine, thus consistent with each other.
+    #[tracing::instrument(level = "debug", skip(self))]
+    #[allow(clippy::type_complexity)]
+    pub async fn get_catalog_snapshot(
+        &self,
+    ) -> common_exception::Result<Vec<(String, Database, Vec<(u64, String, Table)>)>> {
+        // inconsistent get: from local state machine
+        let sm = self.sto.state_machine.read().await;
+
+        let mut res = Vec::with_capacity(sm.get_databases().len());
+        for (db_name, db) in sm.get_databases().iter() {
+            let mut tbls = Vec::with_capacity(db.tables.len());
+            for (tbl_name, tbl_id) in db.tables.iter() {
+                let tbl = sm.tables.get(tbl_id).ok_or_else(|| {
+                    ErrorCode::IllegalMetaState(format!(" table of id {}, not found", tbl_id))
+                })?;
+                tbls.push((*tbl_id, tbl_name.to_string(), tbl.clone()));
+            }
+            tbls.sort_by(|a, b| a.1.cmp(&b.1));
+            res.push((db_name.clone(), db.clone(), tbls));
+        }
+        Ok(res)
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn get_table(&self, tid: &u64) -> Option<Table> {
         // inconsistent get: from local state machine
@@ -1110,6 +1153,20 @@ impl MetaNode {
         }
     }
 
+    /// Build the liveness of this node from the latest local raft metrics, without touching the state machine.
+    pub fn ping(&self) -> PingReply {
+        let metrics = self.metrics_rx.borrow();
+
+        PingReply {
+            node_id: metrics.id,
+            state: format!("{:?}", metrics.state),
+            current_term: metrics.current_term,
+            current_leader: metrics.current_leader,
+            last_applied: metrics.last_applied,
+            uptime_secs: self.started_at.elapsed().as_secs(),
+        }
+    }
+
     /// Try to get the leader from the latest metrics of the local raft node.
     /// If leader is absent, wait for an metrics update in which a leader is set.
     #[tracing::instrument(level = "info", skip(self))]
@@ -1164,7 +1221,7 @@ impl MetaNode {
                 // retryable error
                 ClientWriteError::ForwardToLeader(_, leader) => match leader {
                     Some(id) => Ok(Err(RetryableError::ForwardToLeader { leader: id })),
-                    None => Err(ErrorCode::MetaServiceUnavailable(
+                    None => Err(ErrorCode::MetaServiceNotLeader(
                         "no leader to write".to_string(),
                     )),
                 },
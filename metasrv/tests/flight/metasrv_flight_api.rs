@@ -168,6 +168,41 @@ async fn test_generic_kv_mget() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_generic_kv_concurrent_with_pool() -> anyhow::Result<()> {
+    // Many actions sharing one client are in flight at the same time, each on its own connection.
+
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+
+    let client = MetaFlightClient::with_pool_size(addr.as_str(), "root", "xxx", None, 4).await?;
+    assert_eq!(4, client.pool_size());
+
+    let mut handles = vec![];
+    for i in 0..16 {
+        let client = client.clone();
+        let h = tokio::spawn(async move {
+            let key = format!("k{}", i);
+            client
+                .upsert_kv(&key, MatchSeq::Any, Some(b"v".to_vec()), None)
+                .await
+        });
+        handles.push(h);
+    }
+
+    for h in futures::future::join_all(handles).await {
+        let res = h??;
+        assert!(res.result.is_some());
+    }
+
+    let res = client.prefix_list_kv("k").await?;
+    assert_eq!(16, res.len());
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_generic_kv_list() -> anyhow::Result<()> {
     let (_log_guards, ut_span) = init_meta_ut!();
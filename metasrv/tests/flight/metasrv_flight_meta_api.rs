@@ -0,0 +1,812 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test arrow-flight meta API of metasrv
+
+use std::sync::Arc;
+
+use common_base::tokio;
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
+use common_meta_api::MetaApi;
+use common_meta_flight::MetaFlightClient;
+use common_meta_types::BatchItemReply;
+use common_meta_types::BatchMode;
+use common_meta_types::CreateTableReply;
+use common_meta_types::ListDatabasesReply;
+use common_meta_types::TableInfo;
+use common_meta_types::TableStatistics;
+use common_planners::AddColumnPlan;
+use common_planners::CreateDatabasePlan;
+use common_planners::CreateTablePlan;
+use common_planners::DropDatabasePlan;
+use common_planners::DropTablePlan;
+use common_planners::TruncateTablePlan;
+use common_tracing::tracing;
+use futures::TryStreamExt;
+use metasrv::init_meta_ut;
+use pretty_assertions::assert_eq;
+
+async fn create_db_and_table(
+    client: &MetaFlightClient,
+    db: &str,
+    table: &str,
+) -> anyhow::Result<u64> {
+    client
+        .create_database(CreateDatabasePlan {
+            if_not_exists: false,
+            db: db.to_string(),
+            engine: "Local".to_string(),
+            options: Default::default(),
+        })
+        .await?;
+
+    let reply = client
+        .create_table(CreateTablePlan {
+            if_not_exists: false,
+            db: db.to_string(),
+            table: table.to_string(),
+            schema: DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]),
+            engine: "JSON".to_string(),
+            options: Default::default(),
+        })
+        .await?;
+
+    Ok(reply.table_id)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_add_column() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    let table_id = create_db_and_table(&client, "db1", "tb1").await?;
+
+    tracing::info!("--- add a nullable column");
+    {
+        client
+            .alter_table_add_column(AddColumnPlan {
+                db: "db1".to_string(),
+                table: "tb1".to_string(),
+                field: DataField::new("b", DataType::String, true),
+            })
+            .await?;
+
+        let table = client.get_table("db1", "tb1").await?;
+        assert_eq!(1, table.version);
+        assert_eq!(
+            DataSchemaRefExt::create(vec![
+                DataField::new("a", DataType::Int64, false),
+                DataField::new("b", DataType::String, true),
+            ]),
+            table.schema
+        );
+    }
+
+    tracing::info!("--- readers pinned to the old version see the old schema");
+    {
+        let table = client.get_table_by_id(table_id, Some(0)).await?;
+        assert_eq!(0, table.version);
+        assert_eq!(
+            DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]),
+            table.schema
+        );
+
+        let table = client.get_table_by_id(table_id, None).await?;
+        assert_eq!(1, table.version);
+        assert_eq!(2, table.schema.fields().len());
+    }
+
+    tracing::info!("--- add an existing column");
+    {
+        let res = client
+            .alter_table_add_column(AddColumnPlan {
+                db: "db1".to_string(),
+                table: "tb1".to_string(),
+                field: DataField::new("b", DataType::String, true),
+            })
+            .await;
+        assert_eq!(4011, res.unwrap_err().code());
+    }
+
+    tracing::info!("--- add a non-nullable column");
+    {
+        let res = client
+            .alter_table_add_column(AddColumnPlan {
+                db: "db1".to_string(),
+                table: "tb1".to_string(),
+                field: DataField::new("c", DataType::String, false),
+            })
+            .await;
+        assert_eq!(6, res.unwrap_err().code());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_list_databases() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    for db in ["db3", "db1", "db5", "db2", "db4"] {
+        client
+            .create_database(CreateDatabasePlan {
+                if_not_exists: false,
+                db: db.to_string(),
+                engine: "Local".to_string(),
+                options: Default::default(),
+            })
+            .await?;
+    }
+
+    let names = |reply: &ListDatabasesReply| {
+        reply
+            .databases
+            .iter()
+            .map(|db| db.db.clone())
+            .collect::<Vec<_>>()
+    };
+
+    tracing::info!("--- list databases page by page");
+    {
+        let reply = client.list_databases(None, 2).await?;
+        assert_eq!(vec!["db1", "db2"], names(&reply));
+        assert_eq!(Some("db2".to_string()), reply.next_cursor);
+
+        let reply = client.list_databases(reply.next_cursor, 2).await?;
+        assert_eq!(vec!["db3", "db4"], names(&reply));
+        assert_eq!(Some("db4".to_string()), reply.next_cursor);
+
+        let reply = client.list_databases(reply.next_cursor, 2).await?;
+        assert_eq!(vec!["db5"], names(&reply));
+        assert_eq!(None, reply.next_cursor);
+    }
+
+    tracing::info!("--- list all databases in one page");
+    {
+        let reply = client.list_databases(None, 5).await?;
+        assert_eq!(vec!["db1", "db2", "db3", "db4", "db5"], names(&reply));
+        assert_eq!(None, reply.next_cursor);
+
+        let dbs = client.get_databases().await?;
+        assert_eq!(5, dbs.len());
+    }
+
+    tracing::info!("--- list with zero limit");
+    {
+        let res = client.list_databases(None, 0).await;
+        assert_eq!(6, res.unwrap_err().code());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_get_catalog_snapshot() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    tracing::info!("--- empty catalog");
+    {
+        let snapshot = client.get_catalog_snapshot().await?;
+        assert!(snapshot.databases.is_empty());
+    }
+
+    create_db_and_table(&client, "db2", "tb1").await?;
+    create_db_and_table(&client, "db1", "tb2").await?;
+    client
+        .create_table(CreateTablePlan {
+            if_not_exists: false,
+            db: "db1".to_string(),
+            table: "tb1".to_string(),
+            schema: DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]),
+            engine: "JSON".to_string(),
+            options: Default::default(),
+        })
+        .await?;
+    client
+        .create_database(CreateDatabasePlan {
+            if_not_exists: false,
+            db: "db3".to_string(),
+            engine: "Local".to_string(),
+            options: Default::default(),
+        })
+        .await?;
+
+    tracing::info!("--- databases and tables are ordered by name");
+    {
+        let snapshot = client.get_catalog_snapshot().await?;
+        let got = snapshot
+            .databases
+            .iter()
+            .map(|d| {
+                let tables = d.tables.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+                (d.database.db.clone(), tables)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                ("db1".to_string(), vec![
+                    "tb1".to_string(),
+                    "tb2".to_string()
+                ]),
+                ("db2".to_string(), vec!["tb1".to_string()]),
+                ("db3".to_string(), vec![]),
+            ],
+            got
+        );
+
+        for d in snapshot.databases.iter() {
+            for t in d.tables.iter() {
+                assert_eq!(d.database.db, t.db);
+                assert_eq!(d.database.database_id, t.database_id);
+                assert_eq!(client.get_table(&t.db, &t.name).await?.table_id, t.table_id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_truncate_table() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    let table_id = create_db_and_table(&client, "db1", "tb1").await?;
+
+    tracing::info!("--- truncate table");
+    {
+        client
+            .truncate_table(TruncateTablePlan {
+                db: "db1".to_string(),
+                table: "tb1".to_string(),
+            })
+            .await?;
+
+        let table = client.get_table("db1", "tb1").await?;
+        assert_eq!(table_id, table.table_id);
+        assert_eq!(1, table.version);
+        assert_eq!(
+            DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]),
+            table.schema
+        );
+    }
+
+    tracing::info!("--- truncate absent table");
+    {
+        let res = client
+            .truncate_table(TruncateTablePlan {
+                db: "db1".to_string(),
+                table: "tb2".to_string(),
+            })
+            .await;
+        assert_eq!(25, res.unwrap_err().code());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_exists() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    assert!(!client.database_exists("db1").await?);
+    assert!(!client.table_exists("db1", "tb1").await?);
+
+    create_db_and_table(&client, "db1", "tb1").await?;
+
+    assert!(client.database_exists("db1").await?);
+    assert!(!client.database_exists("db2").await?);
+    assert!(client.table_exists("db1", "tb1").await?);
+    assert!(!client.table_exists("db1", "tb2").await?);
+    assert!(!client.table_exists("db2", "tb1").await?);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_create_tables() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    let tb1_id = create_db_and_table(&client, "db1", "tb1").await?;
+
+    let plan = |table: &str, if_not_exists: bool| CreateTablePlan {
+        if_not_exists,
+        db: "db1".to_string(),
+        table: table.to_string(),
+        schema: DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]),
+        engine: "JSON".to_string(),
+        options: Default::default(),
+    };
+
+    tracing::info!("--- an existing table fails the whole batch");
+    {
+        let res = client
+            .create_tables(
+                vec![plan("tb2", false), plan("tb1", false)],
+                BatchMode::AbortOnError,
+            )
+            .await;
+        assert_eq!(4003, res.unwrap_err().code());
+        assert!(!client.table_exists("db1", "tb2").await?);
+    }
+
+    tracing::info!("--- an existing table with if_not_exists is reused");
+    {
+        let replies = client
+            .create_tables(
+                vec![plan("tb2", false), plan("tb1", true), plan("tb3", false)],
+                BatchMode::AbortOnError,
+            )
+            .await?
+            .into_iter()
+            .map(BatchItemReply::into_result)
+            .collect::<common_exception::Result<Vec<_>>>()?;
+        assert_eq!(3, replies.len());
+        assert_eq!(tb1_id, replies[1].table_id);
+
+        let tb2 = client.get_table("db1", "tb2").await?;
+        let tb3 = client.get_table("db1", "tb3").await?;
+        assert_eq!(tb2.table_id, replies[0].table_id);
+        assert_eq!(tb3.table_id, replies[2].table_id);
+    }
+
+    tracing::info!("--- duplicated tables in a batch");
+    {
+        let res = client
+            .create_tables(
+                vec![plan("tb4", false), plan("tb4", false)],
+                BatchMode::AbortOnError,
+            )
+            .await;
+        assert_eq!(4003, res.unwrap_err().code());
+        assert!(!client.table_exists("db1", "tb4").await?);
+    }
+
+    tracing::info!("--- continue on error creates the other tables");
+    {
+        let replies = client
+            .create_tables(
+                vec![plan("tb4", false), plan("tb1", false), plan("tb5", false)],
+                BatchMode::ContinueOnError,
+            )
+            .await?;
+        assert_eq!(3, replies.len());
+        assert!(replies[0].is_ok());
+        assert!(!replies[1].is_ok());
+        assert!(replies[2].is_ok());
+        assert_eq!(4003, replies[1].clone().into_result().unwrap_err().code());
+
+        let tb4 = client.get_table("db1", "tb4").await?;
+        let tb5 = client.get_table("db1", "tb5").await?;
+        assert_eq!(
+            BatchItemReply::Ok(CreateTableReply {
+                table_id: tb4.table_id
+            }),
+            replies[0]
+        );
+        assert_eq!(
+            BatchItemReply::Ok(CreateTableReply {
+                table_id: tb5.table_id
+            }),
+            replies[2]
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_list_table_versions() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    let table_id = create_db_and_table(&client, "db1", "tb1").await?;
+    assert_eq!(vec![0], client.list_table_versions(table_id).await?);
+
+    client
+        .alter_table_add_column(AddColumnPlan {
+            db: "db1".to_string(),
+            table: "tb1".to_string(),
+            field: DataField::new("b", DataType::String, true),
+        })
+        .await?;
+    client
+        .truncate_table(TruncateTablePlan {
+            db: "db1".to_string(),
+            table: "tb1".to_string(),
+        })
+        .await?;
+
+    let versions = client.list_table_versions(table_id).await?;
+    assert_eq!(vec![0, 1, 2], versions);
+
+    for ver in versions {
+        let table = client.get_table_by_id(table_id, Some(ver)).await?;
+        assert_eq!(ver, table.version);
+    }
+
+    let res = client.list_table_versions(table_id + 100).await;
+    assert_eq!(25, res.unwrap_err().code());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_upsert_database() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    let plan = CreateDatabasePlan {
+        if_not_exists: false,
+        db: "db1".to_string(),
+        engine: "Local".to_string(),
+        options: Default::default(),
+    };
+
+    tracing::info!("--- upsert an absent database");
+    let db_id = {
+        let reply = client.upsert_database(plan.clone()).await?;
+        assert!(reply.created);
+        assert_eq!("db1", reply.database.db);
+        assert_eq!("Local", reply.database.engine);
+        reply.database.database_id
+    };
+
+    tracing::info!("--- upsert an existing database");
+    {
+        let reply = client.upsert_database(plan).await?;
+        assert!(!reply.created);
+        assert_eq!(db_id, reply.database.database_id);
+        assert_eq!(db_id, client.get_database("db1").await?.database_id);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_get_table_statistics() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    create_db_and_table(&client, "db1", "tb1").await?;
+
+    let stat = client.get_table_statistics("db1", "tb1").await?;
+    assert_eq!(TableStatistics::default(), stat);
+
+    let res = client.get_table_statistics("db1", "tb2").await;
+    assert_eq!(25, res.unwrap_err().code());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_drop_table_with_version() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    create_db_and_table(&client, "db1", "tb1").await?;
+    client
+        .truncate_table(TruncateTablePlan {
+            db: "db1".to_string(),
+            table: "tb1".to_string(),
+        })
+        .await?;
+
+    let plan = |expected_version| DropTablePlan {
+        if_exists: false,
+        db: "db1".to_string(),
+        table: "tb1".to_string(),
+        expected_version,
+    };
+
+    tracing::info!("--- drop with a stale version");
+    {
+        let res = client.drop_table(plan(Some(0))).await;
+        assert_eq!(4012, res.unwrap_err().code());
+        assert!(client.table_exists("db1", "tb1").await?);
+    }
+
+    tracing::info!("--- drop with the current version");
+    {
+        client.drop_table(plan(Some(1))).await?;
+        assert!(!client.table_exists("db1", "tb1").await?);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_drop_database_cascade() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    let tb1_id = create_db_and_table(&client, "db1", "tb1").await?;
+
+    let plan = |cascade| DropDatabasePlan {
+        if_exists: false,
+        db: "db1".to_string(),
+        cascade,
+    };
+
+    tracing::info!("--- drop a database with tables without cascade");
+    {
+        let res = client.drop_database(plan(false)).await;
+        assert_eq!(4013, res.unwrap_err().code());
+        assert!(client.database_exists("db1").await?);
+        assert!(client.table_exists("db1", "tb1").await?);
+    }
+
+    tracing::info!("--- drop a database with tables with cascade");
+    {
+        client.drop_database(plan(true)).await?;
+        assert!(!client.database_exists("db1").await?);
+
+        let res = client.get_tables_by_ids(vec![(tb1_id, None)]).await;
+        assert_eq!(25, res.unwrap_err().code());
+    }
+
+    tracing::info!("--- drop an empty database without cascade");
+    {
+        client
+            .create_database(CreateDatabasePlan {
+                if_not_exists: false,
+                db: "db1".to_string(),
+                engine: "Local".to_string(),
+                options: Default::default(),
+            })
+            .await?;
+        client.drop_database(plan(false)).await?;
+        assert!(!client.database_exists("db1").await?);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_get_tables_by_ids() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    let tb1_id = create_db_and_table(&client, "db1", "tb1").await?;
+    let tb2_id = client
+        .create_table(CreateTablePlan {
+            if_not_exists: false,
+            db: "db1".to_string(),
+            table: "tb2".to_string(),
+            schema: DataSchemaRefExt::create(vec![DataField::new("b", DataType::String, true)]),
+            engine: "JSON".to_string(),
+            options: Default::default(),
+        })
+        .await?
+        .table_id;
+
+    tracing::info!("--- tables are returned in request order");
+    {
+        let tables = client
+            .get_tables_by_ids(vec![(tb2_id, None), (tb1_id, Some(0))])
+            .await?;
+        assert_eq!(2, tables.len());
+        assert_eq!("tb2", tables[0].name);
+        assert_eq!("tb1", tables[1].name);
+        assert_eq!(0, tables[1].version);
+    }
+
+    tracing::info!("--- an absent table fails the lookup");
+    {
+        let res = client
+            .get_tables_by_ids(vec![(tb1_id, None), (tb2_id + 100, None)])
+            .await;
+        let err = res.unwrap_err();
+        assert_eq!(25, err.code());
+        assert!(err.message().contains("at index 1"));
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_get_tables_chunked() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    create_db_and_table(&client, "db1", "tb1").await?;
+    for i in 2..=5 {
+        client
+            .create_table(CreateTablePlan {
+                if_not_exists: false,
+                db: "db1".to_string(),
+                table: format!("tb{}", i),
+                schema: DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]),
+                engine: "JSON".to_string(),
+                options: Default::default(),
+            })
+            .await?;
+    }
+
+    let all = client.get_tables("db1").await?;
+    assert_eq!(5, all.len());
+
+    tracing::info!("--- chunks reassemble to the unary reply");
+    {
+        let chunks = client
+            .get_tables_chunked("db1", 2)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        let sizes = chunks.iter().map(|c| c.len()).collect::<Vec<_>>();
+        assert_eq!(vec![2, 2, 1], sizes);
+        assert_eq!(all, chunks.concat());
+    }
+
+    tracing::info!("--- chunk size larger than the number of tables");
+    {
+        let chunks = client
+            .get_tables_chunked("db1", 100)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(vec![all.clone()], chunks);
+    }
+
+    tracing::info!("--- chunk size 0 is treated as 1");
+    {
+        let chunks = client
+            .get_tables_chunked("db1", 0)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(5, chunks.len());
+        assert_eq!(all, chunks.concat());
+    }
+
+    tracing::info!("--- unknown database fails before streaming");
+    {
+        let res = client.get_tables_chunked("db2", 2).await;
+        assert_eq!(3, res.err().unwrap().code());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_ping() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    tracing::info!("--- ping a fresh store");
+    let first = client.ping().await?;
+    assert_eq!(tc.config.raft_config.id, first.node_id);
+    assert_eq!(Some(first.node_id), first.current_leader);
+    assert_eq!("Leader", first.state);
+
+    tracing::info!("--- the applied log grows, the term stays");
+    create_db_and_table(&client, "db1", "tb1").await?;
+    let second = client.ping().await?;
+    assert_eq!(first.current_term, second.current_term);
+    assert!(second.last_applied > first.last_applied);
+    assert!(second.uptime_secs >= first.uptime_secs);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_meta_api_get_tables_with_prefix() -> anyhow::Result<()> {
+    let (_log_guards, ut_span) = init_meta_ut!();
+    let _ent = ut_span.enter();
+
+    let (_tc, addr) = metasrv::tests::start_metasrv().await?;
+    let client = MetaFlightClient::try_create(addr.as_str(), "root", "xxx").await?;
+
+    create_db_and_table(&client, "db1", "foo_1").await?;
+    for table in ["foo_2", "bar", "afoo"] {
+        client
+            .create_table(CreateTablePlan {
+                if_not_exists: false,
+                db: "db1".to_string(),
+                table: table.to_string(),
+                schema: DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]),
+                engine: "JSON".to_string(),
+                options: Default::default(),
+            })
+            .await?;
+    }
+
+    let names = |tables: Vec<Arc<TableInfo>>| {
+        let mut names = tables.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+
+    tracing::info!("--- only the tables with the prefix are returned, ordered by name");
+    {
+        let got = client.get_tables_with_prefix("db1", "foo").await?;
+        let got = got.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+        assert_eq!(vec!["foo_1".to_string(), "foo_2".to_string()], got);
+
+        let got = client.get_tables_with_prefix("db1", "foo_2").await?;
+        assert_eq!(vec!["foo_2".to_string()], names(got));
+
+        let got = client.get_tables_with_prefix("db1", "nope").await?;
+        assert!(got.is_empty());
+    }
+
+    tracing::info!("--- an empty prefix returns all the tables");
+    {
+        let got = client.get_tables_with_prefix("db1", "").await?;
+        let all = client.get_tables("db1").await?;
+        assert_eq!(4, got.len());
+        assert_eq!(
+            vec!["afoo", "bar", "foo_1", "foo_2"],
+            got.iter().map(|t| t.name.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(names(all), names(got));
+    }
+
+    tracing::info!("--- an absent database fails");
+    {
+        let res = client.get_tables_with_prefix("db2", "foo").await;
+        assert!(res.is_err());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+
+use crate::suites::criterion_benchmark_suite_with_settings;
+
+// The source emits 1-row blocks, compare the throughput with and without reblocking.
+fn criterion_benchmark_reblock_query(c: &mut Criterion) {
+    let query = "SELECT sum(number + 1) FROM numbers_mt(100000) WHERE number % 2 = 0";
+
+    criterion_benchmark_suite_with_settings(c, "tiny blocks", query, &[
+        ("max_block_size", "1"),
+        ("block_size", "0"),
+    ]);
+    criterion_benchmark_suite_with_settings(c, "tiny blocks with reblock", query, &[
+        ("max_block_size", "1"),
+        ("block_size", "65536"),
+    ]);
+}
+
+criterion_group!(benches, criterion_benchmark_reblock_query);
+criterion_main!(benches);
@@ -17,6 +17,7 @@ use common_exception::Result;
 use common_planners::PlanNode;
 use criterion::Criterion;
 use databend_query::interpreters::SelectInterpreter;
+use databend_query::sessions::SessionProtocol;
 use databend_query::sql::PlanParser;
 use databend_query::tests::SessionManagerBuilder;
 use futures::StreamExt;
@@ -24,12 +25,21 @@ use futures::StreamExt;
 pub mod bench_aggregate_query_sql;
 pub mod bench_filter_query_sql;
 pub mod bench_limit_query_sql;
+pub mod bench_projection_query_sql;
+pub mod bench_reblock_query_sql;
 pub mod bench_sort_query_sql;
 
 pub async fn select_executor(sql: &str) -> Result<()> {
+    select_executor_with_settings(sql, &[]).await
+}
+
+pub async fn select_executor_with_settings(sql: &str, settings: &[(&str, &str)]) -> Result<()> {
     let sessions = SessionManagerBuilder::create().build()?;
-    let executor_session = sessions.create_session("Benches")?;
+    let executor_session = sessions.create_session(SessionProtocol::Internal)?;
     let ctx = executor_session.create_context().await?;
+    for (key, value) in settings {
+        ctx.get_settings().update_settings(key, value.to_string())?;
+    }
 
     if let PlanNode::Select(plan) = PlanParser::create(ctx.clone()).build_from_sql(sql)? {
         let executor = SelectInterpreter::try_create(ctx, plan)?;
@@ -50,3 +60,18 @@ pub fn criterion_benchmark_suite(c: &mut Criterion, sql: &str) {
         })
     });
 }
+
+pub fn criterion_benchmark_suite_with_settings(
+    c: &mut Criterion,
+    name: &str,
+    sql: &str,
+    settings: &[(&str, &str)],
+) {
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            tokio::runtime::Runtime::new()
+                .unwrap()
+                .block_on(select_executor_with_settings(sql, settings))
+        })
+    });
+}
@@ -24,6 +24,7 @@ use axum::response::IntoResponse;
 use common_exception::Result;
 
 use crate::sessions::SessionManagerRef;
+use crate::sessions::SessionProtocol;
 
 pub struct ClusterTemplate {
     result: Result<String>,
@@ -60,7 +61,7 @@ pub async fn cluster_list_handler(sessions: Extension<SessionManagerRef>) -> Clu
 }
 
 async fn list_nodes(sessions: SessionManagerRef) -> Result<String> {
-    let watch_cluster_session = sessions.create_session("WatchCluster")?;
+    let watch_cluster_session = sessions.create_session(SessionProtocol::HTTP)?;
     let watch_cluster_context = watch_cluster_session.create_context().await?;
 
     let nodes_list = watch_cluster_context.get_cluster().get_nodes();
@@ -29,6 +29,7 @@ use tokio_stream::StreamExt;
 
 use crate::sessions::DatabendQueryContextRef;
 use crate::sessions::SessionManagerRef;
+use crate::sessions::SessionProtocol;
 
 pub struct LogTemplate {
     result: Result<String>,
@@ -58,7 +59,7 @@ pub async fn logs_handler(sessions_extension: Extension<SessionManagerRef>) -> L
 }
 
 async fn select_table(sessions: SessionManagerRef) -> Result<String> {
-    let session = sessions.create_session("WatchLogs")?;
+    let session = sessions.create_session(SessionProtocol::HTTP)?;
     let query_context = session.create_context().await?;
 
     let tracing_table_stream = execute_query(query_context).await?;
@@ -78,7 +79,7 @@ async fn execute_query(context: DatabendQueryContextRef) -> Result<SendableDataB
     let tracing_table_read_plan = tracing_table.read_plan(
         io_ctx.clone(),
         None,
-        Some(context.get_settings().get_max_threads()? as usize),
+        Some(context.get_max_threads()? as usize),
     )?;
 
     tracing_table
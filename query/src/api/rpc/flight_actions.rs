@@ -28,6 +28,9 @@ pub struct ShuffleAction {
     pub plan: PlanNode,
     pub sinks: Vec<String>,
     pub scatters_expression: Expression,
+    /// The max_threads override of the query, if any.
+    #[serde(default)]
+    pub max_threads_hint: Option<u64>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
@@ -36,6 +39,9 @@ pub struct BroadcastAction {
     pub stage_id: String,
     pub plan: PlanNode,
     pub sinks: Vec<String>,
+    /// The max_threads override of the query, if any.
+    #[serde(default)]
+    pub max_threads_hint: Option<u64>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
@@ -162,6 +168,14 @@ impl FlightAction {
             _ => unimplemented!(),
         }
     }
+
+    pub fn get_max_threads_hint(&self) -> Option<u64> {
+        match self {
+            FlightAction::BroadcastAction(action) => action.max_threads_hint,
+            FlightAction::PrepareShuffleAction(action) => action.max_threads_hint,
+            _ => unimplemented!(),
+        }
+    }
 }
 
 impl TryInto<FlightAction> for Action {
@@ -32,6 +32,7 @@ async fn test_shuffle_action_try_into() -> Result<()> {
         plan: parse_query("SELECT number FROM numbers(5)")?,
         sinks: vec![String::from("stream_id")],
         scatters_expression: Expression::create_literal(DataValue::UInt64(Some(1))),
+        max_threads_hint: Some(4),
     };
 
     let from_action = FlightAction::PrepareShuffleAction(shuffle_action);
@@ -49,6 +50,7 @@ async fn test_shuffle_action_try_into() -> Result<()> {
                 action.scatters_expression,
                 Expression::create_literal(DataValue::UInt64(Some(1)))
             );
+            assert_eq!(action.max_threads_hint, Some(4));
         }
     }
 
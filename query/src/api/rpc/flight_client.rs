@@ -24,7 +24,9 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use common_streams::SendableDataBlockStream;
 use tonic::transport::channel::Channel;
+use tonic::Code;
 use tonic::Request;
+use tonic::Status;
 use tonic::Streaming;
 
 use crate::api::rpc::flight_actions::FlightAction;
@@ -62,8 +64,16 @@ impl FlightClient {
         let mut request = Request::new(ticket);
         request.set_timeout(Duration::from_secs(timeout));
 
-        let response = self.inner.do_get(request).await?;
-        Ok(response.into_inner())
+        let response = self.inner.do_get(request).await;
+        Ok(response.map_err(Self::error_of_status)?.into_inner())
+    }
+
+    // The channel connects lazily, so an unreachable node is reported by the request as unavailable.
+    fn error_of_status(status: Status) -> ErrorCode {
+        match status.code() {
+            Code::Unavailable => ErrorCode::CannotConnectNode(status.to_string()),
+            _ => ErrorCode::from(status),
+        }
     }
 
     // Execute do_action.
@@ -46,7 +46,7 @@ struct StreamInfo {
 
 pub struct DatabendQueryFlightDispatcher {
     streams: Arc<RwLock<HashMap<String, StreamInfo>>>,
-    stages_notify: Arc<RwLock<HashMap<String, Arc<Notify>>>>,
+    stages_start: Arc<RwLock<HashMap<String, oneshot::Sender<bool>>>>,
     abort: Arc<AtomicBool>,
 }
 
@@ -56,7 +56,7 @@ impl DatabendQueryFlightDispatcher {
     pub fn create() -> DatabendQueryFlightDispatcher {
         DatabendQueryFlightDispatcher {
             streams: Arc::new(RwLock::new(HashMap::new())),
-            stages_notify: Arc::new(RwLock::new(HashMap::new())),
+            stages_start: Arc::new(RwLock::new(HashMap::new())),
             abort: Arc::new(AtomicBool::new(false)),
         }
     }
@@ -72,8 +72,8 @@ impl DatabendQueryFlightDispatcher {
 
     pub fn get_stream(&self, ticket: &StreamTicket) -> Result<mpsc::Receiver<Result<DataBlock>>> {
         let stage_name = format!("{}/{}", ticket.query_id, ticket.stage_id);
-        if let Some(notify) = self.stages_notify.write().remove(&stage_name) {
-            notify.notify_waiters();
+        if let Some(start) = self.stages_start.write().remove(&stage_name) {
+            start.send(true).ok();
         }
 
         let stream_name = format!("{}/{}", stage_name, ticket.stream);
@@ -83,18 +83,45 @@ impl DatabendQueryFlightDispatcher {
         }
     }
 
+    /// Drops the stages of the query that are not started, e.g. the standby stages on the replicas,
+    /// together with the streams that are not fetched, so that they release their sessions.
+    pub fn cancel_query(&self, query_id: &str) {
+        let prefix = format!("{}/", query_id);
+        let cancelled_stages = {
+            let mut stages_start = self.stages_start.write();
+            let stages_name = stages_start
+                .keys()
+                .filter(|stage_name| stage_name.starts_with(&prefix))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            stages_name
+                .iter()
+                .filter_map(|stage_name| stages_start.remove(stage_name))
+                .collect::<Vec<_>>()
+        };
+
+        for start in cancelled_stages {
+            start.send(false).ok();
+        }
+
+        self.streams
+            .write()
+            .retain(|stream_name, _| !stream_name.starts_with(&prefix));
+    }
+
     pub async fn broadcast_action(&self, session: SessionRef, action: FlightAction) -> Result<()> {
         let query_id = action.get_query_id();
         let stage_id = action.get_stage_id();
         let action_sinks = action.get_sinks();
         let data_schema = action.get_plan().schema();
-        self.create_stage_streams(&query_id, &stage_id, &data_schema, &action_sinks);
+        let start = self.create_stage_streams(&query_id, &stage_id, &data_schema, &action_sinks);
 
         match action.get_sinks().len() {
             0 => Err(ErrorCode::LogicalError("")),
-            1 => self.one_sink_action(session, &action).await,
+            1 => self.one_sink_action(session, &action, start).await,
             _ => {
-                self.action_with_scatter::<BroadcastFlightScatter>(session, &action)
+                self.action_with_scatter::<BroadcastFlightScatter>(session, &action, start)
                     .await
             }
         }
@@ -105,20 +132,26 @@ impl DatabendQueryFlightDispatcher {
         let stage_id = action.get_stage_id();
         let action_sinks = action.get_sinks();
         let data_schema = action.get_plan().schema();
-        self.create_stage_streams(&query_id, &stage_id, &data_schema, &action_sinks);
+        let start = self.create_stage_streams(&query_id, &stage_id, &data_schema, &action_sinks);
 
         match action.get_sinks().len() {
             0 => Err(ErrorCode::LogicalError("")),
-            1 => self.one_sink_action(session, &action).await,
+            1 => self.one_sink_action(session, &action, start).await,
             _ => {
-                self.action_with_scatter::<HashFlightScatter>(session, &action)
+                self.action_with_scatter::<HashFlightScatter>(session, &action, start)
                     .await
             }
         }
     }
 
-    async fn one_sink_action(&self, session: SessionRef, action: &FlightAction) -> Result<()> {
+    async fn one_sink_action(
+        &self,
+        session: SessionRef,
+        action: &FlightAction,
+        start: oneshot::Receiver<bool>,
+    ) -> Result<()> {
         let query_context = session.create_context().await?;
+        query_context.set_max_threads_hint(action.get_max_threads_hint())?;
         let action_context = DatabendQueryContext::new(query_context.clone());
         let pipeline_builder = PipelineBuilder::create(action_context.clone());
 
@@ -132,7 +165,6 @@ impl DatabendQueryFlightDispatcher {
 
         assert_eq!(action_sinks.len(), 1);
         let stage_name = format!("{}/{}", action_query_id, action_stage_id);
-        let stages_notify = self.stages_notify.clone();
 
         let stream_name = format!("{}/{}", stage_name, action_sinks[0]);
         let tx_ref = self.streams.read().get(&stream_name).map(|x| x.tx.clone());
@@ -140,7 +172,9 @@ impl DatabendQueryFlightDispatcher {
 
         query_context.try_spawn(async move {
             let _session = session;
-            wait_start(stage_name, stages_notify).await;
+            if !wait_start(start).await {
+                return;
+            }
 
             match pipeline.execute().await {
                 Err(error) => {
@@ -166,11 +200,13 @@ impl DatabendQueryFlightDispatcher {
         &self,
         session: SessionRef,
         action: &FlightAction,
+        start: oneshot::Receiver<bool>,
     ) -> Result<()>
     where
         T: FlightScatter + Send + 'static,
     {
         let query_context = session.create_context().await?;
+        query_context.set_max_threads_hint(action.get_max_threads_hint())?;
         let action_context = DatabendQueryContext::new(query_context.clone());
         let pipeline_builder = PipelineBuilder::create(action_context.clone());
 
@@ -203,9 +239,6 @@ impl DatabendQueryFlightDispatcher {
             Result::Ok(sinks_tx)
         }?;
 
-        let stage_name = format!("{}/{}", action_query_id, action_stage_id);
-        let stages_notify = self.stages_notify.clone();
-
         let flight_scatter = T::try_create(
             action.get_plan().schema(),
             action.get_scatter_expression(),
@@ -214,7 +247,9 @@ impl DatabendQueryFlightDispatcher {
 
         query_context.try_spawn(async move {
             let _session = session;
-            wait_start(stage_name, stages_notify).await;
+            if !wait_start(start).await {
+                return;
+            }
 
             let sinks_tx_ref = &sinks_tx;
             let forward_blocks = async move {
@@ -256,11 +291,12 @@ impl DatabendQueryFlightDispatcher {
         stage_id: &str,
         schema: &DataSchemaRef,
         streams_name: &[String],
-    ) {
+    ) -> oneshot::Receiver<bool> {
         let stage_name = format!("{}/{}", query_id, stage_id);
-        self.stages_notify
+        let (start_tx, start_rx) = oneshot::channel();
+        self.stages_start
             .write()
-            .insert(stage_name.clone(), Arc::new(Notify::new()));
+            .insert(stage_name.clone(), start_tx);
 
         let mut streams = self.streams.write();
 
@@ -274,16 +310,12 @@ impl DatabendQueryFlightDispatcher {
                 rx,
             });
         }
+
+        start_rx
     }
 }
 
-async fn wait_start(stage_name: String, stages_notify: Arc<RwLock<HashMap<String, Arc<Notify>>>>) {
-    let notify = {
-        let stages_notify = stages_notify.read();
-        stages_notify.get(&stage_name).map(Arc::clone)
-    };
-
-    if let Some(notify) = notify {
-        notify.notified().await;
-    }
+/// Waits until a stream of the stage is fetched, returns false if the query is cancelled first.
+async fn wait_start(start: oneshot::Receiver<bool>) -> bool {
+    matches!(start.await, Ok(true))
 }
@@ -65,6 +65,7 @@ async fn test_run_shuffle_action_with_no_scatters() -> Result<()> {
                     plan: parse_query("SELECT number FROM numbers(5)")?,
                     sinks: vec![stream_id.clone()],
                     scatters_expression: Expression::create_literal(DataValue::UInt64(Some(1))),
+                    max_threads_hint: None,
                 }),
             )
             .await?;
@@ -109,6 +110,7 @@ async fn test_run_shuffle_action_with_scatter() -> Result<()> {
                     plan: parse_query("SELECT number FROM numbers(5)")?,
                     sinks: vec!["stream_1".to_string(), "stream_2".to_string()],
                     scatters_expression: Expression::Column("number".to_string()),
+                    max_threads_hint: None,
                 }),
             )
             .await?;
@@ -150,6 +152,50 @@ async fn test_run_shuffle_action_with_scatter() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cancel_query_with_waiting_stage() -> Result<()> {
+    if let (Some(query_id), Some(stage_id), Some(stream_id)) = generate_uuids(3) {
+        let flight_dispatcher = DatabendQueryFlightDispatcher::create();
+
+        let sessions = SessionManagerBuilder::create().build()?;
+        let rpc_session = sessions.create_rpc_session(query_id.clone(), false)?;
+
+        flight_dispatcher
+            .shuffle_action(
+                rpc_session,
+                FlightAction::PrepareShuffleAction(ShuffleAction {
+                    query_id: query_id.clone(),
+                    stage_id: stage_id.clone(),
+                    plan: parse_query("SELECT number FROM numbers(5)")?,
+                    sinks: vec![stream_id.clone()],
+                    scatters_expression: Expression::create_literal(DataValue::UInt64(Some(1))),
+                    max_threads_hint: None,
+                }),
+            )
+            .await?;
+
+        // The stage is never fetched, e.g. a standby stage on a replica.
+        flight_dispatcher.cancel_query(&query_id);
+
+        let stream = stream_ticket(&query_id, &stage_id, &stream_id);
+        match flight_dispatcher.get_stream(&stream) {
+            Ok(_) => assert!(false, "Return Ok in test_cancel_query_with_waiting_stage."),
+            Err(error) => assert_eq!(error.code(), 29),
+        }
+
+        // The waiting stage releases the session without running.
+        for _ in 0..100 {
+            if sessions.get_session(&query_id).is_none() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(sessions.get_session(&query_id).is_none());
+    }
+
+    Ok(())
+}
+
 fn stream_ticket(query_id: &str, stage_id: &str, stream: &str) -> StreamTicket {
     StreamTicket {
         query_id: query_id.to_string(),
@@ -136,10 +136,12 @@ impl FlightService for DatabendQueryFlightService {
 
         let action_result = match &flight_action {
             FlightAction::CancelAction(action) => {
+                // Drop the stages still waiting to start, they hold the session.
+                self.dispatcher.cancel_query(&action.query_id);
+
                 // We only destroy when session is exist
                 let session_id = action.query_id.clone();
                 if let Some(session) = self.sessions.get_session(&session_id) {
-                    // TODO: remove streams
                     session.force_kill_session();
                 }
 
@@ -21,19 +21,31 @@ use common_arrow::arrow_flight::Empty;
 use common_base::tokio;
 use common_base::tokio::net::TcpListener;
 use common_base::tokio::sync::Notify;
+use common_datablocks::assert_blocks_eq;
+use common_datavalues::DataValue;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_flight_rpc::ConnectionFactory;
 use common_flight_rpc::FlightClientTlsConfig;
+use common_planners::Expression;
+use futures::TryStreamExt;
 use tokio_stream::wrappers::TcpListenerStream;
 
 use crate::api::rpc::DatabendQueryFlightDispatcher;
+use crate::api::FlightAction;
+use crate::api::FlightTicket;
 use crate::api::RpcService;
+use crate::api::ShuffleAction;
+use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::RemoteTransform;
 use crate::servers::Server;
+use crate::tests::parse_query;
 use crate::tests::tls_constants::TEST_CA_CERT;
 use crate::tests::tls_constants::TEST_CN_NAME;
 use crate::tests::tls_constants::TEST_SERVER_CERT;
 use crate::tests::tls_constants::TEST_SERVER_KEY;
+use crate::tests::try_create_cluster_context;
+use crate::tests::ClusterDescriptor;
 use crate::tests::SessionManagerBuilder;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -105,3 +117,76 @@ async fn test_tls_rpc_server_invalid_client_config() -> Result<()> {
     assert_eq!(e.code(), ErrorCode::TLSConfigurationFailure("").code());
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_remote_transform_fail_over_to_replica() -> Result<()> {
+    let query_id = uuid::Uuid::new_v4().to_string();
+    let stage_id = uuid::Uuid::new_v4().to_string();
+    let stream_id = uuid::Uuid::new_v4().to_string();
+
+    // The replica serves the stream.
+    let mut rpc_service = RpcService {
+        abort_notify: Arc::new(Notify::new()),
+        dispatcher: Arc::new(DatabendQueryFlightDispatcher::create()),
+        sessions: SessionManagerBuilder::create().build()?,
+    };
+    let replica_address = rpc_service
+        .start(SocketAddr::from_str("127.0.0.1:0")?)
+        .await?;
+
+    let plan = parse_query("SELECT number FROM numbers(5)")?;
+    let rpc_session = rpc_service
+        .sessions
+        .create_rpc_session(query_id.clone(), false)?;
+    rpc_service
+        .dispatcher
+        .shuffle_action(
+            rpc_session,
+            FlightAction::PrepareShuffleAction(ShuffleAction {
+                query_id: query_id.clone(),
+                stage_id: stage_id.clone(),
+                plan: plan.clone(),
+                sinks: vec![stream_id.clone()],
+                scatters_expression: Expression::create_literal(DataValue::UInt64(Some(1))),
+                max_threads_hint: None,
+            }),
+        )
+        .await?;
+
+    // The primary refuses the connection.
+    let primary_address = {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        listener.local_addr()?
+    };
+
+    let ctx = try_create_cluster_context(
+        ClusterDescriptor::new()
+            .with_node("primary", primary_address.to_string())
+            .with_node("replica", replica_address.to_string())
+            .with_local_id("local"),
+    )?;
+
+    let remote = RemoteTransform::try_create(
+        FlightTicket::stream(&query_id, &stage_id, &stream_id),
+        ctx,
+        "primary".to_string(),
+        plan.schema(),
+    )?
+    .with_replicas(vec!["replica".to_string()]);
+
+    let result = remote.execute().await?.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 0      |",
+        "| 1      |",
+        "| 2      |",
+        "| 3      |",
+        "| 4      |",
+        "+--------+",
+    ];
+    assert_blocks_eq(expected, &result);
+
+    Ok(())
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use common_base::tokio;
 use common_tracing::init_tracing_with_file;
 use common_tracing::set_panic_hook;
@@ -19,6 +21,8 @@ use databend_query::api::HttpService;
 use databend_query::api::RpcService;
 use databend_query::configs::Config;
 use databend_query::metrics::MetricService;
+use databend_query::pipelines::transforms::SpillOptions;
+use databend_query::pipelines::transforms::SpillSweeper;
 use databend_query::servers::ClickHouseHandler;
 use databend_query::servers::MySQLHandler;
 use databend_query::servers::Server;
@@ -59,6 +63,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         *databend_query::configs::DATABEND_COMMIT_VERSION,
     );
 
+    // Remove the spill files left by the queries of a crashed process.
+    {
+        let disk = &conf.storage.disk;
+        let spill_path = SpillOptions::create(&disk.temp_path, 0).path;
+        let sweeper =
+            SpillSweeper::create(spill_path, Duration::from_secs(disk.temp_file_max_age_secs));
+
+        match disk.temp_sweep_interval_secs {
+            0 => {
+                let removed = sweeper.sweep()?;
+                info!("Removed {} orphaned spill files", removed);
+            }
+            secs => {
+                sweeper.start(Duration::from_secs(secs));
+            }
+        }
+    }
+
     let session_manager = SessionManager::from_conf(conf.clone()).await?;
     let mut shutdown_handle = ShutdownHandle::create(session_manager.clone());
 
@@ -127,6 +149,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let cluster_discovery = session_manager.get_cluster_discovery();
         let register_to_metastore = cluster_discovery.register_to_metastore(&conf);
         register_to_metastore.await?;
+        // The servers are already accepting queries, forget the cluster discovered without us.
+        session_manager.invalidate_cluster_cache();
         info!("Databend query has been registered to metastore.");
     }
 
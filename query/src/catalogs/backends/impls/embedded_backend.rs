@@ -18,6 +18,7 @@ use std::sync::Arc;
 
 use common_exception::ErrorCode;
 use common_infallible::RwLock;
+use common_meta_types::table_version_mismatch;
 use common_meta_types::CreateDatabaseReply;
 use common_meta_types::CreateTableReply;
 use common_meta_types::DatabaseInfo;
@@ -119,6 +120,14 @@ impl CatalogBackend for EmbeddedCatalogBackend {
 
         let removed = {
             let mut dbs = self.databases.write();
+            if let Some((_, tables)) = dbs.get(db_name) {
+                if !tables.name2meta.is_empty() && !plan.cascade {
+                    return Err(ErrorCode::DatabaseNotEmpty(format!(
+                        "Database '{}' is not empty, use DROP DATABASE ... CASCADE",
+                        db_name
+                    )));
+                }
+            }
             dbs.remove(db_name)
         };
 
@@ -232,7 +241,19 @@ impl CatalogBackend for EmbeddedCatalogBackend {
                             )));
                         }
                     }
-                    Some(tbl) => tbl.table_id,
+                    Some(tbl) => {
+                        // The same error as the meta service, the table is left unchanged.
+                        if let Some(expected_version) = plan.expected_version {
+                            if tbl.version != expected_version {
+                                return Err(table_version_mismatch(
+                                    format_args!("drop table {}", table_name),
+                                    plan.expected_version,
+                                    tbl.version,
+                                ));
+                            }
+                        }
+                        tbl.table_id
+                    }
                 }
             }
         };
@@ -0,0 +1,64 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_planners::CreateDatabasePlan;
+use common_planners::CreateTablePlan;
+use common_planners::DropTablePlan;
+
+use crate::catalogs::backends::CatalogBackend;
+use crate::catalogs::backends::EmbeddedCatalogBackend;
+
+#[test]
+fn test_embedded_backend_drop_table_expected_version() -> Result<()> {
+    let backend = EmbeddedCatalogBackend::create();
+    backend.create_database(CreateDatabasePlan {
+        if_not_exists: false,
+        db: "db1".to_string(),
+        engine: "Local".to_string(),
+        options: Default::default(),
+    })?;
+    backend.create_table(CreateTablePlan {
+        if_not_exists: false,
+        db: "db1".to_string(),
+        table: "t1".to_string(),
+        schema: DataSchemaRefExt::create(vec![DataField::new("a", DataType::UInt64, false)]),
+        engine: "Memory".to_string(),
+        options: Default::default(),
+    })?;
+
+    let drop_plan = |expected_version| DropTablePlan {
+        if_exists: false,
+        db: "db1".to_string(),
+        table: "t1".to_string(),
+        expected_version,
+    };
+
+    // A mismatched version leaves the table unchanged.
+    let version = backend.get_table("db1", "t1")?.version;
+    let err = backend
+        .drop_table(drop_plan(Some(version + 1)))
+        .unwrap_err();
+    assert_eq!(ErrorCode::TableVersionMismatch("").code(), err.code());
+    assert!(backend.get_table("db1", "t1").is_ok());
+
+    backend.drop_table(drop_plan(Some(version)))?;
+    let err = backend.get_table("db1", "t1").unwrap_err();
+    assert_eq!(ErrorCode::UnknownTable("").code(), err.code());
+
+    Ok(())
+}
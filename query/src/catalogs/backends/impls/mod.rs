@@ -13,6 +13,9 @@
 //  limitations under the License.
 //
 
+#[cfg(test)]
+mod embedded_backend_test;
+
 mod embedded_backend;
 mod remote_backend;
 
@@ -27,6 +27,7 @@ use common_meta_types::CreateTableReply;
 use common_meta_types::DatabaseInfo;
 use common_meta_types::MetaId;
 use common_meta_types::MetaVersion;
+use common_meta_types::PingReply;
 use common_meta_types::TableInfo;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
@@ -64,6 +65,19 @@ impl RemoteCatalogBackend {
             store_api_provider: apis_provider,
         }
     }
+
+    /// Probe the liveness of the meta service, no meta data is read.
+    pub fn ping(&self) -> Result<PingReply> {
+        let cli_provider = self.store_api_provider.clone();
+        let r = self.rt.block_on(
+            async move {
+                let cli = cli_provider.try_get_meta_client().await?;
+                cli.ping().await
+            },
+            self.rpc_time_out,
+        )??;
+        Ok(r)
+    }
 }
 
 impl CatalogBackend for RemoteCatalogBackend {
@@ -168,7 +182,7 @@ impl CatalogBackend for RemoteCatalogBackend {
             database_id: 0,
             db: reply.db.clone(),
             table_id: reply.table_id,
-            version: 0,
+            version: reply.version,
             name: reply.name.clone(),
             is_local: false,
             schema: reply.schema.clone(),
@@ -216,7 +230,7 @@ impl CatalogBackend for RemoteCatalogBackend {
             db: reply.db.clone(),
             database_id: 0,
             table_id: reply.table_id,
-            version: 0,
+            version: reply.version,
             name: reply.name.clone(),
             is_local: false,
             schema: reply.schema.clone(),
@@ -226,8 +240,7 @@ impl CatalogBackend for RemoteCatalogBackend {
 
         let mut cache = self.table_meta_cache.lock();
         let res = Arc::new(res);
-        // TODO version
-        cache.put((reply.table_id, 0), res.clone());
+        cache.put((reply.table_id, reply.version), res.clone());
         Ok(res)
     }
 
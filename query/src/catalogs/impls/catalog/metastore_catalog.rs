@@ -25,6 +25,7 @@ use common_meta_types::MetaId;
 use common_meta_types::MetaVersion;
 use common_planners::CreateDatabasePlan;
 use common_planners::DropDatabasePlan;
+use common_tracing::tracing;
 
 use crate::catalogs::backends::CatalogBackend;
 use crate::catalogs::backends::EmbeddedCatalogBackend;
@@ -67,7 +68,23 @@ impl MetaStoreCatalog {
             Arc::new(EmbeddedCatalogBackend::create())
         } else {
             let store_client_provider = Arc::new(MetaClientProvider::new(&conf));
-            Arc::new(RemoteCatalogBackend::create(store_client_provider))
+            let backend = RemoteCatalogBackend::create(store_client_provider);
+
+            // Only route the catalog to a meta service that is alive.
+            let reply = backend.ping().map_err(|e| {
+                ErrorCode::MetaServiceUnavailable(format!(
+                    "meta service {} is not alive: {}",
+                    conf.meta.meta_address,
+                    e.message()
+                ))
+            })?;
+            tracing::info!(
+                "meta service {} is alive: {:?}",
+                conf.meta.meta_address,
+                reply
+            );
+
+            Arc::new(backend)
         };
 
         let plan = CreateDatabasePlan {
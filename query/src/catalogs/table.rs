@@ -42,6 +42,12 @@ pub trait Table: Sync + Send {
         false
     }
 
+    // The schema of the data that exists before the table is created, e.g. the file of a Parquet table.
+    // It is checked against the declared schema when the table is created.
+    fn data_schema(&self) -> Result<Option<DataSchemaRef>> {
+        Ok(None)
+    }
+
     // Get the read source plan.
     fn read_plan(
         &self,
@@ -79,6 +85,31 @@ pub trait Table: Sync + Send {
             self.name()
         )))
     }
+
+    // The version of the rows of the table, changed by every write.
+    fn data_version(&self) -> u64 {
+        0
+    }
+
+    // Whether the table implements `overwrite`, which DELETE and UPDATE rewrite the table by.
+    fn support_overwrite(&self) -> bool {
+        false
+    }
+
+    // Replace all the rows of the table with the rows of the stream in one step.
+    // Fails without changing the table if the stream fails, or if the table is
+    // no longer at `base_version`, e.g., a concurrent insert happened.
+    async fn overwrite(
+        &self,
+        _io_ctx: Arc<TableIOContext>,
+        _stream: SendableDataBlockStream,
+        _base_version: u64,
+    ) -> Result<()> {
+        Err(ErrorCode::UnImplement(format!(
+            "overwrite for local table {} is not implemented",
+            self.name()
+        )))
+    }
 }
 
 pub type TablePtr = Arc<dyn Table>;
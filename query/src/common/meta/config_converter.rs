@@ -37,6 +37,8 @@ impl From<&Config> for MetaFlightClientConf {
             username: conf.meta.meta_username.clone(),
             password: conf.meta.meta_password.clone(),
             tls_conf: meta_tls_conf,
+            pool_size: conf.meta.meta_client_pool_size as usize,
+            timeout_secs: conf.meta.meta_client_timeout_in_secs,
         };
 
         MetaFlightClientConf {
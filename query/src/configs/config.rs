@@ -106,6 +106,7 @@ impl Config {
         if cfg.query.num_cpus == 0 {
             cfg.query.num_cpus = num_cpus::get() as u64;
         }
+        cfg.storage.s3.check()?;
         Ok(cfg)
     }
 
@@ -130,6 +131,7 @@ impl Config {
         // Query.
         QueryConfig::load_from_env(&mut mut_config);
 
+        mut_config.storage.s3.check()?;
         Ok(mut_config)
     }
 
@@ -23,6 +23,8 @@ use crate::configs::Config;
 pub const META_ADDRESS: &str = "META_ADDRESS";
 pub const META_USERNAME: &str = "META_USERNAME";
 pub const META_PASSWORD: &str = "META_PASSWORD";
+pub const META_CLIENT_POOL_SIZE: &str = "META_CLIENT_POOL_SIZE";
+pub const META_CLIENT_TIMEOUT_IN_SECS: &str = "META_CLIENT_TIMEOUT_IN_SECS";
 pub const META_RPC_TLS_SERVER_ROOT_CA_CERT: &str = "META_RPC_TLS_SERVER_ROOT_CA_CERT";
 pub const META_RPC_TLS_SERVICE_DOMAIN_NAME: &str = "META_RPC_TLS_SERVICE_DOMAIN_NAME";
 
@@ -42,6 +44,24 @@ pub struct MetaConfig {
     #[serde(default)]
     pub meta_password: String,
 
+    #[structopt(
+        long,
+        env = META_CLIENT_POOL_SIZE,
+        default_value = "0",
+        help = "Max number of connections to MetaStore, concurrent requests beyond it wait for an idle connection. 0 means the number of CPUs"
+    )]
+    #[serde(default)]
+    pub meta_client_pool_size: u64,
+
+    #[structopt(
+        long,
+        env = META_CLIENT_TIMEOUT_IN_SECS,
+        default_value = "60",
+        help = "Seconds a request to MetaStore is allowed to take before failing with a timeout error"
+    )]
+    #[serde(default)]
+    pub meta_client_timeout_in_secs: u64,
+
     #[structopt(
         long,
         env = "META_RPC_TLS_SERVER_ROOT_CA_CERT",
@@ -66,6 +86,8 @@ impl MetaConfig {
             meta_address: "".to_string(),
             meta_username: "root".to_string(),
             meta_password: "".to_string(),
+            meta_client_pool_size: 0,
+            meta_client_timeout_in_secs: 60,
             rpc_tls_meta_server_root_ca_cert: "".to_string(),
             rpc_tls_meta_service_domain_name: "localhost".to_string(),
         }
@@ -75,6 +97,20 @@ impl MetaConfig {
         env_helper!(mut_config, meta, meta_address, String, META_ADDRESS);
         env_helper!(mut_config, meta, meta_username, String, META_USERNAME);
         env_helper!(mut_config, meta, meta_password, String, META_PASSWORD);
+        env_helper!(
+            mut_config,
+            meta,
+            meta_client_pool_size,
+            u64,
+            META_CLIENT_POOL_SIZE
+        );
+        env_helper!(
+            mut_config,
+            meta,
+            meta_client_timeout_in_secs,
+            u64,
+            META_CLIENT_TIMEOUT_IN_SECS
+        );
         env_helper!(
             mut_config,
             meta,
@@ -97,7 +133,13 @@ impl fmt::Debug for MetaConfig {
         write!(f, "{{")?;
         write!(f, "meta_address: \"{}\", ", self.meta_address)?;
         write!(f, "meta_user: \"{}\", ", self.meta_username)?;
-        write!(f, "meta_password: \"******\"")?;
+        write!(f, "meta_password: \"******\", ")?;
+        write!(f, "meta_client_pool_size: {}, ", self.meta_client_pool_size)?;
+        write!(
+            f,
+            "meta_client_timeout_in_secs: {}",
+            self.meta_client_timeout_in_secs
+        )?;
         write!(f, "}}")
     }
 }
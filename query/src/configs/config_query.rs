@@ -29,6 +29,7 @@ pub const QUERY_CLICKHOUSE_HANDLER_PORT: &str = "QUERY_CLICKHOUSE_HANDLER_PORT";
 pub const QUERY_FLIGHT_API_ADDRESS: &str = "QUERY_FLIGHT_API_ADDRESS";
 pub const QUERY_HTTP_API_ADDRESS: &str = "QUERY_HTTP_API_ADDRESS";
 pub const QUERY_METRICS_API_ADDRESS: &str = "QUERY_METRIC_API_ADDRESS";
+pub const QUERY_DISCOVERY_CACHE_TTL_SECS: &str = "QUERY_DISCOVERY_CACHE_TTL_SECS";
 const QUERY_API_TLS_SERVER_CERT: &str = "QUERY_API_TLS_SERVER_CERT";
 const QUERY_API_TLS_SERVER_KEY: &str = "QUERY_API_TLS_SERVER_KEY";
 const QUERY_API_TLS_SERVER_ROOT_CA_CERT: &str = "QUERY_API_TLS_SERVER_ROOT_CA_CERT";
@@ -116,6 +117,15 @@ pub struct QueryConfig {
     #[serde(default)]
     pub metric_api_address: String,
 
+    #[structopt(
+        long,
+        env = QUERY_DISCOVERY_CACHE_TTL_SECS,
+        default_value = "0",
+        help = "Seconds to reuse the discovered cluster for new query contexts, 0 means discover every time"
+    )]
+    #[serde(default)]
+    pub discovery_cache_ttl_secs: u64,
+
     #[structopt(long, env = QUERY_API_TLS_SERVER_CERT, default_value = "")]
     #[serde(default)]
     pub api_tls_server_cert: String,
@@ -177,6 +187,7 @@ impl QueryConfig {
             flight_api_address: "127.0.0.1:9090".to_string(),
             http_api_address: "127.0.0.1:8080".to_string(),
             metric_api_address: "127.0.0.1:7070".to_string(),
+            discovery_cache_ttl_secs: 0,
             api_tls_server_cert: "".to_string(),
             api_tls_server_key: "".to_string(),
             api_tls_server_root_ca_cert: "".to_string(),
@@ -247,6 +258,13 @@ impl QueryConfig {
             String,
             QUERY_METRICS_API_ADDRESS
         );
+        env_helper!(
+            mut_config,
+            query,
+            discovery_cache_ttl_secs,
+            u64,
+            QUERY_DISCOVERY_CACHE_TTL_SECS
+        );
 
         // for api http service
         env_helper!(
@@ -15,21 +15,28 @@
 use std::fmt;
 use std::str::FromStr;
 
+use common_dal::Compression;
+use common_exception::Result;
 use structopt::StructOpt;
 use structopt_toml::StructOptToml;
 
 use crate::configs::Config;
 
 pub const STORAGE_TYPE: &str = "STORAGE_TYPE";
+pub const STORAGE_READ_RETRIES: &str = "STORAGE_READ_RETRIES";
 
 // Disk Storage env.
 pub const DISK_STORAGE_DATA_PATH: &str = "DISK_STORAGE_DATA_PATH";
+pub const DISK_STORAGE_TEMP_PATH: &str = "DISK_STORAGE_TEMP_PATH";
+pub const DISK_STORAGE_TEMP_FILE_MAX_AGE_SECS: &str = "DISK_STORAGE_TEMP_FILE_MAX_AGE_SECS";
+pub const DISK_STORAGE_TEMP_SWEEP_INTERVAL_SECS: &str = "DISK_STORAGE_TEMP_SWEEP_INTERVAL_SECS";
 
 // S3 Storage env.
 const S3_STORAGE_REGION: &str = "S3_STORAGE_REGION";
 const S3_STORAGE_ACCESS_KEY_ID: &str = "S3_STORAGE_ACCESS_KEY_ID";
 const S3_STORAGE_SECRET_ACCESS_KEY: &str = "S3_STORAGE_SECRET_ACCESS_KEY";
 const S3_STORAGE_BUCKET: &str = "S3_STORAGE_BUCKET";
+const S3_STORAGE_COMPRESSION: &str = "S3_STORAGE_COMPRESSION";
 
 #[derive(Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub enum StorageType {
@@ -57,14 +64,37 @@ pub struct DiskStorageConfig {
     #[structopt(long, env = DISK_STORAGE_DATA_PATH, default_value = "", help = "Disk storage backend address")]
     #[serde(default)]
     pub data_path: String,
+
+    #[structopt(long, env = DISK_STORAGE_TEMP_PATH, default_value = "", help = "Disk path for the temp files spilled by queries, the system temp dir is used if it is empty")]
+    #[serde(default)]
+    pub temp_path: String,
+
+    #[structopt(long, env = DISK_STORAGE_TEMP_FILE_MAX_AGE_SECS, default_value = "3600", help = "The spill files left by crashed queries are removed once older than this")]
+    #[serde(default = "DiskStorageConfig::default_temp_file_max_age_secs")]
+    pub temp_file_max_age_secs: u64,
+
+    #[structopt(long, env = DISK_STORAGE_TEMP_SWEEP_INTERVAL_SECS, default_value = "600", help = "How often to sweep the orphaned spill files, 0 means only at startup")]
+    #[serde(default = "DiskStorageConfig::default_temp_sweep_interval_secs")]
+    pub temp_sweep_interval_secs: u64,
 }
 
 impl DiskStorageConfig {
     pub fn default() -> Self {
         DiskStorageConfig {
             data_path: "".to_string(),
+            temp_path: "".to_string(),
+            temp_file_max_age_secs: Self::default_temp_file_max_age_secs(),
+            temp_sweep_interval_secs: Self::default_temp_sweep_interval_secs(),
         }
     }
+
+    fn default_temp_file_max_age_secs() -> u64 {
+        3600
+    }
+
+    fn default_temp_sweep_interval_secs() -> u64 {
+        600
+    }
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize, PartialEq, StructOpt, StructOptToml)]
@@ -84,6 +114,10 @@ pub struct S3StorageConfig {
     #[structopt(long, env = S3_STORAGE_BUCKET, default_value = "", help = "S3 Bucket to use for storage")]
     #[serde(default)]
     pub bucket: String,
+
+    #[structopt(long, env = S3_STORAGE_COMPRESSION, default_value = "none", help = "Compression of the data parts stored in S3: none|lz4|zstd")]
+    #[serde(default = "S3StorageConfig::default_compression")]
+    pub compression: String,
 }
 
 impl S3StorageConfig {
@@ -93,15 +127,39 @@ impl S3StorageConfig {
             access_key_id: "".to_string(),
             secret_access_key: "".to_string(),
             bucket: "".to_string(),
+            compression: Self::default_compression(),
         }
     }
+
+    fn default_compression() -> String {
+        "none".to_string()
+    }
+
+    /// Reject an unknown compression when the config is loaded, rather than at the first write.
+    pub fn check(&self) -> Result<()> {
+        Compression::from_str(&self.compression)?;
+        Ok(())
+    }
 }
 
+// Print every field, so a new one is never silently dropped from the logs,
+// but never the secrets: only whether they are set.
 impl fmt::Debug for S3StorageConfig {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{{")?;
-        write!(f, "s3.storage.region: \"{}\", ", self.region)?;
-        write!(f, "}}")
+        f.debug_struct("S3StorageConfig")
+            .field("region", &self.region)
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &redact(&self.secret_access_key))
+            .field("bucket", &self.bucket)
+            .field("compression", &self.compression)
+            .finish()
+    }
+}
+
+fn redact(secret: &str) -> &'static str {
+    match secret.is_empty() {
+        true => "",
+        false => "***",
     }
 }
 
@@ -115,6 +173,10 @@ pub struct StorageConfig {
     #[serde(default)]
     pub storage_type: String,
 
+    #[structopt(long, env = STORAGE_READ_RETRIES, default_value = "3", help = "How many times a partition is read again from the start when reading it fails, 0 means never")]
+    #[serde(default = "StorageConfig::default_read_retries")]
+    pub read_retries: u64,
+
     // Disk storage backend config.
     #[structopt(flatten)]
     pub disk: DiskStorageConfig,
@@ -128,13 +190,19 @@ impl StorageConfig {
     pub fn default() -> Self {
         StorageConfig {
             storage_type: "disk".to_string(),
+            read_retries: Self::default_read_retries(),
             disk: DiskStorageConfig::default(),
             s3: S3StorageConfig::default(),
         }
     }
 
+    fn default_read_retries() -> u64 {
+        3
+    }
+
     pub fn load_from_env(mut_config: &mut Config) {
         env_helper!(mut_config, storage, storage_type, String, STORAGE_TYPE);
+        env_helper!(mut_config, storage, read_retries, u64, STORAGE_READ_RETRIES);
 
         // DISK.
         env_helper!(
@@ -144,6 +212,27 @@ impl StorageConfig {
             String,
             DISK_STORAGE_DATA_PATH
         );
+        env_helper!(
+            mut_config.storage,
+            disk,
+            temp_path,
+            String,
+            DISK_STORAGE_TEMP_PATH
+        );
+        env_helper!(
+            mut_config.storage,
+            disk,
+            temp_file_max_age_secs,
+            u64,
+            DISK_STORAGE_TEMP_FILE_MAX_AGE_SECS
+        );
+        env_helper!(
+            mut_config.storage,
+            disk,
+            temp_sweep_interval_secs,
+            u64,
+            DISK_STORAGE_TEMP_SWEEP_INTERVAL_SECS
+        );
 
         // S3.
         env_helper!(mut_config.storage, s3, region, String, S3_STORAGE_REGION);
@@ -162,5 +251,12 @@ impl StorageConfig {
             S3_STORAGE_SECRET_ACCESS_KEY
         );
         env_helper!(mut_config.storage, s3, bucket, String, S3_STORAGE_BUCKET);
+        env_helper!(
+            mut_config.storage,
+            s3,
+            compression,
+            String,
+            S3_STORAGE_COMPRESSION
+        );
     }
 }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_exception::ErrorCode;
 use common_exception::Result;
 use pretty_assertions::assert_eq;
 
@@ -48,6 +49,7 @@ clickhouse_handler_port = 9000
 flight_api_address = \"127.0.0.1:9090\"
 http_api_address = \"127.0.0.1:8080\"
 metric_api_address = \"127.0.0.1:7070\"
+discovery_cache_ttl_secs = 0
 api_tls_server_cert = \"\"
 api_tls_server_key = \"\"
 api_tls_server_root_ca_cert = \"\"
@@ -64,20 +66,27 @@ log_dir = \"./_logs\"
 meta_address = \"\"
 meta_username = \"root\"
 meta_password = \"\"
+meta_client_pool_size = 0
+meta_client_timeout_in_secs = 60
 rpc_tls_meta_server_root_ca_cert = \"\"
 rpc_tls_meta_service_domain_name = \"localhost\"
 
 [storage]
 storage_type = \"disk\"
+read_retries = 3
 
 [storage.disk]
 data_path = \"\"
+temp_path = \"\"
+temp_file_max_age_secs = 3600
+temp_sweep_interval_secs = 600
 
 [storage.s3]
 region = \"\"
 access_key_id = \"\"
 secret_access_key = \"\"
 bucket = \"\"
+compression = \"none\"
 ";
 
     let tom_actual = toml::to_string(&actual).unwrap();
@@ -101,10 +110,13 @@ fn test_env_config() -> Result<()> {
     std::env::set_var("QUERY_METRIC_API_ADDRESS", "1.2.3.4:7071");
     std::env::set_var("STORAGE_TYPE", "s3");
     std::env::set_var("DISK_STORAGE_DATA_PATH", "/tmp/test");
+    std::env::set_var("DISK_STORAGE_TEMP_PATH", "/tmp/spill");
+    std::env::set_var("DISK_STORAGE_TEMP_FILE_MAX_AGE_SECS", "60");
     std::env::set_var("S3_STORAGE_REGION", "us.region");
     std::env::set_var("S3_STORAGE_ACCESS_KEY_ID", "us.key.id");
     std::env::set_var("S3_STORAGE_SECRET_ACCESS_KEY", "us.key");
     std::env::set_var("S3_STORAGE_BUCKET", "us.bucket");
+    std::env::set_var("S3_STORAGE_COMPRESSION", "lz4");
     std::env::remove_var("CONFIG_FILE");
 
     let default = Config::default();
@@ -126,11 +138,15 @@ fn test_env_config() -> Result<()> {
     assert_eq!("s3", configured.storage.storage_type);
 
     assert_eq!("/tmp/test", configured.storage.disk.data_path);
+    assert_eq!("/tmp/spill", configured.storage.disk.temp_path);
+    assert_eq!(60, configured.storage.disk.temp_file_max_age_secs);
+    assert_eq!(600, configured.storage.disk.temp_sweep_interval_secs);
 
     assert_eq!("us.region", configured.storage.s3.region);
     assert_eq!("us.key.id", configured.storage.s3.access_key_id);
     assert_eq!("us.key", configured.storage.s3.secret_access_key);
     assert_eq!("us.bucket", configured.storage.s3.bucket);
+    assert_eq!("lz4", configured.storage.s3.compression);
 
     // clean up
     std::env::remove_var("LOG_LEVEL");
@@ -147,10 +163,31 @@ fn test_env_config() -> Result<()> {
     std::env::remove_var("QUERY_METRIC_API_ADDRESS");
     std::env::remove_var("STORAGE_TYPE");
     std::env::remove_var("DISK_STORAGE_DATA_PATH");
+    std::env::remove_var("DISK_STORAGE_TEMP_PATH");
+    std::env::remove_var("DISK_STORAGE_TEMP_FILE_MAX_AGE_SECS");
     std::env::remove_var("S3_STORAGE_REGION");
     std::env::remove_var("S3_STORAGE_ACCESS_KEY_ID");
     std::env::remove_var("S3_STORAGE_SECRET_ACCESS_KEY");
     std::env::remove_var("S3_STORAGE_BUCKET");
+    std::env::remove_var("S3_STORAGE_COMPRESSION");
+    Ok(())
+}
+
+#[test]
+fn test_storage_compression_config() -> Result<()> {
+    let mut config = StorageConfig::default();
+    assert_eq!("none", config.s3.compression);
+    config.s3.check()?;
+
+    config.s3.compression = "zstd".to_string();
+    config.s3.check()?;
+
+    // An unknown compression is rejected when the config is loaded.
+    config.s3.compression = "gzip".to_string();
+    assert_eq!(
+        ErrorCode::UnknownCompressionName("").code(),
+        config.s3.check().unwrap_err().code()
+    );
     Ok(())
 }
 
@@ -160,3 +197,26 @@ fn test_fuse_commit_version() -> Result<()> {
     assert!(v.len() > 0);
     Ok(())
 }
+
+#[test]
+fn test_storage_config_debug() -> Result<()> {
+    let mut config = StorageConfig::default();
+    config.storage_type = "s3".to_string();
+    config.s3.region = "us.region".to_string();
+    config.s3.access_key_id = "us.key.id".to_string();
+    config.s3.secret_access_key = "us.key".to_string();
+    config.s3.bucket = "us.bucket".to_string();
+
+    let actual = format!("{:?}", config);
+    assert_eq!(
+        "StorageConfig { storage_type: \"s3\", read_retries: 3, disk: DiskStorageConfig { data_path: \"\", temp_path: \"\", temp_file_max_age_secs: 3600, temp_sweep_interval_secs: 600 }, \
+        s3: S3StorageConfig { region: \"us.region\", access_key_id: \"us.key.id\", secret_access_key: \"***\", bucket: \"us.bucket\", compression: \"none\" } }",
+        actual
+    );
+    assert!(!actual.contains("us.key\""));
+
+    // An unset secret is not hidden.
+    config.s3.secret_access_key = "".to_string();
+    assert!(format!("{:?}", config.s3).contains("secret_access_key: \"\""));
+    Ok(())
+}
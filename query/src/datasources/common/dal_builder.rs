@@ -16,6 +16,7 @@
 use std::str::FromStr;
 use std::sync::Arc;
 
+use common_dal::Compression;
 use common_dal::DataAccessor;
 use common_dal::DataAccessorBuilder;
 use common_dal::Local;
@@ -42,12 +43,16 @@ impl DataAccessorBuilder for ContextDalBuilder {
         match scheme {
             StorageScheme::S3 => {
                 let conf = &conf.s3;
-                Ok(Arc::new(S3::with_credentials(
-                    &conf.region,
-                    &conf.bucket,
-                    &conf.access_key_id,
-                    &conf.secret_access_key,
-                )?))
+                let compression = Compression::from_str(&conf.compression)?;
+                Ok(Arc::new(
+                    S3::with_credentials(
+                        &conf.region,
+                        &conf.bucket,
+                        &conf.access_key_id,
+                        &conf.secret_access_key,
+                    )?
+                    .with_compression(compression),
+                ))
             }
             StorageScheme::LocalFs => Ok(Arc::new(Local::new(conf.disk.data_path.as_str()))),
         }
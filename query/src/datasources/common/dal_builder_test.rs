@@ -24,14 +24,19 @@ use crate::datasources::common::ContextDalBuilder;
 fn test_dal_builder() -> common_exception::Result<()> {
     let mut storage_config = StorageConfig {
         storage_type: "disk".to_string(),
+        read_retries: 3,
         disk: DiskStorageConfig {
             data_path: "/tmp".to_string(),
+            temp_path: "".to_string(),
+            temp_file_max_age_secs: 3600,
+            temp_sweep_interval_secs: 600,
         },
         s3: S3StorageConfig {
             region: "".to_string(),
             access_key_id: "".to_string(),
             secret_access_key: "".to_string(),
             bucket: "".to_string(),
+            compression: "none".to_string(),
         },
     };
 
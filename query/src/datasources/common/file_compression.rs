@@ -0,0 +1,109 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+
+/// The table option to select the compression of the source file: auto | none | gzip | zstd.
+pub const COMPRESSION_OPTION: &str = "compression";
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The compression of a source file, e.g. the file of a CSV table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl FileCompression {
+    /// The compression selected by the `compression` table option.
+    /// If it is absent or `auto`, it is detected by the file extension, then by the magic bytes of the file.
+    pub fn try_create(option: Option<&String>, path: &str) -> Result<FileCompression> {
+        let option = option.map(|s| s.trim_matches(|c| c == '\'' || c == '"').to_lowercase());
+
+        match option.as_deref() {
+            None | Some("") | Some("auto") => Self::detect(path),
+            Some("none") => Ok(FileCompression::None),
+            Some("gzip") | Some("gz") => Ok(FileCompression::Gzip),
+            Some("zstd") | Some("zst") => Ok(FileCompression::Zstd),
+            Some(other) => Err(ErrorCode::BadOption(format!(
+                "Unknown compression {}, supported compressions are auto | none | gzip | zstd",
+                other
+            ))),
+        }
+    }
+
+    fn detect(path: &str) -> Result<FileCompression> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+
+        match extension.as_deref() {
+            Some("gz") | Some("gzip") => return Ok(FileCompression::Gzip),
+            Some("zst") | Some("zstd") => return Ok(FileCompression::Zstd),
+            _ => {}
+        }
+
+        let mut magic = [0u8; 4];
+        let mut file = Self::open_file(path)?;
+        let mut read = 0;
+        while read < magic.len() {
+            match file.read(&mut magic[read..])? {
+                0 => break,
+                n => read += n,
+            }
+        }
+
+        let magic = &magic[..read];
+        if magic.starts_with(&ZSTD_MAGIC) {
+            Ok(FileCompression::Zstd)
+        } else if magic.starts_with(&GZIP_MAGIC) {
+            Ok(FileCompression::Gzip)
+        } else {
+            Ok(FileCompression::None)
+        }
+    }
+
+    /// Open the file to read the decompressed content.
+    /// An uncompressed file is read as is.
+    pub fn open(&self, path: &str) -> Result<Box<dyn Read + Send>> {
+        let file = Self::open_file(path)?;
+        match self {
+            FileCompression::None => Ok(Box::new(file)),
+            FileCompression::Gzip => Ok(Box::new(flate2::read::MultiGzDecoder::new(file))),
+            FileCompression::Zstd => {
+                let decoder = zstd::stream::read::Decoder::new(file)
+                    .map_err_to_code(ErrorCode::CannotReadFile, || {
+                        format!("Cannot read zstd file {}", path)
+                    })?;
+                Ok(Box::new(decoder))
+            }
+        }
+    }
+
+    fn open_file(path: &str) -> Result<File> {
+        File::open(path).map_err_to_code(ErrorCode::CannotReadFile, || {
+            format!("Cannot open file {}", path)
+        })
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::datasources::common::FileCompression;
+
+#[test]
+fn test_file_compression_detect() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let write = |name: &str, data: &[u8]| -> Result<String> {
+        let path = dir.path().join(name);
+        std::fs::write(&path, data)?;
+        Ok(path.display().to_string())
+    };
+
+    let gzip = write("a.bin", &[0x1f, 0x8b, 0x08, 0x00])?;
+    let zstd = write("b.bin", &[0x28, 0xb5, 0x2f, 0xfd, 0x00])?;
+    let plain = write("c.csv", b"1,2,3\n")?;
+    let tiny = write("d.csv", b"1")?;
+    let empty = write("e.csv", b"")?;
+
+    // By magic bytes.
+    assert_eq!(
+        FileCompression::try_create(None, &gzip)?,
+        FileCompression::Gzip
+    );
+    assert_eq!(
+        FileCompression::try_create(None, &zstd)?,
+        FileCompression::Zstd
+    );
+    assert_eq!(
+        FileCompression::try_create(None, &plain)?,
+        FileCompression::None
+    );
+    assert_eq!(
+        FileCompression::try_create(None, &tiny)?,
+        FileCompression::None
+    );
+    assert_eq!(
+        FileCompression::try_create(None, &empty)?,
+        FileCompression::None
+    );
+
+    // By extension, the file is not opened.
+    assert_eq!(
+        FileCompression::try_create(None, "/not/exist.csv.gz")?,
+        FileCompression::Gzip
+    );
+    assert_eq!(
+        FileCompression::try_create(Some(&"'auto'".to_string()), "/not/exist.csv.ZST")?,
+        FileCompression::Zstd
+    );
+
+    // By option, which overrides the detection.
+    assert_eq!(
+        FileCompression::try_create(Some(&"none".to_string()), &gzip)?,
+        FileCompression::None
+    );
+    assert_eq!(
+        FileCompression::try_create(Some(&"Zstd".to_string()), &plain)?,
+        FileCompression::Zstd
+    );
+
+    let res = FileCompression::try_create(Some(&"lz4".to_string()), &plain);
+    assert_eq!(ErrorCode::BadOption("").code(), res.unwrap_err().code());
+
+    let res = FileCompression::try_create(None, "/not/exist.csv");
+    assert_eq!(
+        ErrorCode::CannotReadFile("").code(),
+        res.unwrap_err().code()
+    );
+
+    Ok(())
+}
@@ -14,16 +14,25 @@
 //
 
 pub use dal_builder::ContextDalBuilder;
+pub use file_compression::FileCompression;
+pub use file_compression::COMPRESSION_OPTION;
 pub use line::count_lines;
 pub use part::generate_parts;
+pub use schema_check::check_data_schema;
 
 #[cfg(test)]
 mod dal_builder_test;
 #[cfg(test)]
+mod file_compression_test;
+#[cfg(test)]
 mod line_test;
 #[cfg(test)]
 mod part_test;
+#[cfg(test)]
+mod schema_check_test;
 
 mod dal_builder;
+mod file_compression;
 mod line;
 mod part;
+mod schema_check;
@@ -0,0 +1,55 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+/// Check the declared schema of a table can read the data that already exists, e.g. the file of a Parquet table.
+///
+/// Every declared column must exist in the data, with a compatible type:
+/// the same type, or both integers, both floats, or both dates.
+/// The data may have more columns than the declared schema.
+pub fn check_data_schema(declared: &DataSchema, data: &DataSchema) -> Result<()> {
+    for field in declared.fields() {
+        let data_field = data.fields().iter().find(|f| f.name() == field.name());
+
+        match data_field {
+            None => {
+                return Err(ErrorCode::SchemaMismatch(format!(
+                    "Column {} is not found in the data",
+                    field.name()
+                )));
+            }
+            Some(data_field) if !is_compatible(field.data_type(), data_field.data_type()) => {
+                return Err(ErrorCode::SchemaMismatch(format!(
+                    "Column {} is declared as {:?}, but it is {:?} in the data",
+                    field.name(),
+                    field.data_type(),
+                    data_field.data_type()
+                )));
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn is_compatible(declared: &DataType, data: &DataType) -> bool {
+    declared == data
+        || (is_integer(declared) && is_integer(data))
+        || (is_floating(declared) && is_floating(data))
+        || (is_date_or_date_time(declared) && is_date_or_date_time(data))
+}
@@ -0,0 +1,58 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::datasources::common::check_data_schema;
+
+#[test]
+fn test_check_data_schema() -> Result<()> {
+    let data = DataSchemaRefExt::create(vec![
+        DataField::new("id", DataType::Int32, false),
+        DataField::new("amount", DataType::Float64, false),
+        DataField::new("name", DataType::String, false),
+        DataField::new("ts", DataType::DateTime32(None), false),
+    ]);
+
+    // Compatible: a subset of the columns, with the same or the same kind of types.
+    let declared = DataSchemaRefExt::create(vec![
+        DataField::new("name", DataType::String, false),
+        DataField::new("id", DataType::Int64, false),
+        DataField::new("amount", DataType::Float32, false),
+        DataField::new("ts", DataType::Date32, false),
+    ]);
+    check_data_schema(&declared, &data)?;
+
+    // Mismatched type.
+    let declared = DataSchemaRefExt::create(vec![
+        DataField::new("id", DataType::Int32, false),
+        DataField::new("name", DataType::Int32, false),
+    ]);
+    let err = check_data_schema(&declared, &data).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::SchemaMismatch("").code());
+    assert_eq!(
+        err.message(),
+        "Column name is declared as Int32, but it is String in the data"
+    );
+
+    // Missing column.
+    let declared = DataSchemaRefExt::create(vec![DataField::new("age", DataType::Int32, false)]);
+    let err = check_data_schema(&declared, &data).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::SchemaMismatch("").code());
+    assert_eq!(err.message(), "Column age is not found in the data");
+
+    Ok(())
+}
@@ -28,6 +28,7 @@ use crate::catalogs::Database;
 use crate::catalogs::InMemoryMetas;
 use crate::catalogs::TableMeta;
 use crate::common::MetaClientProvider;
+use crate::datasources::common::check_data_schema;
 use crate::datasources::table_engine_registry::TableEngineRegistry;
 
 pub struct DefaultDatabase {
@@ -77,6 +78,33 @@ impl DefaultDatabase {
 
         Ok(Arc::new(tbl_meta))
     }
+
+    // A table created over existing data must be able to read it,
+    // otherwise the table is created but every read of it fails.
+    fn check_existing_data(&self, plan: &CreateTablePlan) -> common_exception::Result<()> {
+        let provider = match self.table_factory_registry.engine_provider(&plan.engine) {
+            None => return Ok(()),
+            Some(provider) => provider,
+        };
+
+        let table_info = TableInfo {
+            database_id: 0,
+            table_id: 0,
+            version: 0,
+            db: plan.db.clone(),
+            name: plan.table.clone(),
+            is_local: true,
+            schema: plan.schema.clone(),
+            engine: plan.engine.clone(),
+            options: plan.options.clone(),
+        };
+
+        let tbl = provider.try_create(table_info, self.store_api_provider.clone())?;
+        match tbl.data_schema()? {
+            None => Ok(()),
+            Some(data_schema) => check_data_schema(&plan.schema, &data_schema),
+        }
+    }
 }
 
 impl Database for DefaultDatabase {
@@ -132,6 +160,7 @@ impl Database for DefaultDatabase {
 
     fn create_table(&self, plan: CreateTablePlan) -> common_exception::Result<()> {
         // TODO validate table parameters by using TableFactory
+        self.check_existing_data(&plan)?;
         self.catalog_backend.create_table(plan)?;
         Ok(())
     }
@@ -43,7 +43,7 @@ async fn test_configs_table() -> Result<()> {
     let result = stream.try_collect::<Vec<_>>().await?;
     let block = &result[0];
     assert_eq!(block.num_columns(), 4);
-    assert_eq!(block.num_rows(), 25);
+    assert_eq!(block.num_rows(), 28);
 
     let expected = vec![
         "+-----------------------------------+----------------+-------+-------------+",
@@ -54,12 +54,15 @@ async fn test_configs_table() -> Result<()> {
         "| api_tls_server_root_ca_cert       |                | query |             |",
         "| clickhouse_handler_host           | 127.0.0.1      | query |             |",
         "| clickhouse_handler_port           | 9000           | query |             |",
+        "| discovery_cache_ttl_secs          | 0              | query |             |",
         "| flight_api_address                | 127.0.0.1:9090 | query |             |",
         "| http_api_address                  | 127.0.0.1:8080 | query |             |",
         "| log_dir                           | ./_logs        | log   |             |",
         "| log_level                         | INFO           | log   |             |",
         "| max_active_sessions               | 256            | query |             |",
         "| meta_address                      |                | meta  |             |",
+        "| meta_client_pool_size             | 0              | meta  |             |",
+        "| meta_client_timeout_in_secs       | 60             | meta  |             |",
         "| meta_password                     |                | meta  |             |",
         "| meta_username                     | root           | meta  |             |",
         "| metric_api_address                | 127.0.0.1:7070 | query |             |",
@@ -53,6 +53,7 @@ async fn test_datasource() -> Result<()> {
         catalog.drop_database(DropDatabasePlan {
             if_exists: false,
             db: "test_db".to_string(),
+            cascade: false,
         })?;
 
         // Check.
@@ -14,7 +14,6 @@
 //
 
 use std::any::Any;
-use std::fs::File;
 use std::sync::Arc;
 
 use common_context::IOContext;
@@ -32,6 +31,8 @@ use common_streams::SendableDataBlockStream;
 use crate::catalogs::Table;
 use crate::datasources::common::count_lines;
 use crate::datasources::common::generate_parts;
+use crate::datasources::common::FileCompression;
+use crate::datasources::common::COMPRESSION_OPTION;
 use crate::datasources::table::csv::csv_table_stream::CsvTableStream;
 use crate::sessions::DatabendQueryContext;
 
@@ -41,6 +42,13 @@ pub struct CsvTable {
     has_header: bool,
 }
 
+impl CsvTable {
+    // The file is detected on every read, it may be replaced after the table is created.
+    fn compression(&self) -> Result<FileCompression> {
+        FileCompression::try_create(self.tbl_info.options.get(COMPRESSION_OPTION), &self.file)
+    }
+}
+
 impl CsvTable {
     pub fn try_create(tbl_info: TableInfo) -> Result<Box<dyn Table>> {
         let options = &tbl_info.options;
@@ -96,7 +104,7 @@ impl Table for CsvTable {
     ) -> Result<ReadDataSourcePlan> {
         let start_line: usize = if self.has_header { 1 } else { 0 };
         let file = &self.file;
-        let lines_count = count_lines(File::open(file.clone())?)?;
+        let lines_count = count_lines(self.compression()?.open(file)?)?;
 
         let db = &self.tbl_info.db;
         let name = &self.tbl_info.name;
@@ -128,6 +136,7 @@ impl Table for CsvTable {
             ctx,
             self.tbl_info.schema.clone(),
             self.file.clone(),
+            self.compression()?,
         )?))
     }
 }
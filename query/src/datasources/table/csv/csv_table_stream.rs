@@ -19,16 +19,17 @@ use std::task::Poll;
 use common_arrow::arrow::io::csv::read;
 use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
-use common_exception::ErrorCode;
 use common_exception::Result;
 use futures::Stream;
 
+use crate::datasources::common::FileCompression;
 use crate::sessions::DatabendQueryContextRef;
 
 pub struct CsvTableStream {
     ctx: DatabendQueryContextRef,
     file: String,
     schema: DataSchemaRef,
+    compression: FileCompression,
 }
 
 impl CsvTableStream {
@@ -36,8 +37,14 @@ impl CsvTableStream {
         ctx: DatabendQueryContextRef,
         schema: DataSchemaRef,
         file: String,
+        compression: FileCompression,
     ) -> Result<Self> {
-        Ok(CsvTableStream { ctx, file, schema })
+        Ok(CsvTableStream {
+            ctx,
+            file,
+            schema,
+            compression,
+        })
     }
 
     pub fn try_get_one_block(&self) -> Result<Option<DataBlock>> {
@@ -55,8 +62,7 @@ impl CsvTableStream {
         let arrow_schema = Arc::new(self.schema.to_arrow());
         let mut reader = read::ReaderBuilder::new()
             .has_headers(false)
-            .from_path(&self.file)
-            .map_err(|e| ErrorCode::CannotReadFile(e.to_string()))?;
+            .from_reader(self.compression.open(&self.file)?);
 
         let mut rows = vec![read::ByteRecord::default(); block_size];
         let rows_read = read::read_rows(&mut reader, begin, &mut rows)?;
@@ -14,14 +14,19 @@
 //
 
 use std::env;
+use std::io::Write;
 use std::sync::Arc;
 
 use common_base::tokio;
 use common_datablocks::assert_blocks_sorted_eq;
+use common_datablocks::pretty_format_blocks;
+use common_datablocks::DataBlock;
 use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_meta_types::TableInfo;
 use common_planners::*;
+use flate2::write::GzEncoder;
 use futures::TryStreamExt;
 
 use crate::datasources::table::csv::csv_table::CsvTable;
@@ -173,3 +178,79 @@ async fn test_csv_table_parse_error() -> Result<()> {
     );
     Ok(())
 }
+
+async fn read_csv_table(location: &str, compression: Option<&str>) -> Result<Vec<DataBlock>> {
+    let mut options = TableOptions::new();
+    options.insert("location".to_string(), location.to_string());
+    if let Some(compression) = compression {
+        options.insert("compression".to_string(), compression.to_string());
+    }
+
+    let ctx = crate::tests::try_create_context()?;
+    let table = CsvTable::try_create(TableInfo {
+        database_id: 0,
+        db: "default".into(),
+        name: "test_csv".into(),
+        is_local: false,
+        schema: DataSchemaRefExt::create(vec![
+            DataField::new("id", DataType::UInt64, false),
+            DataField::new("name", DataType::String, false),
+            DataField::new("rank", DataType::UInt64, false),
+        ]),
+        engine: "Csv".to_string(),
+        options,
+        table_id: 0,
+        version: 0,
+    })?;
+
+    let io_ctx = Arc::new(ctx.get_single_node_table_io_context()?);
+    let partitions = ctx.get_settings().get_max_threads()? as usize;
+    let source_plan = table.read_plan(io_ctx.clone(), None, Some(partitions))?;
+    ctx.try_set_partitions(source_plan.parts.clone())?;
+
+    let stream = table.read(io_ctx, &source_plan.push_downs).await?;
+    stream.try_collect::<Vec<_>>().await
+}
+
+#[tokio::test]
+async fn test_csv_table_compressed() -> Result<()> {
+    let plain_path = env::current_dir()?.join("../tests/data/sample.csv");
+    let plain = std::fs::read(&plain_path)?;
+    let expected = read_csv_table(&plain_path.display().to_string(), None).await?;
+    let expected = pretty_format_blocks(&expected)?;
+    assert!(expected.contains("Shenzhen"));
+
+    let dir = tempfile::tempdir()?;
+    let gzip = {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&plain)?;
+        encoder.finish()?
+    };
+    let zstd = zstd::stream::encode_all(plain.as_slice(), 0)?;
+
+    let cases = vec![
+        // Detected by the extension.
+        ("sample.csv.gz", &gzip, None),
+        ("sample.csv.zst", &zstd, None),
+        // Detected by the magic bytes.
+        ("sample_gzip.csv", &gzip, None),
+        ("sample_zstd.csv", &zstd, Some("auto")),
+        // Selected by the table option.
+        ("sample_gzip.dat", &gzip, Some("gzip")),
+        ("sample_plain.gz", &plain, Some("none")),
+    ];
+
+    for (name, data, compression) in cases {
+        let path = dir.path().join(name);
+        std::fs::write(&path, data)?;
+
+        let blocks = read_csv_table(&path.display().to_string(), compression).await?;
+        assert_eq!(expected, pretty_format_blocks(&blocks)?, "case: {}", name);
+    }
+
+    // An unknown compression.
+    let res = read_csv_table(&plain_path.display().to_string(), Some("lz4")).await;
+    assert_eq!(ErrorCode::BadOption("").code(), res.unwrap_err().code());
+
+    Ok(())
+}
@@ -14,15 +14,19 @@
 //
 
 use std::any::Any;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use common_context::IOContext;
 use common_context::TableIOContext;
 use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_infallible::RwLock;
+use common_meta_types::table_version_mismatch;
 use common_meta_types::TableInfo;
 use common_planners::Extras;
 use common_planners::InsertIntoPlan;
@@ -35,11 +39,15 @@ use futures::stream::StreamExt;
 use crate::catalogs::Table;
 use crate::datasources::common::generate_parts;
 use crate::datasources::table::memory::memory_table_stream::MemoryTableStream;
+use crate::pipelines::transforms::SourcePruner;
 use crate::sessions::DatabendQueryContext;
+use crate::sessions::ScanMetrics;
 
 pub struct MemoryTable {
     tbl_info: TableInfo,
     blocks: Arc<RwLock<Vec<DataBlock>>>,
+    // Bumped under the write lock of `blocks` on every write.
+    version: AtomicU64,
 }
 
 impl MemoryTable {
@@ -47,9 +55,84 @@ impl MemoryTable {
         let table = Self {
             tbl_info,
             blocks: Arc::new(RwLock::new(vec![])),
+            version: AtomicU64::new(0),
         };
         Ok(Box::new(table))
     }
+
+    /// Whether the source plan reads a table of the memory engine,
+    /// whose blocks can be served directly without partitions.
+    pub fn is_memory_backed(source_plan: &ReadDataSourcePlan) -> bool {
+        source_plan.tbl_args.is_none()
+            && source_plan.table_info.engine.eq_ignore_ascii_case("MEMORY")
+    }
+
+    /// A snapshot of the blocks of the table which may match the pushed down filters, projected as pushed down.
+    ///
+    /// The blocks are pruned by the min/max of their columns, computed when they are appended,
+    /// the pruned blocks are counted into `metrics`.
+    pub fn read_blocks(
+        &self,
+        push_downs: &Option<Extras>,
+        metrics: &ScanMetrics,
+    ) -> Vec<DataBlock> {
+        self.read_block_slots(push_downs, metrics)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Like `read_blocks`, but a pruned block is kept as None,
+    /// so that a block is still at its index, e.g., as the partitions refer to it.
+    fn read_block_slots(
+        &self,
+        push_downs: &Option<Extras>,
+        metrics: &ScanMetrics,
+    ) -> Vec<Option<DataBlock>> {
+        let (pruner, projection) = match push_downs {
+            Some(extras) => (
+                SourcePruner::create(&extras.filters),
+                extras.projection.as_ref(),
+            ),
+            None => (SourcePruner::default(), None),
+        };
+
+        let mut pruned = 0;
+        let slots = self
+            .blocks
+            .read()
+            .iter()
+            .map(|block| {
+                let keep = match block.min_max() {
+                    Some(min_max) => pruner.may_match(&self.tbl_info.schema, min_max),
+                    None => true,
+                };
+                match (keep, projection) {
+                    (false, _) => {
+                        pruned += 1;
+                        None
+                    }
+                    (true, Some(projection)) => Some(Self::project_block(block, projection)),
+                    (true, None) => Some(block.clone()),
+                }
+            })
+            .collect();
+        metrics.incr_pruned(pruned);
+        slots
+    }
+
+    /// Keep only the columns at `projection` of the block.
+    fn project_block(block: &DataBlock, projection: &[usize]) -> DataBlock {
+        let fields = projection
+            .iter()
+            .map(|i| block.schema().field(*i).clone())
+            .collect::<Vec<_>>();
+        let columns = projection
+            .iter()
+            .map(|i| block.column(*i).clone())
+            .collect::<Vec<_>>();
+        DataBlock::create(DataSchemaRefExt::create(fields), columns)
+    }
 }
 
 #[async_trait::async_trait]
@@ -108,17 +191,14 @@ impl Table for MemoryTable {
     async fn read(
         &self,
         io_ctx: Arc<TableIOContext>,
-        _push_downs: &Option<Extras>,
+        push_downs: &Option<Extras>,
     ) -> Result<SendableDataBlockStream> {
         let ctx: Arc<DatabendQueryContext> = io_ctx
             .get_user_data()?
             .expect("DatabendQueryContext should not be None");
 
-        let blocks = self.blocks.read();
-        Ok(Box::pin(MemoryTableStream::try_create(
-            ctx,
-            blocks.clone(),
-        )?))
+        let blocks = self.read_block_slots(push_downs, &ctx.get_scan_metrics());
+        Ok(Box::pin(MemoryTableStream::try_create(ctx, blocks)?))
     }
 
     async fn append_data(
@@ -137,8 +217,10 @@ impl Table for MemoryTable {
         }
 
         while let Some(block) = s.next().await {
+            let block = DataBlock::attach_min_max(block);
             let mut blocks = self.blocks.write();
             blocks.push(block);
+            self.version.fetch_add(1, Ordering::SeqCst);
         }
         Ok(())
     }
@@ -150,6 +232,47 @@ impl Table for MemoryTable {
     ) -> Result<()> {
         let mut blocks = self.blocks.write();
         blocks.clear();
+        self.version.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn data_version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    fn support_overwrite(&self) -> bool {
+        true
+    }
+
+    async fn overwrite(
+        &self,
+        _io_ctx: Arc<TableIOContext>,
+        mut stream: SendableDataBlockStream,
+        base_version: u64,
+    ) -> Result<()> {
+        // The new blocks are kept aside until the stream is done, then swapped in.
+        let mut new_blocks = vec![];
+        while let Some(block) = stream.next().await {
+            let block = block?;
+            if block.schema().fields() != self.tbl_info.schema.fields() {
+                return Err(ErrorCode::BadArguments("DataBlock schema mismatch"));
+            }
+            if block.num_rows() > 0 {
+                new_blocks.push(DataBlock::attach_min_max(block));
+            }
+        }
+
+        let mut blocks = self.blocks.write();
+        let version = self.version.load(Ordering::SeqCst);
+        if version != base_version {
+            return Err(table_version_mismatch(
+                format_args!("table {} changed while being overwritten", self.name()),
+                Some(base_version),
+                version,
+            ));
+        }
+        *blocks = new_blocks;
+        self.version.fetch_add(1, Ordering::SeqCst);
         Ok(())
     }
 }
@@ -33,13 +33,14 @@ pub struct MemoryTableStream {
     ctx: DatabendQueryContextRef,
     block_index: usize,
     block_ranges: Vec<usize>,
-    blocks: Vec<DataBlock>,
+    // None for a block pruned by the pushed down filters.
+    blocks: Vec<Option<DataBlock>>,
 }
 
 impl MemoryTableStream {
     pub fn try_create(
         ctx: DatabendQueryContextRef,
-        blocks: Vec<DataBlock>,
+        blocks: Vec<Option<DataBlock>>,
     ) -> Result<ProgressStream> {
         let stream = Box::pin(MemoryTableStream {
             ctx: ctx.clone(),
@@ -51,35 +52,40 @@ impl MemoryTableStream {
     }
 
     fn try_get_one_block(&mut self) -> Result<Option<DataBlock>> {
-        if (self.block_index as usize) == self.block_ranges.len() {
-            let partitions = self.ctx.try_get_partitions(1)?;
-            if partitions.is_empty() {
-                return Ok(None);
-            }
-            if partitions.len() == 1 && partitions[0].name.is_empty() {
-                return Ok(None);
-            }
+        loop {
+            if (self.block_index as usize) == self.block_ranges.len() {
+                let partitions = self.ctx.try_get_partitions(1)?;
+                if partitions.is_empty() {
+                    return Ok(None);
+                }
+                if partitions.len() == 1 && partitions[0].name.is_empty() {
+                    return Ok(None);
+                }
 
-            let mut block_ranges = Vec::with_capacity(partitions.len());
+                let mut block_ranges = Vec::with_capacity(partitions.len());
 
-            for part in partitions {
-                let names: Vec<_> = part.name.split('-').collect();
-                let begin: usize = names[1].parse()?;
-                let end: usize = names[2].parse()?;
+                for part in partitions {
+                    let names: Vec<_> = part.name.split('-').collect();
+                    let begin: usize = names[1].parse()?;
+                    let end: usize = names[2].parse()?;
 
-                let s: Vec<usize> = (begin..end).collect();
-                block_ranges.extend_from_slice(&s);
+                    let s: Vec<usize> = (begin..end).collect();
+                    block_ranges.extend_from_slice(&s);
+                }
+                self.block_ranges = block_ranges;
+                self.block_index = 0;
             }
-            self.block_ranges = block_ranges;
-            self.block_index = 0;
-        }
 
-        if self.block_index == self.block_ranges.len() {
-            return Ok(None);
+            if self.block_index == self.block_ranges.len() {
+                return Ok(None);
+            }
+            let current = self.block_ranges[self.block_index];
+            self.block_index += 1;
+            // Go on to the next block if this one is pruned.
+            if let Some(block) = &self.blocks[current] {
+                return Ok(Some(block.clone()));
+            }
         }
-        let current = self.block_ranges[self.block_index];
-        self.block_index += 1;
-        Ok(Some(self.blocks[current].clone()))
     }
 }
 
@@ -19,13 +19,16 @@ use common_base::tokio;
 use common_datablocks::assert_blocks_sorted_eq;
 use common_datablocks::DataBlock;
 use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_infallible::Mutex;
 use common_meta_types::TableInfo;
 use common_planners::*;
 use futures::TryStreamExt;
 
+use crate::catalogs::Table;
 use crate::datasources::table::memory::memory_table::MemoryTable;
+use crate::sessions::ScanMetrics;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_memorytable() -> Result<()> {
@@ -124,3 +127,94 @@ async fn test_memorytable() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_memorytable_overwrite() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::UInt64, false)]);
+    let table = MemoryTable::try_create(TableInfo {
+        database_id: 0,
+        db: "default".into(),
+        name: "a".into(),
+        is_local: true,
+        schema: schema.clone(),
+        engine: "Memory".to_string(),
+        options: TableOptions::default(),
+        table_id: 0,
+        version: 0,
+    })?;
+    let io_ctx = Arc::new(ctx.get_single_node_table_io_context()?);
+    let block_of =
+        |values: Vec<u64>| DataBlock::create_by_array(schema.clone(), vec![Series::new(values)]);
+    let read_all = |table: &dyn Table| {
+        let blocks = table
+            .as_any()
+            .downcast_ref::<MemoryTable>()
+            .unwrap()
+            .read_blocks(&None, &ScanMetrics::create());
+        blocks.iter().map(|b| b.num_rows()).sum::<usize>()
+    };
+
+    let insert_plan = InsertIntoPlan {
+        db_name: "default".to_string(),
+        tbl_name: "a".to_string(),
+        tbl_id: 0,
+        schema: schema.clone(),
+        input_stream: InsertIntoPlan::empty_stream(),
+    };
+    insert_plan.set_input_stream(Box::pin(futures::stream::iter(vec![block_of(vec![
+        1, 2, 3,
+    ])])));
+    table.append_data(io_ctx.clone(), insert_plan).await?;
+
+    // A failing input leaves the table unchanged.
+    {
+        let version = table.data_version();
+        let stream = futures::stream::iter(vec![
+            Ok(block_of(vec![1])),
+            Err(ErrorCode::UnknownException("input failed")),
+        ]);
+        let result = table
+            .overwrite(io_ctx.clone(), Box::pin(stream), version)
+            .await;
+        assert!(result.is_err());
+        assert_eq!(read_all(table.as_ref()), 3);
+    }
+
+    // A write after the base version fails the overwrite.
+    {
+        let version = table.data_version();
+        let insert_plan = InsertIntoPlan {
+            db_name: "default".to_string(),
+            tbl_name: "a".to_string(),
+            tbl_id: 0,
+            schema: schema.clone(),
+            input_stream: InsertIntoPlan::empty_stream(),
+        };
+        insert_plan.set_input_stream(Box::pin(futures::stream::iter(vec![block_of(vec![4])])));
+        table.append_data(io_ctx.clone(), insert_plan).await?;
+
+        let stream = futures::stream::iter(vec![Ok(block_of(vec![1]))]);
+        let result = table
+            .overwrite(io_ctx.clone(), Box::pin(stream), version)
+            .await;
+        assert_eq!(
+            result.unwrap_err().code(),
+            ErrorCode::TableVersionMismatch("").code()
+        );
+        assert_eq!(read_all(table.as_ref()), 4);
+    }
+
+    // Swapped in at the current version.
+    {
+        let version = table.data_version();
+        let stream = futures::stream::iter(vec![Ok(block_of(vec![1])), Ok(block_of(vec![2]))]);
+        table
+            .overwrite(io_ctx.clone(), Box::pin(stream), version)
+            .await?;
+        assert_eq!(read_all(table.as_ref()), 2);
+        assert_ne!(table.data_version(), version);
+    }
+
+    Ok(())
+}
@@ -21,6 +21,7 @@ use common_arrow::arrow::io::parquet::read;
 use common_base::tokio::task;
 use common_context::TableIOContext;
 use common_datablocks::DataBlock;
+use common_datavalues::DataSchema;
 use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -109,6 +110,12 @@ impl Table for ParquetTable {
         Ok(self.tbl_info.schema.clone())
     }
 
+    fn data_schema(&self) -> Result<Option<DataSchemaRef>> {
+        let reader = File::open(&self.file)?;
+        let reader = read::RecordReader::try_new(reader, None, None, None, None)?;
+        Ok(Some(Arc::new(DataSchema::from(reader.schema().as_ref()))))
+    }
+
     fn get_id(&self) -> u64 {
         self.tbl_info.table_id
     }
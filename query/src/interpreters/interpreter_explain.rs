@@ -21,12 +21,14 @@ use common_planners::ExplainPlan;
 use common_planners::ExplainType;
 use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
+use futures::StreamExt;
 
 use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterPtr;
 use crate::optimizers::Optimizers;
 use crate::pipelines::processors::PipelineBuilder;
 use crate::sessions::DatabendQueryContextRef;
+use crate::sessions::ScanMetricsValues;
 
 pub struct ExplainInterpreter {
     ctx: DatabendQueryContextRef,
@@ -46,6 +48,7 @@ impl Interpreter for ExplainInterpreter {
             ExplainType::Graph => self.explain_graph(),
             ExplainType::Syntax => self.explain_syntax(),
             ExplainType::Pipeline => self.explain_pipeline(),
+            ExplainType::Analyze => self.explain_analyze().await,
         }?;
 
         Ok(Box::pin(DataBlockStream::create(schema, None, vec![block])))
@@ -101,4 +104,42 @@ impl ExplainInterpreter {
         );
         Ok(DataBlock::create_by_array(schema, vec![formatted_pipeline]))
     }
+
+    /// Runs the pipeline to the end, and shows it with how many blocks the pushed down filters skipped.
+    async fn explain_analyze(&self) -> Result<DataBlock> {
+        let schema = self.schema();
+        let plan = Optimizers::without_scatters(self.ctx.clone()).optimize(&self.explain.input)?;
+        let pipeline_builder = PipelineBuilder::create(self.ctx.clone());
+        let mut pipeline = pipeline_builder.build(&plan)?;
+        let formatted_pipeline = format!("{:?}", pipeline);
+
+        let before = self.ctx.get_scan_metrics_values();
+        let mut stream = pipeline.execute().await?;
+        while let Some(block) = stream.next().await {
+            block?;
+        }
+        let after = self.ctx.get_scan_metrics_values();
+
+        let metrics = ScanMetricsValues {
+            blocks_considered: after.blocks_considered - before.blocks_considered,
+            blocks_scanned: after.blocks_scanned - before.blocks_scanned,
+            rows_read: after.rows_read - before.rows_read,
+        };
+        let formatted_metrics = format!(
+            "Scan: blocks considered: {}, blocks scanned: {}, blocks pruned: {}, rows read: {}",
+            metrics.blocks_considered,
+            metrics.blocks_scanned,
+            metrics.blocks_pruned(),
+            metrics.rows_read,
+        );
+
+        let formatted_analyze = Series::new(
+            formatted_pipeline
+                .lines()
+                .chain(std::iter::once(formatted_metrics.as_str()))
+                .map(|s| s.as_bytes())
+                .collect::<Vec<_>>(),
+        );
+        Ok(DataBlock::create_by_array(schema, vec![formatted_analyze]))
+    }
 }
@@ -54,3 +54,45 @@ async fn test_explain_interpreter() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_explain_analyze_interpreter() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    for sql in [
+        "create table default.mem(a UInt64) Engine = Memory",
+        "insert into default.mem values(1), (2)",
+        "insert into default.mem values(3), (4)",
+        "insert into default.mem values(5), (6)",
+    ] {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        executor.execute().await?;
+    }
+
+    if let PlanNode::Explain(plan) = PlanParser::create(ctx.clone())
+        .build_from_sql("explain analyze select a from default.mem where a > 4")?
+    {
+        assert_eq!(plan.typ, ExplainType::Analyze);
+        let executor = ExplainInterpreter::try_create(ctx, plan)?;
+        let stream = executor.execute().await?;
+        let result = stream.try_collect::<Vec<_>>().await?;
+        let formatted = common_datablocks::pretty_format_blocks(&result)?;
+        assert!(
+            formatted.contains("MemorySourceTransform × 1 processor"),
+            "{}",
+            formatted
+        );
+        assert!(
+            formatted.contains(
+                "Scan: blocks considered: 3, blocks scanned: 1, blocks pruned: 2, rows read: 2"
+            ),
+            "{}",
+            formatted
+        );
+    } else {
+        assert!(false)
+    }
+
+    Ok(())
+}
@@ -38,6 +38,10 @@ pub struct InterpreterFactory;
 
 impl InterpreterFactory {
     pub fn get(ctx: DatabendQueryContextRef, plan: PlanNode) -> Result<Arc<dyn Interpreter>> {
+        if ctx.get_settings().get_readonly()? == 1 {
+            Self::check_read_only(&plan)?;
+        }
+
         match plan {
             PlanNode::Select(v) => SelectInterpreter::try_create(ctx, v),
             PlanNode::Explain(v) => ExplainInterpreter::try_create(ctx, v),
@@ -58,4 +62,32 @@ impl InterpreterFactory {
             ))),
         }
     }
+
+    // A read-only session can still query anything, but can't change the data or the metadata.
+    fn check_read_only(plan: &PlanNode) -> Result<()> {
+        let allowed = match plan {
+            PlanNode::CreateDatabase(_)
+            | PlanNode::DropDatabase(_)
+            | PlanNode::CreateTable(_)
+            | PlanNode::DropTable(_)
+            | PlanNode::TruncateTable(_)
+            | PlanNode::InsertInto(_)
+            | PlanNode::Delete(_)
+            | PlanNode::Update(_) => false,
+            // Otherwise the session can turn itself back to writable.
+            PlanNode::SetVariable(v) => !v
+                .vars
+                .iter()
+                .any(|var| var.variable.eq_ignore_ascii_case("readonly")),
+            _ => true,
+        };
+
+        match allowed {
+            true => Ok(()),
+            false => Result::Err(ErrorCode::ReadOnlySession(format!(
+                "{} is not allowed in a read-only session",
+                plan.name()
+            ))),
+        }
+    }
 }
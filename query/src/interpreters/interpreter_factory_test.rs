@@ -0,0 +1,71 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use futures::TryStreamExt;
+use pretty_assertions::assert_eq;
+
+use crate::interpreters::*;
+use crate::sql::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_interpreter_factory_read_only() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_readonly(1)?;
+
+    // Rejected.
+    for query in &[
+        "create table default.a(a bigint) Engine = Null",
+        "drop table a",
+        "create database db1 Engine = default",
+        "set readonly = 0",
+    ] {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+        let result = InterpreterFactory::get(ctx.clone(), plan);
+        let err = result.err().unwrap();
+        assert_eq!(
+            ErrorCode::ReadOnlySession("").code(),
+            err.code(),
+            "{}",
+            query
+        );
+    }
+
+    // Allowed.
+    {
+        let plan =
+            PlanParser::create(ctx.clone()).build_from_sql("select number from numbers_mt(3)")?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        let stream = executor.execute().await?;
+        let result = stream.try_collect::<Vec<_>>().await?;
+        let expected = vec![
+            "+--------+",
+            "| number |",
+            "+--------+",
+            "| 0      |",
+            "| 1      |",
+            "| 2      |",
+            "+--------+",
+        ];
+        common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+        let plan = PlanParser::create(ctx.clone()).build_from_sql("set max_threads = 1")?;
+        InterpreterFactory::get(ctx.clone(), plan)?;
+    }
+
+    assert!(ctx.get_settings().set_readonly(2).is_err());
+    Ok(())
+}
@@ -44,21 +44,29 @@ impl Interpreter for KillInterpreter {
 
     async fn execute(&self) -> Result<SendableDataBlockStream> {
         let id = &self.plan.id;
-        match self.ctx.get_sessions_manager().get_session(id) {
-            None => Err(ErrorCode::UnknownSession(format!(
-                "Not found session id {}",
-                id
-            ))),
+        let sessions = self.ctx.get_sessions_manager();
+
+        // The id is a session id, or else a query id for KILL QUERY
+        // and a client connection id for KILL CONNECTION.
+        match sessions.get_session(id) {
             Some(kill_session) if self.plan.kill_connection => {
                 kill_session.force_kill_session();
-                let schema = Arc::new(DataSchema::empty());
-                Ok(Box::pin(DataBlockStream::create(schema, None, vec![])))
             }
             Some(kill_session) => {
                 kill_session.force_kill_query();
-                let schema = Arc::new(DataSchema::empty());
-                Ok(Box::pin(DataBlockStream::create(schema, None, vec![])))
+            }
+            None if self.plan.kill_connection => {
+                let conn_id = id.parse::<u32>().map_err(|_| {
+                    ErrorCode::UnknownSession(format!("Not found session id {}", id))
+                })?;
+                sessions.kill_connection(conn_id)?;
+            }
+            None => {
+                sessions.kill_query(id)?;
             }
         }
+
+        let schema = Arc::new(DataSchema::empty());
+        Ok(Box::pin(DataBlockStream::create(schema, None, vec![])))
     }
 }
@@ -60,9 +60,15 @@ impl Interpreter for SelectInterpreter {
     async fn execute(&self) -> Result<SendableDataBlockStream> {
         // TODO: maybe panic?
         let mut scheduled = Scheduled::new();
+        let mut standby = Scheduled::new();
         let timeout = self.ctx.get_settings().get_flight_client_timeout()?;
-        match self.schedule_query(&mut scheduled).await {
-            Ok(stream) => Ok(ScheduledStream::create(scheduled, stream, self.ctx.clone())),
+        match self.schedule_query(&mut scheduled, &mut standby).await {
+            Ok(stream) => Ok(ScheduledStream::create(
+                scheduled,
+                standby,
+                stream,
+                self.ctx.clone(),
+            )),
             Err(error) => {
                 Self::error_handler(scheduled, &self.ctx, timeout).await;
                 Err(error)
@@ -78,7 +84,13 @@ impl Interpreter for SelectInterpreter {
 type Scheduled = HashMap<String, Arc<NodeInfo>>;
 
 impl SelectInterpreter {
-    async fn schedule_query(&self, scheduled: &mut Scheduled) -> Result<SendableDataBlockStream> {
+    /// Sends the actions to the cluster nodes, and collects the nodes that are given standby actions
+    /// into `standby` besides `scheduled`.
+    async fn schedule_query(
+        &self,
+        scheduled: &mut Scheduled,
+        standby: &mut Scheduled,
+    ) -> Result<SendableDataBlockStream> {
         let optimized_plan = Optimizers::create(self.ctx.clone()).optimize(&self.select.input)?;
 
         let scheduler = PlanScheduler::try_create(self.ctx.clone())?;
@@ -94,6 +106,9 @@ impl SelectInterpreter {
 
             executing_action.await?;
             scheduled.insert(node.id.clone(), node.clone());
+            if scheduled_tasks.has_standby(&node.id) {
+                standby.insert(node.id.clone(), node.clone());
+            }
         }
 
         let pipeline_builder = PipelineBuilder::create(self.ctx.clone());
@@ -137,6 +152,7 @@ impl SelectInterpreter {
 
 struct ScheduledStream {
     scheduled: Scheduled,
+    standby: Scheduled,
     is_success: AtomicBool,
     context: DatabendQueryContextRef,
     inner: SendableDataBlockStream,
@@ -145,19 +161,20 @@ struct ScheduledStream {
 impl ScheduledStream {
     pub fn create(
         scheduled: Scheduled,
+        standby: Scheduled,
         inner: SendableDataBlockStream,
         context: DatabendQueryContextRef,
     ) -> SendableDataBlockStream {
         Box::pin(ScheduledStream {
             inner,
             scheduled,
+            standby,
             context,
             is_success: AtomicBool::new(false),
         })
     }
 
-    fn cancel_scheduled_action(&self) -> Result<()> {
-        let scheduled = self.scheduled.clone();
+    fn cancel_scheduled_action(&self, scheduled: Scheduled) -> Result<()> {
         let timeout = self.context.get_settings().get_flight_client_timeout()?;
         let error_handler = SelectInterpreter::error_handler(scheduled, &self.context, timeout);
         futures::executor::block_on(error_handler);
@@ -167,8 +184,14 @@ impl ScheduledStream {
 
 impl Drop for ScheduledStream {
     fn drop(&mut self) {
-        if !self.is_success.load(Ordering::Relaxed) {
-            if let Err(cause) = self.cancel_scheduled_action() {
+        // The standby stages that are not fetched never run, cancel them even if the query succeeds.
+        let scheduled = match self.is_success.load(Ordering::Relaxed) {
+            true => self.standby.clone(),
+            false => self.scheduled.clone(),
+        };
+
+        if !scheduled.is_empty() {
+            if let Err(cause) = self.cancel_scheduled_action(scheduled) {
                 log::error!("Cannot cancel action, cause: {:?}", cause);
             }
         }
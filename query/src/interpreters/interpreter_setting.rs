@@ -49,6 +49,9 @@ impl Interpreter for SettingInterpreter {
             match var.variable.to_lowercase().as_str() {
                 // To be compatible with some drivers
                 "sql_mode" | "autocommit" => {}
+                _ if plan.persistent => {
+                    self.ctx.set_persistent_setting(&var.variable, var.value)?;
+                }
                 "max_threads" => {
                     let threads: u64 = var.value.parse()?;
                     self.ctx.get_settings().set_max_threads(threads)?;
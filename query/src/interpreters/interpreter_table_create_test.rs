@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::env;
+
 use common_base::tokio;
 use common_datavalues::DataType;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_planners::*;
 use futures::stream::StreamExt;
@@ -46,3 +49,46 @@ async fn test_create_table_interpreter() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_create_table_over_existing_data() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let location = env::current_dir()?
+        .join("../tests/data/alltypes_plain.parquet")
+        .display()
+        .to_string();
+
+    // The declared schema matches the data.
+    {
+        let query = format!(
+            "create table default.p1(id int, bigint_col bigint, string_col varchar(255)) Engine = Parquet location = '{}'",
+            location
+        );
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(&query)?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        let mut stream = executor.execute().await?;
+        while let Some(_block) = stream.next().await {}
+    }
+
+    // string_col is not an integer in the data.
+    {
+        let query = format!(
+            "create table default.p2(id int, string_col bigint) Engine = Parquet location = '{}'",
+            location
+        );
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(&query)?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        let res = executor.execute().await;
+        let err = res.err().unwrap();
+        assert_eq!(err.code(), ErrorCode::SchemaMismatch("").code());
+        assert!(
+            err.message().contains("Column string_col"),
+            "{}",
+            err.message()
+        );
+
+        assert!(ctx.get_table("default", "p2").is_err());
+    }
+
+    Ok(())
+}
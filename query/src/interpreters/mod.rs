@@ -21,6 +21,8 @@ mod interpreter_describe_table_test;
 #[cfg(test)]
 mod interpreter_explain_test;
 #[cfg(test)]
+mod interpreter_factory_test;
+#[cfg(test)]
 mod interpreter_select_test;
 #[cfg(test)]
 mod interpreter_setting_test;
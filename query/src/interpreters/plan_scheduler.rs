@@ -14,6 +14,7 @@
 
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::sync::Arc;
 
@@ -61,6 +62,7 @@ pub struct Tasks {
     plan: PlanNode,
     context: DatabendQueryContextRef,
     actions: HashMap<String, VecDeque<FlightAction>>,
+    standby_nodes: HashSet<String>,
 }
 
 pub struct PlanScheduler {
@@ -124,6 +126,7 @@ impl Tasks {
         Tasks {
             context,
             actions: HashMap::new(),
+            standby_nodes: HashSet::new(),
             plan: PlanNode::Empty(EmptyPlan::create()),
         }
     }
@@ -152,6 +155,17 @@ impl Tasks {
         Ok(tasks)
     }
 
+    /// Whether the node is given standby actions, which have to be cancelled even if the query succeeds.
+    pub fn has_standby(&self, node_name: &str) -> bool {
+        self.standby_nodes.contains(node_name)
+    }
+
+    #[allow(clippy::ptr_arg)]
+    pub fn add_standby_task(&mut self, node_name: &String, action: FlightAction) {
+        self.standby_nodes.insert(node_name.to_string());
+        self.add_task(node_name, action);
+    }
+
     #[allow(clippy::ptr_arg)]
     pub fn add_task(&mut self, node_name: &String, action: FlightAction) {
         match self.actions.entry(node_name.to_string()) {
@@ -166,6 +180,38 @@ impl Tasks {
 }
 
 impl PlanScheduler {
+    /// Prepares the action of a stage produced by the local node on the next node of the cluster
+    /// as well, so that the consumers can fetch the stream from it if the local node is unreachable.
+    /// The standby action only runs once a stream of the stage is fetched from it, and is cancelled
+    /// when the query ends otherwise.
+    ///
+    /// Stages produced by every node read partitions that are assigned to that node only, and
+    /// plans that read remote streams cannot be performed twice, so these have no replicas.
+    fn standby_replicas(
+        &self,
+        tasks: &mut Tasks,
+        action: &FlightAction,
+    ) -> HashMap<String, Vec<String>> {
+        let mut replicas = HashMap::new();
+        if self.cluster_nodes.len() > 1 && !Self::reads_remote(&action.get_plan()) {
+            let node_name = &self.cluster_nodes[self.local_pos];
+            let replica_pos = (self.local_pos + 1) % self.cluster_nodes.len();
+            let replica_name = &self.cluster_nodes[replica_pos];
+
+            tasks.add_standby_task(replica_name, action.clone());
+            replicas.insert(node_name.clone(), vec![replica_name.clone()]);
+        }
+
+        replicas
+    }
+
+    fn reads_remote(plan: &PlanNode) -> bool {
+        match plan {
+            PlanNode::Remote(_) => true,
+            _ => plan.inputs().iter().any(|input| Self::reads_remote(input)),
+        }
+    }
+
     fn normal_action(&self, stage: &StagePlan, input: &PlanNode) -> ShuffleAction {
         ShuffleAction {
             stage_id: self.stage_id.clone(),
@@ -173,6 +219,7 @@ impl PlanScheduler {
             plan: input.clone(),
             sinks: self.cluster_nodes.clone(),
             scatters_expression: stage.scatters_expr.clone(),
+            max_threads_hint: self.query_context.get_max_threads_hint(),
         }
     }
 
@@ -183,6 +230,7 @@ impl PlanScheduler {
             stage_id: action.stage_id.clone(),
             stream_id: node_name.to_string(),
             fetch_nodes: self.cluster_nodes.clone(),
+            fetch_node_replicas: HashMap::new(),
         }
     }
 
@@ -213,16 +261,23 @@ impl PlanScheduler {
             plan: input.clone(),
             sinks: self.cluster_nodes.clone(),
             scatters_expression: stage.scatters_expr.clone(),
+            max_threads_hint: self.query_context.get_max_threads_hint(),
         }
     }
 
-    fn expansive_remote_plan(&self, node_name: &str, action: &ShuffleAction) -> PlanNode {
+    fn expansive_remote_plan(
+        &self,
+        node_name: &str,
+        action: &ShuffleAction,
+        replicas: &HashMap<String, Vec<String>>,
+    ) -> PlanNode {
         PlanNode::Remote(RemotePlan {
             schema: action.plan.schema(),
             query_id: action.query_id.clone(),
             stage_id: action.stage_id.clone(),
             stream_id: node_name.to_string(),
             fetch_nodes: vec![self.cluster_nodes[self.local_pos].clone()],
+            fetch_node_replicas: replicas.clone(),
         })
     }
 
@@ -236,14 +291,14 @@ impl PlanScheduler {
         self.running_mode = RunningMode::Cluster;
         let node_name = &self.cluster_nodes[self.local_pos];
         let shuffle_action = self.expansive_action(stage, &self.nodes_plan[self.local_pos]);
-        tasks.add_task(
-            node_name,
-            FlightAction::PrepareShuffleAction(shuffle_action.clone()),
-        );
+        let flight_action = FlightAction::PrepareShuffleAction(shuffle_action.clone());
+        let replicas = self.standby_replicas(tasks, &flight_action);
+        tasks.add_task(node_name, flight_action);
 
         for index in 0..self.nodes_plan.len() {
             let node_name = &self.cluster_nodes[index];
-            self.nodes_plan[index] = self.expansive_remote_plan(node_name, &shuffle_action);
+            self.nodes_plan[index] =
+                self.expansive_remote_plan(node_name, &shuffle_action, &replicas);
         }
 
         Ok(())
@@ -256,6 +311,7 @@ impl PlanScheduler {
             plan: input.clone(),
             sinks: vec![self.cluster_nodes[self.local_pos].clone()],
             scatters_expression: stage.scatters_expr.clone(),
+            max_threads_hint: self.query_context.get_max_threads_hint(),
         }
     }
 
@@ -266,6 +322,7 @@ impl PlanScheduler {
             query_id: self.query_context.get_id(),
             stream_id: node_name.to_string(),
             fetch_nodes: self.cluster_nodes.clone(),
+            fetch_node_replicas: HashMap::new(),
         }
     }
 
@@ -436,6 +493,7 @@ impl PlanScheduler {
             query_id: self.query_context.get_id(),
             plan: input.clone(),
             sinks: self.cluster_nodes.clone(),
+            max_threads_hint: self.query_context.get_max_threads_hint(),
         }
     }
 
@@ -446,6 +504,7 @@ impl PlanScheduler {
             stage_id: action.stage_id.clone(),
             stream_id: node_name.to_string(),
             fetch_nodes: self.cluster_nodes.clone(),
+            fetch_node_replicas: HashMap::new(),
         }
     }
 
@@ -453,7 +512,9 @@ impl PlanScheduler {
         self.running_mode = RunningMode::Cluster;
         let node_name = &self.cluster_nodes[self.local_pos];
         let action = self.broadcast_action(&self.nodes_plan[self.local_pos]);
-        tasks.add_task(node_name, FlightAction::BroadcastAction(action.clone()));
+        let flight_action = FlightAction::BroadcastAction(action.clone());
+        let replicas = self.standby_replicas(tasks, &flight_action);
+        tasks.add_task(node_name, flight_action);
 
         for index in 0..self.nodes_plan.len() {
             let node_name = &self.cluster_nodes[index];
@@ -463,6 +524,7 @@ impl PlanScheduler {
                 stage_id: action.stage_id.clone(),
                 stream_id: node_name.to_string(),
                 fetch_nodes: vec![self.cluster_nodes[self.local_pos].clone()],
+                fetch_node_replicas: replicas.clone(),
             });
         }
     }
@@ -155,7 +155,7 @@ async fn test_scheduler_plan_with_convergent_and_expansive_stage() -> Result<()>
             FlightAction::PrepareShuffleAction(action) => remote_actions.push((node, action)),
         }
     }
-    assert_eq!(remote_actions.len(), 3);
+    assert_eq!(remote_actions.len(), 4);
     assert_eq!(remote_actions[0].0.id, String::from("dummy_local"));
     assert_eq!(remote_actions[0].1.sinks, vec![
         String::from("dummy_local"),
@@ -180,17 +180,23 @@ async fn test_scheduler_plan_with_convergent_and_expansive_stage() -> Result<()>
         Expression::create_literal(DataValue::UInt64(Some(0)))
     );
 
+    // The expansive stage stands by on the other node
     assert_eq!(remote_actions[2].0.id, String::from("dummy"));
-    assert_eq!(remote_actions[2].1.sinks, vec![String::from("dummy_local")]);
+    assert_eq!(remote_actions[2].1.stage_id, remote_actions[0].1.stage_id);
+    assert_eq!(remote_actions[2].1.plan, remote_actions[0].1.plan);
+    assert_eq!(remote_actions[2].1.sinks, remote_actions[0].1.sinks);
+
+    assert_eq!(remote_actions[3].0.id, String::from("dummy"));
+    assert_eq!(remote_actions[3].1.sinks, vec![String::from("dummy_local")]);
     assert_eq!(
-        remote_actions[2].1.scatters_expression,
+        remote_actions[3].1.scatters_expression,
         Expression::create_literal(DataValue::UInt64(Some(0)))
     );
 
     // Perform the same plan in different nodes
     match (
         &remote_actions[1].1.plan,
-        &remote_actions[2].1.plan,
+        &remote_actions[3].1.plan,
         &scheduled_tasks.get_local_task(),
     ) {
         (PlanNode::Select(left), PlanNode::Select(right), PlanNode::Select(finalize)) => {
@@ -200,9 +206,13 @@ async fn test_scheduler_plan_with_convergent_and_expansive_stage() -> Result<()>
                     assert_eq!(left.stream_id, "dummy_local");
                     assert_eq!(left.fetch_nodes, ["dummy_local"]);
                     assert_eq!(right.fetch_nodes, ["dummy_local"]);
+                    assert_eq!(left.fetch_node_replicas.get("dummy_local"), Some(&vec![String::from("dummy")]));
+                    assert_eq!(right.fetch_node_replicas.get("dummy_local"), Some(&vec![String::from("dummy")]));
 
+                    // The converged streams come from the partitions of each node
                     assert_eq!(finalize.stream_id, "dummy_local");
                     assert_eq!(finalize.fetch_nodes, ["dummy_local", "dummy"]);
+                    assert!(finalize.fetch_node_replicas.is_empty());
                 },
                 _ => assert!(false, "test_scheduler_plan_with_convergent_and_expansive_stage must be have Remote plan!"),
             }
@@ -329,6 +339,84 @@ async fn test_scheduler_plan_with_convergent_and_normal_stage() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_scheduler_plan_with_local_broadcast_replicas() -> Result<()> {
+    let context = create_env().await?;
+    let scheduler = PlanScheduler::try_create(context)?;
+    let scheduled_tasks = scheduler.reschedule(&PlanNode::Stage(StagePlan {
+        kind: StageKind::Convergent,
+        scatters_expr: Expression::create_literal(DataValue::UInt64(Some(0))),
+        input: Arc::new(PlanNode::Broadcast(BroadcastPlan {
+            input: Arc::new(PlanNode::Empty(EmptyPlan::create())),
+        })),
+    }))?;
+
+    let mut broadcast_actions = vec![];
+    for (node, remote_action) in scheduled_tasks.get_tasks()? {
+        if let FlightAction::BroadcastAction(action) = remote_action {
+            broadcast_actions.push((node, action));
+        }
+    }
+
+    assert_eq!(broadcast_actions.len(), 2);
+    assert_eq!(broadcast_actions[0].0.id, String::from("dummy_local"));
+    assert_eq!(broadcast_actions[1].0.id, String::from("dummy"));
+    assert_eq!(
+        broadcast_actions[0].1.stage_id,
+        broadcast_actions[1].1.stage_id
+    );
+    assert_eq!(broadcast_actions[0].1.plan, broadcast_actions[1].1.plan);
+
+    // Both nodes fetch the broadcast stream from the local node, and fall back to the other node
+    let mut shuffle_actions = vec![];
+    for (_, remote_action) in scheduled_tasks.get_tasks()? {
+        if let FlightAction::PrepareShuffleAction(action) = remote_action {
+            shuffle_actions.push(action);
+        }
+    }
+
+    assert_eq!(shuffle_actions.len(), 2);
+    for shuffle_action in &shuffle_actions {
+        match &shuffle_action.plan {
+            PlanNode::Remote(plan) => {
+                assert_eq!(plan.fetch_nodes, ["dummy_local"]);
+                assert_eq!(
+                    plan.fetch_node_replicas.get("dummy_local"),
+                    Some(&vec![String::from("dummy")])
+                );
+            }
+            _ => assert!(
+                false,
+                "test_scheduler_plan_with_local_broadcast_replicas must be have Remote plan!"
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_scheduler_plan_with_max_threads_hint() -> Result<()> {
+    let context = create_env().await?;
+    context.set_max_threads_hint(Some(2))?;
+
+    let scheduler = PlanScheduler::try_create(context)?;
+    let scheduled_tasks = scheduler.reschedule(&PlanNode::Stage(StagePlan {
+        kind: StageKind::Convergent,
+        scatters_expr: Expression::create_literal(DataValue::UInt64(Some(0))),
+        input: Arc::new(PlanNode::Empty(EmptyPlan::cluster())),
+    }))?;
+
+    // The plans run by the other nodes are sized by the hint of the query too.
+    let remote_actions = scheduled_tasks.get_tasks()?;
+    assert_eq!(remote_actions.len(), 2);
+    for (_, remote_action) in remote_actions {
+        assert_eq!(Some(2), remote_action.get_max_threads_hint());
+    }
+
+    Ok(())
+}
+
 async fn create_env() -> Result<DatabendQueryContextRef> {
     try_create_cluster_context(
         ClusterDescriptor::new()
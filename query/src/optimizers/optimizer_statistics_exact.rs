@@ -89,10 +89,7 @@ impl PlanRewriter for StatisticsExactImpl<'_> {
                                                 .read_plan(
                                                     Arc::new(io_ctx),
                                                     Some(dummy_scan_plan.push_downs.clone()),
-                                                    Some(
-                                                        self.ctx.get_settings().get_max_threads()?
-                                                            as usize,
-                                                    ),
+                                                    Some(self.ctx.get_max_threads()? as usize),
                                                 )
                                                 .map(PlanNode::ReadSource)
                                         }
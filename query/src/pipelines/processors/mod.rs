@@ -19,8 +19,14 @@ mod pipeline_builder_test;
 #[cfg(test)]
 mod pipeline_display_test;
 #[cfg(test)]
+mod pipeline_test;
+#[cfg(test)]
 mod pipeline_walker_test;
 #[cfg(test)]
+mod processor_channel_test;
+#[cfg(test)]
+mod processor_concat_test;
+#[cfg(test)]
 mod processor_empty_test;
 #[cfg(test)]
 mod processor_merge_test;
@@ -33,6 +39,8 @@ mod pipeline_builder;
 mod pipeline_display;
 mod pipeline_walker;
 mod processor;
+mod processor_channel;
+mod processor_concat;
 mod processor_empty;
 mod processor_merge;
 mod processor_mixed;
@@ -42,6 +50,11 @@ pub use pipeline::Pipeline;
 pub use pipeline_builder::PipelineBuilder;
 pub use processor::FormatterSettings;
 pub use processor::Processor;
+pub use processor_channel::block_channel;
+pub use processor_channel::BackpressureMode;
+pub use processor_channel::BlockReceiver;
+pub use processor_channel::BlockSender;
+pub use processor_concat::ConcatProcessor;
 pub use processor_empty::EmptyProcessor;
 pub use processor_merge::MergeProcessor;
 pub use processor_mixed::MixedProcessor;
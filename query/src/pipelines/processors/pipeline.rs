@@ -13,25 +13,42 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
+use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::Expression;
 use common_streams::SendableDataBlockStream;
+use common_streams::TimeoutStream;
 
 use super::MixedProcessor;
+use crate::pipelines::processors::ConcatProcessor;
 use crate::pipelines::processors::MergeProcessor;
 use crate::pipelines::processors::Pipe;
 use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::SortRangePartitionTransform;
 use crate::sessions::DatabendQueryContextRef;
 
 pub struct Pipeline {
     ctx: DatabendQueryContextRef,
     pipes: Vec<Pipe>,
+    max_execution_time: Option<Duration>,
 }
 
 impl Pipeline {
     pub fn create(ctx: DatabendQueryContextRef) -> Self {
-        Pipeline { ctx, pipes: vec![] }
+        Pipeline {
+            ctx,
+            pipes: vec![],
+            max_execution_time: None,
+        }
+    }
+
+    /// Abort the query if its execution takes longer than `max_execution_time`,
+    /// the timer starts when the pipeline is executed.
+    pub fn set_max_execution_time(&mut self, max_execution_time: Option<Duration>) {
+        self.max_execution_time = max_execution_time;
     }
 
     /// Reset the pipeline.
@@ -118,6 +135,56 @@ impl Pipeline {
         Ok(())
     }
 
+    /// Merge every `fan_in` processors into one-way, 0 means merge all of them.
+    ///
+    /// processor1 --
+    ///               \
+    /// processor2      --> processor1
+    ///
+    /// processor3 --
+    ///               \
+    /// processor4      --> processor2
+    ///
+    pub fn merge_processor_with_fan_in(&mut self, fan_in: usize) -> Result<()> {
+        let last_pipe = self.last_pipe()?;
+        if fan_in == 0 || fan_in >= last_pipe.nums() {
+            return self.merge_processor();
+        }
+
+        let mut new_pipe = Pipe::create();
+        for group in last_pipe.processors().chunks(fan_in) {
+            let mut merge = MergeProcessor::create(self.ctx.clone());
+            for x in group {
+                merge.connect_to(x.clone())?;
+            }
+            new_pipe.add(Arc::from(merge));
+        }
+        self.pipes.push(new_pipe);
+        Ok(())
+    }
+
+    /// Concatenate many(or one)-ways processors into one-way, in the order of the processors.
+    ///
+    /// processor1 --
+    ///               \
+    /// processor2      --> processor: [processor1 ...] [processor2 ...] [processor3 ...]
+    ///               /
+    /// processor3 --
+    ///
+    pub fn concat_processor(&mut self) -> Result<()> {
+        let last_pipe = self.last_pipe()?;
+        if last_pipe.nums() > 1 {
+            let mut concat = ConcatProcessor::create(self.ctx.clone());
+            for x in last_pipe.processors() {
+                concat.connect_to(x.clone())?;
+            }
+            let mut new_pipe = Pipe::create();
+            new_pipe.add(Arc::from(concat));
+            self.pipes.push(new_pipe);
+        }
+        Ok(())
+    }
+
     /// Mixed M processors into N processes.
     ///
     /// processor1 --          processor1
@@ -153,10 +220,56 @@ impl Pipeline {
         Ok(())
     }
 
+    /// Split M sorted processors into N processors of disjoint key ranges, in the sort order.
+    ///
+    /// processor1 --          processor1: [range1 ...]
+    ///               \      /
+    /// processor2      -->   processor2: [range2 ...]
+    ///               /      \
+    /// processor3 --          processor3: [range3 ...]
+    ///
+    pub fn range_partition_processor(
+        &mut self,
+        schema: DataSchemaRef,
+        exprs: Vec<Expression>,
+        n: usize,
+    ) -> Result<()> {
+        let last_pipe = self.last_pipe()?;
+        let mut processor = SortRangePartitionTransform::create(self.ctx.clone(), schema, exprs, n);
+        for x in last_pipe.processors() {
+            processor.connect_to(x)?;
+        }
+
+        // The ranges are concatenated in the order of the pipe.
+        let mut new_pipe = Pipe::create();
+        let shares = (1..n)
+            .map(|_| processor.share())
+            .collect::<Result<Vec<_>>>()?;
+        new_pipe.add(Arc::from(processor));
+        for share in shares {
+            new_pipe.add(Arc::from(share));
+        }
+        self.pipes.push(new_pipe);
+
+        Ok(())
+    }
+
     pub async fn execute(&mut self) -> Result<SendableDataBlockStream> {
         if self.last_pipe()?.nums() > 1 {
             self.merge_processor()?;
         }
-        self.last_pipe()?.first().execute().await
+
+        let stream = self.last_pipe()?.first().execute().await?;
+        Ok(match self.max_execution_time {
+            None => stream,
+            Some(max_execution_time) => {
+                let ctx = self.ctx.clone();
+                Box::pin(TimeoutStream::create(
+                    stream,
+                    max_execution_time,
+                    Box::new(move || ctx.kill()),
+                ))
+            }
+        })
     }
 }
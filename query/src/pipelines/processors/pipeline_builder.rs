@@ -11,16 +11,28 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::Duration;
 
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::col;
+use common_planners::lit;
 use common_planners::AggregatorFinalPlan;
 use common_planners::AggregatorPartialPlan;
 use common_planners::BroadcastPlan;
+use common_planners::CrossJoinPlan;
+use common_planners::DeletePlan;
+use common_planners::Expression;
 use common_planners::ExpressionPlan;
+use common_planners::Extras;
 use common_planners::FilterPlan;
 use common_planners::HavingPlan;
+use common_planners::JoinPlan;
 use common_planners::LimitByPlan;
 use common_planners::LimitPlan;
 use common_planners::PlanNode;
@@ -31,48 +43,134 @@ use common_planners::SelectPlan;
 use common_planners::SortPlan;
 use common_planners::StagePlan;
 use common_planners::SubQueriesSetPlan;
+use common_planners::UpdatePlan;
+use common_planners::WindowPlan;
 use common_tracing::tracing;
 
 use crate::api::FlightTicket;
+use crate::catalogs::TablePtr;
+use crate::datasources::table::memory::memory_table::MemoryTable;
 use crate::pipelines::processors::Pipeline;
+use crate::pipelines::transforms::can_range_partition;
 use crate::pipelines::transforms::AggregatorFinalTransform;
 use crate::pipelines::transforms::AggregatorPartialTransform;
+use crate::pipelines::transforms::ColumnProjectionTransform;
 use crate::pipelines::transforms::CreateSetsTransform;
+use crate::pipelines::transforms::CrossJoinTransform;
 use crate::pipelines::transforms::ExpressionTransform;
 use crate::pipelines::transforms::GroupByFinalTransform;
 use crate::pipelines::transforms::GroupByPartialTransform;
+use crate::pipelines::transforms::HashJoinTransform;
 use crate::pipelines::transforms::HavingTransform;
+use crate::pipelines::transforms::JoinAlgorithm;
 use crate::pipelines::transforms::LimitByTransform;
 use crate::pipelines::transforms::LimitTransform;
+use crate::pipelines::transforms::MemorySourceTransform;
 use crate::pipelines::transforms::ProjectionTransform;
+use crate::pipelines::transforms::ReblockTransform;
 use crate::pipelines::transforms::RemoteTransform;
 use crate::pipelines::transforms::SortMergeTransform;
 use crate::pipelines::transforms::SortPartialTransform;
 use crate::pipelines::transforms::SourceTransform;
+use crate::pipelines::transforms::SpillOptions;
 use crate::pipelines::transforms::SubQueriesPuller;
+use crate::pipelines::transforms::TableRewriteTransform;
 use crate::pipelines::transforms::WhereTransform;
+use crate::pipelines::transforms::WindowTransform;
+use crate::sessions::DatabendQueryContext;
 use crate::sessions::DatabendQueryContextRef;
 
 pub struct PipelineBuilder {
     ctx: DatabendQueryContextRef,
 
     limit: Option<usize>,
+
+    // The names of the plan nodes from the root to the visiting one, for error messages.
+    plan_path: Vec<String>,
 }
 
 impl PipelineBuilder {
     pub fn create(ctx: DatabendQueryContextRef) -> PipelineBuilder {
-        PipelineBuilder { ctx, limit: None }
+        PipelineBuilder {
+            ctx,
+            limit: None,
+            plan_path: vec![],
+        }
+    }
+
+    /// The names of the plan nodes which can be built into a pipeline.
+    pub fn supported_nodes() -> &'static [&'static str] {
+        &[
+            "SelectPlan",
+            "StagePlan",
+            "BroadcastPlan",
+            "RemotePlan",
+            "ExpressionPlan",
+            "ProjectionPlan",
+            "AggregatorPartialPlan",
+            "AggregatorFinalPlan",
+            "FilterPlan",
+            "HavingPlan",
+            "SortPlan",
+            "LimitPlan",
+            "LimitByPlan",
+            "WindowPlan",
+            "CrossJoinPlan",
+            "JoinPlan",
+            "ReadSourcePlan",
+            "CreateSubQueriesSets",
+            "DeletePlan",
+            "UpdatePlan",
+        ]
     }
 
     #[tracing::instrument(level = "info", skip(self))]
     pub fn build(mut self, node: &PlanNode) -> Result<Pipeline> {
+        let node = Self::normalize(node)?;
         tracing::debug!("Received plan:\n{:?}", node);
-        let pipeline = self.visit(node)?;
+        let mut pipeline = self.visit(&node)?;
+
+        let max_execution_time = self.ctx.get_settings().get_max_execution_time()?;
+        if max_execution_time > 0 {
+            pipeline.set_max_execution_time(Some(Duration::from_secs(max_execution_time)));
+        }
         tracing::debug!("Pipeline:\n{:?}", pipeline);
         Ok(pipeline)
     }
 
+    /// Normalize the plan before it is built, the result is the same.
+    ///
+    /// The Select nodes directly under a Select are removed, a Select only wraps its input,
+    /// so the plan logged and walked is not obscured by the nested queries.
+    pub fn normalize(node: &PlanNode) -> Result<PlanNode> {
+        if let PlanNode::Select(plan) = node {
+            if let PlanNode::Select(_) = plan.input.as_ref() {
+                return Self::normalize(&plan.input);
+            }
+        }
+
+        let inputs = node
+            .inputs()
+            .iter()
+            .map(|input| Self::normalize(input))
+            .collect::<Result<Vec<_>>>()?;
+        if inputs.is_empty() {
+            return Ok(node.clone());
+        }
+
+        let mut node = node.clone();
+        node.set_inputs(inputs.iter().collect())?;
+        Ok(node)
+    }
+
     fn visit(&mut self, node: &PlanNode) -> Result<Pipeline> {
+        self.plan_path.push(node.name().to_string());
+        let pipeline = self.visit_plan_node(node);
+        self.plan_path.pop();
+        pipeline
+    }
+
+    fn visit_plan_node(&mut self, node: &PlanNode) -> Result<Pipeline> {
         match node {
             PlanNode::Select(node) => self.visit_select(node),
             PlanNode::Stage(node) => self.visit_stage(node),
@@ -87,15 +185,188 @@ impl PipelineBuilder {
             PlanNode::Sort(node) => self.visit_sort(node),
             PlanNode::Limit(node) => self.visit_limit(node),
             PlanNode::LimitBy(node) => self.visit_limit_by(node),
+            PlanNode::Window(node) => self.visit_window(node),
+            PlanNode::CrossJoin(node) => self.visit_cross_join(node),
+            PlanNode::Join(node) => self.visit_join(node),
             PlanNode::ReadSource(node) => self.visit_read_data_source(node),
             PlanNode::SubQueryExpression(node) => self.visit_create_sets(node),
-            other => Result::Err(ErrorCode::UnknownPlan(format!(
-                "Build pipeline from the plan node unsupported:{:?}",
-                other.name()
-            ))),
+            PlanNode::Delete(node) => self.visit_delete(node),
+            PlanNode::Update(node) => self.visit_update(node),
+            // Statements are executed by their interpreters, they never appear inside a query.
+            PlanNode::Empty(_)
+            | PlanNode::Scan(_)
+            | PlanNode::Explain(_)
+            | PlanNode::CreateDatabase(_)
+            | PlanNode::DropDatabase(_)
+            | PlanNode::CreateTable(_)
+            | PlanNode::DescribeTable(_)
+            | PlanNode::DropTable(_)
+            | PlanNode::TruncateTable(_)
+            | PlanNode::UseDatabase(_)
+            | PlanNode::SetVariable(_)
+            | PlanNode::InsertInto(_)
+            | PlanNode::ShowCreateTable(_)
+            | PlanNode::Kill(_) => Result::Err(Self::unsupported_node_error(node, &self.plan_path)),
         }
     }
 
+    /// Check the plan can be built into a pipeline, without building it.
+    ///
+    /// Every node must be supported and every column its expressions reference must be
+    /// provided by its input. Nothing is bound to the context: partitions are not set,
+    /// sources are not created and the stages are not rescheduled, so the inputs of
+    /// Stage and Broadcast nodes are checked as they would be on the executing node.
+    pub fn validate(&self, node: &PlanNode) -> Result<()> {
+        let mut plan_path = vec![];
+        Self::validate_node(node, &mut plan_path)
+    }
+
+    fn validate_node(node: &PlanNode, plan_path: &mut Vec<String>) -> Result<()> {
+        plan_path.push(node.name().to_string());
+        let res = Self::validate_plan_node(node, plan_path);
+        plan_path.pop();
+        res
+    }
+
+    fn validate_plan_node(node: &PlanNode, plan_path: &mut Vec<String>) -> Result<()> {
+        let (input_schema, exprs) = match node {
+            PlanNode::Select(plan) => return Self::validate_node(&plan.input, plan_path),
+            PlanNode::Stage(plan) => return Self::validate_node(&plan.input, plan_path),
+            PlanNode::Broadcast(plan) => return Self::validate_node(&plan.input, plan_path),
+            PlanNode::Remote(_) | PlanNode::ReadSource(_) => return Ok(()),
+            PlanNode::Expression(plan) => (plan.input.schema(), plan.exprs.clone()),
+            PlanNode::Projection(plan) => (plan.input.schema(), plan.expr.clone()),
+            PlanNode::AggregatorPartial(plan) => {
+                let mut exprs = plan.aggr_expr.clone();
+                exprs.extend_from_slice(&plan.group_expr);
+                (plan.input.schema(), exprs)
+            }
+            PlanNode::AggregatorFinal(plan) => {
+                // The final aggregation evaluates on the schema before the partial one.
+                let mut exprs = plan.aggr_expr.clone();
+                exprs.extend_from_slice(&plan.group_expr);
+                (plan.schema_before_group_by.clone(), exprs)
+            }
+            PlanNode::Filter(plan) => (plan.input.schema(), vec![plan.predicate.clone()]),
+            PlanNode::Having(plan) => (plan.input.schema(), vec![plan.predicate.clone()]),
+            PlanNode::Sort(plan) => (plan.input.schema(), plan.order_by.clone()),
+            PlanNode::Limit(plan) => (plan.input.schema(), vec![]),
+            PlanNode::LimitBy(plan) => (plan.input.schema(), plan.limit_by.clone()),
+            PlanNode::Window(plan) => (plan.input.schema(), plan.sort_exprs()),
+            PlanNode::CrossJoin(plan) => (plan.schema(), vec![]),
+            PlanNode::Join(plan) => {
+                // The keys of each side are evaluated on its own input.
+                Self::validate_node(&plan.probe, plan_path)?;
+                Self::validate_node(&plan.build, plan_path)?;
+                Self::validate_exprs(node, &plan.probe_keys, &plan.probe.schema(), plan_path)?;
+                return Self::validate_exprs(
+                    node,
+                    &plan.build_keys,
+                    &plan.build.schema(),
+                    plan_path,
+                );
+            }
+            PlanNode::SubQueryExpression(plan) => {
+                for expr in &plan.expressions {
+                    match expr {
+                        Expression::Subquery { query_plan, .. }
+                        | Expression::ScalarSubquery { query_plan, .. } => {
+                            Self::validate_node(query_plan, plan_path)?
+                        }
+                        _ => {}
+                    }
+                }
+                (plan.input.schema(), vec![])
+            }
+            PlanNode::Delete(plan) => (plan.input.schema(), vec![plan.predicate.clone()]),
+            PlanNode::Update(plan) => {
+                let mut exprs = vec![plan.predicate.clone()];
+                exprs.extend(plan.assignments.iter().map(|(_, value)| value.clone()));
+                (plan.input.schema(), exprs)
+            }
+            PlanNode::Empty(_)
+            | PlanNode::Scan(_)
+            | PlanNode::Explain(_)
+            | PlanNode::CreateDatabase(_)
+            | PlanNode::DropDatabase(_)
+            | PlanNode::CreateTable(_)
+            | PlanNode::DescribeTable(_)
+            | PlanNode::DropTable(_)
+            | PlanNode::TruncateTable(_)
+            | PlanNode::UseDatabase(_)
+            | PlanNode::SetVariable(_)
+            | PlanNode::InsertInto(_)
+            | PlanNode::ShowCreateTable(_)
+            | PlanNode::Kill(_) => {
+                return Result::Err(Self::unsupported_node_error(node, plan_path));
+            }
+        };
+
+        // Inputs first, the same order as the errors of build.
+        for input in node.inputs() {
+            Self::validate_node(&input, plan_path)?;
+        }
+        Self::validate_exprs(node, &exprs, &input_schema, plan_path)
+    }
+
+    fn validate_exprs(
+        node: &PlanNode,
+        exprs: &[Expression],
+        input_schema: &DataSchemaRef,
+        plan_path: &[String],
+    ) -> Result<()> {
+        for expr in exprs {
+            if let Some(column) = Self::find_missing_column(expr, input_schema) {
+                return Result::Err(ErrorCode::LogicalError(format!(
+                    "Column `{}` required by {} is not in its input schema [{}] (plan path: {})",
+                    column,
+                    node.name(),
+                    input_schema
+                        .fields()
+                        .iter()
+                        .map(|field| field.name().as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    plan_path.join(" -> ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // An expression already computed by the input is looked up by its name.
+    fn find_missing_column(expr: &Expression, schema: &DataSchemaRef) -> Option<String> {
+        if schema.field_with_name(&expr.column_name()).is_ok() {
+            return None;
+        }
+
+        match expr {
+            Expression::Column(name) => Some(name.clone()),
+            Expression::Alias(_, expr)
+            | Expression::UnaryExpression { expr, .. }
+            | Expression::Sort { expr, .. }
+            | Expression::Cast { expr, .. } => Self::find_missing_column(expr, schema),
+            Expression::BinaryExpression { left, right, .. } => {
+                Self::find_missing_column(left, schema)
+                    .or_else(|| Self::find_missing_column(right, schema))
+            }
+            Expression::ScalarFunction { args, .. }
+            | Expression::AggregateFunction { args, .. } => args
+                .iter()
+                .find_map(|arg| Self::find_missing_column(arg, schema)),
+            _ => None,
+        }
+    }
+
+    /// The error of a statement node inside a query, which can not be built into a pipeline.
+    fn unsupported_node_error(node: &PlanNode, plan_path: &[String]) -> ErrorCode {
+        ErrorCode::UnknownPlan(format!(
+            "{} can not be built into an execution pipeline, the plan is malformed (plan path: {})",
+            node.name(),
+            plan_path.join(" -> ")
+        ))
+    }
+
     fn visit_select(&mut self, node: &SelectPlan) -> Result<Pipeline> {
         self.visit(&*node.input)
     }
@@ -119,12 +390,21 @@ impl PipelineBuilder {
             let flight_ticket =
                 FlightTicket::stream(&plan.query_id, &plan.stage_id, &plan.stream_id);
 
-            pipeline.add_source(Arc::new(RemoteTransform::try_create(
-                flight_ticket,
-                self.ctx.clone(),
-                /* fetch_node_name */ fetch_node.clone(),
-                /* fetch_stream_schema */ plan.schema.clone(),
-            )?))?;
+            let replicas = plan
+                .fetch_node_replicas
+                .get(fetch_node)
+                .cloned()
+                .unwrap_or_default();
+
+            pipeline.add_source(Arc::new(
+                RemoteTransform::try_create(
+                    flight_ticket,
+                    self.ctx.clone(),
+                    /* fetch_node_name */ fetch_node.clone(),
+                    /* fetch_stream_schema */ plan.schema.clone(),
+                )?
+                .with_replicas(replicas),
+            ))?;
         }
 
         Ok(pipeline)
@@ -144,12 +424,30 @@ impl PipelineBuilder {
 
     fn visit_projection(&mut self, node: &ProjectionPlan) -> Result<Pipeline> {
         let mut pipeline = self.visit(&*node.input)?;
+        let collect_min_max = self.ctx.get_settings().get_collect_block_min_max()? == 1;
+
+        // Selecting or renaming the columns doesn't need the expression executor.
+        if let Some(names) =
+            ColumnProjectionTransform::input_names(&node.input.schema(), &node.expr)
+        {
+            pipeline.add_simple_transform(|| {
+                Ok(Box::new(
+                    ColumnProjectionTransform::create(node.schema(), names.clone())
+                        .with_collect_min_max(collect_min_max),
+                ))
+            })?;
+            return Ok(pipeline);
+        }
+
         pipeline.add_simple_transform(|| {
-            Ok(Box::new(ProjectionTransform::try_create(
-                node.input.schema(),
-                node.schema(),
-                node.expr.clone(),
-            )?))
+            Ok(Box::new(
+                ProjectionTransform::try_create(
+                    node.input.schema(),
+                    node.schema(),
+                    node.expr.clone(),
+                )?
+                .with_collect_min_max(collect_min_max),
+            ))
         })?;
         Ok(pipeline)
     }
@@ -192,22 +490,41 @@ impl PipelineBuilder {
             })?;
         } else {
             let max_block_size = self.ctx.get_settings().get_max_block_size()? as usize;
+            let max_bytes = self
+                .ctx
+                .get_settings()
+                .get_max_bytes_before_external_group_by()?;
+            let spill = self.spill_options(max_bytes as usize);
             pipeline.add_simple_transform(|| {
-                Ok(Box::new(GroupByFinalTransform::create(
-                    node.schema(),
-                    max_block_size,
-                    node.schema_before_group_by.clone(),
-                    node.aggr_expr.clone(),
-                    node.group_expr.clone(),
-                )))
+                Ok(Box::new(
+                    GroupByFinalTransform::create(
+                        node.schema(),
+                        max_block_size,
+                        node.schema_before_group_by.clone(),
+                        node.aggr_expr.clone(),
+                        node.group_expr.clone(),
+                    )
+                    .with_spill(spill.clone()),
+                ))
             })?;
-            pipeline.mixed_processor(self.ctx.get_settings().get_max_threads()? as usize)?;
+            pipeline.mixed_processor(self.ctx.get_max_threads()? as usize)?;
         }
         Ok(pipeline)
     }
 
     fn visit_filter(&mut self, node: &FilterPlan) -> Result<Pipeline> {
-        let mut pipeline = self.visit(&*node.input)?;
+        let mut pipeline = match &*node.input {
+            // Push the predicate down so that the sources can skip blocks early,
+            // the WhereTransform below is still required for correctness.
+            PlanNode::ReadSource(plan) => {
+                let mut plan = plan.clone();
+                let mut extras = plan.push_downs.take().unwrap_or_else(Extras::default);
+                extras.filters.push(node.predicate.clone());
+                plan.push_downs = Some(extras);
+                self.visit_read_data_source(&plan)?
+            }
+            input => self.visit(input)?,
+        };
         pipeline.add_simple_transform(|| {
             Ok(Box::new(WhereTransform::try_create(
                 node.schema(),
@@ -230,47 +547,339 @@ impl PipelineBuilder {
 
     fn visit_sort(&mut self, plan: &SortPlan) -> Result<Pipeline> {
         let mut pipeline = self.visit(&*plan.input)?;
+        let estimated_rows = estimated_read_rows(&plan.input);
+        self.add_sort_transforms(
+            &mut pipeline,
+            plan.schema(),
+            &plan.order_by,
+            self.limit,
+            estimated_rows,
+        )?;
+        Ok(pipeline)
+    }
+
+    fn visit_window(&mut self, plan: &WindowPlan) -> Result<Pipeline> {
+        let mut pipeline = self.visit(&*plan.input)?;
+
+        // The rows of one partition must be adjacent and ordered,
+        // the limit is applied after ranking so it can't be used to sort.
+        let input_schema = plan.input.schema();
+        let estimated_rows = estimated_read_rows(&plan.input);
+        self.add_sort_transforms(
+            &mut pipeline,
+            input_schema,
+            &plan.sort_exprs(),
+            None,
+            estimated_rows,
+        )?;
+
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(WindowTransform::try_create(
+                plan.func,
+                plan.schema(),
+                plan.partition_by.clone(),
+                plan.order_by.clone(),
+            )?))
+        })?;
+        Ok(pipeline)
+    }
+
+    fn visit_cross_join(&mut self, plan: &CrossJoinPlan) -> Result<Pipeline> {
+        // The build side has its own context, the partitions of both sides are bound separately.
+        let build_ctx = DatabendQueryContext::new(self.ctx.clone());
+        let build_pipeline = PipelineBuilder::create(build_ctx).build(&plan.build)?;
+        let build_side = CrossJoinTransform::build_side(build_pipeline);
+
+        // The limit above the join doesn't bound the rows of the probe side.
+        self.limit = None;
+        let mut pipeline = self.visit(&*plan.probe)?;
+
+        let max_rows = self.ctx.get_settings().get_max_cross_join_rows()? as usize;
+        let emitted_rows = Arc::new(AtomicUsize::new(0));
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(CrossJoinTransform::try_create(
+                plan.schema(),
+                build_side.clone(),
+                emitted_rows.clone(),
+                max_rows,
+            )?))
+        })?;
+        Ok(pipeline)
+    }
+
+    fn visit_join(&mut self, plan: &JoinPlan) -> Result<Pipeline> {
+        let algorithm = self.ctx.get_settings().get_join_algorithm()?;
+        match JoinAlgorithm::from_setting(algorithm)? {
+            // Hash join is the only algorithm yet, it is picked whatever the statistics of the sides are.
+            JoinAlgorithm::Auto | JoinAlgorithm::Hash => self.visit_hash_join(plan),
+        }
+    }
+
+    fn visit_hash_join(&mut self, plan: &JoinPlan) -> Result<Pipeline> {
+        // The build side has its own context, the partitions of both sides are bound separately.
+        let build_ctx = DatabendQueryContext::new(self.ctx.clone());
+        let build_pipeline = PipelineBuilder::create(build_ctx).build(&plan.build)?;
+        let build_side = HashJoinTransform::build_side(
+            build_pipeline,
+            plan.build.schema(),
+            plan.build_keys.clone(),
+        );
+
+        // The limit above the join doesn't bound the rows of the probe side.
+        self.limit = None;
+        let mut pipeline = self.visit(&*plan.probe)?;
+
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(HashJoinTransform::try_create(
+                plan.join_type,
+                plan.schema(),
+                plan.probe_keys.clone(),
+                build_side.clone(),
+            )?))
+        })?;
+        Ok(pipeline)
+    }
 
+    fn add_sort_transforms(
+        &self,
+        pipeline: &mut Pipeline,
+        schema: DataSchemaRef,
+        order_by: &[Expression],
+        limit: Option<usize>,
+        estimated_rows: usize,
+    ) -> Result<()> {
         // processor 1: block ---> sort_stream
         // processor 2: block ---> sort_stream
         // processor 3: block ---> sort_stream
         pipeline.add_simple_transform(|| {
             Ok(Box::new(SortPartialTransform::try_create(
-                plan.schema(),
-                plan.order_by.clone(),
-                self.limit,
+                schema.clone(),
+                order_by.to_vec(),
+                limit,
             )?))
         })?;
 
         // processor 1: [sorted blocks ...] ---> merge to one sorted block
         // processor 2: [sorted blocks ...] ---> merge to one sorted block
         // processor 3: [sorted blocks ...] ---> merge to one sorted block
+        let max_bytes = self
+            .ctx
+            .get_settings()
+            .get_max_bytes_before_external_sort()?;
+        let spill = self.spill_options(max_bytes as usize);
         pipeline.add_simple_transform(|| {
-            Ok(Box::new(SortMergeTransform::try_create(
-                plan.schema(),
-                plan.order_by.clone(),
-                self.limit,
-            )?))
+            Ok(Box::new(
+                SortMergeTransform::try_create(schema.clone(), order_by.to_vec(), limit)?
+                    .with_spill(spill.clone()),
+            ))
         })?;
 
+        // processor1 sorted block --          range1 --> merge to one sorted block --
+        //                             \      /                                       \
+        // processor2 sorted block ----> ---> range2 --> merge to one sorted block ----> concat
+        //                             /      \                                       /
+        // processor3 sorted block --          range3 --> merge to one sorted block --
+        //
+        // A large sort without a limit is merged in max_threads key ranges at once,
+        // the serial merge below is used when the keys can't be split into ranges.
+        let max_threads = self.ctx.get_max_threads()? as usize;
+        let max_block_size = self.ctx.get_settings().get_max_block_size()? as usize;
+        if limit.is_none()
+            && max_threads > 1
+            && pipeline.last_pipe()?.nums() > 1
+            && estimated_rows >= max_block_size
+            && can_range_partition(&schema, order_by)
+        {
+            pipeline.range_partition_processor(schema.clone(), order_by.to_vec(), max_threads)?;
+            pipeline.add_simple_transform(|| {
+                Ok(Box::new(
+                    SortMergeTransform::try_create(schema.clone(), order_by.to_vec(), limit)?
+                        .with_spill(spill.clone()),
+                ))
+            })?;
+            return pipeline.concat_processor();
+        }
+
         // processor1 sorted block --
         //                             \
         // processor2 sorted block ----> processor  --> merge to one sorted block
         //                             /
         // processor3 sorted block --
-        if pipeline.last_pipe()?.nums() > 1 {
-            pipeline.merge_processor()?;
+        //
+        // With sort_merge_fan_in = k, every k sorted blocks are merged at each stage,
+        // until only one sorted block left.
+        let fan_in = self.ctx.get_settings().get_sort_merge_fan_in()? as usize;
+        while pipeline.last_pipe()?.nums() > 1 {
+            pipeline.merge_processor_with_fan_in(fan_in)?;
             pipeline.add_simple_transform(|| {
-                Ok(Box::new(SortMergeTransform::try_create(
-                    plan.schema(),
-                    plan.order_by.clone(),
-                    self.limit,
-                )?))
+                Ok(Box::new(
+                    SortMergeTransform::try_create(schema.clone(), order_by.to_vec(), limit)?
+                        .with_spill(spill.clone()),
+                ))
             })?;
         }
+        Ok(())
+    }
+
+    fn visit_delete(&mut self, plan: &DeletePlan) -> Result<Pipeline> {
+        let table = self.mutated_table(
+            "DELETE",
+            &plan.db,
+            &plan.table,
+            &plan.input,
+            &plan.predicate,
+        )?;
+        let schema = plan.input.schema();
+        let mut pipeline = self.visit(&*plan.input)?;
+
+        // Rewrite the table with the rows the predicate is not true for, NULL included.
+        let keep = Expression::ScalarFunction {
+            op: "if".to_string(),
+            args: vec![plan.predicate.clone(), lit(false), lit(true)],
+        };
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(WhereTransform::try_create(
+                schema.clone(),
+                keep.clone(),
+            )?))
+        })?;
+        self.add_table_rewrite(&mut pipeline, table)?;
+        Ok(pipeline)
+    }
+
+    fn visit_update(&mut self, plan: &UpdatePlan) -> Result<Pipeline> {
+        let table = self.mutated_table(
+            "UPDATE",
+            &plan.db,
+            &plan.table,
+            &plan.input,
+            &plan.predicate,
+        )?;
+        let table_schema = table.schema()?;
+        for (column, value) in &plan.assignments {
+            if table_schema.field_with_name(column).is_err() {
+                return Result::Err(ErrorCode::BadArguments(format!(
+                    "Can not update column {} which is not in table {}.{}",
+                    column, plan.db, plan.table
+                )));
+            }
+            value.to_data_field(&table_schema)?;
+        }
+
+        // Rewrite the table with all the rows, the rows the predicate is true for are updated,
+        // the values are cast back to the types of the columns.
+        let schema = plan.input.schema();
+        let exprs = schema
+            .fields()
+            .iter()
+            .map(
+                |field| match plan.assignments.iter().find(|(c, _)| c == field.name()) {
+                    None => col(field.name()),
+                    Some((_, value)) => Expression::Alias(
+                        field.name().clone(),
+                        Box::new(Expression::Cast {
+                            expr: Box::new(Expression::ScalarFunction {
+                                op: "if".to_string(),
+                                args: vec![
+                                    plan.predicate.clone(),
+                                    value.clone(),
+                                    col(field.name()),
+                                ],
+                            }),
+                            data_type: field.data_type().clone(),
+                        }),
+                    ),
+                },
+            )
+            .collect::<Vec<_>>();
+        let fields = exprs
+            .iter()
+            .map(|expr| expr.to_data_field(&schema))
+            .collect::<Result<Vec<_>>>()?;
+        let output_schema = DataSchemaRefExt::create(fields);
+
+        let mut pipeline = self.visit(&*plan.input)?;
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(ProjectionTransform::try_create(
+                schema.clone(),
+                output_schema.clone(),
+                exprs.clone(),
+            )?))
+        })?;
+        self.add_table_rewrite(&mut pipeline, table)?;
         Ok(pipeline)
     }
 
+    // The table a delete or an update rewrites, the input must read all its columns
+    // and the predicate must be a boolean expression on them.
+    fn mutated_table(
+        &self,
+        statement: &str,
+        db: &str,
+        table: &str,
+        input: &PlanNode,
+        predicate: &Expression,
+    ) -> Result<TablePtr> {
+        let table_meta = self.ctx.get_table(db, table)?;
+        let table_ptr = table_meta.raw().clone();
+        if !table_ptr.support_overwrite() {
+            return Result::Err(ErrorCode::UnImplement(format!(
+                "{} is not supported by table {}.{} of engine {}",
+                statement,
+                db,
+                table,
+                table_ptr.engine()
+            )));
+        }
+        let table_schema = table_ptr.schema()?;
+
+        let input_schema = input.schema();
+        let same_columns = input_schema.fields().len() == table_schema.fields().len()
+            && input_schema
+                .fields()
+                .iter()
+                .zip(table_schema.fields().iter())
+                .all(|(l, r)| l.name() == r.name() && l.data_type() == r.data_type());
+        if !same_columns {
+            return Result::Err(ErrorCode::BadArguments(format!(
+                "The input of the mutation must read all the columns of table {}.{}, but got [{}]",
+                db,
+                table,
+                input_schema
+                    .fields()
+                    .iter()
+                    .map(|field| field.name().as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+
+        let predicate_type = predicate.to_data_type(&table_schema)?;
+        if predicate_type != DataType::Boolean {
+            return Result::Err(ErrorCode::BadArguments(format!(
+                "The predicate of the mutation of table {}.{} must be Boolean, but got {:?}: {:?}",
+                db, table, predicate_type, predicate
+            )));
+        }
+        Ok(table_ptr)
+    }
+
+    // Merge all the rows into one stream, the table is rewritten by a single writer.
+    fn add_table_rewrite(&self, pipeline: &mut Pipeline, table: TablePtr) -> Result<()> {
+        pipeline.merge_processor()?;
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(TableRewriteTransform::try_create(
+                self.ctx.clone(),
+                table.clone(),
+            )?))
+        })
+    }
+
+    fn spill_options(&self, max_bytes_in_memory: usize) -> SpillOptions {
+        let temp_path = self.ctx.get_config().storage.disk.temp_path;
+        SpillOptions::create(&temp_path, max_bytes_in_memory)
+    }
+
     fn visit_limit(&mut self, node: &LimitPlan) -> Result<Pipeline> {
         self.limit = node.n;
 
@@ -295,11 +904,19 @@ impl PipelineBuilder {
     }
 
     fn visit_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<Pipeline> {
+        let mut pipeline = Pipeline::create(self.ctx.clone());
+
+        // The blocks of a memory table are already in memory, a single source serves them all.
+        if MemoryTable::is_memory_backed(plan) {
+            let source = MemorySourceTransform::try_create(self.ctx.clone(), plan.clone())?;
+            pipeline.add_source(Arc::new(source))?;
+            return Ok(pipeline);
+        }
+
         // Bind plan partitions to context.
         self.ctx.try_set_partitions(plan.parts.clone())?;
 
-        let mut pipeline = Pipeline::create(self.ctx.clone());
-        let max_threads = self.ctx.get_settings().get_max_threads()? as usize;
+        let max_threads = self.ctx.get_max_threads()? as usize;
         let max_threads = std::cmp::min(max_threads, plan.parts.len());
         let workers = std::cmp::max(max_threads, 1);
 
@@ -307,6 +924,13 @@ impl PipelineBuilder {
             let source = SourceTransform::try_create(self.ctx.clone(), plan.clone())?;
             pipeline.add_source(Arc::new(source))?;
         }
+
+        // Avoid paying the per-block overhead downstream for tiny blocks.
+        let block_size = self.ctx.get_settings().get_block_size()? as usize;
+        if block_size > 0 {
+            pipeline
+                .add_simple_transform(|| Ok(Box::new(ReblockTransform::try_create(block_size)?)))?;
+        }
         Ok(pipeline)
     }
 
@@ -327,3 +951,15 @@ impl PipelineBuilder {
         Ok(pipeline)
     }
 }
+
+// The rows the sources of the plan are estimated to read.
+fn estimated_read_rows(plan: &PlanNode) -> usize {
+    match plan {
+        PlanNode::ReadSource(v) => v.statistics.read_rows,
+        _ => plan
+            .inputs()
+            .iter()
+            .map(|input| estimated_read_rows(input))
+            .sum(),
+    }
+}
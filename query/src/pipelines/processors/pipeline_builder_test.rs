@@ -12,12 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use common_base::tokio;
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::add;
+use common_planners::col;
+use common_planners::lit;
+use common_planners::sort;
+use common_planners::DeletePlan;
+use common_planners::JoinType;
+use common_planners::PlanBuilder;
+use common_planners::PlanNode;
+use common_planners::UpdatePlan;
 use futures::TryStreamExt;
 use pretty_assertions::assert_eq;
 
+use crate::interpreters::InterpreterFactory;
 use crate::pipelines::processors::*;
+use crate::sessions::DatabendQueryContextRef;
 use crate::sql::*;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -41,7 +56,7 @@ async fn test_local_pipeline_builds() -> Result<()> {
             \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
 
             pipeline: "\
-            ProjectionTransform × 1 processor\
+            ColumnProjectionTransform × 1 processor\
             \n  SortMergeTransform × 1 processor\
             \n    Merge (SortMergeTransform × 8 processors) to (SortMergeTransform × 1)\
             \n      SortMergeTransform × 8 processors\
@@ -77,7 +92,7 @@ async fn test_local_pipeline_builds() -> Result<()> {
 
 
             pipeline: "\
-            ProjectionTransform × 1 processor\
+            ColumnProjectionTransform × 1 processor\
             \n  SortMergeTransform × 1 processor\
             \n    Merge (SortMergeTransform × 8 processors) to (SortMergeTransform × 1)\
             \n      SortMergeTransform × 8 processors\
@@ -113,7 +128,7 @@ async fn test_local_pipeline_builds() -> Result<()> {
             \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
 
             pipeline: "\
-            ProjectionTransform × 1 processor\
+            ColumnProjectionTransform × 1 processor\
             \n  SortMergeTransform × 1 processor\
             \n    Merge (SortMergeTransform × 8 processors) to (SortMergeTransform × 1)\
             \n      SortMergeTransform × 8 processors\
@@ -147,6 +162,9 @@ async fn test_local_pipeline_builds() -> Result<()> {
         let actual_plan = format!("{:?}", plan);
         assert_eq!(test.plan, actual_plan, "{:#?}", test.name);
 
+        // Pipeline validate check.
+        PipelineBuilder::create(ctx.clone()).validate(&plan)?;
+
         // Pipeline build check.
         let pipeline_builder = PipelineBuilder::create(ctx.clone());
         let mut pipeline = pipeline_builder.build(&plan)?;
@@ -160,3 +178,498 @@ async fn test_local_pipeline_builds() -> Result<()> {
     }
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_local_pipeline_builds_with_sort_merge_fan_in() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_sort_merge_fan_in(2)?;
+
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(10) order by number desc")?;
+
+    // 8 sorted streams are merged in log2(8) = 3 stages, each merge has only 2 inputs.
+    let pipeline_builder = PipelineBuilder::create(ctx.clone());
+    let mut pipeline = pipeline_builder.build(&plan)?;
+    let expect = "\
+    ColumnProjectionTransform × 1 processor\
+    \n  SortMergeTransform × 1 processor\
+    \n    Merge (SortMergeTransform × 2 processors) to (SortMergeTransform × 1)\
+    \n      SortMergeTransform × 2 processors\
+    \n        Merge (SortMergeTransform × 4 processors) to (SortMergeTransform × 2)\
+    \n          SortMergeTransform × 4 processors\
+    \n            Merge (SortMergeTransform × 8 processors) to (SortMergeTransform × 4)\
+    \n              SortMergeTransform × 8 processors\
+    \n                SortPartialTransform × 8 processors\
+    \n                  SourceTransform × 8 processors";
+    assert_eq!(expect, format!("{:?}", pipeline));
+
+    for pipe in pipeline.pipes() {
+        for processor in pipe.processors() {
+            if processor.name() == "MergeProcessor" {
+                assert!(processor.inputs().len() <= 2);
+            }
+        }
+    }
+
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 9      |",
+        "| 8      |",
+        "| 7      |",
+        "| 6      |",
+        "| 5      |",
+        "| 4      |",
+        "| 3      |",
+        "| 2      |",
+        "| 1      |",
+        "| 0      |",
+        "+--------+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, result.as_slice());
+
+    // A fan-in of 1 can never reduce the streams.
+    assert!(ctx.get_settings().set_sort_merge_fan_in(1).is_err());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_local_pipeline_builds_with_parallel_sort_merge() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_threads(8)?;
+
+    // The sorted streams are split into 8 key ranges, merged at once and concatenated in order.
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(100000) order by number desc")?;
+    let pipeline_builder = PipelineBuilder::create(ctx.clone());
+    let mut pipeline = pipeline_builder.build(&plan)?;
+    let display = format!("{:?}", pipeline);
+    assert!(display.contains("ConcatProcessor × 1 processor"));
+    assert!(display.contains("SortRangePartitionTransform × 8 processors"));
+    assert!(!display.contains("MergeProcessor"));
+
+    let result = pipeline.execute().await?.try_collect::<Vec<_>>().await?;
+    let mut values = vec![];
+    for block in result.iter() {
+        let column = block.column(0);
+        for row in 0..block.num_rows() {
+            values.push(column.try_get(row)?.as_u64()?);
+        }
+    }
+    let expected = (0..100000).rev().collect::<Vec<u64>>();
+    assert_eq!(expected, values);
+
+    // A sort with a limit is merged serially.
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(100000) order by number desc limit 3")?;
+    let pipeline_builder = PipelineBuilder::create(ctx.clone());
+    let pipeline = pipeline_builder.build(&plan)?;
+    let display = format!("{:?}", pipeline);
+    assert!(!display.contains("ConcatProcessor"));
+    assert!(!display.contains("SortRangePartitionTransform"));
+
+    // More blocks than the ones the bounds are picked from: the rest are split as they come.
+    ctx.get_settings().set_max_block_size(500)?;
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(100000) order by number")?;
+    let pipeline_builder = PipelineBuilder::create(ctx.clone());
+    let mut pipeline = pipeline_builder.build(&plan)?;
+    let result = pipeline.execute().await?.try_collect::<Vec<_>>().await?;
+    let mut values = vec![];
+    for block in result.iter() {
+        let column = block.column(0);
+        for row in 0..block.num_rows() {
+            values.push(column.try_get(row)?.as_u64()?);
+        }
+    }
+    let expected = (0..100000).collect::<Vec<u64>>();
+    assert_eq!(expected, values);
+    Ok(())
+}
+
+#[test]
+fn test_pipeline_builder_unsupported_node() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    let plan = PlanBuilder::empty().explain()?.select()?.build()?;
+    let result = PipelineBuilder::create(ctx).build(&plan);
+    let err = result.err().unwrap();
+    assert_eq!(11, err.code());
+    assert_eq!(
+        "ExplainPlan can not be built into an execution pipeline, the plan is malformed (plan path: SelectPlan -> ExplainPlan)",
+        err.message()
+    );
+
+    assert!(PipelineBuilder::supported_nodes().contains(&"ReadSourcePlan"));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_local_pipeline_builds_with_block_min_max() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let query = "select number as a, number + 1 as b from numbers_mt(10)";
+
+    // Not collected by default.
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+    let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+    let result = pipeline.execute().await?.try_collect::<Vec<_>>().await?;
+    assert!(result.iter().all(|block| block.min_max().is_none()));
+
+    ctx.get_settings().set_collect_block_min_max(1)?;
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+    let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+    let result = pipeline.execute().await?.try_collect::<Vec<_>>().await?;
+    assert!(!result.is_empty());
+
+    for block in result.iter().filter(|block| !block.is_empty()) {
+        let min_max = block.min_max().unwrap();
+        assert_eq!(2, min_max.len());
+
+        for (i, (min, max)) in min_max.iter().enumerate() {
+            let column = block.column(i);
+            let values = (0..block.num_rows())
+                .map(|row| column.try_get(row)?.as_u64())
+                .collect::<Result<Vec<_>>>()?;
+            assert_eq!(*values.iter().min().unwrap(), min.as_u64()?);
+            assert_eq!(*values.iter().max().unwrap(), max.as_u64()?);
+        }
+    }
+
+    assert!(ctx.get_settings().set_collect_block_min_max(2).is_err());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_local_pipeline_builds_with_max_threads_hint() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_threads(8)?;
+
+    // The query hint takes precedence over the session setting.
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(10000) settings max_threads = 1")?;
+    assert_eq!(1, ctx.get_max_threads()?);
+
+    let pipeline_builder = PipelineBuilder::create(ctx.clone());
+    let mut pipeline = pipeline_builder.build(&plan)?;
+    let expect = "\
+    ColumnProjectionTransform × 1 processor\
+    \n  SourceTransform × 1 processor";
+    assert_eq!(expect, format!("{:?}", pipeline));
+
+    let stream = pipeline.execute().await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = blocks.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(10000, rows);
+
+    // The session setting is not changed.
+    assert_eq!(8, ctx.get_settings().get_max_threads()?);
+
+    // Without the hint, the session setting is used.
+    ctx.set_max_threads_hint(None)?;
+    assert_eq!(8, ctx.get_max_threads()?);
+
+    let err = ctx.set_max_threads_hint(Some(0)).unwrap_err();
+    assert_eq!(6, err.code());
+
+    // Only max_threads can be set for a query.
+    let err = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(10) settings max_block_size = 1")
+        .unwrap_err();
+    assert_eq!(ErrorCode::SyntaxException("").code(), err.code());
+
+    Ok(())
+}
+
+#[test]
+fn test_pipeline_builder_join_algorithm() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    let join_plan = || -> Result<PlanNode> {
+        let probe = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(5)?);
+        let probe = PlanBuilder::from(&probe)
+            .project(&[col("number").alias("a")])?
+            .build()?;
+        let build = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(3)?);
+        let build = PlanBuilder::from(&build)
+            .project(&[col("number").alias("b")])?
+            .build()?;
+        PlanBuilder::from(&probe)
+            .join(JoinType::Inner, &build, &[col("a")], &[col("b")])?
+            .build()
+    };
+
+    // Auto by default, and forced.
+    for algorithm in [0, 1] {
+        ctx.get_settings().set_join_algorithm(algorithm)?;
+        let pipeline = PipelineBuilder::create(ctx.clone()).build(&join_plan()?)?;
+        let pipeline = format!("{:?}", pipeline);
+        assert!(pipeline.contains("HashJoinTransform"), "{}", pipeline);
+    }
+
+    // An unknown algorithm is rejected when it is set, not when a query is built.
+    let err = ctx.get_settings().set_join_algorithm(2).unwrap_err();
+    assert_eq!(ErrorCode::BadArguments("").code(), err.code());
+    let err = ctx
+        .get_settings()
+        .update_settings("join_algorithm", "3".to_string())
+        .unwrap_err();
+    assert_eq!(ErrorCode::BadArguments("").code(), err.code());
+    assert_eq!(1, ctx.get_settings().get_join_algorithm()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_pipeline_builder_validate() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+    let source = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(10)?);
+    // Drain the partitions bound by the test source.
+    ctx.try_get_partitions(usize::MAX)?;
+
+    // Ok.
+    {
+        let plan = PlanBuilder::from(&source)
+            .filter(col("number").eq(lit(1u64)))?
+            .sort(&[sort("number", false, false)])?
+            .build()?;
+        PipelineBuilder::create(ctx.clone()).validate(&plan)?;
+
+        // Nothing is bound to the context.
+        assert!(ctx.try_get_partitions(usize::MAX)?.is_empty());
+    }
+
+    // Unknown column.
+    {
+        let plan = PlanBuilder::from(&source)
+            .filter(col("c").eq(lit(1u64)))?
+            .build()?;
+        let err = PipelineBuilder::create(ctx.clone())
+            .validate(&plan)
+            .err()
+            .unwrap();
+        assert_eq!(15, err.code());
+        assert_eq!(
+            "Column `c` required by FilterPlan is not in its input schema [number] (plan path: FilterPlan)",
+            err.message()
+        );
+    }
+
+    // Unsupported node.
+    {
+        let plan = PlanBuilder::empty().explain()?.select()?.build()?;
+        let err = PipelineBuilder::create(ctx).validate(&plan).err().unwrap();
+        assert_eq!(11, err.code());
+        assert_eq!(
+            "ExplainPlan can not be built into an execution pipeline, the plan is malformed (plan path: SelectPlan -> ExplainPlan)",
+            err.message()
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_pipeline_builder_normalize_nested_selects() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(10) where number > 6")?;
+    let nested = PlanBuilder::from(&plan).select()?.select()?.build()?;
+
+    // The nested Selects are flattened into the Select of the query.
+    assert_eq!(plan, PipelineBuilder::normalize(&nested)?);
+    assert_eq!(plan, PipelineBuilder::normalize(&plan)?);
+
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 7      |",
+        "| 8      |",
+        "| 9      |",
+        "+--------+",
+    ];
+    let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+    let flattened = format!("{:?}", pipeline);
+    let result = pipeline.execute().await?.try_collect::<Vec<_>>().await?;
+    common_datablocks::assert_blocks_sorted_eq(expected.clone(), result.as_slice());
+
+    let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&nested)?;
+    assert_eq!(flattened, format!("{:?}", pipeline));
+    let result = pipeline.execute().await?.try_collect::<Vec<_>>().await?;
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    // The plan path of the errors has no nested Selects either.
+    let plan = PlanBuilder::empty()
+        .explain()?
+        .select()?
+        .select()?
+        .build()?;
+    let err = PipelineBuilder::create(ctx).build(&plan).err().unwrap();
+    assert_eq!(
+        "ExplainPlan can not be built into an execution pipeline, the plan is malformed (plan path: SelectPlan -> ExplainPlan)",
+        err.message()
+    );
+
+    Ok(())
+}
+
+async fn execute_sql(ctx: &DatabendQueryContextRef, sql: &str) -> Result<Vec<DataBlock>> {
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+    let interpreter = InterpreterFactory::get(ctx.clone(), plan)?;
+    interpreter.execute().await?.try_collect::<Vec<_>>().await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_pipeline_builder_delete_and_update() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    execute_sql(
+        &ctx,
+        "create table default.t(a UInt64, b UInt64) Engine = Memory",
+    )
+    .await?;
+    execute_sql(
+        &ctx,
+        "insert into default.t values(1, 10), (2, 20), (3, 30), (4, 40)",
+    )
+    .await?;
+    let input = PlanParser::create(ctx.clone()).build_from_sql("select * from default.t")?;
+
+    // Delete the rows with a > 2.
+    {
+        let plan = PlanNode::Delete(DeletePlan {
+            db: "default".to_string(),
+            table: "t".to_string(),
+            predicate: col("a").gt(lit(2u64)),
+            input: Arc::new(input.clone()),
+        });
+        let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+        let result = pipeline.execute().await?.try_collect::<Vec<_>>().await?;
+        assert!(result.is_empty());
+
+        let expected = vec![
+            "+---+----+",
+            "| a | b  |",
+            "+---+----+",
+            "| 1 | 10 |",
+            "| 2 | 20 |",
+            "+---+----+",
+        ];
+        let result = execute_sql(&ctx, "select * from default.t").await?;
+        common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+    }
+
+    // Update b of the rows with a = 1.
+    {
+        let plan = PlanNode::Update(UpdatePlan {
+            db: "default".to_string(),
+            table: "t".to_string(),
+            assignments: vec![("b".to_string(), add(col("b"), lit(1u64)))],
+            predicate: col("a").eq(lit(1u64)),
+            input: Arc::new(input.clone()),
+        });
+        let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+        pipeline.execute().await?.try_collect::<Vec<_>>().await?;
+
+        let expected = vec![
+            "+---+----+",
+            "| a | b  |",
+            "+---+----+",
+            "| 1 | 11 |",
+            "| 2 | 20 |",
+            "+---+----+",
+        ];
+        let result = execute_sql(&ctx, "select * from default.t").await?;
+        common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+    }
+
+    // The predicate is validated against the table schema before anything is changed.
+    {
+        let plan = PlanNode::Delete(DeletePlan {
+            db: "default".to_string(),
+            table: "t".to_string(),
+            predicate: col("a"),
+            input: Arc::new(input.clone()),
+        });
+        let err = PipelineBuilder::create(ctx.clone())
+            .build(&plan)
+            .err()
+            .unwrap();
+        assert_eq!(ErrorCode::BadArguments("").code(), err.code());
+        assert_eq!(
+            "The predicate of the mutation of table default.t must be Boolean, but got UInt64: a",
+            err.message()
+        );
+
+        let plan = PlanNode::Update(UpdatePlan {
+            db: "default".to_string(),
+            table: "t".to_string(),
+            assignments: vec![("c".to_string(), lit(1u64))],
+            predicate: col("a").eq(lit(1u64)),
+            input: Arc::new(input),
+        });
+        let err = PipelineBuilder::create(ctx.clone())
+            .build(&plan)
+            .err()
+            .unwrap();
+        assert_eq!(
+            "Can not update column c which is not in table default.t",
+            err.message()
+        );
+
+        let expected = vec![
+            "+---+----+",
+            "| a | b  |",
+            "+---+----+",
+            "| 1 | 11 |",
+            "| 2 | 20 |",
+            "+---+----+",
+        ];
+        let result = execute_sql(&ctx, "select * from default.t").await?;
+        common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+    }
+
+    // An engine that can not rewrite the table is rejected before anything runs.
+    {
+        execute_sql(&ctx, "create table default.n(a UInt64) Engine = Null").await?;
+        let input = PlanParser::create(ctx.clone()).build_from_sql("select * from default.n")?;
+
+        let plan = PlanNode::Delete(DeletePlan {
+            db: "default".to_string(),
+            table: "n".to_string(),
+            predicate: col("a").eq(lit(1u64)),
+            input: Arc::new(input.clone()),
+        });
+        let err = PipelineBuilder::create(ctx.clone())
+            .build(&plan)
+            .err()
+            .unwrap();
+        assert_eq!(ErrorCode::UnImplement("").code(), err.code());
+        assert_eq!(
+            "DELETE is not supported by table default.n of engine Null",
+            err.message()
+        );
+
+        let plan = PlanNode::Update(UpdatePlan {
+            db: "default".to_string(),
+            table: "n".to_string(),
+            assignments: vec![("a".to_string(), lit(2u64))],
+            predicate: col("a").eq(lit(1u64)),
+            input: Arc::new(input),
+        });
+        let err = PipelineBuilder::create(ctx.clone())
+            .build(&plan)
+            .err()
+            .unwrap();
+        assert_eq!(
+            "UPDATE is not supported by table default.n of engine Null",
+            err.message()
+        );
+    }
+
+    Ok(())
+}
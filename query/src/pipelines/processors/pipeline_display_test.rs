@@ -29,7 +29,7 @@ async fn test_pipeline_display() -> Result<()> {
     let pipeline_builder = PipelineBuilder::create(ctx);
     let pipeline = pipeline_builder.build(plan.input(0).as_ref())?;
     let expect = "LimitTransform × 1 processor\
-    \n  ProjectionTransform × 1 processor\
+    \n  ColumnProjectionTransform × 1 processor\
     \n    ExpressionTransform × 1 processor\
     \n      AggregatorFinalTransform × 1 processor\
     \n        Merge (AggregatorPartialTransform × 8 processors) to (AggregatorFinalTransform × 1)\
@@ -0,0 +1,98 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use common_base::tokio;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_streams::SendableDataBlockStream;
+use futures::StreamExt;
+use pretty_assertions::assert_eq;
+
+use crate::pipelines::processors::*;
+use crate::sessions::DatabendQueryContextRef;
+
+/// A source which emits one row every 100ms, forever.
+struct SlowSource {
+    ctx: DatabendQueryContextRef,
+}
+
+#[async_trait::async_trait]
+impl Processor for SlowSource {
+    fn name(&self) -> &str {
+        "SlowSource"
+    }
+
+    fn connect_to(&mut self, _: Arc<dyn Processor>) -> Result<()> {
+        Err(ErrorCode::LogicalError("Cannot call SlowSource connect_to"))
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![Arc::new(EmptyProcessor::create())]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::UInt64, false)]);
+        let stream = futures::stream::unfold(0u64, move |n| {
+            let schema = schema.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                let block = DataBlock::create_by_array(schema, vec![Series::new(vec![n])]);
+                Some((Ok(block), n + 1))
+            }
+        });
+        Ok(Box::pin(self.ctx.try_create_abortable(Box::pin(stream))?))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_pipeline_max_execution_time() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+    pipeline.add_source(Arc::new(SlowSource { ctx: ctx.clone() }))?;
+    pipeline.set_max_execution_time(Some(Duration::from_secs(1)));
+
+    let start = Instant::now();
+    let mut stream = pipeline.execute().await?;
+
+    let mut rows = 0;
+    let error = loop {
+        match stream.next().await {
+            Some(Ok(block)) => rows += block.num_rows(),
+            Some(Err(cause)) => break cause,
+            None => panic!("the slow source never ends"),
+        }
+    };
+
+    let elapsed = start.elapsed();
+    assert_eq!(ErrorCode::QueryTimeout("").code(), error.code());
+    assert!(elapsed >= Duration::from_secs(1), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(2), "{:?}", elapsed);
+    assert!(rows > 0 && rows < 10, "{}", rows);
+
+    // The query is finished once timed out.
+    assert!(stream.next().await.is_none());
+    Ok(())
+}
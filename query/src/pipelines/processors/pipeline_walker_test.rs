@@ -40,7 +40,7 @@ async fn test_pipeline_walker() -> Result<()> {
 
         let expect = vec![
             "LimitTransform x 1".to_string(),
-            "ProjectionTransform x 1".to_string(),
+            "ColumnProjectionTransform x 1".to_string(),
             "ExpressionTransform x 1".to_string(),
             "AggregatorFinalTransform x 1".to_string(),
             "MergeProcessor x 1".to_string(),
@@ -69,7 +69,7 @@ async fn test_pipeline_walker() -> Result<()> {
             "MergeProcessor x 1".to_string(),
             "AggregatorFinalTransform x 1".to_string(),
             "ExpressionTransform x 1".to_string(),
-            "ProjectionTransform x 1".to_string(),
+            "ColumnProjectionTransform x 1".to_string(),
             "LimitTransform x 1".to_string(),
         ];
         assert_eq!(expect, actual);
@@ -0,0 +1,229 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use common_base::tokio::sync::Notify;
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::Mutex;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+
+/// What a processor does when the channel to its downstream is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackpressureMode {
+    /// Wait until the downstream takes a block, it never loses data.
+    Block,
+    /// Drop the oldest block in the channel to make room for the new one, errors are kept.
+    /// The upstream never waits, but the query result is approximate,
+    /// e.g., for monitoring queries that only care about the latest data.
+    DropOldest,
+    /// Fail the query with `PipelineBufferFull`.
+    ErrorOnFull,
+}
+
+impl BackpressureMode {
+    /// Parse the `pipeline_backpressure` setting.
+    pub fn from_setting(value: u64) -> Result<Self> {
+        match value {
+            0 => Ok(BackpressureMode::Block),
+            1 => Ok(BackpressureMode::DropOldest),
+            2 => Ok(BackpressureMode::ErrorOnFull),
+            _ => Err(ErrorCode::BadArguments(format!(
+                "Unknown pipeline_backpressure: {}, it must be 0(block), 1(drop oldest) or 2(error on full)",
+                value
+            ))),
+        }
+    }
+}
+
+struct ChannelState {
+    queue: VecDeque<Result<DataBlock>>,
+    senders: usize,
+    receiver_closed: bool,
+    // The error to deliver before the queued blocks, in `ErrorOnFull` mode.
+    error: Option<ErrorCode>,
+    dropped_blocks: usize,
+}
+
+struct Channel {
+    capacity: usize,
+    mode: BackpressureMode,
+    state: Mutex<ChannelState>,
+    not_empty: Notify,
+    not_full: Notify,
+}
+
+/// Create a bounded channel of blocks between processors, which behaves as `mode` once it holds
+/// `capacity` blocks. A capacity of 0 is treated as 1.
+pub fn block_channel(capacity: usize, mode: BackpressureMode) -> (BlockSender, BlockReceiver) {
+    let channel = Arc::new(Channel {
+        capacity: capacity.max(1),
+        mode,
+        state: Mutex::new(ChannelState {
+            queue: VecDeque::new(),
+            senders: 1,
+            receiver_closed: false,
+            error: None,
+            dropped_blocks: 0,
+        }),
+        not_empty: Notify::new(),
+        not_full: Notify::new(),
+    });
+
+    let sender = BlockSender {
+        channel: channel.clone(),
+    };
+    (sender, BlockReceiver { channel })
+}
+
+pub struct BlockSender {
+    channel: Arc<Channel>,
+}
+
+impl BlockSender {
+    /// Send an item, returns an error if the receiver is closed,
+    /// or if the channel is full in `ErrorOnFull` mode.
+    pub async fn send(&self, item: Result<DataBlock>) -> Result<()> {
+        let channel = &self.channel;
+        let mut item = Some(item);
+
+        loop {
+            let not_full = channel.not_full.notified();
+            {
+                let mut state = channel.state.lock();
+                if state.receiver_closed || state.error.is_some() {
+                    // Wake up the next blocked sender to see it too.
+                    channel.not_full.notify_one();
+                    return Err(ErrorCode::BrokenChannel(
+                        "The receiver of the pipeline channel is closed",
+                    ));
+                }
+
+                if state.queue.len() >= channel.capacity {
+                    match channel.mode {
+                        BackpressureMode::Block => {}
+                        BackpressureMode::DropOldest => {
+                            // Errors are never dropped: if the channel is full of errors,
+                            // wait as in `Block` mode.
+                            let oldest_block = state.queue.iter().position(|item| item.is_ok());
+                            if let Some(position) = oldest_block {
+                                state.queue.remove(position);
+                                state.dropped_blocks += 1;
+                            }
+                        }
+                        BackpressureMode::ErrorOnFull => {
+                            let error = ErrorCode::PipelineBufferFull(format!(
+                                "The downstream processor can not keep up, the pipeline channel is full of {} blocks",
+                                channel.capacity
+                            ));
+                            state.error = Some(error.clone());
+                            channel.not_empty.notify_one();
+                            return Err(error);
+                        }
+                    }
+                }
+
+                if state.queue.len() < channel.capacity {
+                    state.queue.push_back(item.take().unwrap());
+                    channel.not_empty.notify_one();
+                    return Ok(());
+                }
+            }
+            not_full.await;
+        }
+    }
+}
+
+impl Clone for BlockSender {
+    fn clone(&self) -> Self {
+        self.channel.state.lock().senders += 1;
+        BlockSender {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+impl Drop for BlockSender {
+    fn drop(&mut self) {
+        let mut state = self.channel.state.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            self.channel.not_empty.notify_one();
+        }
+    }
+}
+
+pub struct BlockReceiver {
+    channel: Arc<Channel>,
+}
+
+impl BlockReceiver {
+    /// Receive the next item, returns None once all of the senders are dropped and the channel is drained.
+    pub async fn recv(&mut self) -> Option<Result<DataBlock>> {
+        let channel = &self.channel;
+
+        loop {
+            let not_empty = channel.not_empty.notified();
+            {
+                let mut state = channel.state.lock();
+                if let Some(error) = state.error.take() {
+                    // The query fails, nothing after the error is delivered.
+                    state.queue.clear();
+                    state.receiver_closed = true;
+                    channel.not_full.notify_one();
+                    return Some(Err(error));
+                }
+
+                if let Some(item) = state.queue.pop_front() {
+                    channel.not_full.notify_one();
+                    return Some(item);
+                }
+
+                if state.senders == 0 || state.receiver_closed {
+                    return None;
+                }
+            }
+            not_empty.await;
+        }
+    }
+
+    /// The number of blocks dropped in `DropOldest` mode.
+    pub fn dropped_blocks(&self) -> usize {
+        self.channel.state.lock().dropped_blocks
+    }
+
+    pub fn into_stream(self) -> SendableDataBlockStream {
+        Box::pin(futures::stream::unfold(self, |mut receiver| async move {
+            receiver.recv().await.map(|item| (item, receiver))
+        }))
+    }
+}
+
+impl Drop for BlockReceiver {
+    fn drop(&mut self) {
+        let mut state = self.channel.state.lock();
+        state.receiver_closed = true;
+        if state.dropped_blocks > 0 {
+            tracing::warn!(
+                "Pipeline channel dropped {} blocks, the result is approximate",
+                state.dropped_blocks
+            );
+        }
+        self.channel.not_full.notify_one();
+    }
+}
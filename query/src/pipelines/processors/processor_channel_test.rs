@@ -0,0 +1,135 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_base::tokio;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use pretty_assertions::assert_eq;
+
+use crate::pipelines::processors::*;
+
+fn block(n: u64) -> DataBlock {
+    let schema = DataSchemaRefExt::create(vec![DataField::new("n", DataType::UInt64, false)]);
+    DataBlock::create_by_array(schema, vec![Series::new(vec![n])])
+}
+
+/// Send 10 blocks into a channel of capacity 2, which are received by a consumer
+/// sleeping before every receive.
+/// Returns the results of the sends and the items received.
+async fn run_slow_consumer(
+    mode: BackpressureMode,
+) -> Result<(Vec<Result<()>>, Vec<Result<u64>>, usize)> {
+    let (sender, mut receiver) = block_channel(2, mode);
+
+    let producer = tokio::spawn(async move {
+        let mut sent = vec![];
+        for n in 0..10 {
+            sent.push(sender.send(Ok(block(n))).await);
+        }
+        sent
+    });
+
+    let mut received = vec![];
+    loop {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        match receiver.recv().await {
+            None => break,
+            Some(item) => {
+                received.push(item.and_then(|b| b.column(0).to_array()?.try_get(0)?.as_u64()))
+            }
+        }
+    }
+
+    let sent = producer.await.unwrap();
+    Ok((sent, received, receiver.dropped_blocks()))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_block_channel_block() -> Result<()> {
+    let (sent, received, dropped) = run_slow_consumer(BackpressureMode::Block).await?;
+
+    assert!(sent.iter().all(|r| r.is_ok()));
+    let received = received.into_iter().collect::<Result<Vec<_>>>()?;
+    assert_eq!((0..10).collect::<Vec<_>>(), received);
+    assert_eq!(0, dropped);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_block_channel_drop_oldest() -> Result<()> {
+    let (sent, received, dropped) = run_slow_consumer(BackpressureMode::DropOldest).await?;
+
+    // The producer never waits, only the latest blocks are left to the slow consumer.
+    assert!(sent.iter().all(|r| r.is_ok()));
+    let received = received.into_iter().collect::<Result<Vec<_>>>()?;
+    assert_eq!(vec![8, 9], received);
+    assert_eq!(8, dropped);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_block_channel_drop_oldest_keeps_errors() -> Result<()> {
+    let (sender, mut receiver) = block_channel(2, BackpressureMode::DropOldest);
+
+    sender.send(Err(ErrorCode::BadBytes("bad block"))).await?;
+    for n in 0..3 {
+        sender.send(Ok(block(n))).await?;
+    }
+    drop(sender);
+
+    // The blocks are dropped in place of the error.
+    let code = ErrorCode::BadBytes("").code();
+    assert_eq!(code, receiver.recv().await.unwrap().unwrap_err().code());
+    let last = receiver.recv().await.unwrap()?;
+    assert_eq!(2, last.column(0).to_array()?.try_get(0)?.as_u64()?);
+    assert!(receiver.recv().await.is_none());
+    assert_eq!(2, receiver.dropped_blocks());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_block_channel_error_on_full() -> Result<()> {
+    let (sent, received, dropped) = run_slow_consumer(BackpressureMode::ErrorOnFull).await?;
+
+    // The third send finds the channel full, the following ones fail too.
+    let code = ErrorCode::PipelineBufferFull("").code();
+    assert!(sent[0..2].iter().all(|r| r.is_ok()));
+    assert_eq!(code, sent[2].as_ref().unwrap_err().code());
+    assert!(sent[3..].iter().all(|r| r.is_err()));
+
+    // The error is delivered before the buffered blocks, nothing follows it.
+    assert_eq!(1, received.len());
+    assert_eq!(code, received[0].as_ref().unwrap_err().code());
+    assert_eq!(0, dropped);
+    Ok(())
+}
+
+#[test]
+fn test_backpressure_mode_from_setting() -> Result<()> {
+    assert_eq!(BackpressureMode::Block, BackpressureMode::from_setting(0)?);
+    assert_eq!(
+        BackpressureMode::DropOldest,
+        BackpressureMode::from_setting(1)?
+    );
+    assert_eq!(
+        BackpressureMode::ErrorOnFull,
+        BackpressureMode::from_setting(2)?
+    );
+    assert!(BackpressureMode::from_setting(3).is_err());
+    Ok(())
+}
@@ -0,0 +1,108 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_base::TrySpawn;
+use common_exception::Result;
+use common_streams::SendableDataBlockStream;
+use log::error;
+use tokio_stream::StreamExt;
+
+use crate::pipelines::processors::processor_channel::block_channel;
+use crate::pipelines::processors::processor_channel::BackpressureMode;
+use crate::pipelines::processors::Processor;
+use crate::sessions::DatabendQueryContextRef;
+
+/// Concatenate the outputs of the inputs in the order they are connected,
+/// e.g., the range-disjoint sorted partitions of a parallel sort merge.
+///
+/// All the inputs are executed at once, the output of an input waits in its channel
+/// until the inputs before it are drained.
+pub struct ConcatProcessor {
+    ctx: DatabendQueryContextRef,
+    inputs: Vec<Arc<dyn Processor>>,
+}
+
+impl ConcatProcessor {
+    pub fn create(ctx: DatabendQueryContextRef) -> Self {
+        ConcatProcessor {
+            ctx,
+            inputs: vec![],
+        }
+    }
+
+    fn concat(&self) -> Result<SendableDataBlockStream> {
+        let mut streams = Vec::with_capacity(self.inputs.len());
+        for processor in self.inputs.iter().cloned() {
+            // An input waits for the ones before it, whatever pipeline_backpressure is,
+            // a dropped block or a full channel error would break the order.
+            let (sender, receiver) = block_channel(1, BackpressureMode::Block);
+            self.ctx.try_spawn(async move {
+                let mut stream = match processor.execute().await {
+                    Err(e) => {
+                        if let Err(error) = sender.send(Err(e)).await {
+                            error!("Concat processor cannot push data: {}", error);
+                        }
+                        return;
+                    }
+                    Ok(stream) => stream,
+                };
+
+                while let Some(item) = stream.next().await {
+                    let failed = item.is_err();
+                    if let Err(error) = sender.send(item).await {
+                        // Stop pulling data
+                        error!("Concat processor cannot push data: {}", error);
+                        return;
+                    }
+                    if failed {
+                        return;
+                    }
+                }
+            })?;
+            streams.push(receiver.into_stream());
+        }
+
+        Ok(Box::pin(futures::stream::iter(streams).flatten()))
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for ConcatProcessor {
+    fn name(&self) -> &str {
+        "ConcatProcessor"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.inputs.push(input);
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        self.inputs.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        match self.inputs.len() {
+            1 => self.inputs[0].execute().await,
+            _ => self.concat(),
+        }
+    }
+}
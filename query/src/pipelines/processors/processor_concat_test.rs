@@ -0,0 +1,93 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::tokio;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_streams::SendableDataBlockStream;
+use futures::TryStreamExt;
+use pretty_assertions::assert_eq;
+
+use crate::pipelines::processors::*;
+
+/// Outputs the blocks of `values` after `delay`.
+struct DelayedSource {
+    values: Vec<u64>,
+    delay: Duration,
+}
+
+#[async_trait::async_trait]
+impl Processor for DelayedSource {
+    fn name(&self) -> &str {
+        "DelayedSource"
+    }
+
+    fn connect_to(&mut self, _: Arc<dyn Processor>) -> Result<()> {
+        unimplemented!()
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![Arc::new(EmptyProcessor::create())]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        let schema = DataSchemaRefExt::create(vec![DataField::new("n", DataType::UInt64, false)]);
+        let blocks = self
+            .values
+            .iter()
+            .map(|n| {
+                Ok(DataBlock::create_by_array(schema.clone(), vec![
+                    Series::new(vec![*n]),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        tokio::time::sleep(self.delay).await;
+        Ok(Box::pin(futures::stream::iter(blocks)))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_processor_concat() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    // The earlier inputs are slower, their outputs still come first.
+    let mut pipeline = Pipeline::create(ctx);
+    for (values, delay) in [(vec![1, 2], 60), (vec![3], 30), (vec![4, 5], 0)] {
+        pipeline.add_source(Arc::new(DelayedSource {
+            values,
+            delay: Duration::from_millis(delay),
+        }))?;
+    }
+    pipeline.concat_processor()?;
+    assert_eq!(1, pipeline.last_pipe()?.nums());
+
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+---+", "| n |", "+---+", "| 1 |", "| 2 |", "| 3 |", "| 4 |", "| 5 |", "+---+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, result.as_slice());
+
+    Ok(())
+}
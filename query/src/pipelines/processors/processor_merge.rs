@@ -15,16 +15,15 @@
 use std::any::Any;
 use std::sync::Arc;
 
-use common_base::tokio::sync::mpsc;
 use common_base::TrySpawn;
-use common_datablocks::DataBlock;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_streams::SendableDataBlockStream;
 use log::error;
-use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 
+use crate::pipelines::processors::processor_channel::block_channel;
+use crate::pipelines::processors::processor_channel::BackpressureMode;
 use crate::pipelines::processors::Processor;
 use crate::sessions::DatabendQueryContextRef;
 
@@ -41,6 +40,8 @@ impl MergeProcessor {
         }
     }
 
+    /// Merge the inputs into a channel, which behaves as the `pipeline_backpressure` setting
+    /// once the downstream can not keep up.
     pub fn merge(&self) -> Result<SendableDataBlockStream> {
         let len = self.inputs.len();
         if len == 0 {
@@ -49,7 +50,9 @@ impl MergeProcessor {
             ));
         }
 
-        let (sender, receiver) = mpsc::channel::<Result<DataBlock>>(len);
+        let mode =
+            BackpressureMode::from_setting(self.ctx.get_settings().get_pipeline_backpressure()?)?;
+        let (sender, receiver) = block_channel(len, mode);
         for i in 0..len {
             let processor = self.inputs[i].clone();
             let sender = sender.clone();
@@ -84,7 +87,7 @@ impl MergeProcessor {
                 }
             })?;
         }
-        Ok(Box::pin(ReceiverStream::new(receiver)))
+        Ok(receiver.into_stream())
     }
 }
 
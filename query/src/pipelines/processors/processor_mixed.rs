@@ -18,17 +18,17 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
-use common_base::tokio::sync::mpsc;
 use common_base::TrySpawn;
-use common_datablocks::DataBlock;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_infallible::RwLock;
 use common_streams::SendableDataBlockStream;
 use log::error;
-use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 
+use crate::pipelines::processors::processor_channel::block_channel;
+use crate::pipelines::processors::processor_channel::BackpressureMode;
+use crate::pipelines::processors::processor_channel::BlockReceiver;
 use crate::pipelines::processors::processor_merge::MergeProcessor;
 use crate::pipelines::processors::Processor;
 use crate::sessions::DatabendQueryContextRef;
@@ -39,7 +39,7 @@ struct MixedWorker {
     n: usize,
     shared_num: AtomicUsize,
     started: AtomicBool,
-    receivers: Vec<Option<mpsc::Receiver<Result<DataBlock>>>>,
+    receivers: Vec<Option<BlockReceiver>>,
     merger: MergeProcessor,
 }
 
@@ -52,9 +52,11 @@ impl MixedWorker {
         let inputs_len = self.merger.inputs().len();
         let outputs_len = self.n;
 
+        let mode =
+            BackpressureMode::from_setting(self.ctx.get_settings().get_pipeline_backpressure()?)?;
         let mut senders = Vec::with_capacity(outputs_len);
         for _i in 0..self.n {
-            let (sender, receiver) = mpsc::channel::<Result<DataBlock>>(inputs_len);
+            let (sender, receiver) = block_channel(inputs_len, mode);
             senders.push(sender);
             self.receivers.push(Some(receiver));
         }
@@ -141,6 +143,6 @@ impl Processor for MixedProcessor {
         }
         .unwrap();
 
-        Ok(Box::pin(ReceiverStream::new(receiver)))
+        Ok(receiver.into_stream())
     }
 }
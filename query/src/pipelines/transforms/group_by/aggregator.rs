@@ -209,6 +209,11 @@ impl<Method: HashMethod + PolymorphicKeysHelper<Method>> Aggregator<Method> {
                 func.serialize(arg_place, &mut bytes)?;
                 state_builders[idx].append_value(&bytes[..]);
                 bytes.clear();
+
+                // The state is serialized, release the memory it owns out of the arena.
+                if func.need_manual_drop_state() {
+                    unsafe { func.drop_state(arg_place) };
+                }
             }
 
             group_key_builder.append_value(group_entity.get_state_key());
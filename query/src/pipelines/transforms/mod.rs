@@ -12,29 +12,47 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub use spill::SpillOptions;
+pub use spill::SpillSweeper;
 pub use transform_aggregator_final::AggregatorFinalTransform;
 pub use transform_aggregator_partial::AggregatorPartialTransform;
 pub use transform_create_sets::CreateSetsTransform;
 pub use transform_create_sets::SubQueriesPuller;
+pub use transform_cross_join::CrossJoinBuildSide;
+pub use transform_cross_join::CrossJoinTransform;
 pub use transform_expression::ExpressionTransform;
 pub use transform_expression_executor::ExpressionExecutor;
 pub use transform_filter::HavingTransform;
 pub use transform_filter::WhereTransform;
 pub use transform_group_by_final::GroupByFinalTransform;
 pub use transform_group_by_partial::GroupByPartialTransform;
+pub use transform_hash_join::HashJoinBuildSide;
+pub use transform_hash_join::HashJoinTransform;
+pub use transform_hash_join::JoinAlgorithm;
+pub use transform_hash_join::JoinHashTable;
 pub use transform_limit::LimitTransform;
 pub use transform_limit_by::LimitByTransform;
+pub use transform_projection::ColumnProjectionTransform;
 pub use transform_projection::ProjectionTransform;
+pub use transform_reblock::ReblockTransform;
 pub use transform_remote::RemoteTransform;
 pub use transform_sort_merge::SortMergeTransform;
 pub use transform_sort_partial::SortPartialTransform;
+pub use transform_sort_range_partition::can_range_partition;
+pub use transform_sort_range_partition::SortRangePartitionTransform;
 pub use transform_source::SourceTransform;
+pub use transform_source_memory::MemorySourceTransform;
+pub use transform_source_pruner::SourcePruner;
+pub use transform_table_rewrite::TableRewriteTransform;
+pub use transform_window::WindowTransform;
 
 #[cfg(test)]
 mod transform_aggregator_final_test;
 #[cfg(test)]
 mod transform_aggregator_partial_test;
 #[cfg(test)]
+mod transform_cross_join_test;
+#[cfg(test)]
 mod transform_expression_test;
 #[cfg(test)]
 mod transform_filter_test;
@@ -43,30 +61,49 @@ mod transform_group_by_final_test;
 #[cfg(test)]
 mod transform_group_by_partial_test;
 #[cfg(test)]
+mod transform_hash_join_test;
+#[cfg(test)]
 mod transform_limit_by_test;
 #[cfg(test)]
 mod transform_limit_test;
 #[cfg(test)]
 mod transform_projection_test;
 #[cfg(test)]
+mod transform_reblock_test;
+#[cfg(test)]
 mod transform_sort_test;
 #[cfg(test)]
+mod transform_source_memory_test;
+#[cfg(test)]
+mod transform_source_pruner_test;
+#[cfg(test)]
 mod transform_source_test;
+#[cfg(test)]
+mod transform_window_test;
 
 mod transform_aggregator_final;
 mod transform_aggregator_partial;
 mod transform_create_sets;
+mod transform_cross_join;
 mod transform_expression;
 mod transform_expression_executor;
 mod transform_filter;
 mod transform_group_by_final;
 mod transform_group_by_partial;
+mod transform_hash_join;
 mod transform_limit;
 mod transform_limit_by;
 mod transform_projection;
+mod transform_reblock;
 mod transform_remote;
 mod transform_sort_merge;
 mod transform_sort_partial;
+mod transform_sort_range_partition;
 mod transform_source;
+mod transform_source_memory;
+mod transform_source_pruner;
+mod transform_table_rewrite;
+mod transform_window;
 
 mod group_by;
+mod spill;
@@ -0,0 +1,29 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod spill_test;
+
+mod spill_file;
+mod spill_merge;
+mod spill_reader;
+mod spill_sweeper;
+mod spill_writer;
+
+pub use spill_file::SpillFile;
+pub use spill_file::SpillOptions;
+pub use spill_merge::SpillMerger;
+pub use spill_reader::SpillReader;
+pub use spill_sweeper::SpillSweeper;
+pub use spill_writer::SpillWriter;
@@ -0,0 +1,116 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use common_tracing::tracing;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// Identifies the spill files of this process.
+    /// A spill file is named `spill-<instance id>-<uuid>.bin`,
+    /// so the sweeper never removes the files of the queries running in this process.
+    pub static ref SPILL_INSTANCE_ID: String = uuid::Uuid::new_v4().to_simple().to_string();
+}
+
+/// The lock file `spill-<instance id>.lock` of an instance in a spill dir, it holds the pid of the instance.
+///
+/// It is written before the first spill file of the instance,
+/// the sweepers of the other processes sharing the dir do not remove the spill files of a live instance.
+pub fn spill_lock_path(dir: &Path, instance: &str) -> PathBuf {
+    dir.join(format!("spill-{}.lock", instance))
+}
+
+/// Where and when a transform spills its state to disk.
+#[derive(Clone, Debug)]
+pub struct SpillOptions {
+    pub path: PathBuf,
+    // The state is spilled once it exceeds this size, 0 means never spill.
+    pub max_bytes_in_memory: usize,
+}
+
+impl SpillOptions {
+    /// `temp_path` is the configured `storage.disk.temp_path`,
+    /// the system temp dir is used if it is empty.
+    pub fn create(temp_path: &str, max_bytes_in_memory: usize) -> SpillOptions {
+        let path = match temp_path.is_empty() {
+            true => std::env::temp_dir().join("databend-spill"),
+            false => PathBuf::from(temp_path),
+        };
+
+        SpillOptions {
+            path,
+            max_bytes_in_memory,
+        }
+    }
+
+    pub fn disabled() -> SpillOptions {
+        SpillOptions::create("", 0)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_bytes_in_memory > 0
+    }
+}
+
+/// A temp file of spilled blocks, it is removed when dropped,
+/// so an aborted query does not leave its spill files behind.
+#[derive(Debug)]
+pub struct SpillFile {
+    path: PathBuf,
+}
+
+impl SpillFile {
+    pub fn create(dir: &Path) -> Result<(SpillFile, File)> {
+        std::fs::create_dir_all(dir).map_err_to_code(ErrorCode::CannotWriteFile, || {
+            format!("Cannot create spill dir {:?}", dir)
+        })?;
+
+        let lock = spill_lock_path(dir, &SPILL_INSTANCE_ID);
+        if !lock.exists() {
+            std::fs::write(&lock, std::process::id().to_string())
+                .map_err_to_code(ErrorCode::CannotWriteFile, || {
+                    format!("Cannot create spill lock file {:?}", lock)
+                })?;
+        }
+
+        let path = dir.join(format!(
+            "spill-{}-{}.bin",
+            *SPILL_INSTANCE_ID,
+            uuid::Uuid::new_v4()
+        ));
+        let file = File::create(&path).map_err_to_code(ErrorCode::CannotWriteFile, || {
+            format!("Cannot create spill file {:?}", path)
+        })?;
+
+        Ok((SpillFile { path }, file))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if let Err(cause) = std::fs::remove_file(&self.path) {
+            tracing::warn!("Cannot remove spill file {:?}: {}", self.path, cause);
+        }
+    }
+}
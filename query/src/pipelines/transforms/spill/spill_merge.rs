@@ -0,0 +1,137 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datablocks::DataBlock;
+use common_datablocks::SortColumnDescription;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+
+use crate::pipelines::transforms::spill::SpillReader;
+
+const RUN_COLUMN: &str = "_spill_run";
+const ROW_COLUMN: &str = "_spill_row";
+
+/// K-way merge of sorted runs spilled to disk, only the head block of every
+/// run is kept in memory.
+///
+/// Each round merges the heads, tagged with their run and row, and emits the
+/// merged rows up to the last row of the first exhausted head: all the rows
+/// not read yet sort after it, so the emitted prefix is final.
+pub struct SpillMerger {
+    schema: DataSchemaRef,
+    tagged_schema: DataSchemaRef,
+    sort_columns_descriptions: Vec<SortColumnDescription>,
+    runs: Vec<SpillReader>,
+    heads: Vec<Option<DataBlock>>,
+}
+
+impl SpillMerger {
+    pub fn create(
+        schema: DataSchemaRef,
+        sort_columns_descriptions: Vec<SortColumnDescription>,
+        runs: Vec<SpillReader>,
+    ) -> SpillMerger {
+        let mut fields = schema.fields().clone();
+        fields.push(DataField::new(RUN_COLUMN, DataType::UInt32, false));
+        fields.push(DataField::new(ROW_COLUMN, DataType::UInt32, false));
+
+        let heads = runs.iter().map(|_| None).collect();
+        SpillMerger {
+            schema,
+            tagged_schema: DataSchemaRefExt::create(fields),
+            sort_columns_descriptions,
+            runs,
+            heads,
+        }
+    }
+
+    /// Returns None once all the runs are merged.
+    pub fn next_block(&mut self) -> Result<Option<DataBlock>> {
+        self.fill_heads()?;
+
+        let active = (0..self.heads.len())
+            .filter(|index| self.heads[*index].is_some())
+            .collect::<Vec<_>>();
+
+        match active.len() {
+            0 => Ok(None),
+            1 => Ok(self.heads[active[0]].take()),
+            _ => self.merge_heads(&active).map(Some),
+        }
+    }
+
+    fn fill_heads(&mut self) -> Result<()> {
+        for (run, head) in self.runs.iter_mut().zip(self.heads.iter_mut()) {
+            while head.is_none() {
+                match run.read()? {
+                    None => break,
+                    Some(block) if block.num_rows() == 0 => continue,
+                    Some(block) => *head = Some(block),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_heads(&mut self, active: &[usize]) -> Result<DataBlock> {
+        let mut tagged = Vec::with_capacity(active.len());
+        for index in active {
+            let head = self.heads[*index].as_ref().unwrap();
+            let rows = head.num_rows() as u32;
+
+            let mut columns = head.columns().to_vec();
+            columns.push(DataColumn::Array(Series::new(vec![
+                *index as u32;
+                rows as usize
+            ])));
+            columns.push(DataColumn::Array(Series::new(
+                (0..rows).collect::<Vec<_>>(),
+            )));
+            tagged.push(DataBlock::create(self.tagged_schema.clone(), columns));
+        }
+
+        let merged = DataBlock::merge_sort_blocks(&tagged, &self.sort_columns_descriptions, None)?;
+
+        let num_columns = self.schema.fields().len();
+        let run_array = merged.column(num_columns).to_array()?;
+        let row_array = merged.column(num_columns + 1).to_array()?;
+
+        let mut consumed = vec![0usize; self.heads.len()];
+        let mut emitted = 0;
+        let runs = run_array.u32()?.into_no_null_iter();
+        let rows = row_array.u32()?.into_no_null_iter();
+        for (run, row) in runs.zip(rows) {
+            let run = *run as usize;
+            consumed[run] += 1;
+            emitted += 1;
+
+            let head_rows = self.heads[run].as_ref().unwrap().num_rows();
+            if *row as usize + 1 == head_rows {
+                break;
+            }
+        }
+
+        for index in active {
+            let head = self.heads[*index].take().unwrap();
+            let remain = head.num_rows() - consumed[*index];
+            if remain > 0 {
+                self.heads[*index] = Some(head.slice(consumed[*index], remain));
+            }
+        }
+
+        let merged = merged.slice(0, emitted);
+        let columns = merged.columns()[0..num_columns].to_vec();
+        Ok(DataBlock::create(self.schema.clone(), columns))
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::sync::Arc;
+
+use common_arrow::arrow_flight::utils::flight_data_to_arrow_batch;
+use common_arrow::arrow_flight::FlightData;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use prost::Message;
+
+use crate::pipelines::transforms::spill::SpillFile;
+
+/// Reads back the blocks of a spill file in the order they were written.
+pub struct SpillReader {
+    file: SpillFile,
+    schema: DataSchemaRef,
+    reader: BufReader<File>,
+}
+
+impl SpillReader {
+    pub fn try_create(file: SpillFile, schema: DataSchemaRef) -> Result<SpillReader> {
+        let handle = File::open(file.path()).map_err_to_code(ErrorCode::CannotReadFile, || {
+            format!("Cannot open spill file {:?}", file.path())
+        })?;
+
+        Ok(SpillReader {
+            file,
+            schema,
+            reader: BufReader::new(handle),
+        })
+    }
+
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    /// Returns None once all the blocks are read.
+    pub fn read(&mut self) -> Result<Option<DataBlock>> {
+        let mut len = [0u8; 8];
+        match self.reader.read_exact(&mut len) {
+            Err(cause) if cause.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            res => res.map_err_to_code(ErrorCode::CannotReadFile, || {
+                format!("Cannot read spill file {:?}", self.file.path())
+            })?,
+        };
+
+        let mut bytes = vec![0u8; u64::from_le_bytes(len) as usize];
+        self.reader
+            .read_exact(&mut bytes)
+            .map_err_to_code(ErrorCode::CannotReadFile, || {
+                format!("Cannot read spill file {:?}", self.file.path())
+            })?;
+
+        let flight_data = FlightData::decode(bytes.as_slice())
+            .map_err_to_code(ErrorCode::FileDamaged, || {
+                format!("Cannot decode spill file {:?}", self.file.path())
+            })?;
+
+        let record_batch =
+            flight_data_to_arrow_batch(&flight_data, Arc::new(self.schema.to_arrow()), true, &[])?;
+
+        let columns = record_batch
+            .columns()
+            .iter()
+            .map(|column| DataColumn::Array(column.clone().into_series()))
+            .collect::<Vec<_>>();
+
+        Ok(Some(DataBlock::create(self.schema.clone(), columns)))
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use common_base::tokio;
+use common_base::tokio::task::JoinHandle;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use common_tracing::tracing;
+
+use crate::pipelines::transforms::spill::spill_file::spill_lock_path;
+use crate::pipelines::transforms::spill::spill_file::SPILL_INSTANCE_ID;
+
+/// Removes the spill files left behind by crashed queries.
+///
+/// A spill file is removed when it is dropped, but not if the process crashes.
+/// The sweeper removes the spill files of the dead instances that are older than `max_age`,
+/// the files of this instance and of the other live instances belong to the running queries and are never removed.
+///
+/// An instance is alive if the process of the pid in its lock file is, see `spill_lock_path`.
+/// Only the processes of the same host can be checked, the spill dir must not be shared across hosts.
+#[derive(Clone, Debug)]
+pub struct SpillSweeper {
+    path: PathBuf,
+    max_age: Duration,
+}
+
+impl SpillSweeper {
+    pub fn create(path: impl Into<PathBuf>, max_age: Duration) -> SpillSweeper {
+        SpillSweeper {
+            path: path.into(),
+            max_age,
+        }
+    }
+
+    /// Remove the orphaned spill files older than `max_age`, returns how many are removed.
+    pub fn sweep(&self) -> Result<usize> {
+        let deadline = SystemTime::now()
+            .checked_sub(self.max_age)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        self.sweep_modified_before(deadline)
+    }
+
+    /// Remove the orphaned spill files not modified since `deadline`.
+    pub fn sweep_modified_before(&self, deadline: SystemTime) -> Result<usize> {
+        if !self.path.exists() {
+            return Ok(0);
+        }
+
+        let entries = std::fs::read_dir(&self.path)
+            .map_err_to_code(ErrorCode::CannotReadFile, || {
+                format!("Cannot read spill dir {:?}", self.path)
+            })?;
+
+        let mut removed = 0;
+        let mut alive = HashMap::new();
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            let (instance, is_lock) = match Self::parse_name(&path) {
+                None => continue,
+                Some(parsed) => parsed,
+            };
+
+            if instance == SPILL_INSTANCE_ID.as_str() {
+                continue;
+            }
+
+            let is_alive = *alive
+                .entry(instance.clone())
+                .or_insert_with(|| self.is_alive(&instance));
+            if is_alive {
+                continue;
+            }
+
+            let modified = entry.metadata()?.modified()?;
+            if modified >= deadline {
+                continue;
+            }
+
+            match std::fs::remove_file(&path) {
+                Ok(_) if !is_lock => removed += 1,
+                Ok(_) => {}
+                Err(cause) => tracing::warn!("Cannot remove spill file {:?}: {}", path, cause),
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Sweep once now and then every `interval` in background.
+    pub fn start(self, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.sweep() {
+                    Ok(0) => {}
+                    Ok(removed) => tracing::info!(
+                        "Removed {} orphaned spill files in {:?}",
+                        removed,
+                        self.path
+                    ),
+                    Err(cause) => tracing::warn!("Cannot sweep spill files: {}", cause),
+                }
+
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    // A spill file is named `spill-<instance id>-<uuid>.bin`, and the lock file `spill-<instance id>.lock`.
+    // Returns the instance id, and whether it is the lock file.
+    fn parse_name(path: &Path) -> Option<(String, bool)> {
+        let name = path.file_name().and_then(|n| n.to_str())?;
+        let name = name.strip_prefix("spill-")?;
+
+        if let Some(instance) = name.strip_suffix(".lock") {
+            return Some((instance.to_string(), true));
+        }
+
+        name.strip_suffix(".bin")
+            .and_then(|n| n.split_once('-'))
+            .map(|(instance, _)| (instance.to_string(), false))
+    }
+
+    // An instance without lock file is dead, e.g. its lock file is already swept.
+    fn is_alive(&self, instance: &str) -> bool {
+        let lock = spill_lock_path(&self.path, instance);
+        match std::fs::read_to_string(&lock) {
+            Ok(pid) => match pid.trim().parse::<u32>() {
+                Ok(pid) => Self::is_pid_alive(pid),
+                Err(_) => false,
+            },
+            Err(cause) if cause.kind() == std::io::ErrorKind::NotFound => false,
+            Err(cause) => {
+                tracing::warn!("Cannot read spill lock file {:?}: {}", lock, cause);
+                true
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn is_pid_alive(pid: u32) -> bool {
+        Path::new("/proc").join(pid.to_string()).exists()
+    }
+
+    // The liveness can not be checked, the files are kept.
+    #[cfg(not(target_os = "linux"))]
+    fn is_pid_alive(_pid: u32) -> bool {
+        true
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_datablocks::*;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::*;
+use crate::pipelines::transforms::spill::*;
+use crate::sql::*;
+
+fn create_block(schema: &DataSchemaRef, numbers: Vec<i64>) -> DataBlock {
+    let names = numbers
+        .iter()
+        .map(|n| format!("n{}", n))
+        .collect::<Vec<_>>();
+    let names = names.iter().map(|n| n.as_str()).collect::<Vec<_>>();
+    DataBlock::create_by_array(schema.clone(), vec![
+        Series::new(numbers),
+        Series::new(names),
+    ])
+}
+
+// The spill files in `dir`, without the lock file of this instance.
+fn spill_files(dir: &std::path::Path) -> Result<Vec<String>> {
+    let mut names = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        if !name.ends_with(".lock") {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+#[test]
+fn test_spill_writer_reader() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("number", DataType::Int64, false),
+        DataField::new("name", DataType::String, false),
+    ]);
+
+    let mut writer = SpillWriter::try_create(dir.path(), schema.clone())?;
+    writer.write(&create_block(&schema, vec![1, 2, 3]))?;
+    writer.write(&create_block(&schema, vec![]))?;
+    writer.write(&create_block(&schema, vec![4, 5]))?;
+    assert_eq!(writer.written_blocks(), 3);
+    assert_eq!(spill_files(dir.path())?.len(), 1);
+
+    let mut reader = writer.finish()?;
+    let mut blocks = vec![];
+    while let Some(block) = reader.read()? {
+        assert_eq!(block.schema(), &schema);
+        blocks.push(block);
+    }
+
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[1].num_rows(), 0);
+    assert_blocks_eq(
+        vec![
+            "+--------+------+",
+            "| number | name |",
+            "+--------+------+",
+            "| 1      | n1   |",
+            "| 2      | n2   |",
+            "| 3      | n3   |",
+            "| 4      | n4   |",
+            "| 5      | n5   |",
+            "+--------+------+",
+        ],
+        &blocks,
+    );
+
+    // The spill file is removed along with its reader.
+    drop(reader);
+    assert_eq!(spill_files(dir.path())?.len(), 0);
+
+    // Also when the writer is dropped without finishing, e.g. the query failed.
+    let mut writer = SpillWriter::try_create(dir.path(), schema.clone())?;
+    writer.write(&create_block(&schema, vec![1]))?;
+    drop(writer);
+    assert_eq!(spill_files(dir.path())?.len(), 0);
+
+    // Failing to create the spill file is a write error, e.g. the spill dir is a file.
+    let file = dir.path().join("not-a-dir");
+    std::fs::write(&file, "")?;
+    let err = SpillWriter::try_create(&file, schema).err().unwrap();
+    assert_eq!(err.code(), ErrorCode::CannotWriteFile("").code());
+    Ok(())
+}
+
+#[test]
+fn test_spill_merger() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("number", DataType::Int64, false),
+        DataField::new("name", DataType::String, false),
+    ]);
+
+    let runs = vec![
+        vec![vec![1, 4], vec![7, 10]],
+        vec![vec![2, 2, 3], vec![], vec![11]],
+        vec![vec![5, 6, 7, 8, 9]],
+    ];
+
+    let mut readers = vec![];
+    for run in runs {
+        let mut writer = SpillWriter::try_create(dir.path(), schema.clone())?;
+        for numbers in run {
+            writer.write(&create_block(&schema, numbers))?;
+        }
+        readers.push(writer.finish()?);
+    }
+
+    let descriptions = vec![SortColumnDescription {
+        column_name: "number".to_string(),
+        asc: true,
+        nulls_first: false,
+    }];
+
+    let mut merger = SpillMerger::create(schema, descriptions, readers);
+    let mut numbers = vec![];
+    while let Some(block) = merger.next_block()? {
+        let array = block.column(0).to_array()?;
+        numbers.extend(array.i64()?.into_no_null_iter().copied());
+    }
+
+    assert_eq!(numbers, vec![1, 2, 2, 3, 4, 5, 6, 7, 7, 8, 9, 10, 11]);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_spill_sort_and_group_by() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_block_size(10)?;
+    ctx.get_settings().set_max_bytes_before_external_sort(1)?;
+    ctx.get_settings()
+        .set_max_bytes_before_external_group_by(1)?;
+
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(1000) order by number desc limit 3")?;
+    let pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    assert_blocks_eq(
+        vec![
+            "+--------+",
+            "| number |",
+            "+--------+",
+            "| 999    |",
+            "| 998    |",
+            "| 997    |",
+            "+--------+",
+        ],
+        &result,
+    );
+
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(
+        "select number % 3 as a, count(*) as c from numbers_mt(1000) group by number % 3",
+    )?;
+    let pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    assert_blocks_sorted_eq(
+        vec![
+            "+---+-----+",
+            "| a | c   |",
+            "+---+-----+",
+            "| 0 | 334 |",
+            "| 1 | 333 |",
+            "| 2 | 333 |",
+            "+---+-----+",
+        ],
+        &result,
+    );
+    Ok(())
+}
+
+#[test]
+fn test_spill_sweeper() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let schema = DataSchemaRefExt::create(vec![DataField::new("number", DataType::Int64, false)]);
+    let touch = |name: &str| std::fs::write(dir.path().join(name), b"");
+
+    // Stale: the spill files of crashed processes, and of a query running in this process.
+    touch("spill-crashed1-00000000-0000-0000-0000-000000000001.bin")?;
+    touch("spill-crashed2-00000000-0000-0000-0000-000000000002.bin")?;
+    let running = SpillWriter::try_create(dir.path(), schema)?;
+    // Stale: a spill file of another process still running, by the pid in its lock file.
+    touch("spill-live-00000000-0000-0000-0000-000000000004.bin")?;
+    std::fs::write(
+        dir.path().join("spill-live.lock"),
+        std::process::id().to_string(),
+    )?;
+    // Not a spill file.
+    touch("other-file.bin")?;
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let deadline = std::time::SystemTime::now();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    // Fresh: a spill file just written by another process.
+    touch("spill-alive-00000000-0000-0000-0000-000000000003.bin")?;
+
+    let sweeper = SpillSweeper::create(dir.path(), std::time::Duration::from_secs(3600));
+    assert_eq!(sweeper.sweep()?, 0);
+    assert_eq!(sweeper.sweep_modified_before(deadline)?, 2);
+
+    let names = spill_files(dir.path())?;
+    assert_eq!(names.len(), 4);
+    assert!(names.contains(&"other-file.bin".to_string()));
+    assert!(names.contains(&"spill-alive-00000000-0000-0000-0000-000000000003.bin".to_string()));
+    assert!(names.contains(&"spill-live-00000000-0000-0000-0000-000000000004.bin".to_string()));
+    assert!(!names.iter().any(|n| n.contains("crashed")));
+    assert!(dir.path().join("spill-live.lock").exists());
+
+    // The spill file of the running query is still removed by the query itself.
+    drop(running);
+    assert_eq!(spill_files(dir.path())?.len(), 3);
+
+    // The stale spill file and the lock file of a process which is gone.
+    #[cfg(target_os = "linux")]
+    {
+        touch("spill-dead-00000000-0000-0000-0000-000000000005.bin")?;
+        std::fs::write(dir.path().join("spill-dead.lock"), u32::MAX.to_string())?;
+        let deadline = std::time::SystemTime::now() + std::time::Duration::from_secs(1);
+        // The fresh spill file above has no lock file either, it is removed once stale.
+        assert_eq!(sweeper.sweep_modified_before(deadline)?, 2);
+        assert!(!dir.path().join("spill-dead.lock").exists());
+        assert!(dir.path().join("spill-live.lock").exists());
+        let names = spill_files(dir.path())?;
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"spill-live-00000000-0000-0000-0000-000000000004.bin".to_string()));
+    }
+
+    // A missing spill dir has nothing to sweep.
+    let missing = SpillSweeper::create(dir.path().join("missing"), Default::default());
+    assert_eq!(missing.sweep()?, 0);
+
+    Ok(())
+}
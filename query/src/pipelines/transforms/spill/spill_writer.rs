@@ -0,0 +1,101 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
+use common_arrow::arrow::io::ipc::write::common::IpcWriteOptions;
+use common_arrow::arrow::record_batch::RecordBatch;
+use common_arrow::arrow_flight::utils::flight_data_from_arrow_batch;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use prost::Message;
+
+use crate::pipelines::transforms::spill::SpillFile;
+use crate::pipelines::transforms::spill::SpillReader;
+
+/// Writes blocks to a spill file, every block is encoded as a FlightData
+/// prefixed by its length.
+pub struct SpillWriter {
+    file: SpillFile,
+    schema: DataSchemaRef,
+    writer: BufWriter<File>,
+    options: IpcWriteOptions,
+    written_blocks: usize,
+    written_bytes: usize,
+}
+
+impl SpillWriter {
+    pub fn try_create(dir: &Path, schema: DataSchemaRef) -> Result<SpillWriter> {
+        let (file, handle) = SpillFile::create(dir)?;
+        Ok(SpillWriter {
+            file,
+            schema,
+            writer: BufWriter::new(handle),
+            options: IpcWriteOptions::default(),
+            written_blocks: 0,
+            written_bytes: 0,
+        })
+    }
+
+    pub fn write(&mut self, block: &DataBlock) -> Result<()> {
+        let record_batch: RecordBatch = block.clone().try_into()?;
+        let (dicts, flight_data) = flight_data_from_arrow_batch(&record_batch, &self.options);
+
+        if !dicts.is_empty() {
+            return Err(ErrorCode::UnImplement(
+                "DatabendQuery does not implement dicts.",
+            ));
+        }
+
+        let bytes = flight_data.encode_to_vec();
+        self.writer
+            .write_all(&(bytes.len() as u64).to_le_bytes())
+            .and_then(|_| self.writer.write_all(&bytes))
+            .map_err_to_code(ErrorCode::CannotWriteFile, || {
+                format!("Cannot write spill file {:?}", self.file.path())
+            })?;
+
+        self.written_blocks += 1;
+        self.written_bytes += bytes.len();
+        Ok(())
+    }
+
+    pub fn written_blocks(&self) -> usize {
+        self.written_blocks
+    }
+
+    pub fn written_bytes(&self) -> usize {
+        self.written_bytes
+    }
+
+    /// Flush the spilled blocks, the returned reader takes over the spill file.
+    pub fn finish(mut self) -> Result<SpillReader> {
+        self.writer
+            .flush()
+            .map_err_to_code(ErrorCode::CannotWriteFile, || {
+                format!("Cannot flush spill file {:?}", self.file.path())
+            })?;
+
+        // Close the write handle before reading it back.
+        drop(self.writer);
+        SpillReader::try_create(self.file, self.schema)
+    }
+}
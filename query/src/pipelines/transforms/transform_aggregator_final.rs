@@ -115,6 +115,9 @@ impl Processor for AggregatorFinalTransform {
 
                 func.deserialize(temp_addr, &mut data)?;
                 func.merge(place, temp_addr)?;
+                if func.need_manual_drop_state() {
+                    unsafe { func.drop_state(temp_addr) };
+                }
             }
         }
         let delta = start.elapsed();
@@ -125,6 +128,9 @@ impl Processor for AggregatorFinalTransform {
             let place = places[idx].into();
             let merge_result = func.merge_result(place)?;
             final_result.push(merge_result.to_series_with_size(1)?);
+            if func.need_manual_drop_state() {
+                unsafe { func.drop_state(place) };
+            }
         }
 
         let mut blocks = vec![];
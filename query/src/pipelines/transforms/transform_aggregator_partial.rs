@@ -141,6 +141,13 @@ impl Processor for AggregatorPartialTransform {
             columns.push(col);
         }
 
+        // The states are serialized, release the memory they own out of the arena.
+        for (idx, func) in funcs.iter().enumerate() {
+            if func.need_manual_drop_state() {
+                unsafe { func.drop_state(places[idx].into()) };
+            }
+        }
+
         let block = DataBlock::create_by_array(self.schema.clone(), columns);
 
         Ok(Box::pin(DataBlockStream::create(
@@ -0,0 +1,165 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use futures::future::BoxFuture;
+use futures::future::Shared;
+use futures::FutureExt;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::EmptyProcessor;
+use crate::pipelines::processors::Pipeline;
+use crate::pipelines::processors::Processor;
+
+/// The materialized build side, shared by all the processors of the probe side.
+/// It is `None` if the build side has no rows.
+pub type CrossJoinBuildSide = Shared<BoxFuture<'static, Result<Option<DataBlock>>>>;
+
+/// Emits the cartesian product of the probe input and the build side.
+///
+/// The build side is read once into one block, the probe blocks are streamed
+/// and every probe row is paired with every build row.
+pub struct CrossJoinTransform {
+    schema: DataSchemaRef,
+    build_side: CrossJoinBuildSide,
+    // The rows emitted by all the processors of this join, checked against max_rows.
+    emitted_rows: Arc<AtomicUsize>,
+    max_rows: usize,
+    input: Arc<dyn Processor>,
+}
+
+impl CrossJoinTransform {
+    /// The `max_rows` (0 means no limit) applies to the whole product,
+    /// `emitted_rows` must be shared by all the processors of one join.
+    pub fn try_create(
+        schema: DataSchemaRef,
+        build_side: CrossJoinBuildSide,
+        emitted_rows: Arc<AtomicUsize>,
+        max_rows: usize,
+    ) -> Result<Self> {
+        Ok(CrossJoinTransform {
+            schema,
+            build_side,
+            emitted_rows,
+            max_rows,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+
+    /// Read all the blocks of the build pipeline into one block, when first polled.
+    pub fn build_side(mut pipeline: Pipeline) -> CrossJoinBuildSide {
+        let future = async move {
+            let stream = pipeline.execute().await?;
+            let blocks = stream
+                .try_filter(|block| futures::future::ready(block.num_rows() > 0))
+                .try_collect::<Vec<_>>()
+                .await?;
+
+            match blocks.len() {
+                0 => Ok(None),
+                _ => Ok(Some(DataBlock::concat_blocks(&blocks)?)),
+            }
+        };
+        future.boxed().shared()
+    }
+}
+
+fn cross_join_block(
+    schema: &DataSchemaRef,
+    probe: &DataBlock,
+    build: &DataBlock,
+) -> Result<DataBlock> {
+    let probe_rows = probe.num_rows();
+    let build_rows = build.num_rows();
+
+    // Every probe row is repeated for all the build rows, the build block is repeated for all the probe rows.
+    let mut probe_indices = Vec::with_capacity(probe_rows * build_rows);
+    let mut build_indices = Vec::with_capacity(probe_rows * build_rows);
+    for probe_row in 0..probe_rows as u32 {
+        for build_row in 0..build_rows as u32 {
+            probe_indices.push(probe_row);
+            build_indices.push(build_row);
+        }
+    }
+
+    let probe = DataBlock::block_take_by_indices(probe, &[], &probe_indices)?;
+    let build = DataBlock::block_take_by_indices(build, &[], &build_indices)?;
+
+    let mut columns = probe.columns().to_vec();
+    columns.extend_from_slice(build.columns());
+    Ok(DataBlock::create(schema.clone(), columns))
+}
+
+#[async_trait::async_trait]
+impl Processor for CrossJoinTransform {
+    fn name(&self) -> &str {
+        "CrossJoinTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        tracing::debug!("execute...");
+
+        let build = match self.build_side.clone().await? {
+            Some(build) => build,
+            None => return Ok(Box::pin(futures::stream::empty())),
+        };
+
+        let schema = self.schema.clone();
+        let emitted_rows = self.emitted_rows.clone();
+        let max_rows = self.max_rows;
+
+        let stream = self.input.execute().await?;
+        Ok(Box::pin(stream.try_filter_map(move |probe| {
+            let res = match probe.num_rows() {
+                0 => Ok(None),
+                probe_rows => {
+                    let rows = probe_rows * build.num_rows();
+                    let total_rows = emitted_rows.fetch_add(rows, Ordering::Relaxed) + rows;
+                    if max_rows > 0 && total_rows > max_rows {
+                        Err(ErrorCode::TooManyJoinRows(format!(
+                            "Cross join produces more than {} rows, exceeds max_cross_join_rows",
+                            max_rows
+                        )))
+                    } else {
+                        cross_join_block(&schema, &probe, &build).map(Some)
+                    }
+                }
+            };
+            futures::future::ready(res)
+        })))
+    }
+}
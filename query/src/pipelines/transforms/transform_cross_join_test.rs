@@ -0,0 +1,86 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use common_planners::*;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_cross_join() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    let probe = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(3)?);
+    let build = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(2)?);
+    let build = PlanBuilder::from(&build)
+        .expression(&[add(col("number"), lit(10))], "")?
+        .project(&[col("(number + 10)").alias("b")])?
+        .build()?;
+    let plan = PlanBuilder::from(&probe)
+        .project(&[col("number").alias("a")])?
+        .cross_join(&build)?
+        .build()?;
+
+    let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+
+    // 3 probe rows * 2 build rows, the probe columns come first.
+    assert_eq!(
+        6,
+        result.iter().map(|block| block.num_rows()).sum::<usize>()
+    );
+    let expected = vec![
+        "+---+----+",
+        "| a | b  |",
+        "+---+----+",
+        "| 0 | 10 |",
+        "| 0 | 11 |",
+        "| 1 | 10 |",
+        "| 1 | 11 |",
+        "| 2 | 10 |",
+        "| 2 | 11 |",
+        "+---+----+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_cross_join_max_rows() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_cross_join_rows(5)?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    let probe = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(3)?);
+    let build = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(2)?);
+    let build = PlanBuilder::from(&build)
+        .project(&[col("number").alias("b")])?
+        .build()?;
+    let plan = PlanBuilder::from(&probe).cross_join(&build)?.build()?;
+
+    let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await;
+
+    assert!(result.is_err());
+    assert_eq!(
+        "Code: 60, displayText = Cross join produces more than 5 rows, exceeds max_cross_join_rows.",
+        result.err().unwrap().to_string()
+    );
+    Ok(())
+}
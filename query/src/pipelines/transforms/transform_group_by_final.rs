@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
@@ -33,6 +34,11 @@ use futures::stream::StreamExt;
 
 use crate::pipelines::processors::EmptyProcessor;
 use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::spill::SpillOptions;
+use crate::pipelines::transforms::spill::SpillWriter;
+
+// The number of partitions the partial states are scattered into once spilled.
+const SPILL_PARTITIONS: usize = 16;
 
 pub struct GroupByFinalTransform {
     max_block_size: usize,
@@ -40,6 +46,7 @@ pub struct GroupByFinalTransform {
     group_exprs: Vec<Expression>,
     schema: DataSchemaRef,
     schema_before_group_by: DataSchemaRef,
+    spill: SpillOptions,
     input: Arc<dyn Processor>,
 }
 
@@ -57,9 +64,16 @@ impl GroupByFinalTransform {
             group_exprs,
             schema,
             schema_before_group_by,
+            spill: SpillOptions::disabled(),
             input: Arc::new(EmptyProcessor::create()),
         }
     }
+
+    /// Spill the partial states by partitions once they exceed `spill.max_bytes_in_memory`.
+    pub fn with_spill(mut self, spill: SpillOptions) -> Self {
+        self.spill = spill;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -83,6 +97,103 @@ impl Processor for GroupByFinalTransform {
 
     async fn execute(&self) -> Result<SendableDataBlockStream> {
         tracing::debug!("execute...");
+        let stream = self.input.execute().await?;
+
+        let blocks = match self.spill.is_enabled() {
+            true => self.merge_with_spill(stream).await?,
+            false => self.merge_partial_states(stream).await?,
+        };
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            blocks,
+        )))
+    }
+}
+
+impl GroupByFinalTransform {
+    /// Buffer the partial states until they exceed `spill.max_bytes_in_memory`, then scatter
+    /// them by group key hash into spilled partitions. The partitions share no group, so they
+    /// are merged one by one and only one partition's hash table is in memory at a time.
+    async fn merge_with_spill(
+        &self,
+        mut stream: SendableDataBlockStream,
+    ) -> Result<Vec<DataBlock>> {
+        let mut blocks = vec![];
+        let mut blocks_bytes = 0;
+        let mut partitions: Vec<SpillWriter> = vec![];
+
+        while let Some(block) = stream.next().await {
+            let block = block?;
+
+            if !partitions.is_empty() {
+                self.spill_partitions(&block, &mut partitions)?;
+                continue;
+            }
+
+            blocks_bytes += block.memory_size();
+            blocks.push(block);
+
+            if blocks_bytes > self.spill.max_bytes_in_memory {
+                for _ in 0..SPILL_PARTITIONS {
+                    partitions.push(SpillWriter::try_create(
+                        &self.spill.path,
+                        blocks[0].schema().clone(),
+                    )?);
+                }
+
+                for block in blocks.drain(..) {
+                    self.spill_partitions(&block, &mut partitions)?;
+                }
+            }
+        }
+
+        if partitions.is_empty() {
+            let schema = self.schema_before_group_by.clone();
+            let stream = DataBlockStream::create(schema, None, blocks);
+            return self.merge_partial_states(Box::pin(stream)).await;
+        }
+
+        let mut results = vec![];
+        for partition in partitions {
+            tracing::debug!(
+                "Merge spilled group by partition of {} blocks, {} bytes",
+                partition.written_blocks(),
+                partition.written_bytes()
+            );
+
+            let mut reader = partition.finish()?;
+            let blocks = std::iter::from_fn(move || reader.read().transpose());
+            let stream = Box::pin(futures::stream::iter(blocks));
+            results.extend(self.merge_partial_states(stream).await?);
+        }
+        Ok(results)
+    }
+
+    fn spill_partitions(&self, block: &DataBlock, partitions: &mut [SpillWriter]) -> Result<()> {
+        // The group keys column follows the aggregate states columns.
+        let keys = block.column(self.aggr_exprs.len()).to_array()?;
+        let hashes = keys.vec_hash(DFHasher::SipHasher(DefaultHasher::new()))?;
+        let indices = hashes
+            .into_no_null_iter()
+            .map(|hash| hash % SPILL_PARTITIONS as u64)
+            .collect::<Vec<_>>();
+
+        let indices = DataColumn::Array(Series::new(indices));
+        let scattered = DataBlock::scatter_block(block, &indices, SPILL_PARTITIONS)?;
+        for (partition, block) in partitions.iter_mut().zip(scattered.iter()) {
+            if block.num_rows() > 0 {
+                partition.write(block)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn merge_partial_states(
+        &self,
+        mut stream: SendableDataBlockStream,
+    ) -> Result<Vec<DataBlock>> {
         let funcs = self
             .aggr_exprs
             .iter()
@@ -107,7 +218,6 @@ impl Processor for GroupByFinalTransform {
         let start = Instant::now();
         let arena = Bump::new();
 
-        let mut stream = self.input.execute().await?;
         let sample_block = DataBlock::empty_with_schema(self.schema_before_group_by.clone());
         let method = DataBlock::choose_hash_method(&sample_block, &group_cols)?;
 
@@ -167,6 +277,9 @@ impl Processor for GroupByFinalTransform {
                                     funcs[idx].init_state(temp_addr);
                                     func.deserialize(temp_addr, &mut data)?;
                                     func.merge(arg_place, temp_addr)?;
+                                    if func.need_manual_drop_state() {
+                                        unsafe { func.drop_state(temp_addr) };
+                                    }
                                 }
                             }
                         };
@@ -194,6 +307,9 @@ impl Processor for GroupByFinalTransform {
                         let arg_place = place.next(offsets_aggregate_states[idx]);
                         let merge = func.merge_result(arg_place)?;
                         aggr_values[idx].push(merge);
+                        if func.need_manual_drop_state() {
+                            unsafe { func.drop_state(arg_place) };
+                        }
                     }
                 }
 
@@ -218,11 +334,7 @@ impl Processor for GroupByFinalTransform {
                     blocks = DataBlock::split_block_by_size(&block, self.max_block_size)?;
                 }
 
-                Ok(Box::pin(DataBlockStream::create(
-                    self.schema.clone(),
-                    None,
-                    blocks,
-                )))
+                Ok(blocks)
             }};
         }
 
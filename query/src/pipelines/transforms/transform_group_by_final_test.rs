@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use common_base::tokio;
@@ -23,6 +25,7 @@ use pretty_assertions::assert_eq;
 
 use crate::pipelines::processors::*;
 use crate::pipelines::transforms::*;
+use crate::sql::PlanParser;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_transform_final_group_by() -> Result<()> {
@@ -92,3 +95,47 @@ async fn test_transform_final_group_by() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_final_group_by_count_distinct() -> Result<()> {
+    // The distinct values of `number % 7` in every group of `number % 10`.
+    let mut expected = BTreeMap::<u64, HashSet<u64>>::new();
+    for number in 0..1000u64 {
+        expected.entry(number % 10).or_default().insert(number % 7);
+    }
+    let expected = expected
+        .into_iter()
+        .map(|(k, set)| (k, set.len() as u64))
+        .collect::<BTreeMap<_, _>>();
+
+    // Without and with spilling the partial states.
+    for max_bytes in [0, 1] {
+        let ctx = crate::tests::try_create_context()?;
+        ctx.get_settings().set_max_block_size(10)?;
+        ctx.get_settings()
+            .set_max_bytes_before_external_group_by(max_bytes)?;
+
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(
+            "select number % 10 as k, count(distinct number % 7) as c from numbers_mt(1000) group by number % 10",
+        )?;
+        let pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+        let stream = pipeline.execute().await?;
+        let result = stream.try_collect::<Vec<_>>().await?;
+
+        let mut actual = BTreeMap::new();
+        for block in result.iter() {
+            let keys = block.column(0).to_array()?;
+            let counts = block.column(1).to_array()?;
+            for row in 0..block.num_rows() {
+                actual.insert(keys.try_get(row)?.as_u64()?, counts.try_get(row)?.as_u64()?);
+            }
+        }
+        assert_eq!(
+            expected, actual,
+            "max_bytes_before_external_group_by: {}",
+            max_bytes
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,241 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_planners::Expression;
+use common_planners::JoinType;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use futures::future::BoxFuture;
+use futures::future::Shared;
+use futures::FutureExt;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::EmptyProcessor;
+use crate::pipelines::processors::Pipeline;
+use crate::pipelines::processors::Processor;
+
+/// The algorithm of a join on equal keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinAlgorithm {
+    /// Picked by the builder, by the statistics of the sides once there is more than one algorithm.
+    Auto,
+    /// Read the build side into a hash table and stream the probe side.
+    Hash,
+}
+
+impl JoinAlgorithm {
+    /// Parse the `join_algorithm` setting.
+    pub fn from_setting(value: u64) -> Result<Self> {
+        match value {
+            0 => Ok(JoinAlgorithm::Auto),
+            1 => Ok(JoinAlgorithm::Hash),
+            _ => Err(ErrorCode::BadArguments(format!(
+                "Unknown join_algorithm: {}, it must be 0(auto) or 1(hash)",
+                value
+            ))),
+        }
+    }
+}
+
+/// The rows of the build side indexed by their keys, rows with a NULL key never match.
+pub struct JoinHashTable {
+    block: DataBlock,
+    rows: HashMap<Vec<u8>, Vec<u32>>,
+}
+
+/// The hash table of the build side, shared by all the processors of the probe side.
+pub type HashJoinBuildSide = Shared<BoxFuture<'static, Result<Arc<JoinHashTable>>>>;
+
+/// Joins the probe input with the build side on the equality of the keys.
+///
+/// The build side is read once into a hash table, the probe blocks are streamed.
+/// For a left outer join, the unmatched probe rows of every block are emitted
+/// after its matched rows, with NULLs for the build columns.
+pub struct HashJoinTransform {
+    join_type: JoinType,
+    schema: DataSchemaRef,
+    probe_keys: Vec<Expression>,
+    build_side: HashJoinBuildSide,
+    input: Arc<dyn Processor>,
+}
+
+impl HashJoinTransform {
+    pub fn try_create(
+        join_type: JoinType,
+        schema: DataSchemaRef,
+        probe_keys: Vec<Expression>,
+        build_side: HashJoinBuildSide,
+    ) -> Result<Self> {
+        Ok(HashJoinTransform {
+            join_type,
+            schema,
+            probe_keys,
+            build_side,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+
+    /// Read all the blocks of the build pipeline into a hash table, when first polled.
+    pub fn build_side(
+        mut pipeline: Pipeline,
+        schema: DataSchemaRef,
+        build_keys: Vec<Expression>,
+    ) -> HashJoinBuildSide {
+        let future = async move {
+            let stream = pipeline.execute().await?;
+            let blocks = stream
+                .try_filter(|block| futures::future::ready(block.num_rows() > 0))
+                .try_collect::<Vec<_>>()
+                .await?;
+
+            let block = match blocks.len() {
+                0 => DataBlock::empty_with_schema(schema),
+                _ => DataBlock::concat_blocks(&blocks)?,
+            };
+
+            let mut rows: HashMap<Vec<u8>, Vec<u32>> = HashMap::new();
+            for (row, key) in join_keys(&block, &build_keys)?.into_iter().enumerate() {
+                if let Some(key) = key {
+                    rows.entry(key).or_default().push(row as u32);
+                }
+            }
+            Ok(Arc::new(JoinHashTable { block, rows }))
+        };
+        future.boxed().shared()
+    }
+}
+
+// The serialized keys of every row, None if any of them is NULL.
+fn join_keys(block: &DataBlock, keys: &[Expression]) -> Result<Vec<Option<Vec<u8>>>> {
+    let num_rows = block.num_rows();
+    let columns = keys
+        .iter()
+        .map(|key| block.try_column_by_name(&key.column_name()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut serialized = vec![Vec::new(); num_rows];
+    let mut arrays = Vec::with_capacity(columns.len());
+    for column in columns {
+        column.serialize(&mut serialized)?;
+        arrays.push(column.to_array()?);
+    }
+
+    Ok(serialized
+        .into_iter()
+        .enumerate()
+        .map(
+            |(row, key)| match arrays.iter().any(|array| array.is_null(row)) {
+                true => None,
+                false => Some(key),
+            },
+        )
+        .collect())
+}
+
+fn join_block(
+    join_type: JoinType,
+    schema: &DataSchemaRef,
+    probe_keys: &[Expression],
+    table: &JoinHashTable,
+    probe: &DataBlock,
+) -> Result<Option<DataBlock>> {
+    let mut probe_indices = vec![];
+    let mut build_indices = vec![];
+    let mut unmatched_indices = vec![];
+    for (row, key) in join_keys(probe, probe_keys)?.into_iter().enumerate() {
+        match key.and_then(|key| table.rows.get(&key)) {
+            Some(build_rows) => {
+                for build_row in build_rows {
+                    probe_indices.push(row as u32);
+                    build_indices.push(*build_row);
+                }
+            }
+            None => unmatched_indices.push(row as u32),
+        }
+    }
+
+    let mut blocks = vec![];
+    if !probe_indices.is_empty() {
+        let probe = DataBlock::block_take_by_indices(probe, &[], &probe_indices)?;
+        let build = DataBlock::block_take_by_indices(&table.block, &[], &build_indices)?;
+
+        let mut columns = probe.columns().to_vec();
+        columns.extend_from_slice(build.columns());
+        blocks.push(DataBlock::create(schema.clone(), columns));
+    }
+
+    if join_type == JoinType::Left && !unmatched_indices.is_empty() {
+        let rows = unmatched_indices.len();
+        let probe = DataBlock::block_take_by_indices(probe, &[], &unmatched_indices)?;
+
+        let mut columns = probe.columns().to_vec();
+        for field in &schema.fields()[columns.len()..] {
+            let null = DataValue::from(field.data_type());
+            columns.push(DataColumn::Array(null.to_series_with_size(rows)?));
+        }
+        blocks.push(DataBlock::create(schema.clone(), columns));
+    }
+
+    match blocks.len() {
+        0 => Ok(None),
+        1 => Ok(blocks.pop()),
+        _ => Ok(Some(DataBlock::concat_blocks(&blocks)?)),
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for HashJoinTransform {
+    fn name(&self) -> &str {
+        "HashJoinTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        tracing::debug!("execute...");
+
+        let table = self.build_side.clone().await?;
+        if self.join_type == JoinType::Inner && table.rows.is_empty() {
+            return Ok(Box::pin(futures::stream::empty()));
+        }
+
+        let join_type = self.join_type;
+        let schema = self.schema.clone();
+        let probe_keys = self.probe_keys.clone();
+
+        let stream = self.input.execute().await?;
+        Ok(Box::pin(stream.try_filter_map(move |probe| {
+            futures::future::ready(join_block(join_type, &schema, &probe_keys, &table, &probe))
+        })))
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use common_planners::*;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_hash_join() -> Result<()> {
+    struct Test {
+        name: &'static str,
+        join_type: JoinType,
+        expect: Vec<&'static str>,
+    }
+
+    // Probe a: 0..5, build b: 0..3, the probe rows 3 and 4 have no match.
+    let tests = vec![
+        Test {
+            name: "inner",
+            join_type: JoinType::Inner,
+            expect: vec![
+                "+---+---+---+",
+                "| a | b | c |",
+                "+---+---+---+",
+                "| 0 | 0 | 0 |",
+                "| 1 | 1 | 1 |",
+                "| 2 | 2 | 0 |",
+                "+---+---+---+",
+            ],
+        },
+        Test {
+            name: "left",
+            join_type: JoinType::Left,
+            expect: vec![
+                "+---+------+------+",
+                "| a | b    | c    |",
+                "+---+------+------+",
+                "| 0 | 0    | 0    |",
+                "| 1 | 1    | 1    |",
+                "| 2 | 2    | 0    |",
+                "| 3 | NULL | NULL |",
+                "| 4 | NULL | NULL |",
+                "+---+------+------+",
+            ],
+        },
+    ];
+
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    for test in tests {
+        let probe = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(5)?);
+        let probe = PlanBuilder::from(&probe)
+            .project(&[col("number").alias("a")])?
+            .build()?;
+        let build = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(3)?);
+        let build = PlanBuilder::from(&build)
+            .expression(&[modular(col("number"), lit(2))], "")?
+            .project(&[col("number").alias("b"), col("(number % 2)").alias("c")])?
+            .build()?;
+        let plan = PlanBuilder::from(&probe)
+            .join(test.join_type, &build, &[col("a")], &[col("b")])?
+            .build()?;
+
+        let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+        let stream = pipeline.execute().await?;
+        let result = stream.try_collect::<Vec<_>>().await?;
+        common_datablocks::assert_blocks_sorted_eq_with_name(
+            test.name,
+            test.expect,
+            result.as_slice(),
+        );
+    }
+
+    Ok(())
+}
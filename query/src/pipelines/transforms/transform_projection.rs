@@ -30,6 +30,8 @@ use crate::pipelines::transforms::ExpressionExecutor;
 
 pub struct ProjectionTransform {
     executor: ExpressionExecutor,
+    // Whether to attach the min and max of every column to the output blocks.
+    collect_min_max: bool,
     input: Arc<dyn Processor>,
 }
 
@@ -49,9 +51,15 @@ impl ProjectionTransform {
 
         Ok(ProjectionTransform {
             executor,
+            collect_min_max: false,
             input: Arc::new(EmptyProcessor::create()),
         })
     }
+
+    pub fn with_collect_min_max(mut self, collect_min_max: bool) -> Self {
+        self.collect_min_max = collect_min_max;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -77,16 +85,21 @@ impl Processor for ProjectionTransform {
         tracing::debug!("execute...");
 
         let executor = self.executor.clone();
+        let collect_min_max = self.collect_min_max;
         let input_stream = self.input.execute().await?;
 
         let executor_fn =
-            |executor: &ExpressionExecutor, block: Result<DataBlock>| -> Result<DataBlock> {
+            move |executor: &ExpressionExecutor, block: Result<DataBlock>| -> Result<DataBlock> {
                 let block = block?;
                 let start = Instant::now();
 
                 let r = executor.execute(&block);
                 let delta = start.elapsed();
                 tracing::debug!("Projection cost: {:?}", delta);
+
+                if collect_min_max {
+                    return r.map(DataBlock::attach_min_max);
+                }
                 r
             };
 
@@ -96,3 +109,99 @@ impl Processor for ProjectionTransform {
         Ok(Box::pin(stream))
     }
 }
+
+/// A projection which only selects, reorders or renames the input columns.
+///
+/// The columns are moved to the output as they are, without the expression executor.
+pub struct ColumnProjectionTransform {
+    schema: DataSchemaRef,
+    // The name of the input column of every output column.
+    input_names: Vec<String>,
+    // Whether to attach the min and max of every column to the output blocks.
+    collect_min_max: bool,
+    input: Arc<dyn Processor>,
+}
+
+impl ColumnProjectionTransform {
+    pub fn create(output_schema: DataSchemaRef, input_names: Vec<String>) -> Self {
+        ColumnProjectionTransform {
+            schema: output_schema,
+            input_names,
+            collect_min_max: false,
+            input: Arc::new(EmptyProcessor::create()),
+        }
+    }
+
+    pub fn with_collect_min_max(mut self, collect_min_max: bool) -> Self {
+        self.collect_min_max = collect_min_max;
+        self
+    }
+
+    /// The input column of every projected expression,
+    /// None if any expression has to be computed.
+    pub fn input_names(input_schema: &DataSchemaRef, exprs: &[Expression]) -> Option<Vec<String>> {
+        exprs
+            .iter()
+            .map(|expr| Self::input_name(input_schema, expr))
+            .collect()
+    }
+
+    // An expression already computed by the input is looked up by its name, as the executor does.
+    fn input_name(input_schema: &DataSchemaRef, expr: &Expression) -> Option<String> {
+        let expr = match expr {
+            Expression::Alias(_, expr) => expr.as_ref(),
+            _ => expr,
+        };
+
+        let name = expr.column_name();
+        match input_schema.field_with_name(&name) {
+            Ok(_) => Some(name),
+            Err(_) => None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for ColumnProjectionTransform {
+    fn name(&self) -> &str {
+        "ColumnProjectionTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        tracing::debug!("execute...");
+
+        let schema = self.schema.clone();
+        let input_names = self.input_names.clone();
+        let collect_min_max = self.collect_min_max;
+        let input_stream = self.input.execute().await?;
+
+        let stream = input_stream.map(move |block| {
+            let block = block?;
+            let columns = input_names
+                .iter()
+                .map(|name| block.try_column_by_name(name).map(|column| column.clone()))
+                .collect::<Result<Vec<_>>>()?;
+
+            let block = DataBlock::create(schema.clone(), columns);
+            if collect_min_max {
+                return Ok(DataBlock::attach_min_max(block));
+            }
+            Ok(block)
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
@@ -75,3 +75,56 @@ async fn test_transform_projection() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_column_projection() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let source = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(source))?;
+
+    if let PlanNode::Projection(plan) = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .project(&[col("number").alias("a"), col("number")])?
+        .build()?
+    {
+        let input_names =
+            ColumnProjectionTransform::input_names(&plan.input.schema(), &plan.expr).unwrap();
+        assert_eq!(input_names, vec![
+            "number".to_string(),
+            "number".to_string()
+        ]);
+
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(ColumnProjectionTransform::create(
+                plan.schema.clone(),
+                input_names.clone(),
+            )))
+        })?;
+    }
+
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let block = &result[0];
+    assert_eq!(block.num_columns(), 2);
+
+    let expected = vec![
+        "+---+--------+",
+        "| a | number |",
+        "+---+--------+",
+        "| 3 | 3      |",
+        "| 2 | 2      |",
+        "| 1 | 1      |",
+        "| 0 | 0      |",
+        "+---+--------+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    // Computed expressions need the expression executor.
+    let schema = test_source.number_schema_for_test()?;
+    let exprs = vec![col("number"), add(col("number"), lit(1u64))];
+    assert!(ColumnProjectionTransform::input_names(&schema, &exprs).is_none());
+
+    Ok(())
+}
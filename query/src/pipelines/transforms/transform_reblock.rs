@@ -0,0 +1,65 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_streams::ReblockStream;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+
+use crate::pipelines::processors::EmptyProcessor;
+use crate::pipelines::processors::Processor;
+
+/// Coalesces the small blocks of the input into blocks of at least `block_size` rows.
+pub struct ReblockTransform {
+    block_size: usize,
+    input: Arc<dyn Processor>,
+}
+
+impl ReblockTransform {
+    pub fn try_create(block_size: usize) -> Result<Self> {
+        Ok(ReblockTransform {
+            block_size,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for ReblockTransform {
+    fn name(&self) -> &str {
+        "ReblockTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        tracing::debug!("execute...");
+        let input_stream = self.input.execute().await?;
+        Ok(Box::pin(ReblockStream::new(input_stream, self.block_size)))
+    }
+}
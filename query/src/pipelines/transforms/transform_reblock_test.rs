@@ -0,0 +1,61 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::tokio;
+use common_exception::Result;
+use futures::TryStreamExt;
+use pretty_assertions::assert_eq;
+
+use crate::pipelines::processors::*;
+use crate::pipelines::transforms::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_reblock() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_threads(1)?;
+    ctx.get_settings().set_max_block_size(1)?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    // The source emits 10 blocks with 1 row each.
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let source = test_source.number_source_transform_for_test(10)?;
+    pipeline.add_source(Arc::new(source))?;
+    pipeline.add_simple_transform(|| Ok(Box::new(ReblockTransform::try_create(4)?)))?;
+
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let rows = result.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+    assert_eq!(vec![4, 4, 2], rows);
+
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 0      |",
+        "| 1      |",
+        "| 2      |",
+        "| 3      |",
+        "| 4      |",
+        "| 5      |",
+        "| 6      |",
+        "| 7      |",
+        "| 8      |",
+        "| 9      |",
+        "+--------+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, result.as_slice());
+    Ok(())
+}
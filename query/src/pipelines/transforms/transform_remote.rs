@@ -30,6 +30,7 @@ use crate::sessions::DatabendQueryContextRef;
 pub struct RemoteTransform {
     ticket: FlightTicket,
     fetch_node_name: String,
+    replica_node_names: Vec<String>,
     schema: DataSchemaRef,
     pub ctx: DatabendQueryContextRef,
 }
@@ -44,18 +45,39 @@ impl RemoteTransform {
         Ok(RemoteTransform {
             ticket,
             fetch_node_name,
+            replica_node_names: vec![],
             schema,
             ctx: context,
         })
     }
 
-    async fn flight_client(&self) -> Result<FlightClient> {
-        let context = self.ctx.clone();
-        let node_name = self.fetch_node_name.clone();
+    /// Set the nodes to fail over to, in order, if the fetch node is unreachable.
+    pub fn with_replicas(mut self, replica_node_names: Vec<String>) -> Self {
+        self.replica_node_names = replica_node_names;
+        self
+    }
+
+    /// Whether the stream failed to set up by `error` because the node is unreachable,
+    /// other errors are returned by the node and would be the same on a replica.
+    pub fn is_unreachable(error: &ErrorCode) -> bool {
+        error.code() == ErrorCode::CannotConnectNode("").code()
+    }
 
-        let cluster = context.get_cluster();
+    async fn flight_client(&self, node_name: &str) -> Result<FlightClient> {
+        let cluster = self.ctx.get_cluster();
         cluster
-            .create_node_conn(&node_name, &self.ctx.get_config())
+            .create_node_conn(node_name, &self.ctx.get_config())
+            .await
+    }
+
+    async fn fetch_stream(&self, node_name: &str) -> Result<SendableDataBlockStream> {
+        let data_schema = self.schema.clone();
+        let timeout = self.ctx.get_settings().get_flight_client_timeout()?;
+
+        let fetch_ticket = self.ticket.clone();
+        let mut flight_client = self.flight_client(node_name).await?;
+        flight_client
+            .fetch_stream(fetch_ticket, data_schema, timeout)
             .await
     }
 }
@@ -87,14 +109,26 @@ impl Processor for RemoteTransform {
             self.fetch_node_name
         );
 
-        let data_schema = self.schema.clone();
-        let timeout = self.ctx.get_settings().get_flight_client_timeout()?;
+        // Only the stream setup to an unreachable node fails over,
+        // errors in the middle of a stream are returned as is.
+        let mut node_name = &self.fetch_node_name;
+        let mut fetch_stream = self.fetch_stream(node_name).await;
+        for replica_node_name in &self.replica_node_names {
+            match &fetch_stream {
+                Err(cause) if Self::is_unreachable(cause) => {
+                    tracing::warn!(
+                        "fetch stream from node {:#} failed: {}, fail over to node {:#}",
+                        node_name,
+                        cause,
+                        replica_node_name
+                    );
+                    node_name = replica_node_name;
+                    fetch_stream = self.fetch_stream(node_name).await;
+                }
+                _ => break,
+            }
+        }
 
-        let fetch_ticket = self.ticket.clone();
-        let mut flight_client = self.flight_client().await?;
-        let fetch_stream = flight_client.fetch_stream(fetch_ticket, data_schema, timeout);
-        Ok(Box::pin(
-            self.ctx.try_create_abortable(fetch_stream.await?)?,
-        ))
+        Ok(Box::pin(self.ctx.try_create_abortable(fetch_stream?)?))
     }
 }
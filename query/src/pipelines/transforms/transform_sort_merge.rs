@@ -17,6 +17,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_datablocks::DataBlock;
+use common_datablocks::SortColumnDescription;
 use common_datavalues::DataSchemaRef;
 use common_exception::Result;
 use common_planners::Expression;
@@ -28,12 +29,20 @@ use futures::StreamExt;
 
 use crate::pipelines::processors::EmptyProcessor;
 use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::spill::SpillMerger;
+use crate::pipelines::transforms::spill::SpillOptions;
+use crate::pipelines::transforms::spill::SpillReader;
+use crate::pipelines::transforms::spill::SpillWriter;
 use crate::pipelines::transforms::transform_sort_partial::get_sort_descriptions;
 
+// The rows of each block a sorted run is spilled in, the merge keeps one block of every run in memory.
+const SPILL_BLOCK_ROWS: usize = 8192;
+
 pub struct SortMergeTransform {
     schema: DataSchemaRef,
     exprs: Vec<Expression>,
     limit: Option<usize>,
+    spill: SpillOptions,
     input: Arc<dyn Processor>,
 }
 
@@ -47,9 +56,37 @@ impl SortMergeTransform {
             schema,
             exprs,
             limit,
+            spill: SpillOptions::disabled(),
             input: Arc::new(EmptyProcessor::create()),
         })
     }
+
+    /// Spill the buffered blocks as sorted runs once they exceed `spill.max_bytes_in_memory`,
+    /// the runs are k-way merged from disk.
+    pub fn with_spill(mut self, spill: SpillOptions) -> Self {
+        self.spill = spill;
+        self
+    }
+
+    fn spill_sorted_run(
+        &self,
+        blocks: &[DataBlock],
+        sort_columns_descriptions: &[SortColumnDescription],
+    ) -> Result<SpillReader> {
+        let sorted = DataBlock::merge_sort_blocks(blocks, sort_columns_descriptions, self.limit)?;
+
+        let mut writer = SpillWriter::try_create(&self.spill.path, self.schema.clone())?;
+        for block in DataBlock::split_block_by_size(&sorted, SPILL_BLOCK_ROWS)? {
+            writer.write(&block)?;
+        }
+
+        tracing::debug!(
+            "Spilled sorted run of {} rows, {} bytes",
+            sorted.num_rows(),
+            writer.written_bytes()
+        );
+        writer.finish()
+    }
 }
 
 #[async_trait]
@@ -76,10 +113,50 @@ impl Processor for SortMergeTransform {
 
         let sort_columns_descriptions = get_sort_descriptions(&self.schema, &self.exprs)?;
         let mut blocks = vec![];
+        let mut blocks_bytes = 0;
+        let mut runs = vec![];
         let mut stream = self.input.execute().await?;
 
         while let Some(block) = stream.next().await {
-            blocks.push(block?);
+            let block = block?;
+            blocks_bytes += block.memory_size();
+            blocks.push(block);
+
+            if self.spill.is_enabled() && blocks_bytes > self.spill.max_bytes_in_memory {
+                runs.push(self.spill_sorted_run(&blocks, &sort_columns_descriptions)?);
+                blocks.clear();
+                blocks_bytes = 0;
+            }
+        }
+
+        if !runs.is_empty() {
+            if !blocks.is_empty() {
+                runs.push(self.spill_sorted_run(&blocks, &sort_columns_descriptions)?);
+            }
+
+            let mut remain = self.limit.unwrap_or(usize::MAX);
+            let mut merger =
+                SpillMerger::create(self.schema.clone(), sort_columns_descriptions, runs);
+            let merged = std::iter::from_fn(move || match remain {
+                0 => None,
+                _ => match merger.next_block() {
+                    Ok(None) => None,
+                    Ok(Some(block)) => {
+                        let rows = block.num_rows().min(remain);
+                        remain -= rows;
+                        Some(Ok(block.slice(0, rows)))
+                    }
+                    Err(cause) => {
+                        remain = 0;
+                        Some(Err(cause))
+                    }
+                },
+            });
+
+            return Ok(Box::pin(CorrectWithSchemaStream::new(
+                Box::pin(futures::stream::iter(merged)),
+                self.schema.clone(),
+            )));
         }
 
         let results = match blocks.len() {
@@ -0,0 +1,287 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common_base::TrySpawn;
+use common_datablocks::DataBlock;
+use common_datablocks::SortColumnDescription;
+use common_datavalues::is_date_or_date_time;
+use common_datavalues::is_numeric;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataType;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::RwLock;
+use common_planners::Expression;
+use common_streams::SendableDataBlockStream;
+use log::error;
+use tokio_stream::StreamExt;
+
+use crate::pipelines::processors::block_channel;
+use crate::pipelines::processors::BackpressureMode;
+use crate::pipelines::processors::BlockReceiver;
+use crate::pipelines::processors::BlockSender;
+use crate::pipelines::processors::MergeProcessor;
+use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::transform_sort_partial::get_sort_descriptions;
+use crate::sessions::DatabendQueryContextRef;
+
+// The rows sampled from each sorted block to pick the range bounds.
+const SAMPLE_ROWS_PER_BLOCK: usize = 128;
+
+// The blocks per output held to pick the range bounds from, the blocks after them are split as they come.
+const SAMPLE_BLOCKS_PER_OUTPUT: usize = 4;
+
+/// Whether the rows sorted by `exprs` can be split into key ranges,
+/// every sort key must be a column of `schema` with an orderable type.
+pub fn can_range_partition(schema: &DataSchemaRef, exprs: &[Expression]) -> bool {
+    let sort_columns_descriptions = match get_sort_descriptions(schema, exprs) {
+        Ok(sort_columns_descriptions) => sort_columns_descriptions,
+        Err(_) => return false,
+    };
+
+    sort_columns_descriptions
+        .iter()
+        .all(|f| match schema.field_with_name(&f.column_name) {
+            Ok(field) => {
+                let data_type = field.data_type();
+                is_numeric(data_type)
+                    || is_date_or_date_time(data_type)
+                    || matches!(data_type, DataType::String | DataType::Boolean)
+            }
+            Err(_) => false,
+        })
+}
+
+// M sorted inputs--> N outputs of disjoint key ranges, in the sort order
+struct SortRangePartitionWorker {
+    ctx: DatabendQueryContextRef,
+    schema: DataSchemaRef,
+    exprs: Vec<Expression>,
+    n: usize,
+    shared_num: AtomicUsize,
+    started: bool,
+    receivers: Vec<Option<BlockReceiver>>,
+    merger: MergeProcessor,
+}
+
+impl SortRangePartitionWorker {
+    pub fn start(&mut self) -> Result<()> {
+        if self.started {
+            return Ok(());
+        }
+
+        let sort_columns_descriptions = get_sort_descriptions(&self.schema, &self.exprs)?;
+
+        // The outputs are merged at once and concatenated in order,
+        // a dropped block or a full channel error would lose rows.
+        let mut senders = Vec::with_capacity(self.n);
+        for _i in 0..self.n {
+            let (sender, receiver) = block_channel(1, BackpressureMode::Block);
+            senders.push(sender);
+            self.receivers.push(Some(receiver));
+        }
+
+        let stream = self.merger.merge()?;
+        let sample_blocks = self.n * SAMPLE_BLOCKS_PER_OUTPUT;
+        self.ctx.try_spawn(async move {
+            if let Err(e) =
+                Self::partition(&senders, stream, sample_blocks, &sort_columns_descriptions).await
+            {
+                Self::send_error(&senders, e).await;
+            }
+        })?;
+
+        self.started = true;
+        Ok(())
+    }
+
+    // The bounds are picked from the first `sample_blocks` blocks, which are the only ones held in memory,
+    // every other block is split as it comes, the outputs spill by themselves if they are too large.
+    async fn partition(
+        senders: &[BlockSender],
+        mut stream: SendableDataBlockStream,
+        sample_blocks: usize,
+        sort_columns_descriptions: &[SortColumnDescription],
+    ) -> Result<()> {
+        let mut prefix = vec![];
+        while prefix.len() < sample_blocks {
+            match stream.next().await {
+                None => break,
+                Some(block) => {
+                    let block = block?;
+                    if block.num_rows() > 0 {
+                        prefix.push(block);
+                    }
+                }
+            }
+        }
+
+        if prefix.is_empty() {
+            return Ok(());
+        }
+
+        let bounds = Self::bounds(&prefix, sort_columns_descriptions, senders.len())?;
+        for block in prefix {
+            Self::split(senders, &block, &bounds, sort_columns_descriptions).await?;
+        }
+
+        while let Some(block) = stream.next().await {
+            let block = block?;
+            if block.num_rows() > 0 {
+                Self::split(senders, &block, &bounds, sort_columns_descriptions).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn split(
+        senders: &[BlockSender],
+        block: &DataBlock,
+        bounds: &DataBlock,
+        sort_columns_descriptions: &[SortColumnDescription],
+    ) -> Result<()> {
+        let ranges = DataBlock::split_sorted_block(block, bounds, sort_columns_descriptions)?;
+        for (sender, range) in senders.iter().zip(ranges) {
+            if range.num_rows() == 0 {
+                continue;
+            }
+            sender.send(Ok(range)).await?;
+        }
+        Ok(())
+    }
+
+    // Pick the n - 1 bounds at the quantiles of the rows sampled evenly from every sorted block.
+    // A key out of the sampled range goes to the first or the last output, so the bounds only affect the balance.
+    fn bounds(
+        blocks: &[DataBlock],
+        sort_columns_descriptions: &[SortColumnDescription],
+        n: usize,
+    ) -> Result<DataBlock> {
+        let samples = blocks
+            .iter()
+            .map(|block| {
+                let rows = block.num_rows();
+                let sample_rows = rows.min(SAMPLE_ROWS_PER_BLOCK);
+                let indices = (0..sample_rows)
+                    .map(|i| (i * rows / sample_rows) as u32)
+                    .collect::<Vec<_>>();
+                DataBlock::block_take_by_indices(block, &[], &indices)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let samples = DataBlock::concat_blocks(&samples)?;
+        let samples = DataBlock::sort_block(&samples, sort_columns_descriptions, None)?;
+
+        let sample_rows = samples.num_rows();
+        let indices = (1..n)
+            .map(|i| (i * sample_rows / n) as u32)
+            .collect::<Vec<_>>();
+        DataBlock::block_take_by_indices(&samples, &[], &indices)
+    }
+
+    async fn send_error(senders: &[BlockSender], e: ErrorCode) {
+        for sender in senders {
+            if let Err(error) = sender.send(Err(e.clone())).await {
+                error!("Sort range partition cannot push error: {}", error);
+            }
+        }
+    }
+}
+
+/// Split the sorted outputs of the inputs into `n` disjoint key ranges, output `i` gets range `i`.
+///
+/// Every range is merged independently, concatenating the merged ranges in the output order
+/// gives the globally sorted rows.
+pub struct SortRangePartitionTransform {
+    worker: Arc<RwLock<SortRangePartitionWorker>>,
+    index: usize,
+}
+
+impl SortRangePartitionTransform {
+    pub fn create(
+        ctx: DatabendQueryContextRef,
+        schema: DataSchemaRef,
+        exprs: Vec<Expression>,
+        n: usize,
+    ) -> Self {
+        let worker = SortRangePartitionWorker {
+            ctx: ctx.clone(),
+            schema,
+            exprs,
+            n,
+            shared_num: AtomicUsize::new(0),
+            started: false,
+            receivers: vec![],
+            merger: MergeProcessor::create(ctx),
+        };
+
+        let index = worker.shared_num.fetch_add(1, Ordering::Relaxed);
+        Self {
+            worker: Arc::new(RwLock::new(worker)),
+            index,
+        }
+    }
+
+    pub fn share(&self) -> Result<Self> {
+        let worker = self.worker.read();
+        let index = worker.shared_num.fetch_add(1, Ordering::Relaxed);
+        if index >= worker.n {
+            return Err(ErrorCode::LogicalError(
+                "Sort range partition shared num overflow",
+            ));
+        }
+
+        Ok(Self {
+            worker: self.worker.clone(),
+            index,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for SortRangePartitionTransform {
+    fn name(&self) -> &str {
+        "SortRangePartitionTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        let mut worker = self.worker.write();
+        worker.merger.connect_to(input)
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        let worker = self.worker.read();
+        worker.merger.inputs()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        let receiver = {
+            let mut worker = self.worker.write();
+            worker.start()?;
+            worker.receivers[self.index].take()
+        }
+        .unwrap();
+
+        Ok(receiver.into_stream())
+    }
+}
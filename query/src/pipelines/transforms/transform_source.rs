@@ -13,24 +13,48 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::future::Future;
 use std::sync::Arc;
 
+use common_base::TrySpawn;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchema;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_infallible::RwLock;
+use common_planners::Extras;
+use common_planners::Partitions;
 use common_planners::ReadDataSourcePlan;
 use common_streams::CorrectWithSchemaStream;
 use common_streams::SendableDataBlockStream;
+use common_streams::ThrottleStream;
 use common_tracing::tracing;
+use futures::Stream;
+use futures::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
 
+use crate::catalogs::TablePtr;
 use crate::pipelines::processors::EmptyProcessor;
 use crate::pipelines::processors::Processor;
+use crate::sessions::DatabendQueryContext;
 use crate::sessions::DatabendQueryContextRef;
+use crate::sessions::ScanMetrics;
 
 pub struct SourceTransform {
     ctx: DatabendQueryContextRef,
     source_plan: ReadDataSourcePlan,
 }
 
+struct RetryState<F> {
+    read: F,
+    stream: Option<SendableDataBlockStream>,
+    // The blocks emitted by all the attempts, and the blocks read by the current attempt.
+    emitted: usize,
+    read_blocks: usize,
+    retried: u64,
+    failed: bool,
+}
+
 impl SourceTransform {
     pub fn try_create(
         ctx: DatabendQueryContextRef,
@@ -39,29 +63,405 @@ impl SourceTransform {
         Ok(SourceTransform { ctx, source_plan })
     }
 
-    async fn read_table(&self, _db: &str) -> Result<SendableDataBlockStream> {
+    fn get_table(&self) -> Result<TablePtr> {
         let table_id = self.source_plan.table_info.table_id;
         let table_ver = self.source_plan.table_info.version;
-        let table = if self.source_plan.tbl_args.is_none() {
-            self.ctx
+        if self.source_plan.tbl_args.is_none() {
+            Ok(self
+                .ctx
                 .get_table_by_id(table_id, Some(table_ver))?
                 .raw()
-                .clone()
+                .clone())
         } else {
             let func_meta = self.ctx.get_table_function(
                 &self.source_plan.table_info.name,
                 self.source_plan.tbl_args.clone(),
             )?;
-            func_meta.raw().clone().as_table()
+            Ok(func_meta.raw().clone().as_table())
+        }
+    }
+
+    async fn read_table(&self, _db: &str) -> Result<SendableDataBlockStream> {
+        let table = self.get_table()?;
+        let push_downs = Self::projected_push_downs(&self.source_plan, &table.schema()?);
+        let retries = self.ctx.get_config().storage.read_retries;
+        let readahead = self.ctx.get_settings().get_source_readahead()? as usize;
+        let table_stream = match (retries, readahead) {
+            (0, 0) => {
+                // TODO(xp): get_single_node_table_io_context() or
+                //           get_cluster_table_io_context()?
+                let io_ctx = Arc::new(self.ctx.get_cluster_table_io_context()?);
+                table.read(io_ctx, &push_downs).await?
+            }
+            (_, 0) => self.read_with_fallback(table, push_downs, retries).await?,
+            _ => Self::read_partitions(&self.ctx, table, push_downs, retries, readahead, true)?,
         };
-        // TODO(xp): get_single_node_table_io_context() or
-        //           get_cluster_table_io_context()?
-        let io_ctx = Arc::new(self.ctx.get_cluster_table_io_context()?);
-        let table_stream = table.read(io_ctx, &self.source_plan.push_downs);
+        let table_stream = Self::count_blocks(table_stream, self.ctx.get_scan_metrics());
+        let table_stream = Self::throttle(&self.ctx, table_stream)?;
+        Ok(Box::pin(self.ctx.try_create_abortable(table_stream)?))
+    }
+
+    /// Reads the table once, as if there were no retries, by a context recording the partitions it takes.
+    ///
+    /// Once the read fails with a storage error, it falls back to `read_partitions`:
+    /// the partitions taken are read again, then the rest one by one,
+    /// and the blocks emitted before the failure are skipped.
+    async fn read_with_fallback(
+        &self,
+        table: TablePtr,
+        push_downs: Option<Extras>,
+        retries: u64,
+    ) -> Result<SendableDataBlockStream> {
+        let taken = Arc::new(RwLock::new(vec![]));
+        let read_ctx = DatabendQueryContext::with_taken_partitions(&self.ctx, taken.clone());
+        let io_ctx = Arc::new(read_ctx.get_cluster_table_io_context()?);
+        let stream = table.read(io_ctx, &push_downs).await?;
+
+        let ctx = self.ctx.clone();
+        let fallback = move |emitted: usize| -> Result<SendableDataBlockStream> {
+            // The partitions are taken from the back of the queue.
+            let taken = taken.read().iter().rev().cloned().collect::<Partitions>();
+            let read_unpartitioned = taken.is_empty();
+
+            let mut reads = vec![];
+            if !taken.is_empty() {
+                let (ctx, table, push_downs) = (ctx.clone(), table.clone(), push_downs.clone());
+                let read = move || {
+                    let part_ctx = DatabendQueryContext::with_partitions(&ctx, taken.clone());
+                    let table = table.clone();
+                    let push_downs = push_downs.clone();
+                    async move {
+                        let io_ctx = Arc::new(part_ctx.get_cluster_table_io_context()?);
+                        table.read(io_ctx, &push_downs).await
+                    }
+                };
+                reads.push(Self::read_with_retry(read, retries - 1));
+            }
+            reads.push(Self::read_partitions(
+                &ctx,
+                table,
+                push_downs,
+                retries,
+                0,
+                read_unpartitioned,
+            )?);
+
+            let mut skipped = 0;
+            let blocks = futures::stream::iter(reads).flatten();
+            Ok(Box::pin(blocks.filter(move |item| {
+                let skip = item.is_ok() && skipped < emitted;
+                if skip {
+                    skipped += 1;
+                }
+                futures::future::ready(!skip)
+            })))
+        };
+
+        Ok(Box::pin(futures::stream::unfold(
+            (stream, 0, Some(fallback)),
+            |(mut stream, mut emitted, mut fallback)| async move {
+                match stream.next().await {
+                    None => None,
+                    Some(Ok(block)) => {
+                        emitted += 1;
+                        Some((Ok(block), (stream, emitted, fallback)))
+                    }
+                    Some(Err(e)) => match fallback.take() {
+                        Some(fallback) if Self::is_retryable(&e) => {
+                            tracing::warn!(
+                                "Read table failed, read the partitions again one by one: {}",
+                                e
+                            );
+                            let mut stream = match fallback(emitted) {
+                                Ok(stream) => stream,
+                                Err(e) => Box::pin(futures::stream::once(async { Err(e) })),
+                            };
+                            let item = stream.next().await?;
+                            Some((item, (stream, emitted, None)))
+                        }
+                        _ => Some((Err(e), (stream, emitted, None))),
+                    },
+                }
+            },
+        )))
+    }
+
+    /// Reads the partitions one by one, every one by a context holding only it,
+    /// so that a partition failed to read, e.g. by a storage error, can be read again from the start,
+    /// and the next `readahead` blocks can be read while the current one is processed.
+    ///
+    /// If no partition is left at all, the table is read once by `ctx` if `read_unpartitioned`,
+    /// e.g., a system table which reads no partitions.
+    fn read_partitions(
+        ctx: &DatabendQueryContextRef,
+        table: TablePtr,
+        push_downs: Option<Extras>,
+        retries: u64,
+        readahead: usize,
+        read_unpartitioned: bool,
+    ) -> Result<SendableDataBlockStream> {
+        let source_ctx = ctx.clone();
+        let mut first = read_unpartitioned;
+        let mut done = false;
+
+        let reads = std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+
+            let partitions = match source_ctx.try_get_partitions(1) {
+                Ok(partitions) if !partitions.is_empty() => Some(partitions),
+                // A table which reads no partitions, e.g. a system table, is read once by the context of the source.
+                Ok(_) if first => None,
+                Ok(_) => return None,
+                Err(e) => {
+                    done = true;
+                    let failed: SendableDataBlockStream =
+                        Box::pin(futures::stream::once(async { Err(e) }));
+                    return Some(failed);
+                }
+            };
+            first = false;
+            done = partitions.is_none();
+
+            let ctx = source_ctx.clone();
+            let table = table.clone();
+            let push_downs = push_downs.clone();
+            let read = move || {
+                let part_ctx = match &partitions {
+                    Some(partitions) => {
+                        DatabendQueryContext::with_partitions(&ctx, partitions.clone())
+                    }
+                    None => ctx.clone(),
+                };
+                let table = table.clone();
+                let push_downs = push_downs.clone();
+                async move {
+                    let io_ctx = Arc::new(part_ctx.get_cluster_table_io_context()?);
+                    table.read(io_ctx, &push_downs).await
+                }
+            };
+            Some(Self::read_with_retry(read, retries))
+        });
+
+        let reads = futures::stream::iter(reads);
+        match readahead {
+            0 => Ok(Box::pin(reads.flatten())),
+            depth => Self::readahead(ctx, reads, depth),
+        }
+    }
+
+    /// Reads the partitions of `reads` in a task of the query, ahead of the downstream,
+    /// so that the reads overlap with the processing of the blocks already read.
+    ///
+    /// The blocks are forwarded one by one, at most `depth` blocks read ahead are buffered,
+    /// the task waits for the downstream once it is full.
+    pub fn readahead(
+        ctx: &DatabendQueryContextRef,
+        reads: impl Stream<Item = SendableDataBlockStream> + Send + 'static,
+        depth: usize,
+    ) -> Result<SendableDataBlockStream> {
+        let (tx, rx) = common_base::tokio::sync::mpsc::channel(depth);
+
+        ctx.try_spawn(async move {
+            let mut blocks = Box::pin(reads.flatten());
+            while let Some(block) = blocks.next().await {
+                // The downstream is gone, e.g. the query is aborted.
+                if tx.send(block).await.is_err() {
+                    break;
+                }
+            }
+        })?;
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    /// Whether a read failed by `error` may succeed if it is done again, i.e., it failed to reach the storage.
+    pub fn is_retryable(error: &ErrorCode) -> bool {
+        [
+            ErrorCode::CannotReadFile("").code(),
+            ErrorCode::CannotConnectNode("").code(),
+            ErrorCode::Timeout("").code(),
+            ErrorCode::ReadFileError("").code(),
+            ErrorCode::DALTransportError("").code(),
+        ]
+        .contains(&error.code())
+    }
+
+    /// Reads a partition by `read`, and reads it again from the start if it fails by a storage error,
+    /// at most `retries` times.
+    ///
+    /// A table reads the blocks of a partition in the same order every time,
+    /// so the blocks emitted before the failure are skipped when it is read again.
+    pub fn read_with_retry<F, Fut>(read: F, retries: u64) -> SendableDataBlockStream
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<SendableDataBlockStream>> + Send + 'static,
+    {
+        let state = RetryState {
+            read,
+            stream: None,
+            emitted: 0,
+            read_blocks: 0,
+            retried: 0,
+            failed: false,
+        };
+
+        Box::pin(futures::stream::unfold(
+            state,
+            move |mut state| async move {
+                loop {
+                    if state.failed {
+                        return None;
+                    }
+
+                    let item = match state.stream.as_mut() {
+                        Some(stream) => stream.next().await,
+                        None => match (state.read)().await {
+                            Ok(stream) => {
+                                state.stream = Some(stream);
+                                continue;
+                            }
+                            Err(e) => Some(Err(e)),
+                        },
+                    };
+
+                    match item {
+                        None => return None,
+                        Some(Ok(block)) => {
+                            state.read_blocks += 1;
+                            if state.read_blocks > state.emitted {
+                                state.emitted = state.read_blocks;
+                                return Some((Ok(block), state));
+                            }
+                        }
+                        Some(Err(e)) if state.retried < retries && Self::is_retryable(&e) => {
+                            state.retried += 1;
+                            tracing::warn!(
+                                "Read partition failed, read it again from the start ({}/{}): {}",
+                                state.retried,
+                                retries,
+                                e
+                            );
+                            state.stream = None;
+                            state.read_blocks = 0;
+                        }
+                        Some(Err(e)) => {
+                            state.failed = true;
+                            return Some((Err(e), state));
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Attaches the min and max of every column to the blocks read, if `collect_block_min_max` is set.
+    /// They are computed on the blocks with the schema of the plan, i.e., after they are corrected.
+    fn collect_min_max(&self, stream: SendableDataBlockStream) -> Result<SendableDataBlockStream> {
+        if self.ctx.get_settings().get_collect_block_min_max()? == 0 {
+            return Ok(stream);
+        }
+
         Ok(Box::pin(
-            self.ctx.try_create_abortable(table_stream.await?)?,
+            stream.map(|block| block.map(DataBlock::attach_min_max)),
         ))
     }
+
+    /// Reports the partitions this source is done with into the progress of the context, as it advances.
+    ///
+    /// The rows read are reported by the tables as they decode the blocks, the partitions are reported here,
+    /// after every block and when the source finishes, so that a client can tell how far a long scan is.
+    fn report_progress(&self, stream: SendableDataBlockStream) -> SendableDataBlockStream {
+        let ctx = self.ctx.clone();
+        let partitions = ctx.get_partition_progress();
+        partitions.source_started();
+
+        Box::pin(futures::stream::unfold(stream, move |mut stream| {
+            let ctx = ctx.clone();
+            let partitions = partitions.clone();
+            async move {
+                let item = stream.next().await;
+                if item.is_none() {
+                    partitions.source_finished();
+                }
+                ctx.report_partition_progress();
+                item.map(|block| (block, stream))
+            }
+        }))
+    }
+
+    /// Caps the rate the blocks are read at, if `max_source_rows_per_second` or
+    /// `max_source_bytes_per_second` is set, e.g., for a background query.
+    ///
+    /// The limiter is shared by all the sources of the query, the rates cap them together.
+    pub fn throttle(
+        ctx: &DatabendQueryContextRef,
+        stream: SendableDataBlockStream,
+    ) -> Result<SendableDataBlockStream> {
+        let limiter = ctx.get_source_throttle()?;
+        if limiter.is_unlimited() {
+            return Ok(stream);
+        }
+
+        Ok(Box::pin(ThrottleStream::create_with_limiter(
+            stream, limiter,
+        )))
+    }
+
+    /// Push down the columns the plan requires as a projection, so that the table decodes only these columns.
+    ///
+    /// The projection push down optimizer narrows the schema of the read plan to the required columns,
+    /// the projection is the indices of these columns in the schema of the table.
+    /// Nothing is pushed down if all the columns are required, or a projection is already set.
+    pub fn projected_push_downs(
+        source_plan: &ReadDataSourcePlan,
+        table_schema: &DataSchema,
+    ) -> Option<Extras> {
+        let push_downs = &source_plan.push_downs;
+        if let Some(Extras {
+            projection: Some(_),
+            ..
+        }) = push_downs
+        {
+            return push_downs.clone();
+        }
+
+        let required = source_plan.table_info.schema.fields();
+        if required.len() >= table_schema.fields().len() {
+            return push_downs.clone();
+        }
+
+        let projection = required
+            .iter()
+            .map(|f| table_schema.index_of(f.name()))
+            .collect::<Result<Vec<_>>>();
+
+        match projection {
+            // A column not in the table, e.g. of a table function, reads all the columns.
+            Err(_) => push_downs.clone(),
+            Ok(projection) => {
+                let mut extras = push_downs.clone().unwrap_or_else(Extras::default);
+                extras.projection = Some(projection);
+                Some(extras)
+            }
+        }
+    }
+
+    /// Counts every block read into `metrics`.
+    ///
+    /// The blocks which can not match the pushed down filters are skipped by the table before they are read,
+    /// e.g., by `MemoryTable::read_blocks`, which counts them.
+    pub fn count_blocks(
+        stream: SendableDataBlockStream,
+        metrics: Arc<ScanMetrics>,
+    ) -> SendableDataBlockStream {
+        Box::pin(stream.inspect(move |item| {
+            if let Ok(block) = item {
+                metrics.incr_read(block.num_rows());
+            }
+        }))
+    }
 }
 
 #[async_trait::async_trait]
@@ -92,9 +492,11 @@ impl Processor for SourceTransform {
 
         // We need to keep the block struct with the schema
         // Because the table may not support require columns
-        Ok(Box::pin(CorrectWithSchemaStream::new(
+        let stream = Box::pin(CorrectWithSchemaStream::new(
             self.read_table(&db).await?,
             self.source_plan.table_info.schema.clone(),
-        )))
+        ));
+        let stream = self.collect_min_max(stream)?;
+        Ok(self.report_progress(stream))
     }
 }
@@ -0,0 +1,112 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_planners::ReadDataSourcePlan;
+use common_streams::CorrectWithSchemaStream;
+use common_streams::ProgressStream;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+
+use crate::datasources::table::memory::memory_table::MemoryTable;
+use crate::pipelines::processors::EmptyProcessor;
+use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::SourceTransform;
+use crate::sessions::DatabendQueryContextRef;
+
+/// The source of a memory engine table.
+///
+/// The blocks are served straight from the table held in the catalog,
+/// without partitions or table io context.
+pub struct MemorySourceTransform {
+    ctx: DatabendQueryContextRef,
+    source_plan: ReadDataSourcePlan,
+}
+
+impl MemorySourceTransform {
+    pub fn try_create(
+        ctx: DatabendQueryContextRef,
+        source_plan: ReadDataSourcePlan,
+    ) -> Result<Self> {
+        Ok(MemorySourceTransform { ctx, source_plan })
+    }
+
+    fn read_table(&self) -> Result<SendableDataBlockStream> {
+        let table_info = &self.source_plan.table_info;
+        let table = self
+            .ctx
+            .get_table_by_id(table_info.table_id, Some(table_info.version))?
+            .raw()
+            .clone();
+
+        let memory_table = table
+            .as_any()
+            .downcast_ref::<MemoryTable>()
+            .ok_or_else(|| {
+                ErrorCode::LogicalError(format!(
+                    "Table {}.{} is not a memory table",
+                    table_info.db, table_info.name
+                ))
+            })?;
+
+        let push_downs = SourceTransform::projected_push_downs(&self.source_plan, &table.schema()?);
+        let metrics = self.ctx.get_scan_metrics();
+        let blocks = memory_table.read_blocks(&push_downs, &metrics);
+
+        let stream = Box::pin(futures::stream::iter(blocks.into_iter().map(Ok)));
+        let stream = SourceTransform::throttle(&self.ctx, stream)?;
+        let stream = ProgressStream::try_create(stream, self.ctx.progress_callback()?)?;
+        let stream = SourceTransform::count_blocks(Box::pin(stream), metrics);
+        Ok(Box::pin(self.ctx.try_create_abortable(stream)?))
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for MemorySourceTransform {
+    fn name(&self) -> &str {
+        "MemorySourceTransform"
+    }
+
+    fn connect_to(&mut self, _: Arc<dyn Processor>) -> Result<()> {
+        Result::Err(ErrorCode::LogicalError(
+            "Cannot call MemorySourceTransform connect_to",
+        ))
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![Arc::new(EmptyProcessor::create())]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        let table_info = &self.source_plan.table_info;
+        tracing::debug!(
+            "execute, table:{:#}.{:#} ...",
+            table_info.db,
+            table_info.name
+        );
+
+        Ok(Box::pin(CorrectWithSchemaStream::new(
+            self.read_table()?,
+            table_info.schema.clone(),
+        )))
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use futures::TryStreamExt;
+
+use crate::interpreters::InterpreterFactory;
+use crate::pipelines::processors::*;
+use crate::sql::PlanParser;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_memory_test() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    for sql in [
+        "create table default.mem(a UInt64, b UInt64) Engine = Memory",
+        "insert into default.mem values(1, 11), (2, 22)",
+        "insert into default.mem values(3, 33)",
+    ] {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        executor.execute().await?;
+    }
+
+    // A single source serves the blocks of the memory table.
+    let sql = "select b, a + 1 as c from default.mem where a > 1";
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+    let pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+    let display = format!("{:?}", pipeline);
+    assert!(
+        display.contains("MemorySourceTransform × 1 processor"),
+        "{}",
+        display
+    );
+    assert!(!display.contains(" SourceTransform"), "{}", display);
+
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+    let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+    let result = executor.execute().await?.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+----+---+",
+        "| b  | c |",
+        "+----+---+",
+        "| 22 | 3 |",
+        "| 33 | 4 |",
+        "+----+---+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_scan_metrics_test() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    for sql in [
+        "create table default.mem(a UInt64) Engine = Memory",
+        "insert into default.mem values(1), (2)",
+        "insert into default.mem values(3), (4)",
+        "insert into default.mem values(5), (6)",
+    ] {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        executor.execute().await?;
+    }
+
+    // Only the last block can match, the other two are pruned by their min/max.
+    let plan =
+        PlanParser::create(ctx.clone()).build_from_sql("select a from default.mem where a > 4")?;
+    let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+    let result = executor.execute().await?.try_collect::<Vec<_>>().await?;
+    let expected = vec!["+---+", "| a |", "+---+", "| 5 |", "| 6 |", "+---+"];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    let metrics = ctx.get_scan_metrics_values();
+    assert_eq!(metrics.blocks_considered, 3);
+    assert_eq!(metrics.blocks_scanned, 1);
+    assert_eq!(metrics.blocks_pruned(), 2);
+    assert_eq!(metrics.rows_read, 2);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_memory_throttle_test() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    for sql in [
+        "create table default.mem(a UInt64) Engine = Memory",
+        "insert into default.mem values(1), (2)",
+        "insert into default.mem values(3), (4)",
+        "insert into default.mem values(5), (6)",
+    ] {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        executor.execute().await?;
+    }
+
+    // 6 rows at 10 rows/s take at least 0.5 second.
+    ctx.get_settings().set_max_source_rows_per_second(10)?;
+    let start = std::time::Instant::now();
+    let plan = PlanParser::create(ctx.clone()).build_from_sql("select a from default.mem")?;
+    let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+    let result = executor.execute().await?.try_collect::<Vec<_>>().await?;
+    let elapsed = start.elapsed();
+
+    let rows = result.iter().map(|b| b.num_rows()).sum::<usize>();
+    assert_eq!(6, rows);
+    assert!(
+        elapsed >= std::time::Duration::from_millis(500),
+        "{:?}",
+        elapsed
+    );
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+
+use common_datablocks::BlockMinMax;
+use common_datavalues::prelude::*;
+use common_planners::Expression;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CompareOp {
+    Eq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+impl CompareOp {
+    fn parse(op: &str) -> Option<Self> {
+        match op {
+            "=" => Some(CompareOp::Eq),
+            "<" => Some(CompareOp::Lt),
+            "<=" => Some(CompareOp::LtEq),
+            ">" => Some(CompareOp::Gt),
+            ">=" => Some(CompareOp::GtEq),
+            _ => None,
+        }
+    }
+
+    /// `a op b` is the same as `b op.flip() a`.
+    fn flip(self) -> Self {
+        match self {
+            CompareOp::Eq => CompareOp::Eq,
+            CompareOp::Lt => CompareOp::Gt,
+            CompareOp::LtEq => CompareOp::GtEq,
+            CompareOp::Gt => CompareOp::Lt,
+            CompareOp::GtEq => CompareOp::LtEq,
+        }
+    }
+}
+
+/// `column op literal`, extracted from a pushed down filter.
+#[derive(Clone, Debug)]
+struct ColumnPredicate {
+    column: String,
+    op: CompareOp,
+    value: DataValue,
+}
+
+impl ColumnPredicate {
+    /// Returns false only if no row whose column value lies in `[min, max]` can match.
+    fn may_match(&self, min: &DataValue, max: &DataValue) -> bool {
+        let (lo, hi) = match (
+            compare_value(min, &self.value),
+            compare_value(max, &self.value),
+        ) {
+            (Some(lo), Some(hi)) => (lo, hi),
+            _ => return true,
+        };
+
+        match self.op {
+            CompareOp::Eq => lo != Ordering::Greater && hi != Ordering::Less,
+            CompareOp::Lt => lo == Ordering::Less,
+            CompareOp::LtEq => lo != Ordering::Greater,
+            CompareOp::Gt => hi == Ordering::Greater,
+            CompareOp::GtEq => hi != Ordering::Less,
+        }
+    }
+}
+
+/// SourcePruner skips whole blocks before they are read, using the min/max of the
+/// columns referenced by the pushed down filters, stored along with the blocks.
+///
+/// Only conjunctions of simple `column op literal` comparisons are used, any
+/// other expression is ignored. Pruning is best-effort: the filter still has
+/// to be evaluated downstream on the blocks that are kept.
+#[derive(Clone, Debug, Default)]
+pub struct SourcePruner {
+    predicates: Vec<ColumnPredicate>,
+}
+
+impl SourcePruner {
+    pub fn create(filters: &[Expression]) -> Self {
+        let mut predicates = vec![];
+        for filter in filters {
+            Self::extract(filter, &mut predicates);
+        }
+        SourcePruner { predicates }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.predicates.is_empty()
+    }
+
+    /// Returns false if a block can not contain any row matching the filters,
+    /// `min_max` is the (min, max) of every column of the block, whose schema is `schema`.
+    pub fn may_match(&self, schema: &DataSchema, min_max: &BlockMinMax) -> bool {
+        self.predicates.iter().all(|predicate| {
+            let column = schema
+                .index_of(&predicate.column)
+                .ok()
+                .and_then(|i| min_max.get(i));
+            match column {
+                Some((min, max)) => predicate.may_match(min, max),
+                None => true,
+            }
+        })
+    }
+
+    fn extract(expr: &Expression, predicates: &mut Vec<ColumnPredicate>) {
+        if let Expression::BinaryExpression { left, op, right } = expr {
+            if op.to_lowercase() == "and" {
+                Self::extract(left, predicates);
+                Self::extract(right, predicates);
+                return;
+            }
+
+            let op = match CompareOp::parse(op) {
+                Some(op) => op,
+                None => return,
+            };
+
+            match (left.as_ref(), right.as_ref()) {
+                (Expression::Column(column), Expression::Literal { value, .. }) => {
+                    predicates.push(ColumnPredicate {
+                        column: column.clone(),
+                        op,
+                        value: value.clone(),
+                    })
+                }
+                (Expression::Literal { value, .. }, Expression::Column(column)) => {
+                    predicates.push(ColumnPredicate {
+                        column: column.clone(),
+                        op: op.flip(),
+                        value: value.clone(),
+                    })
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Compares integers exactly, other numbers as f64 and strings as bytes,
+/// None if not comparable.
+fn compare_value(a: &DataValue, b: &DataValue) -> Option<Ordering> {
+    match (a, b) {
+        (DataValue::String(Some(a)), DataValue::String(Some(b))) => Some(a.cmp(b)),
+        (a, b) => match (integer_value(a), integer_value(b)) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => match (float_value(a), float_value(b)) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ => None,
+            },
+        },
+    }
+}
+
+fn integer_value(v: &DataValue) -> Option<i128> {
+    match v {
+        DataValue::Int8(Some(v)) => Some(*v as i128),
+        DataValue::Int16(Some(v)) => Some(*v as i128),
+        DataValue::Int32(Some(v)) => Some(*v as i128),
+        DataValue::Int64(Some(v)) => Some(*v as i128),
+        DataValue::UInt8(Some(v)) => Some(*v as i128),
+        DataValue::UInt16(Some(v)) => Some(*v as i128),
+        DataValue::UInt32(Some(v)) => Some(*v as i128),
+        DataValue::UInt64(Some(v)) => Some(*v as i128),
+        _ => None,
+    }
+}
+
+fn float_value(v: &DataValue) -> Option<f64> {
+    match v {
+        DataValue::Float32(Some(v)) => Some(*v as f64),
+        DataValue::Float64(Some(v)) => Some(*v),
+        v => integer_value(v).map(|v| v as f64),
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::tokio;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_planners::*;
+use futures::TryStreamExt;
+use pretty_assertions::assert_eq;
+
+use crate::interpreters::InterpreterFactory;
+use crate::pipelines::processors::*;
+use crate::pipelines::transforms::*;
+use crate::sql::PlanParser;
+
+#[test]
+fn test_source_pruner() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]);
+    let block = DataBlock::create_by_array(schema.clone(), vec![Series::new(vec![10i64, 20, 30])]);
+    let min_max = DataBlock::compute_min_max(&block);
+
+    struct Test {
+        name: &'static str,
+        filter: Expression,
+        keep: bool,
+    }
+
+    let tests = vec![
+        Test {
+            name: "a > max",
+            filter: col("a").gt(lit(30i64)),
+            keep: false,
+        },
+        Test {
+            name: "a >= max",
+            filter: col("a").gt_eq(lit(30i64)),
+            keep: true,
+        },
+        Test {
+            name: "a < min",
+            filter: col("a").lt(lit(10i64)),
+            keep: false,
+        },
+        Test {
+            name: "a = in range",
+            filter: col("a").eq(lit(15i64)),
+            keep: true,
+        },
+        Test {
+            name: "literal on the left",
+            filter: lit(5i64).gt(col("a")),
+            keep: false,
+        },
+        Test {
+            name: "conjunction",
+            filter: col("a").gt(lit(10i64)).and(col("a").lt(lit(5i64))),
+            keep: false,
+        },
+        Test {
+            name: "unsupported expression",
+            filter: col("a").not_eq(lit(20i64)),
+            keep: true,
+        },
+        Test {
+            name: "unknown column",
+            filter: col("b").gt(lit(100i64)),
+            keep: true,
+        },
+    ];
+
+    for t in tests {
+        let pruner = SourcePruner::create(&[t.filter]);
+        assert_eq!(t.keep, pruner.may_match(&schema, &min_max), "{}", t.name);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_source_transform_with_filter_push_down() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_threads(1)?;
+
+    // 3 blocks: [1, 2], [3, 4], [5, 6].
+    for sql in [
+        "create table default.mem(a UInt64) Engine = Memory",
+        "insert into default.mem values(1), (2)",
+        "insert into default.mem values(3), (4)",
+        "insert into default.mem values(5), (6)",
+    ] {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        executor.execute().await?;
+    }
+
+    let table = ctx.get_table("default", "mem")?.raw().clone();
+    let io_ctx = Arc::new(ctx.get_single_node_table_io_context()?);
+    let predicate = col("a").gt(lit(5u64));
+
+    // Without push down: all the 3 blocks are read.
+    {
+        let source_plan = table.read_plan(io_ctx.clone(), None, None)?;
+        ctx.try_set_partitions(source_plan.parts.clone())?;
+        let source = SourceTransform::try_create(ctx.clone(), source_plan)?;
+        let result = source.execute().await?.try_collect::<Vec<_>>().await?;
+        assert_eq!(3, result.len());
+    }
+
+    let source_plan = table.read_plan(
+        io_ctx,
+        Some(Extras {
+            filters: vec![predicate.clone()],
+            ..Extras::default()
+        }),
+        None,
+    )?;
+
+    // With push down: only the last block may match, the other 2 blocks are pruned before they are read.
+    {
+        ctx.try_set_partitions(source_plan.parts.clone())?;
+        let source = SourceTransform::try_create(ctx.clone(), source_plan.clone())?;
+        let result = source.execute().await?.try_collect::<Vec<_>>().await?;
+        assert_eq!(1, result.len());
+        assert_eq!(2, result[0].num_rows());
+    }
+
+    // The filter still has to run downstream.
+    {
+        ctx.try_set_partitions(source_plan.parts.clone())?;
+
+        let mut pipeline = Pipeline::create(ctx.clone());
+        let schema = source_plan.schema();
+        pipeline.add_source(Arc::new(SourceTransform::try_create(
+            ctx.clone(),
+            source_plan,
+        )?))?;
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(WhereTransform::try_create(
+                schema.clone(),
+                predicate.clone(),
+            )?))
+        })?;
+
+        let result = pipeline.execute().await?.try_collect::<Vec<_>>().await?;
+        let expected = vec!["+---+", "| a |", "+---+", "| 6 |", "+---+"];
+        common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+    }
+
+    Ok(())
+}
@@ -12,14 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use common_base::tokio;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_datavalues::DataSchemaRefExt;
+use common_exception::ErrorCode;
 use common_exception::Result;
+use common_infallible::RwLock;
+use common_streams::SendableDataBlockStream;
+use futures::StreamExt;
 use futures::TryStreamExt;
 use pretty_assertions::assert_eq;
 
+use crate::interpreters::InterpreterFactory;
 use crate::pipelines::processors::*;
+use crate::pipelines::transforms::SourceTransform;
+use crate::sessions::DatabendQueryContext;
+use crate::sql::PlanParser;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn transform_source_test() -> Result<()> {
@@ -53,3 +66,316 @@ async fn transform_source_test() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_projection_push_down_test() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    // A wide table with 5 columns.
+    for sql in [
+        "create table default.wide(a UInt64, b UInt64, c UInt64, d UInt64, e UInt64) Engine = Memory",
+        "insert into default.wide values(0, 1, 2, 3, 4), (10, 11, 12, 13, 14)",
+    ] {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(sql)?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        executor.execute().await?;
+    }
+
+    let table = ctx.get_table("default", "wide")?.raw().clone();
+    let schema = table.schema()?;
+
+    let io_ctx = Arc::new(ctx.get_single_node_table_io_context()?);
+    let source_plan = table.read_plan(io_ctx.clone(), None, None)?;
+
+    // All the columns are required, nothing to push down.
+    let push_downs = SourceTransform::projected_push_downs(&source_plan, &schema);
+    assert_eq!(source_plan.push_downs, push_downs);
+
+    // The optimizer narrows the schema of the plan to `b` and `d`.
+    let mut narrowed = source_plan.clone();
+    narrowed.table_info = narrowed
+        .table_info
+        .clone()
+        .schema(DataSchemaRefExt::create(vec![
+            schema.field(1).clone(),
+            schema.field(3).clone(),
+        ]));
+
+    let push_downs = SourceTransform::projected_push_downs(&narrowed, &schema);
+    assert_eq!(Some(vec![1, 3]), push_downs.as_ref().unwrap().projection);
+
+    // Only the 2 required columns are read from the table.
+    ctx.try_set_partitions(source_plan.parts.clone())?;
+    let stream = table.read(io_ctx, &push_downs).await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    assert_eq!(2, result[0].num_columns());
+
+    let expected = vec![
+        "+----+----+",
+        "| b  | d  |",
+        "+----+----+",
+        "| 1  | 3  |",
+        "| 11 | 13 |",
+        "+----+----+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected.clone(), result.as_slice());
+
+    // The same through a query, with a filter on a column not selected.
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select b, d from default.wide where e > 0")?;
+    let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+    let result = executor.execute().await?.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+----+----+",
+        "| b  | d  |",
+        "+----+----+",
+        "| 1  | 3  |",
+        "| 11 | 13 |",
+        "+----+----+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_throttle_test() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_block_size(1000)?;
+    ctx.get_settings().set_max_source_rows_per_second(40000)?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    // 20000 rows at 40000 rows/s take at least 0.5 second.
+    let start = std::time::Instant::now();
+    let mut pipeline = Pipeline::create(ctx);
+    let source = test_source.number_source_transform_for_test(20000)?;
+    pipeline.add_source(Arc::new(source))?;
+
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let elapsed = start.elapsed();
+
+    let rows = result.iter().map(|b| b.num_rows()).sum::<usize>();
+    assert_eq!(20000, rows);
+    assert!(elapsed >= std::time::Duration::from_millis(500));
+    assert!(rows as f64 / elapsed.as_secs_f64() <= 40000.0);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn transform_source_throttle_shared_test() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_block_size(1000)?;
+    ctx.get_settings().set_max_source_rows_per_second(40000)?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    // 2 sources of 20000 rows share 40000 rows/s, they take at least 1 second together.
+    let start = std::time::Instant::now();
+    let mut pipeline = Pipeline::create(ctx);
+    for _ in 0..2 {
+        let source = test_source.number_source_transform_for_test(20000)?;
+        pipeline.add_source(Arc::new(source))?;
+    }
+
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let elapsed = start.elapsed();
+
+    let rows = result.iter().map(|b| b.num_rows()).sum::<usize>();
+    assert_eq!(40000, rows);
+    assert!(
+        elapsed >= std::time::Duration::from_secs(1),
+        "{:?}",
+        elapsed
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_progress_test() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_threads(8)?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    // 8 partitions of 10000 rows, a block for each.
+    let source = test_source.number_source_transform_for_test(80000)?;
+    let mut stream = source.execute().await?;
+
+    let percent = |ctx: &crate::sessions::DatabendQueryContextRef| {
+        let progress = ctx.get_progress_value();
+        progress.read_partitions * 100 / progress.total_partitions
+    };
+
+    let mut percents = vec![];
+    while let Some(block) = stream.next().await {
+        block?;
+        percents.push(percent(&ctx));
+    }
+    percents.push(percent(&ctx));
+
+    assert!(percents.windows(2).all(|w| w[0] <= w[1]), "{:?}", percents);
+    assert!(
+        percents.iter().any(|p| *p > 0 && *p < 100),
+        "{:?}",
+        percents
+    );
+    assert_eq!(Some(&100), percents.last());
+
+    let progress = ctx.get_progress_value();
+    assert_eq!(8, progress.total_partitions);
+    assert_eq!(8, progress.read_partitions);
+    assert_eq!(80000, progress.read_rows);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_read_retry_test() -> Result<()> {
+    fn block(n: u64) -> DataBlock {
+        let schema = DataSchemaRefExt::create(vec![DataField::new("n", DataType::UInt64, false)]);
+        DataBlock::create_by_array(schema, vec![Series::new(vec![n])])
+    }
+
+    // A partition of 3 blocks, the first read of which fails after the first block.
+    let mock_read = |failures: usize| {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let read = {
+            let attempts = attempts.clone();
+            move || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    let mut items = vec![Ok(block(0)), Ok(block(1)), Ok(block(2))];
+                    if attempt < failures {
+                        items.insert(1, Err(ErrorCode::DALTransportError("mock read failure")));
+                    }
+                    let stream: SendableDataBlockStream = Box::pin(futures::stream::iter(items));
+                    Ok(stream)
+                }
+            }
+        };
+        (read, attempts)
+    };
+
+    // Failed once, read again, every block is emitted exactly once.
+    {
+        let (read, attempts) = mock_read(1);
+        let stream = SourceTransform::read_with_retry(read, 3);
+        let result = stream.try_collect::<Vec<_>>().await?;
+        let expected = vec![
+            "+---+", "| n |", "+---+", "| 0 |", "| 1 |", "| 2 |", "+---+",
+        ];
+        common_datablocks::assert_blocks_eq(expected, result.as_slice());
+        assert_eq!(2, attempts.load(Ordering::SeqCst));
+    }
+
+    // Failed more times than the retries, the error is returned.
+    {
+        let (read, attempts) = mock_read(usize::MAX);
+        let stream = SourceTransform::read_with_retry(read, 2);
+        let result = stream.collect::<Vec<_>>().await;
+        assert_eq!(2, result.len());
+        assert!(result[0].is_ok());
+        assert_eq!(
+            ErrorCode::DALTransportError("").code(),
+            result[1].as_ref().unwrap_err().code()
+        );
+        assert_eq!(3, attempts.load(Ordering::SeqCst));
+    }
+
+    // Failed by an error other than a storage error, it is not read again.
+    {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let read = {
+            let attempts = attempts.clone();
+            move || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    let items = vec![Ok(block(0)), Err(ErrorCode::BadBytes("mock bad block"))];
+                    let stream: SendableDataBlockStream = Box::pin(futures::stream::iter(items));
+                    Ok(stream)
+                }
+            }
+        };
+        let stream = SourceTransform::read_with_retry(read, 3);
+        let result = stream.collect::<Vec<_>>().await;
+        assert_eq!(2, result.len());
+        assert_eq!(
+            ErrorCode::BadBytes("").code(),
+            result[1].as_ref().unwrap_err().code()
+        );
+        assert_eq!(1, attempts.load(Ordering::SeqCst));
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_taken_partitions_test() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings().set_max_threads(8)?;
+    let source_plan =
+        crate::tests::NumberTestData::create(ctx.clone()).number_read_source_plan_for_test(80)?;
+    assert_eq!(8, source_plan.parts.len());
+    ctx.try_set_partitions(source_plan.parts.clone())?;
+
+    // The recording context takes the partitions of the source, and records the ones it takes only.
+    let taken = Arc::new(RwLock::new(vec![]));
+    let read_ctx = DatabendQueryContext::with_taken_partitions(&ctx, taken.clone());
+    let mut read = read_ctx.try_get_partitions(2)?;
+    ctx.try_get_partitions(1)?;
+    read.extend(read_ctx.try_get_partitions(1)?);
+
+    assert_eq!(3, read.len());
+    assert_eq!(read, *taken.read());
+    assert_eq!(
+        source_plan.parts.len() - 4,
+        ctx.try_get_partitions(usize::MAX)?.len()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_readahead_test() -> Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    fn block(n: u64) -> DataBlock {
+        let schema = DataSchemaRefExt::create(vec![DataField::new("n", DataType::UInt64, false)]);
+        DataBlock::create_by_array(schema, vec![Series::new(vec![n])])
+    }
+
+    // 4 partitions of a block, every one takes 20ms to read and records when it starts.
+    let started = Arc::new(AtomicUsize::new(0));
+    let reads = {
+        let started = started.clone();
+        futures::stream::iter((0..4).map(move |n| {
+            let started = started.clone();
+            let read: SendableDataBlockStream = Box::pin(futures::stream::once(async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                Ok(block(n))
+            }));
+            read
+        }))
+    };
+
+    let mut stream = SourceTransform::readahead(&ctx, reads, 1)?;
+    stream.next().await.unwrap()?;
+    assert!(started.load(Ordering::SeqCst) >= 1);
+
+    // While the first block is processed downstream, the next partitions are read:
+    // the block of one is buffered, and the block of one more waits for the room in the buffer.
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert_eq!(3, started.load(Ordering::SeqCst));
+
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+---+", "| n |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, result.as_slice());
+    assert_eq!(4, started.load(Ordering::SeqCst));
+
+    Ok(())
+}
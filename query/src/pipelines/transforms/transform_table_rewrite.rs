@@ -0,0 +1,100 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchema;
+use common_datavalues::DataSchemaRef;
+use common_exception::Result;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use futures::TryStreamExt;
+
+use crate::catalogs::TablePtr;
+use crate::pipelines::processors::EmptyProcessor;
+use crate::pipelines::processors::Processor;
+use crate::sessions::DatabendQueryContextRef;
+
+/// Replace all the rows of a table with the rows of the input, e.g., the rows kept by a delete.
+///
+/// The rows are streamed into `Table::overwrite`, which swaps them in only once the input is done,
+/// so the table is left unchanged if the input fails or the table is written to concurrently.
+pub struct TableRewriteTransform {
+    ctx: DatabendQueryContextRef,
+    table: TablePtr,
+    schema: DataSchemaRef,
+    input: Arc<dyn Processor>,
+}
+
+impl TableRewriteTransform {
+    pub fn try_create(ctx: DatabendQueryContextRef, table: TablePtr) -> Result<Self> {
+        let schema = table.schema()?;
+        Ok(TableRewriteTransform {
+            ctx,
+            table,
+            schema,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for TableRewriteTransform {
+    fn name(&self) -> &str {
+        "TableRewriteTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        tracing::debug!("execute...");
+
+        // Taken before the input starts reading the table, so that any write in between
+        // makes the overwrite fail instead of being lost.
+        let base_version = self.table.data_version();
+
+        // The blocks are written with the schema of the table, whatever their field names are.
+        let schema = self.schema.clone();
+        let stream = self
+            .input
+            .execute()
+            .await?
+            .map_ok(move |block| DataBlock::create(schema.clone(), block.columns().to_vec()));
+
+        let io_ctx = Arc::new(self.ctx.get_cluster_table_io_context()?);
+        self.table
+            .overwrite(io_ctx, Box::pin(stream), base_version)
+            .await?;
+
+        Ok(Box::pin(DataBlockStream::create(
+            Arc::new(DataSchema::empty()),
+            None,
+            vec![],
+        )))
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_planners::Expression;
+use common_planners::WindowFunction;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use futures::StreamExt;
+
+use crate::pipelines::processors::EmptyProcessor;
+use crate::pipelines::processors::Processor;
+
+/// Computes a ranking function over the partitions of the input.
+///
+/// The input must be sorted by the partition keys then the order keys,
+/// so that the rows of one partition are adjacent and ordered.
+pub struct WindowTransform {
+    func: WindowFunction,
+    schema: DataSchemaRef,
+    partition_by: Vec<Expression>,
+    order_by: Vec<Expression>,
+    input: Arc<dyn Processor>,
+}
+
+impl WindowTransform {
+    pub fn try_create(
+        func: WindowFunction,
+        schema: DataSchemaRef,
+        partition_by: Vec<Expression>,
+        order_by: Vec<Expression>,
+    ) -> Result<Self> {
+        Ok(WindowTransform {
+            func,
+            schema,
+            partition_by,
+            order_by,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+}
+
+/// The ranking state, kept across the blocks of the input.
+#[derive(Default)]
+struct WindowState {
+    partition: Option<Vec<DataValue>>,
+    order: Option<Vec<DataValue>>,
+    row_number: u64,
+    rank: u64,
+    dense_rank: u64,
+}
+
+impl WindowState {
+    fn next(
+        &mut self,
+        func: WindowFunction,
+        partition: Vec<DataValue>,
+        order: Vec<DataValue>,
+    ) -> u64 {
+        if self.partition.as_ref() != Some(&partition) {
+            self.partition = Some(partition);
+            self.order = None;
+            self.row_number = 0;
+            self.rank = 0;
+            self.dense_rank = 0;
+        }
+
+        self.row_number += 1;
+        // Rows tied on the order keys share the same rank.
+        if self.order.as_ref() != Some(&order) {
+            self.order = Some(order);
+            self.rank = self.row_number;
+            self.dense_rank += 1;
+        }
+
+        match func {
+            WindowFunction::RowNumber => self.row_number,
+            WindowFunction::Rank => self.rank,
+            WindowFunction::DenseRank => self.dense_rank,
+        }
+    }
+}
+
+fn key_columns<'a>(block: &'a DataBlock, exprs: &[Expression]) -> Result<Vec<&'a DataColumn>> {
+    exprs
+        .iter()
+        .map(|expr| block.try_column_by_name(&expr.column_name()))
+        .collect()
+}
+
+fn key_values(columns: &[&DataColumn], row: usize) -> Result<Vec<DataValue>> {
+    columns.iter().map(|column| column.try_get(row)).collect()
+}
+
+fn rank_block(
+    func: WindowFunction,
+    schema: &DataSchemaRef,
+    partition_by: &[Expression],
+    order_by: &[Expression],
+    state: &mut WindowState,
+    block: DataBlock,
+) -> Result<DataBlock> {
+    let partition_columns = key_columns(&block, partition_by)?;
+    let order_columns = key_columns(&block, order_by)?;
+
+    let mut values = Vec::with_capacity(block.num_rows());
+    for row in 0..block.num_rows() {
+        let partition = key_values(&partition_columns, row)?;
+        let order = key_values(&order_columns, row)?;
+        values.push(state.next(func, partition, order));
+    }
+
+    let mut columns = block.columns().to_vec();
+    columns.push(DataColumn::Array(Series::new(values)));
+    Ok(DataBlock::create(schema.clone(), columns))
+}
+
+#[async_trait::async_trait]
+impl Processor for WindowTransform {
+    fn name(&self) -> &str {
+        "WindowTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        tracing::debug!("execute...");
+
+        let func = self.func;
+        let schema = self.schema.clone();
+        let partition_by = self.partition_by.clone();
+        let order_by = self.order_by.clone();
+        let mut state = WindowState::default();
+
+        let stream = self.input.execute().await?;
+        Ok(Box::pin(stream.map(move |block| {
+            rank_block(func, &schema, &partition_by, &order_by, &mut state, block?)
+        })))
+    }
+}
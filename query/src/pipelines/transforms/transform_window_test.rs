@@ -0,0 +1,124 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use common_planners::*;
+use futures::TryStreamExt;
+
+use crate::pipelines::processors::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_window() -> Result<()> {
+    struct Test {
+        name: &'static str,
+        func: WindowFunction,
+        expect: Vec<&'static str>,
+    }
+
+    // Partition 0: numbers 0, 2, 4, 6, 8 with (number % 3) 0, 2, 1, 0, 2
+    // Partition 1: numbers 1, 3, 5, 7, 9 with (number % 3) 1, 0, 2, 1, 0
+    let tests = vec![
+        Test {
+            name: "row_number",
+            func: WindowFunction::RowNumber,
+            expect: vec![
+                "+--------------+--------------+---+",
+                "| (number % 2) | (number % 3) | r |",
+                "+--------------+--------------+---+",
+                "| 0            | 0            | 1 |",
+                "| 0            | 0            | 2 |",
+                "| 0            | 1            | 3 |",
+                "| 0            | 2            | 4 |",
+                "| 0            | 2            | 5 |",
+                "| 1            | 0            | 1 |",
+                "| 1            | 0            | 2 |",
+                "| 1            | 1            | 3 |",
+                "| 1            | 1            | 4 |",
+                "| 1            | 2            | 5 |",
+                "+--------------+--------------+---+",
+            ],
+        },
+        Test {
+            name: "rank",
+            func: WindowFunction::Rank,
+            expect: vec![
+                "+--------------+--------------+---+",
+                "| (number % 2) | (number % 3) | r |",
+                "+--------------+--------------+---+",
+                "| 0            | 0            | 1 |",
+                "| 0            | 0            | 1 |",
+                "| 0            | 1            | 3 |",
+                "| 0            | 2            | 4 |",
+                "| 0            | 2            | 4 |",
+                "| 1            | 0            | 1 |",
+                "| 1            | 0            | 1 |",
+                "| 1            | 1            | 3 |",
+                "| 1            | 1            | 3 |",
+                "| 1            | 2            | 5 |",
+                "+--------------+--------------+---+",
+            ],
+        },
+        Test {
+            name: "dense_rank",
+            func: WindowFunction::DenseRank,
+            expect: vec![
+                "+--------------+--------------+---+",
+                "| (number % 2) | (number % 3) | r |",
+                "+--------------+--------------+---+",
+                "| 0            | 0            | 1 |",
+                "| 0            | 0            | 1 |",
+                "| 0            | 1            | 2 |",
+                "| 0            | 2            | 3 |",
+                "| 0            | 2            | 3 |",
+                "| 1            | 0            | 1 |",
+                "| 1            | 0            | 1 |",
+                "| 1            | 1            | 2 |",
+                "| 1            | 1            | 2 |",
+                "| 1            | 2            | 3 |",
+                "+--------------+--------------+---+",
+            ],
+        },
+    ];
+
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    for test in tests {
+        let source = PlanNode::ReadSource(test_source.number_read_source_plan_for_test(10)?);
+        let plan = PlanBuilder::from(&source)
+            .expression(
+                &[
+                    col("number"),
+                    modular(col("number"), lit(2)),
+                    modular(col("number"), lit(3)),
+                ],
+                "",
+            )?
+            .window(test.func, "r", &[col("(number % 2)")], &[sort(
+                "(number % 3)",
+                true,
+                false,
+            )])?
+            .project(&[col("(number % 2)"), col("(number % 3)"), col("r")])?
+            .build()?;
+
+        let mut pipeline = PipelineBuilder::create(ctx.clone()).build(&plan)?;
+        let stream = pipeline.execute().await?;
+        let result = stream.try_collect::<Vec<_>>().await?;
+        common_datablocks::assert_blocks_eq_with_name(test.name, test.expect, result.as_slice());
+    }
+
+    Ok(())
+}
@@ -35,6 +35,7 @@ use crate::servers::server::ListeningStream;
 use crate::servers::server::Server;
 use crate::sessions::SessionManager;
 use crate::sessions::SessionManagerRef;
+use crate::sessions::SessionProtocol;
 
 pub struct ClickHouseHandler {
     sessions: SessionManagerRef,
@@ -94,7 +95,12 @@ impl ClickHouseHandler {
     }
 
     fn accept_socket(sessions: Arc<SessionManager>, executor: Arc<Runtime>, socket: TcpStream) {
-        match sessions.create_session("ClickHouseSession") {
+        if !sessions.can_accept() {
+            let error = sessions.reject_connection();
+            return Self::reject_connection(socket, executor, error);
+        }
+
+        match sessions.create_session(SessionProtocol::ClickHouse) {
             Err(error) => Self::reject_connection(socket, executor, error),
             Ok(session) => {
                 log::info!("ClickHouse connection coming: {:?}", socket.peer_addr());
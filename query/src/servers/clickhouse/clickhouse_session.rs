@@ -36,8 +36,16 @@ impl ClickHouseConnection {
 
         std::thread::spawn(move || {
             let join_handle = query_executor.spawn(async move {
-                let interactive_worker = InteractiveWorker::create(session);
-                ClickHouseServer::run_on_stream(interactive_worker, non_blocking_stream).await
+                let interactive_worker = InteractiveWorker::create(session.clone());
+                let result =
+                    ClickHouseServer::run_on_stream(interactive_worker, non_blocking_stream).await;
+
+                if !session.is_aborting() {
+                    if let Err(error) = session.close().await {
+                        log::error!("Cannot close ClickHouse session {}", error);
+                    }
+                }
+                result
             });
 
             let _ = futures::executor::block_on(join_handle);
@@ -49,7 +57,7 @@ impl ClickHouseConnection {
     fn attach_session(session: &SessionRef, blocking_stream: &std::net::TcpStream) -> Result<()> {
         let host = blocking_stream.peer_addr().ok();
         let blocking_stream_ref = blocking_stream.try_clone()?;
-        session.attach(host, move || {
+        session.attach(host, None, move || {
             if let Err(error) = blocking_stream_ref.shutdown(Shutdown::Both) {
                 log::error!("Cannot shutdown ClickHouse session io {}", error);
             }
@@ -99,6 +99,16 @@ impl ClickHouseSession for InteractiveWorker {
     fn authenticate(&self, user: &str, password: &[u8], client_addr: &str) -> bool {
         let user_mgr = self.session.get_user_manager();
         if let Ok(res) = user_mgr.auth_user(user, password, client_addr) {
+            if res {
+                if let Err(cause) = self.session.set_current_user(user) {
+                    log::error!(
+                        "clickhouse load user settings failed, client_addr: {} user: {}, error: {}",
+                        client_addr,
+                        user,
+                        cause
+                    );
+                }
+            }
             return res;
         }
         log::error!(
@@ -36,6 +36,7 @@ use crate::servers::server::ListeningStream;
 use crate::servers::server::Server;
 use crate::sessions::SessionManager;
 use crate::sessions::SessionManagerRef;
+use crate::sessions::SessionProtocol;
 
 pub struct MySQLHandler {
     sessions: SessionManagerRef,
@@ -85,7 +86,12 @@ impl MySQLHandler {
     }
 
     fn accept_socket(sessions: Arc<SessionManager>, executor: Arc<Runtime>, socket: TcpStream) {
-        match sessions.create_session("MySQL") {
+        if !sessions.can_accept() {
+            let error = sessions.reject_connection();
+            return Self::reject_session(socket, executor, error);
+        }
+
+        match sessions.create_session(SessionProtocol::MySQL) {
             Err(error) => Self::reject_session(socket, executor, error),
             Ok(session) => {
                 log::info!("MySQL connection coming: {:?}", socket.peer_addr());
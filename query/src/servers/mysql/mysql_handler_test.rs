@@ -65,7 +65,7 @@ async fn test_rejected_session_with_sequence() -> Result<()> {
             Ok(_) => assert!(false, "Expected rejected connection"),
             Err(error) => {
                 assert_eq!(error.code(), 1000);
-                assert_eq!(error.message(), "Reject connection, cause: MySqlError { ERROR 1203 (42000): The current accept connection has exceeded mysql_handler_thread_num config }");
+                assert_eq!(error.message(), "Reject connection, cause: MySqlError { ERROR 1203 (42000): Too many connections, the max_active_sessions is 1 }");
             }
         };
 
@@ -102,7 +102,7 @@ async fn test_rejected_session_with_parallel() -> Result<()> {
                 Err(error) => {
                     destroy_barrier.wait();
                     assert_eq!(error.code(), 1000);
-                    assert_eq!(error.message(), "Reject connection, cause: MySqlError { ERROR 1203 (42000): The current accept connection has exceeded mysql_handler_thread_num config }");
+                    assert_eq!(error.message(), "Reject connection, cause: MySqlError { ERROR 1203 (42000): Too many connections, the max_active_sessions is 1 }");
                     CreateServerResult::Rejected
                 }
             }
@@ -59,7 +59,10 @@ impl<W: std::io::Write> MysqlShim<W> for InteractiveWorker<W> {
     }
 
     fn connect_id(&self) -> u32 {
-        u32::from_le_bytes([0x08, 0x00, 0x00, 0x00])
+        match self.session.get_client_conn_id() {
+            Some(conn_id) => conn_id,
+            None => u32::from_le_bytes([0x08, 0x00, 0x00, 0x00]),
+        }
     }
 
     fn default_auth_plugin(&self) -> &str {
@@ -116,6 +119,16 @@ impl<W: std::io::Write> MysqlShim<W> for InteractiveWorker<W> {
             if let Ok(res) =
                 user_mgr.auth_user(user_name.as_ref(), encode_password, &self.client_addr)
             {
+                if res {
+                    if let Err(cause) = self.session.set_current_user(user_name.as_ref()) {
+                        log::error!(
+                            "mysql load user settings failed, client_addr: {} user: {}, error: {}",
+                            self.client_addr,
+                            user_name,
+                            cause
+                        );
+                    }
+                }
                 return res;
             }
         }
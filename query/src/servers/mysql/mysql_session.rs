@@ -13,6 +13,8 @@
 // limitations under the License.
 
 use std::net::Shutdown;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
 
 use common_base::tokio::net::TcpStream;
 use common_exception::exception::ABORT_SESSION;
@@ -24,6 +26,9 @@ use msql_srv::MysqlIntermediary;
 use crate::servers::mysql::mysql_interactive_worker::InteractiveWorker;
 use crate::sessions::SessionRef;
 
+// The MySQL connection id is assigned by server, it is unique in the current process.
+static MYSQL_CONNECTION_ID: AtomicU32 = AtomicU32::new(1);
+
 pub struct MySQLConnection;
 
 impl MySQLConnection {
@@ -39,7 +44,7 @@ impl MySQLConnection {
 
     fn session_executor(session: SessionRef, blocking_stream: std::net::TcpStream) {
         let client_addr = blocking_stream.peer_addr().unwrap().to_string();
-        let interactive_worker = InteractiveWorker::create(session, client_addr);
+        let interactive_worker = InteractiveWorker::create(session.clone(), client_addr);
         if let Err(error) = MysqlIntermediary::run_on_tcp(interactive_worker, blocking_stream) {
             if error.code() != ABORT_SESSION {
                 log::error!(
@@ -48,12 +53,19 @@ impl MySQLConnection {
                 );
             }
         };
+
+        if !session.is_aborting() {
+            if let Err(error) = futures::executor::block_on(session.close()) {
+                log::error!("Cannot close MySQL session {}", error);
+            }
+        }
     }
 
     fn attach_session(session: &SessionRef, blocking_stream: &std::net::TcpStream) -> Result<()> {
         let host = blocking_stream.peer_addr().ok();
         let blocking_stream_ref = blocking_stream.try_clone()?;
-        session.attach(host, move || {
+        let conn_id = MYSQL_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+        session.attach(host, Some(conn_id), move || {
             if let Err(error) = blocking_stream_ref.shutdown(Shutdown::Both) {
                 log::error!("Cannot shutdown MySQL session io {}", error);
             }
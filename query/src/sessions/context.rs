@@ -36,6 +36,7 @@ use common_planners::PlanNode;
 use common_planners::Statistics;
 use common_streams::AbortStream;
 use common_streams::SendableDataBlockStream;
+use common_streams::ThrottleLimiter;
 
 use crate::catalogs::impls::DatabaseCatalog;
 use crate::catalogs::Catalog;
@@ -46,12 +47,20 @@ use crate::configs::Config;
 use crate::datasources::common::ContextDalBuilder;
 use crate::datasources::table_func_engine::TableArgs;
 use crate::sessions::context_shared::DatabendQueryContextShared;
+use crate::sessions::PartitionProgress;
+use crate::sessions::ScanMetrics;
+use crate::sessions::ScanMetricsValues;
 use crate::sessions::SessionManagerRef;
 use crate::sessions::Settings;
 
 pub struct DatabendQueryContext {
     statistics: Arc<RwLock<Statistics>>,
     partition_queue: Arc<RwLock<VecDeque<Part>>>,
+    // Whether the partitions taken from the queue are counted into the partition progress,
+    // they are not if they are counted already by the context they were taken from.
+    count_taken_partitions: bool,
+    // The partitions taken from the queue are recorded into it if any, e.g., to read them again.
+    taken_partitions: Option<Arc<RwLock<Partitions>>>,
     version: String,
     shared: Arc<DatabendQueryContextShared>,
 }
@@ -63,14 +72,55 @@ impl DatabendQueryContext {
         DatabendQueryContext::from_shared(other.shared.clone())
     }
 
+    /// Create a context of the same query as `other` to read only `partitions`,
+    /// e.g., to read again a partition taken from `other`, it is not counted as taken again.
+    pub fn with_partitions(
+        other: &DatabendQueryContextRef,
+        partitions: Partitions,
+    ) -> DatabendQueryContextRef {
+        DatabendQueryContext::create(
+            other.shared.clone(),
+            partitions.into_iter().collect(),
+            false,
+        )
+    }
+
+    /// Create a context of the same query as `other` to take the partitions of `other`,
+    /// the partitions it takes are recorded into `taken` in the order they are taken.
+    pub fn with_taken_partitions(
+        other: &DatabendQueryContextRef,
+        taken: Arc<RwLock<Partitions>>,
+    ) -> DatabendQueryContextRef {
+        other.shared.increment_ref_count();
+
+        Arc::new(DatabendQueryContext {
+            statistics: other.statistics.clone(),
+            partition_queue: other.partition_queue.clone(),
+            count_taken_partitions: other.count_taken_partitions,
+            taken_partitions: Some(taken),
+            version: other.version.clone(),
+            shared: other.shared.clone(),
+        })
+    }
+
     pub fn from_shared(shared: Arc<DatabendQueryContextShared>) -> DatabendQueryContextRef {
+        DatabendQueryContext::create(shared, VecDeque::new(), true)
+    }
+
+    fn create(
+        shared: Arc<DatabendQueryContextShared>,
+        partitions: VecDeque<Part>,
+        count_taken_partitions: bool,
+    ) -> DatabendQueryContextRef {
         shared.increment_ref_count();
 
         log::info!("Create DatabendQueryContext");
 
         Arc::new(DatabendQueryContext {
             statistics: Arc::new(RwLock::new(Statistics::default())),
-            partition_queue: Arc::new(RwLock::new(VecDeque::new())),
+            partition_queue: Arc::new(RwLock::new(partitions)),
+            count_taken_partitions,
+            taken_partitions: None,
             version: format!(
                 "DatabendQuery v-{}",
                 *crate::configs::DATABEND_COMMIT_VERSION
@@ -97,6 +147,45 @@ impl DatabendQueryContext {
         self.shared.progress.as_ref().get_and_reset()
     }
 
+    /// The block counters of the sources, shared by the query and its subqueries.
+    pub fn get_scan_metrics(&self) -> Arc<ScanMetrics> {
+        self.shared.scan_metrics.clone()
+    }
+
+    pub fn get_scan_metrics_values(&self) -> ScanMetricsValues {
+        self.shared.scan_metrics.get_values()
+    }
+
+    /// The limiter of the rate the sources of the query, and its subqueries, read at together,
+    /// by `max_source_rows_per_second` and `max_source_bytes_per_second`.
+    pub fn get_source_throttle(&self) -> Result<Arc<ThrottleLimiter>> {
+        let mut throttle = self.shared.source_throttle.lock();
+        if let Some(limiter) = throttle.as_ref() {
+            return Ok(limiter.clone());
+        }
+
+        let settings = self.get_settings();
+        let limiter = Arc::new(ThrottleLimiter::create(
+            settings.get_max_source_rows_per_second()?,
+            settings.get_max_source_bytes_per_second()?,
+        ));
+        *throttle = Some(limiter.clone());
+        Ok(limiter)
+    }
+
+    /// The partitions the sources of the query, and its subqueries, are done with.
+    pub fn get_partition_progress(&self) -> Arc<PartitionProgress> {
+        self.shared.partition_progress.clone()
+    }
+
+    /// Report the partitions the sources are done with since the last report into the progress.
+    pub fn report_partition_progress(&self) {
+        let completed = self.shared.partition_progress.advance();
+        if completed > 0 {
+            self.shared.progress.incr_read_partitions(completed);
+        }
+    }
+
     // Some table can estimate the approx total rows, such as NumbersTable
     pub fn add_total_rows_approx(&self, total_rows: usize) {
         self.shared
@@ -117,11 +206,18 @@ impl DatabendQueryContext {
                 }
             }
         }
+        if self.count_taken_partitions {
+            self.shared.partition_progress.incr_taken(partitions.len());
+        }
+        if let Some(taken) = &self.taken_partitions {
+            taken.write().extend(partitions.iter().cloned());
+        }
         Ok(partitions)
     }
 
     // Update the context partition pool from the pipeline builder.
     pub fn try_set_partitions(&self, partitions: Partitions) -> Result<()> {
+        self.shared.progress.add_total_partitions(partitions.len());
         for part in partitions {
             self.partition_queue.write().push_back(part);
         }
@@ -171,6 +267,11 @@ impl DatabendQueryContext {
         self.shared.init_query_id.as_ref().read().clone()
     }
 
+    /// Abort the sources of the query, the same as killing the query.
+    pub fn kill(&self) {
+        self.shared.kill();
+    }
+
     pub fn try_create_abortable(&self, input: SendableDataBlockStream) -> Result<AbortStream> {
         let (abort_handle, abort_stream) = AbortStream::try_create(input)?;
         self.shared.add_source_abort_handle(abort_handle);
@@ -203,6 +304,34 @@ impl DatabendQueryContext {
         self.shared.get_settings()
     }
 
+    /// Override the max_threads setting for this query only, without changing the session setting.
+    /// `None` removes the override.
+    /// It must be set before the query starts to run, the query runtime is sized by it on creation.
+    pub fn set_max_threads_hint(&self, hint: Option<u64>) -> Result<()> {
+        if hint == Some(0) {
+            return Err(ErrorCode::BadArguments(
+                "Hint max_threads must be greater than 0",
+            ));
+        }
+        *self.shared.max_threads_hint.write() = hint;
+        Ok(())
+    }
+
+    /// The max_threads override of this query, it is sent along with the plans run by other nodes.
+    pub fn get_max_threads_hint(&self) -> Option<u64> {
+        *self.shared.max_threads_hint.read()
+    }
+
+    /// The max number of threads of this query: the query hint if any, otherwise the session setting.
+    pub fn get_max_threads(&self) -> Result<u64> {
+        self.shared.get_max_threads()
+    }
+
+    /// Apply a setting and persist it for the user of the session.
+    pub fn set_persistent_setting(&self, name: &str, value: String) -> Result<()> {
+        self.shared.session.set_persistent_setting(name, value)
+    }
+
     pub fn get_config(&self) -> Config {
         self.shared.conf.clone()
     }
@@ -235,8 +364,7 @@ impl DatabendQueryContext {
             ..Default::default()
         })];
 
-        let settings = self.get_settings();
-        let max_threads = settings.get_max_threads()? as usize;
+        let max_threads = self.get_max_threads()? as usize;
 
         Ok(TableIOContext::new(
             self.get_shared_runtime()?,
@@ -251,8 +379,7 @@ impl DatabendQueryContext {
     pub fn get_cluster_table_io_context(self: &Arc<Self>) -> Result<TableIOContext> {
         let cluster = self.get_cluster();
         let nodes = cluster.get_nodes();
-        let settings = self.get_settings();
-        let max_threads = settings.get_max_threads()? as usize;
+        let max_threads = self.get_max_threads()? as usize;
 
         Ok(TableIOContext::new(
             self.get_shared_runtime()?,
@@ -23,6 +23,7 @@ use common_exception::Result;
 use common_infallible::Mutex;
 use common_infallible::RwLock;
 use common_planners::PlanNode;
+use common_streams::ThrottleLimiter;
 use futures::future::AbortHandle;
 use uuid::Uuid;
 
@@ -31,6 +32,8 @@ use crate::catalogs::Catalog;
 use crate::catalogs::TableMeta;
 use crate::clusters::ClusterRef;
 use crate::configs::Config;
+use crate::sessions::PartitionProgress;
+use crate::sessions::ScanMetrics;
 use crate::sessions::Session;
 use crate::sessions::Settings;
 
@@ -58,6 +61,12 @@ pub struct DatabendQueryContextShared {
     pub(in crate::sessions) running_query: Arc<RwLock<Option<String>>>,
     pub(in crate::sessions) running_plan: Arc<RwLock<Option<PlanNode>>>,
     pub(in crate::sessions) tables_meta: Arc<Mutex<HashMap<DatabaseAndTable, Arc<TableMeta>>>>,
+    /// Overrides the max_threads setting for this query only.
+    pub(in crate::sessions) max_threads_hint: Arc<RwLock<Option<u64>>>,
+    pub(in crate::sessions) scan_metrics: Arc<ScanMetrics>,
+    /// Caps the rate all the sources of the query read at, created from the settings by the first source.
+    pub(in crate::sessions) source_throttle: Arc<Mutex<Option<Arc<ThrottleLimiter>>>>,
+    pub(in crate::sessions) partition_progress: Arc<PartitionProgress>,
 }
 
 impl DatabendQueryContextShared {
@@ -79,6 +88,10 @@ impl DatabendQueryContextShared {
             running_query: Arc::new(RwLock::new(None)),
             running_plan: Arc::new(RwLock::new(None)),
             tables_meta: Arc::new(Mutex::new(HashMap::new())),
+            max_threads_hint: Arc::new(RwLock::new(None)),
+            scan_metrics: Arc::new(ScanMetrics::create()),
+            source_throttle: Arc::new(Mutex::new(None)),
+            partition_progress: Arc::new(PartitionProgress::create()),
         })
     }
 
@@ -135,8 +148,7 @@ impl DatabendQueryContextShared {
         match &*query_runtime {
             Some(query_runtime) => Ok(query_runtime.clone()),
             None => {
-                let settings = self.get_settings();
-                let max_threads = settings.get_max_threads()? as usize;
+                let max_threads = self.get_max_threads()? as usize;
                 let runtime = Arc::new(Runtime::with_worker_threads(max_threads)?);
                 *query_runtime = Some(runtime.clone());
                 Ok(runtime)
@@ -144,6 +156,15 @@ impl DatabendQueryContextShared {
         }
     }
 
+    /// The max number of threads of this query.
+    /// The query hint takes precedence over the session setting, which defaults to the config.
+    pub fn get_max_threads(&self) -> Result<u64> {
+        match *self.max_threads_hint.read() {
+            Some(hint) => Ok(hint),
+            None => self.get_settings().get_max_threads(),
+        }
+    }
+
     pub fn attach_query_str(&self, query: &str) {
         let mut running_query = self.running_query.write();
         *running_query = Some(query.to_string());
@@ -162,7 +183,7 @@ impl DatabendQueryContextShared {
 
 impl Session {
     pub(in crate::sessions) fn destroy_context_shared(&self) {
-        let mut mutable_state = self.mutable_state.lock();
+        let mut mutable_state = self.lock_state("destroy_context_shared");
         mutable_state.context_shared.take();
     }
 }
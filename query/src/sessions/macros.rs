@@ -44,12 +44,17 @@ macro_rules! apply_initial_settings {
 }
 
 macro_rules! apply_parse_value {
-    ($VALUE: expr, String) => {
+    ($NAME: expr, $VALUE: expr, String) => {
         $VALUE
     };
 
-    ($VALUE: expr, $TYPE: tt) => {
-        $VALUE.parse::<$TYPE>().map_err(ErrorCode::from)?
+    ($NAME: expr, $VALUE: expr, $TYPE: tt) => {
+        $VALUE.parse::<$TYPE>().map_err(|e| {
+            ErrorCode::BadArguments(format!(
+                "Cannot set setting {} to {:?}, cause: {}",
+                $NAME, $VALUE, e
+            ))
+        })?
     };
 }
 
@@ -59,7 +64,7 @@ macro_rules! apply_update_settings {
             paste::paste! {
                 $(
                     if (key.to_lowercase().as_str() == $NAME) {
-                        let v = apply_parse_value!{value, $TYPE};
+                        let v = apply_parse_value!{$NAME, value, $TYPE};
                         return self.inner.[<try_update_ $TYPE:lower>]($NAME, v);
                     }
                 )*
@@ -68,6 +73,20 @@ macro_rules! apply_update_settings {
                 format!("Unknown variable: {:?}", key)
             ))
         }
+
+        pub fn update_default_settings(&self, key: &str, value: String) -> Result<()> {
+            paste::paste! {
+                $(
+                    if (key.to_lowercase().as_str() == $NAME) {
+                        let v = apply_parse_value!{$NAME, value, $TYPE};
+                        return self.inner.[<try_update_default_ $TYPE:lower>]($NAME, v);
+                    }
+                )*
+            }
+            Err(ErrorCode::UnknownVariable(
+                format!("Unknown variable: {:?}", key)
+            ))
+        }
     };
 }
 
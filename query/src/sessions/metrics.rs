@@ -14,3 +14,4 @@
 
 pub static METRIC_SESSION_CONNECT_NUMBERS: &str = "session.connect_numbers";
 pub static METRIC_SESSION_CLOSE_NUMBERS: &str = "session.close_numbers";
+pub static METRIC_SESSION_REJECTED_NUMBERS: &str = "session.rejected_numbers";
@@ -12,26 +12,48 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(test)]
+mod session_ref_test;
+#[cfg(test)]
+mod session_test;
+#[cfg(test)]
+mod sessions_test;
+#[cfg(test)]
+mod settings_test;
+
 #[macro_use]
 mod macros;
 
 mod context;
 mod context_shared;
 mod metrics;
+mod partition_progress;
+mod scan_metrics;
 mod session;
 mod session_info;
+mod session_lock;
+mod session_protocol;
 mod session_ref;
 #[allow(clippy::module_inception)]
 mod sessions;
 mod sessions_info;
 mod settings;
+mod transaction;
 
 pub use context::DatabendQueryContext;
 pub use context::DatabendQueryContextRef;
 pub use context_shared::DatabendQueryContextShared;
+pub use partition_progress::PartitionProgress;
+pub use scan_metrics::ScanMetrics;
+pub use scan_metrics::ScanMetricsValues;
 pub use session::Session;
 pub use session_info::ProcessInfo;
+pub use session_info::SessionInfo;
+pub use session_protocol::SessionProtocol;
 pub use session_ref::SessionRef;
 pub use sessions::SessionManager;
 pub use sessions::SessionManagerRef;
+pub use sessions::ShutdownSummary;
+pub use settings::SettingChange;
 pub use settings::Settings;
+pub use transaction::TransactionState;
@@ -0,0 +1,56 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Tracks how many partitions the sources of a query are done with, to report the progress of a long scan.
+///
+/// A source does not tell when it is done with a partition, but a source takes the next partitions
+/// only after it is done with the previous ones, and it is done with all of them when it finishes.
+/// Thus, with every running source holding at most one partition, `taken - running` partitions are done.
+/// It never overestimates, and it reaches all the partitions taken when all the sources finish.
+#[derive(Debug, Default)]
+pub struct PartitionProgress {
+    taken: AtomicUsize,
+    running_sources: AtomicUsize,
+    completed: AtomicUsize,
+}
+
+impl PartitionProgress {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    /// `partitions` are taken from the partition pool by a source.
+    pub fn incr_taken(&self, partitions: usize) {
+        self.taken.fetch_add(partitions, Ordering::Relaxed);
+    }
+
+    pub fn source_started(&self) {
+        self.running_sources.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn source_finished(&self) {
+        self.running_sources.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of partitions completed since the last call, so that the progress never goes back.
+    pub fn advance(&self) -> usize {
+        let running = self.running_sources.load(Ordering::Relaxed);
+        let done = self.taken.load(Ordering::Relaxed).saturating_sub(running);
+        let prev = self.completed.fetch_max(done, Ordering::Relaxed);
+        done.saturating_sub(prev)
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Counters of the blocks read by the sources of a query,
+/// they tell how effective the pushed down filters are.
+///
+/// A block is the unit skipped by the pushed down filters, by the min and max of its columns.
+#[derive(Debug, Default)]
+pub struct ScanMetrics {
+    blocks_considered: AtomicUsize,
+    blocks_scanned: AtomicUsize,
+    rows_read: AtomicUsize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScanMetricsValues {
+    pub blocks_considered: usize,
+    pub blocks_scanned: usize,
+    /// The rows of every block read from the tables, whether or not they are filtered out later.
+    pub rows_read: usize,
+}
+
+impl ScanMetrics {
+    pub fn create() -> Self {
+        Self::default()
+    }
+
+    /// A block of `rows` is read by a source.
+    pub fn incr_read(&self, rows: usize) {
+        self.blocks_considered.fetch_add(1, Ordering::Relaxed);
+        self.blocks_scanned.fetch_add(1, Ordering::Relaxed);
+        self.rows_read.fetch_add(rows, Ordering::Relaxed);
+    }
+
+    /// `blocks` are skipped by the pushed down filters, they are not read.
+    pub fn incr_pruned(&self, blocks: usize) {
+        self.blocks_considered.fetch_add(blocks, Ordering::Relaxed);
+    }
+
+    pub fn get_values(&self) -> ScanMetricsValues {
+        ScanMetricsValues {
+            blocks_considered: self.blocks_considered.load(Ordering::Relaxed),
+            blocks_scanned: self.blocks_scanned.load(Ordering::Relaxed),
+            rows_read: self.rows_read.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl ScanMetricsValues {
+    pub fn blocks_pruned(&self) -> usize {
+        self.blocks_considered - self.blocks_scanned
+    }
+}
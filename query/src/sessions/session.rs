@@ -15,7 +15,9 @@
 use std::net::SocketAddr;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::SystemTime;
 
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_infallible::Mutex;
 use futures::channel::oneshot::Sender;
@@ -27,49 +29,61 @@ use crate::sessions::context_shared::DatabendQueryContextShared;
 use crate::sessions::DatabendQueryContext;
 use crate::sessions::DatabendQueryContextRef;
 use crate::sessions::SessionManagerRef;
+use crate::sessions::SessionProtocol;
 use crate::sessions::Settings;
+use crate::sessions::TransactionState;
 use crate::users::UserManagerRef;
 
 pub(in crate::sessions) struct MutableStatus {
     pub(in crate::sessions) abort: bool,
     pub(in crate::sessions) current_database: String,
+    pub(in crate::sessions) current_user: Option<String>,
     pub(in crate::sessions) session_settings: Arc<Settings>,
     pub(in crate::sessions) client_host: Option<SocketAddr>,
+    pub(in crate::sessions) client_conn_id: Option<u32>,
     pub(in crate::sessions) io_shutdown_tx: Option<Sender<Sender<()>>>,
     pub(in crate::sessions) context_shared: Option<Arc<DatabendQueryContextShared>>,
+    pub(in crate::sessions) transaction: TransactionState,
 }
 
 #[derive(Clone)]
 pub struct Session {
     pub(in crate::sessions) id: String,
-    pub(in crate::sessions) typ: String,
+    pub(in crate::sessions) protocol: SessionProtocol,
     pub(in crate::sessions) config: Config,
     pub(in crate::sessions) sessions: SessionManagerRef,
     pub(in crate::sessions) ref_count: Arc<AtomicUsize>,
+    /// Only locked through `lock_state`, see `session_lock` for the lock rules.
     pub(in crate::sessions) mutable_state: Arc<Mutex<MutableStatus>>,
+    pub(in crate::sessions) created_at: SystemTime,
 }
 
 impl Session {
     pub fn try_create(
         config: Config,
         id: String,
-        typ: String,
+        protocol: SessionProtocol,
         sessions: SessionManagerRef,
     ) -> Result<Arc<Session>> {
+        let session_settings = sessions.create_settings()?;
         Ok(Arc::new(Session {
             id,
-            typ,
+            protocol,
             config,
             sessions,
             ref_count: Arc::new(AtomicUsize::new(0)),
             mutable_state: Arc::new(Mutex::new(MutableStatus {
                 abort: false,
                 current_database: String::from("default"),
-                session_settings: Settings::try_create()?,
+                current_user: None,
+                session_settings,
                 client_host: None,
+                client_conn_id: None,
                 io_shutdown_tx: None,
                 context_shared: None,
+                transaction: TransactionState::None,
             })),
+            created_at: SystemTime::now(),
         }))
     }
 
@@ -78,15 +92,23 @@ impl Session {
     }
 
     pub fn get_type(self: &Arc<Self>) -> String {
-        self.typ.clone()
+        self.protocol.to_string()
+    }
+
+    pub fn get_protocol(self: &Arc<Self>) -> SessionProtocol {
+        self.protocol
+    }
+
+    pub fn get_client_conn_id(self: &Arc<Self>) -> Option<u32> {
+        self.lock_state("get_client_conn_id").client_conn_id
     }
 
     pub fn is_aborting(self: &Arc<Self>) -> bool {
-        self.mutable_state.lock().abort
+        self.lock_state("is_aborting").abort
     }
 
     pub fn kill(self: &Arc<Self>) {
-        let mut mutable_state = self.mutable_state.lock();
+        let mut mutable_state = self.lock_state("kill");
 
         mutable_state.abort = true;
         if mutable_state.context_shared.is_none() {
@@ -100,13 +122,37 @@ impl Session {
         }
     }
 
+    /// The id of the query the session is executing, None if it is idle.
+    pub fn get_current_query_id(self: &Arc<Self>) -> Option<String> {
+        let mutable_state = self.lock_state("get_current_query_id");
+        mutable_state
+            .context_shared
+            .as_ref()
+            .map(|shared| shared.init_query_id.read().clone())
+    }
+
+    /// Close the session on a clean disconnect, release the session resources.
+    pub async fn close(self: &Arc<Self>) -> Result<()> {
+        let mut mutable_state = self.lock_state("close");
+        mutable_state.context_shared.take();
+        mutable_state.io_shutdown_tx.take();
+        Ok(())
+    }
+
+    /// Whether the session is executing a query, it becomes false once the query finished.
+    pub fn has_running_query(self: &Arc<Self>) -> bool {
+        self.lock_state("has_running_query")
+            .context_shared
+            .is_some()
+    }
+
     pub fn force_kill_session(self: &Arc<Self>) {
         self.force_kill_query();
         self.kill(/* shutdown io stream */);
     }
 
     pub fn force_kill_query(self: &Arc<Self>) {
-        let mut mutable_state = self.mutable_state.lock();
+        let mut mutable_state = self.lock_state("force_kill_query");
 
         if let Some(context_shared) = mutable_state.context_shared.take() {
             context_shared.kill(/* shutdown executing query */);
@@ -118,7 +164,7 @@ impl Session {
     /// We can bind the environment to the context in create_context method.
     pub async fn create_context(self: &Arc<Self>) -> Result<DatabendQueryContextRef> {
         let context_shared = {
-            let mutable_state = self.mutable_state.lock();
+            let mutable_state = self.lock_state("create_context");
             mutable_state.context_shared.as_ref().map(Clone::clone)
         };
 
@@ -126,13 +172,11 @@ impl Session {
             Some(shared) => DatabendQueryContext::from_shared(shared.clone()),
             None => {
                 let config = self.config.clone();
-                let discovery = self.sessions.get_cluster_discovery();
-
                 let session = self.clone();
-                let cluster = discovery.discover().await?;
+                let cluster = self.sessions.get_cluster().await?;
                 let shared = DatabendQueryContextShared::try_create(config, session, cluster);
 
-                let mut mutable_state = self.mutable_state.lock();
+                let mut mutable_state = self.lock_state("create_context");
 
                 match mutable_state.context_shared.as_ref() {
                     Some(shared) => DatabendQueryContext::from_shared(shared.clone()),
@@ -145,11 +189,18 @@ impl Session {
         })
     }
 
-    pub fn attach<F>(self: &Arc<Self>, host: Option<SocketAddr>, io_shutdown: F)
-    where F: FnOnce() + Send + 'static {
+    pub fn attach<F>(
+        self: &Arc<Self>,
+        host: Option<SocketAddr>,
+        conn_id: Option<u32>,
+        io_shutdown: F,
+    ) where
+        F: FnOnce() + Send + 'static,
+    {
         let (tx, rx) = futures::channel::oneshot::channel();
-        let mut inner = self.mutable_state.lock();
+        let mut inner = self.lock_state("attach");
         inner.client_host = host;
+        inner.client_conn_id = conn_id;
         inner.io_shutdown_tx = Some(tx);
 
         common_base::tokio::spawn(async move {
@@ -161,17 +212,113 @@ impl Session {
     }
 
     pub fn set_current_database(self: &Arc<Self>, database_name: String) {
-        let mut inner = self.mutable_state.lock();
+        let mut inner = self.lock_state("set_current_database");
         inner.current_database = database_name;
     }
 
     pub fn get_current_database(self: &Arc<Self>) -> String {
-        let inner = self.mutable_state.lock();
+        let inner = self.lock_state("get_current_database");
         inner.current_database.clone()
     }
 
     pub fn get_settings(self: &Arc<Self>) -> Arc<Settings> {
-        self.mutable_state.lock().session_settings.clone()
+        self.lock_state("get_settings").session_settings.clone()
+    }
+
+    pub fn get_current_user(self: &Arc<Self>) -> Option<String> {
+        self.lock_state("get_current_user").current_user.clone()
+    }
+
+    /// Bind the authenticated user to the session and apply the settings persisted for it.
+    pub fn set_current_user(self: &Arc<Self>, user: &str) -> Result<()> {
+        self.lock_state("set_current_user").current_user = Some(user.to_string());
+
+        let session_settings = self.get_settings();
+        for setting in self.get_user_manager().get_user_settings(user)? {
+            // A setting removed or changed since it was persisted must not refuse the login.
+            if let Err(cause) = session_settings.update_settings(&setting.name, setting.value) {
+                log::warn!(
+                    "Ignore persisted setting {} of user {}, cause: {}",
+                    setting.name,
+                    user,
+                    cause
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply a setting to the session and persist it for the current user as the statement runs,
+    /// it is applied to the next sessions of the user too.
+    pub fn set_persistent_setting(self: &Arc<Self>, name: &str, value: String) -> Result<()> {
+        let user = self.get_current_user().ok_or_else(|| {
+            ErrorCode::UnknownUser("SET PERSISTENT requires an authenticated user")
+        })?;
+
+        // Validated by the session first, an invalid value is never persisted.
+        self.get_settings().update_settings(name, value.clone())?;
+        self.get_user_manager()
+            .set_user_setting(&user, &name.to_lowercase(), &value)
+    }
+
+    pub fn get_transaction_state(self: &Arc<Self>) -> TransactionState {
+        self.lock_state("get_transaction_state").transaction
+    }
+
+    /// Statements executed while in a transaction are flagged through this.
+    pub fn in_transaction(self: &Arc<Self>) -> bool {
+        self.get_transaction_state() != TransactionState::None
+    }
+
+    pub fn begin_transaction(self: &Arc<Self>) -> Result<()> {
+        let mut inner = self.lock_state("begin_transaction");
+        match inner.transaction {
+            TransactionState::None => {
+                inner.transaction = TransactionState::Active;
+                Ok(())
+            }
+            state => Err(ErrorCode::IllegalTransactionState(format!(
+                "Cannot begin transaction, current transaction state is {:?}",
+                state
+            ))),
+        }
+    }
+
+    pub fn commit_transaction(self: &Arc<Self>) -> Result<()> {
+        let mut inner = self.lock_state("commit_transaction");
+        match inner.transaction {
+            TransactionState::Active => {
+                inner.transaction = TransactionState::None;
+                Ok(())
+            }
+            TransactionState::Failed => Err(ErrorCode::IllegalTransactionState(
+                "Current transaction is failed, only ROLLBACK is accepted",
+            )),
+            TransactionState::None => Err(ErrorCode::IllegalTransactionState(
+                "Cannot commit, there is no active transaction",
+            )),
+        }
+    }
+
+    pub fn rollback_transaction(self: &Arc<Self>) -> Result<()> {
+        let mut inner = self.lock_state("rollback_transaction");
+        match inner.transaction {
+            TransactionState::Active | TransactionState::Failed => {
+                inner.transaction = TransactionState::None;
+                Ok(())
+            }
+            TransactionState::None => Err(ErrorCode::IllegalTransactionState(
+                "Cannot rollback, there is no active transaction",
+            )),
+        }
+    }
+
+    /// Mark the active transaction as failed after a statement error.
+    pub fn fail_transaction(self: &Arc<Self>) {
+        let mut inner = self.lock_state("fail_transaction");
+        if inner.transaction == TransactionState::Active {
+            inner.transaction = TransactionState::Failed;
+        }
     }
 
     pub fn get_sessions_manager(self: &Arc<Self>) -> SessionManagerRef {
@@ -14,36 +14,72 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::UNIX_EPOCH;
 
 use crate::sessions::session::MutableStatus;
 use crate::sessions::Session;
+use crate::sessions::SessionProtocol;
 use crate::sessions::Settings;
 
 pub struct ProcessInfo {
     pub id: String,
     pub typ: String,
+    pub protocol: SessionProtocol,
     pub state: String,
     pub database: String,
     #[allow(unused)]
     pub settings: Arc<Settings>,
     pub client_address: Option<SocketAddr>,
+    pub client_conn_id: Option<u32>,
     pub session_extra_info: Option<String>,
 }
 
+/// A snapshot of a live session, e.g., a row of `SHOW PROCESSLIST`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub typ: String,
+    pub client_host: Option<String>,
+    pub database: String,
+    pub has_running_query: bool,
+    /// Seconds since the unix epoch when the session was created.
+    pub created_at: u64,
+}
+
 impl Session {
+    pub fn session_info(self: &Arc<Self>) -> SessionInfo {
+        let status = self.lock_state("session_info");
+        let created_at = self
+            .created_at
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        SessionInfo {
+            id: self.id.clone(),
+            typ: self.protocol.to_string(),
+            client_host: status.client_host.map(|host| host.to_string()),
+            database: status.current_database.clone(),
+            has_running_query: status.context_shared.is_some(),
+            created_at,
+        }
+    }
+
     pub fn process_info(self: &Arc<Self>) -> ProcessInfo {
-        let session_mutable_state = self.mutable_state.lock();
+        let session_mutable_state = self.lock_state("process_info");
         self.to_process_info(&session_mutable_state)
     }
 
     fn to_process_info(self: &Arc<Self>, status: &MutableStatus) -> ProcessInfo {
         ProcessInfo {
             id: self.id.clone(),
-            typ: self.typ.clone(),
+            typ: self.protocol.to_string(),
+            protocol: self.protocol,
             state: self.process_state(status),
             database: status.current_database.clone(),
             settings: status.session_settings.clone(),
             client_address: status.client_host,
+            client_conn_id: status.client_conn_id,
             session_extra_info: self.process_extra_info(status),
         }
     }
@@ -57,8 +93,8 @@ impl Session {
     }
 
     fn process_extra_info(self: &Arc<Self>, status: &MutableStatus) -> Option<String> {
-        match self.typ.as_str() {
-            "RPCSession" => Session::rpc_extra_info(status),
+        match self.protocol {
+            SessionProtocol::RPC => Session::rpc_extra_info(status),
             _ => Session::query_extra_info(status),
         }
     }
@@ -0,0 +1,108 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(debug_assertions)]
+use std::cell::RefCell;
+#[cfg(debug_assertions)]
+use std::ops::Deref;
+use std::ops::DerefMut;
+#[cfg(debug_assertions)]
+use std::sync::Arc;
+
+use crate::sessions::session::MutableStatus;
+use crate::sessions::Session;
+
+// The mutable state lock of a session is not reentrant:
+// a method must never call another locking method of the session while it holds the guard,
+// and must release the guard before any await, e.g. `create_context` locks, releases, awaits, then re-locks.
+//
+// With debug assertions, every thread keeps the session states it holds,
+// so that re-entering a lock panics with the methods involved instead of deadlocking.
+// It compiles out entirely in release.
+
+#[cfg(debug_assertions)]
+thread_local! {
+    // The address of every session state the current thread holds the lock of, and the method holding it.
+    static HELD_STATES: RefCell<Vec<(usize, &'static str)>> = RefCell::new(vec![]);
+}
+
+impl Session {
+    /// Lock the mutable state of the session, `method` names the caller.
+    #[cfg(not(debug_assertions))]
+    #[inline]
+    pub(in crate::sessions) fn lock_state(
+        &self,
+        _method: &'static str,
+    ) -> impl DerefMut<Target = MutableStatus> + '_ {
+        self.mutable_state.lock()
+    }
+
+    /// Lock the mutable state of the session, `method` names the caller.
+    /// Panics if the current thread already holds it.
+    #[cfg(debug_assertions)]
+    pub(in crate::sessions) fn lock_state(
+        &self,
+        method: &'static str,
+    ) -> impl DerefMut<Target = MutableStatus> + '_ {
+        let state = Arc::as_ptr(&self.mutable_state) as usize;
+
+        HELD_STATES.with(|held| {
+            if let Some((_, holder)) = held.borrow().iter().find(|(s, _)| *s == state) {
+                panic!(
+                    "Session::{} locks the session state already held by Session::{} on the same thread, it would deadlock",
+                    method, holder
+                );
+            }
+        });
+
+        let guard = self.mutable_state.lock();
+        HELD_STATES.with(|held| held.borrow_mut().push((state, method)));
+        TrackedGuard { guard, state }
+    }
+}
+
+#[cfg(debug_assertions)]
+struct TrackedGuard<G> {
+    guard: G,
+    state: usize,
+}
+
+#[cfg(debug_assertions)]
+impl<G: Deref<Target = MutableStatus>> Deref for TrackedGuard<G> {
+    type Target = MutableStatus;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+#[cfg(debug_assertions)]
+impl<G: DerefMut<Target = MutableStatus>> DerefMut for TrackedGuard<G> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+#[cfg(debug_assertions)]
+impl<G> Drop for TrackedGuard<G> {
+    fn drop(&mut self) {
+        let state = self.state;
+        HELD_STATES.with(|held| {
+            let mut held = held.borrow_mut();
+            if let Some(i) = held.iter().rposition(|(s, _)| *s == state) {
+                held.remove(i);
+            }
+        });
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// The client protocol which a session is serving.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SessionProtocol {
+    MySQL,
+    ClickHouse,
+    HTTP,
+    RPC,
+    /// Sessions created by the server itself, e.g. tests or background tasks.
+    Internal,
+}
+
+impl Display for SessionProtocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionProtocol::MySQL => write!(f, "MySQL"),
+            SessionProtocol::ClickHouse => write!(f, "ClickHouseSession"),
+            SessionProtocol::HTTP => write!(f, "HTTPSession"),
+            SessionProtocol::RPC => write!(f, "RPCSession"),
+            SessionProtocol::Internal => write!(f, "InternalSession"),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::Ordering;
+
+use common_base::tokio;
+use common_exception::Result;
+
+use crate::sessions::SessionProtocol;
+use crate::tests::SessionManagerBuilder;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_session_ref_destroy_once() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+
+    // Extra manager references must not affect the session lifetime.
+    let _sessions_clone = sessions.clone();
+
+    let session = sessions.create_session(SessionProtocol::Internal)?;
+    let other_session = sessions.create_session(SessionProtocol::Internal)?;
+    let session_id = session.get_id();
+
+    let session_clones = (0..5).map(|_| session.clone()).collect::<Vec<_>>();
+    assert_eq!(session.ref_count.load(Ordering::Relaxed), 6);
+
+    drop(session_clones);
+    assert_eq!(session.ref_count.load(Ordering::Relaxed), 1);
+    assert!(sessions.get_session(&session_id).is_some());
+    assert_eq!(sessions.active_sessions.read().len(), 2);
+
+    drop(session);
+    assert!(sessions.get_session(&session_id).is_none());
+    assert_eq!(sessions.active_sessions.read().len(), 1);
+
+    drop(other_session);
+    assert_eq!(sessions.active_sessions.read().len(), 0);
+
+    Ok(())
+}
@@ -0,0 +1,226 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_exception::Result;
+use futures::TryStreamExt;
+
+use crate::interpreters::InterpreterFactory;
+use crate::sessions::SessionProtocol;
+use crate::sessions::SessionRef;
+use crate::sessions::TransactionState;
+use crate::sql::PlanParser;
+use crate::tests::SessionManagerBuilder;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_session_transaction() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let session = sessions.create_session(SessionProtocol::Internal)?;
+
+    assert_eq!(session.get_transaction_state(), TransactionState::None);
+    assert!(!session.in_transaction());
+
+    // Begin -> Commit.
+    {
+        session.begin_transaction()?;
+        assert_eq!(session.get_transaction_state(), TransactionState::Active);
+        assert!(session.in_transaction());
+
+        session.commit_transaction()?;
+        assert_eq!(session.get_transaction_state(), TransactionState::None);
+    }
+
+    // Begin -> Fail -> Rollback.
+    {
+        session.begin_transaction()?;
+        session.fail_transaction();
+        assert_eq!(session.get_transaction_state(), TransactionState::Failed);
+
+        session.rollback_transaction()?;
+        assert_eq!(session.get_transaction_state(), TransactionState::None);
+    }
+
+    // Fail without active transaction is no-op.
+    {
+        session.fail_transaction();
+        assert_eq!(session.get_transaction_state(), TransactionState::None);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_session_illegal_transaction_transitions() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let session = sessions.create_session(SessionProtocol::Internal)?;
+
+    // Commit without active transaction.
+    {
+        let result = session.commit_transaction();
+        assert!(result.is_err());
+        let error = result.unwrap_err();
+        assert_eq!(error.code(), 57);
+        assert_eq!(
+            error.message(),
+            "Cannot commit, there is no active transaction"
+        );
+    }
+
+    // Rollback without active transaction.
+    {
+        let result = session.rollback_transaction();
+        assert!(result.is_err());
+        let error = result.unwrap_err();
+        assert_eq!(error.code(), 57);
+        assert_eq!(
+            error.message(),
+            "Cannot rollback, there is no active transaction"
+        );
+    }
+
+    // Begin inside an active transaction.
+    {
+        session.begin_transaction()?;
+        let result = session.begin_transaction();
+        assert!(result.is_err());
+        let error = result.unwrap_err();
+        assert_eq!(error.code(), 57);
+        assert_eq!(
+            error.message(),
+            "Cannot begin transaction, current transaction state is Active"
+        );
+        assert_eq!(session.get_transaction_state(), TransactionState::Active);
+    }
+
+    // Begin and commit inside a failed transaction.
+    {
+        session.fail_transaction();
+
+        let result = session.begin_transaction();
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().message(),
+            "Cannot begin transaction, current transaction state is Failed"
+        );
+
+        let result = session.commit_transaction();
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().message(),
+            "Current transaction is failed, only ROLLBACK is accepted"
+        );
+        assert_eq!(session.get_transaction_state(), TransactionState::Failed);
+
+        session.rollback_transaction()?;
+        assert_eq!(session.get_transaction_state(), TransactionState::None);
+    }
+
+    Ok(())
+}
+
+async fn execute_query(session: &SessionRef, query: &str) -> Result<()> {
+    let ctx = session.create_context().await?;
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+    let stream = InterpreterFactory::get(ctx, plan)?.execute().await?;
+    stream.try_collect::<Vec<_>>().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_session_persistent_settings() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+
+    // Persist a setting, the plain SET stays in the session.
+    {
+        let session = sessions.create_session(SessionProtocol::Internal)?;
+        session.set_current_user("test-user1")?;
+        execute_query(&session, "SET PERSISTENT max_block_size = 1000").await?;
+        execute_query(&session, "SET sort_merge_fan_in = 4").await?;
+        assert_eq!(session.get_settings().get_max_block_size()?, 1000);
+        assert_eq!(session.get_settings().get_sort_merge_fan_in()?, 4);
+        session.close().await?;
+    }
+
+    // A new session of the same user is created with the persisted setting.
+    {
+        let session = sessions.create_session(SessionProtocol::Internal)?;
+        session.set_current_user("test-user1")?;
+        assert_eq!(session.get_current_user(), Some("test-user1".to_string()));
+        assert_eq!(session.get_settings().get_max_block_size()?, 1000);
+        assert_eq!(session.get_settings().get_sort_merge_fan_in()?, 0);
+    }
+
+    // The other users are not affected.
+    {
+        let session = sessions.create_session(SessionProtocol::Internal)?;
+        session.set_current_user("test-user2")?;
+        assert_eq!(session.get_settings().get_max_block_size()?, 10000);
+    }
+
+    // Nothing to persist the setting for without a user.
+    {
+        let session = sessions.create_session(SessionProtocol::Internal)?;
+        let result = execute_query(&session, "SET PERSISTENT max_block_size = 1000").await;
+        assert!(result.is_err());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_session_persistent_settings_written_through() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let persisted_max_block_size = |user: &str| -> Result<u64> {
+        let session = sessions.create_session(SessionProtocol::Internal)?;
+        session.set_current_user(user)?;
+        session.get_settings().get_max_block_size()
+    };
+
+    // The setting is written to meta as the statement runs, the last one wins.
+    {
+        let session = sessions.create_session(SessionProtocol::Internal)?;
+        session.set_current_user("test-user1")?;
+        execute_query(&session, "SET PERSISTENT max_block_size = 2000").await?;
+        assert_eq!(persisted_max_block_size("test-user1")?, 2000);
+
+        execute_query(&session, "SET PERSISTENT max_block_size = 3000").await?;
+        assert_eq!(persisted_max_block_size("test-user1")?, 3000);
+    }
+
+    // A killed session keeps the settings persisted before.
+    {
+        let session = sessions.create_session(SessionProtocol::Internal)?;
+        session.set_current_user("test-user2")?;
+        execute_query(&session, "SET PERSISTENT max_block_size = 2000").await?;
+
+        session.kill();
+        drop(session);
+        assert_eq!(persisted_max_block_size("test-user2")?, 2000);
+    }
+
+    Ok(())
+}
+
+#[cfg(debug_assertions)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[should_panic(
+    expected = "Session::get_current_query_id locks the session state already held by Session::test_session_lock_reentry on the same thread"
+)]
+async fn test_session_lock_reentry() {
+    let sessions = SessionManagerBuilder::create().build().unwrap();
+    let session = sessions.create_session(SessionProtocol::Internal).unwrap();
+
+    let _state = session.lock_state("test_session_lock_reentry");
+    session.get_current_query_id();
+}
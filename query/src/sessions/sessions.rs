@@ -16,8 +16,12 @@ use std::collections::hash_map::Entry::Occupied;
 use std::collections::hash_map::Entry::Vacant;
 use std::collections::HashMap;
 use std::future::Future;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use common_base::tokio;
 use common_base::tokio::sync::mpsc::Receiver;
@@ -31,21 +35,36 @@ use crate::catalogs::impls::DatabaseCatalog;
 use crate::catalogs::Catalog;
 use crate::clusters::ClusterDiscovery;
 use crate::clusters::ClusterDiscoveryRef;
+use crate::clusters::ClusterRef;
 use crate::configs::Config;
 use crate::datasources::database::example::ExampleDatabaseEngine;
 use crate::sessions::session::Session;
 use crate::sessions::session_ref::SessionRef;
+use crate::sessions::SessionProtocol;
+use crate::sessions::Settings;
 use crate::users::UserManager;
 use crate::users::UserManagerRef;
 
 pub struct SessionManager {
     pub(in crate::sessions) conf: Config,
     pub(in crate::sessions) discovery: ClusterDiscoveryRef,
+    // The last discovered cluster and when it was discovered.
+    pub(in crate::sessions) discovered_cluster: Arc<RwLock<Option<(Instant, ClusterRef)>>>,
     pub(in crate::sessions) catalog: Arc<DatabaseCatalog>,
     pub(in crate::sessions) user: UserManagerRef,
 
     pub(in crate::sessions) max_sessions: usize,
     pub(in crate::sessions) active_sessions: Arc<RwLock<HashMap<String, Arc<Session>>>>,
+    pub(in crate::sessions) shutting_down: Arc<AtomicBool>,
+    pub(in crate::sessions) rejected_connections: Arc<AtomicU64>,
+    // The defaults applied by apply_global_setting, in the order they are applied.
+    pub(in crate::sessions) global_settings: Arc<RwLock<Vec<(String, String)>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShutdownSummary {
+    pub finished_sessions: usize,
+    pub force_killed_sessions: usize,
 }
 
 pub type SessionManagerRef = Arc<SessionManager>;
@@ -66,9 +85,13 @@ impl SessionManager {
             catalog,
             conf,
             discovery,
+            discovered_cluster: Arc::new(RwLock::new(None)),
             user,
             max_sessions: max_active_sessions,
             active_sessions: Arc::new(RwLock::new(HashMap::with_capacity(max_active_sessions))),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            rejected_connections: Arc::new(AtomicU64::new(0)),
+            global_settings: Arc::new(RwLock::new(vec![])),
         }))
     }
 
@@ -80,6 +103,32 @@ impl SessionManager {
         self.discovery.clone()
     }
 
+    /// Get the cluster for a new query context.
+    /// The discovered cluster is reused within `discovery_cache_ttl_secs`, so the
+    /// topology is never staler than the TTL.
+    pub async fn get_cluster(self: &Arc<Self>) -> Result<ClusterRef> {
+        let ttl = Duration::from_secs(self.conf.query.discovery_cache_ttl_secs);
+        if ttl.is_zero() {
+            return self.discovery.discover().await;
+        }
+
+        if let Some((discovered_at, cluster)) = &*self.discovered_cluster.read() {
+            if discovered_at.elapsed() < ttl {
+                return Ok(cluster.clone());
+            }
+        }
+
+        let cluster = self.discovery.discover().await?;
+        *self.discovered_cluster.write() = Some((Instant::now(), cluster.clone()));
+        Ok(cluster)
+    }
+
+    /// Drop the cached cluster, the next context will discover the cluster again.
+    /// Call it when the membership of the cluster is known to be changed.
+    pub fn invalidate_cluster_cache(&self) {
+        *self.discovered_cluster.write() = None;
+    }
+
     // Get the user api provider.
     pub fn get_user_manager(self: &Arc<Self>) -> UserManagerRef {
         self.user.clone()
@@ -89,19 +138,51 @@ impl SessionManager {
         self.catalog.clone()
     }
 
-    pub fn create_session(self: &Arc<Self>, typ: impl Into<String>) -> Result<SessionRef> {
+    /// Whether a new connection can be accepted, checked by the servers before creating a session.
+    /// It is false when the server is shutting down or `max_active_sessions` is reached.
+    pub fn can_accept(&self) -> bool {
+        !self.shutting_down.load(Ordering::Relaxed)
+            && self.active_sessions.read().len() < self.max_sessions
+    }
+
+    /// Reject a connection because the server is at capacity (or shutting down).
+    /// Returns the error to report to the client with the protocol.
+    pub fn reject_connection(&self) -> ErrorCode {
+        counter!(super::metrics::METRIC_SESSION_REJECTED_NUMBERS, 1);
+        self.rejected_connections.fetch_add(1, Ordering::Relaxed);
+
+        match self.shutting_down.load(Ordering::Relaxed) {
+            true => ErrorCode::AbortedSession("Aborting server."),
+            false => ErrorCode::TooManyUserConnections(format!(
+                "Too many connections, the max_active_sessions is {}",
+                self.max_sessions
+            )),
+        }
+    }
+
+    /// The number of connections rejected by `reject_connection`.
+    pub fn get_rejected_connections(&self) -> u64 {
+        self.rejected_connections.load(Ordering::Relaxed)
+    }
+
+    pub fn create_session(self: &Arc<Self>, protocol: SessionProtocol) -> Result<SessionRef> {
         counter!(super::metrics::METRIC_SESSION_CONNECT_NUMBERS, 1);
 
+        if self.shutting_down.load(Ordering::Relaxed) {
+            return Err(ErrorCode::AbortedSession("Aborting server."));
+        }
+
         let mut sessions = self.active_sessions.write();
-        match sessions.len() == self.max_sessions {
-            true => Err(ErrorCode::TooManyUserConnections(
-                "The current accept connection has exceeded mysql_handler_thread_num config",
-            )),
+        match sessions.len() >= self.max_sessions {
+            true => Err(ErrorCode::TooManyUserConnections(format!(
+                "Too many connections, the max_active_sessions is {}",
+                self.max_sessions
+            ))),
             false => {
                 let session = Session::try_create(
                     self.conf.clone(),
                     uuid::Uuid::new_v4().to_string(),
-                    typ.into(),
+                    protocol,
                     self.clone(),
                 )?;
 
@@ -115,6 +196,7 @@ impl SessionManager {
         counter!(super::metrics::METRIC_SESSION_CONNECT_NUMBERS, 1);
 
         let mut sessions = self.active_sessions.write();
+        let aborted = aborted || self.shutting_down.load(Ordering::Relaxed);
 
         let session = match sessions.entry(id) {
             Occupied(entry) => entry.get().clone(),
@@ -123,7 +205,7 @@ impl SessionManager {
                 let session = Session::try_create(
                     self.conf.clone(),
                     entry.key().clone(),
-                    String::from("RPCSession"),
+                    SessionProtocol::RPC,
                     self.clone(),
                 )?;
 
@@ -142,6 +224,91 @@ impl SessionManager {
             .map(|session| SessionRef::create(session.clone()))
     }
 
+    /// Kill the running query with id `query_id`, the session it belongs to is kept.
+    pub fn kill_query(self: &Arc<Self>, query_id: &str) -> Result<()> {
+        let session = self.find_session(|s| s.get_current_query_id().as_deref() == Some(query_id));
+
+        match session {
+            None => Err(ErrorCode::UnknownSession(format!(
+                "Not found query id {}",
+                query_id
+            ))),
+            Some(session) => {
+                session.force_kill_query();
+                Ok(())
+            }
+        }
+    }
+
+    /// Kill the session of the client connection `conn_id`, with the query it is running.
+    pub fn kill_connection(self: &Arc<Self>, conn_id: u32) -> Result<()> {
+        let session = self.find_session(|s| s.get_client_conn_id() == Some(conn_id));
+
+        match session {
+            None => Err(ErrorCode::UnknownSession(format!(
+                "Not found connection id {}",
+                conn_id
+            ))),
+            Some(session) => {
+                session.force_kill_session();
+                Ok(())
+            }
+        }
+    }
+
+    // The sessions are collected before locking any of them,
+    // killing a session must not happen while holding the lock of `active_sessions`.
+    fn find_session<F>(self: &Arc<Self>, predicate: F) -> Option<Arc<Session>>
+    where F: Fn(&Arc<Session>) -> bool {
+        let sessions = self
+            .active_sessions
+            .read()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        sessions.into_iter().find(|s| predicate(s))
+    }
+
+    /// Change the default value of the setting `name` for all the sessions, e.g., to lower a limit at runtime.
+    ///
+    /// The live sessions still using the old default take the new one at once, the sessions
+    /// which set the setting to another value keep it. The sessions created later start with it.
+    pub fn apply_global_setting(self: &Arc<Self>, name: &str, value: String) -> Result<()> {
+        // Validated by a new settings first, an invalid value is never applied.
+        Settings::try_create()?.update_default_settings(name, value.clone())?;
+
+        let name = name.to_lowercase();
+        {
+            let mut global_settings = self.global_settings.write();
+            global_settings.retain(|(n, _)| n != &name);
+            global_settings.push((name.clone(), value.clone()));
+        }
+
+        let sessions = self
+            .active_sessions
+            .read()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for session in sessions {
+            session
+                .get_settings()
+                .update_default_settings(&name, value.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Create the settings of a new session, with the defaults applied by `apply_global_setting`.
+    pub(in crate::sessions) fn create_settings(&self) -> Result<Arc<Settings>> {
+        let settings = Settings::try_create()?;
+        for (name, value) in self.global_settings.read().iter() {
+            settings.update_default_settings(name, value.clone())?;
+        }
+        Ok(settings)
+    }
+
     #[allow(clippy::ptr_arg)]
     pub fn destroy_session(self: &Arc<Self>, session_id: &String) {
         counter!(super::metrics::METRIC_SESSION_CLOSE_NUMBERS, 1);
@@ -178,6 +345,47 @@ impl SessionManager {
         }
     }
 
+    /// Stop accepting new sessions and wait up to `timeout` for the running queries to finish,
+    /// the sessions which are still running a query after the timeout will be killed forcefully.
+    pub async fn graceful_shutdown(self: &Arc<Self>, timeout: Duration) -> ShutdownSummary {
+        self.shutting_down.store(true, Ordering::Relaxed);
+
+        let sessions = self
+            .active_sessions
+            .read()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        log::info!("Waiting for {} sessions to finish.", sessions.len());
+        let instant = Instant::now();
+        loop {
+            // Shutdown the io of idle sessions, the running sessions will be killed after finished.
+            sessions.iter().for_each(Session::kill);
+
+            let running = sessions.iter().filter(|s| s.has_running_query()).count();
+            if running == 0 || instant.elapsed() >= timeout {
+                break;
+            }
+
+            log::info!("Waiting for {} running queries to finish.", running);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        let mut force_killed_sessions = 0;
+        for session in &sessions {
+            if session.has_running_query() {
+                force_killed_sessions += 1;
+                session.force_kill_session();
+            }
+        }
+
+        ShutdownSummary {
+            finished_sessions: sessions.len() - force_killed_sessions,
+            force_killed_sessions,
+        }
+    }
+
     fn destroy_idle_sessions(sessions: &Arc<RwLock<HashMap<String, Arc<Session>>>>) -> bool {
         // Read lock does not support reentrant
         // https://github.com/Amanieu/parking_lot/blob/lock_api-0.4.4/lock_api/src/rwlock.rs#L422
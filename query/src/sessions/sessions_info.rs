@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use crate::sessions::ProcessInfo;
 use crate::sessions::Session;
+use crate::sessions::SessionInfo;
 use crate::sessions::SessionManager;
 
 impl SessionManager {
@@ -26,4 +27,34 @@ impl SessionManager {
             .map(Session::process_info)
             .collect::<Vec<_>>()
     }
+
+    /// Snapshot all the live sessions.
+    ///
+    /// The sessions are collected before locking any of them,
+    /// thus the lock of `active_sessions` is never held while waiting for the lock of a session.
+    pub fn list_sessions(self: &Arc<Self>) -> Vec<SessionInfo> {
+        let sessions = self
+            .active_sessions
+            .read()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        sessions
+            .iter()
+            .map(Session::session_info)
+            .collect::<Vec<_>>()
+    }
+
+    /// Visit all the live sessions without cloning them, e.g., to count the running queries.
+    ///
+    /// `f` is called while holding the read lock of `active_sessions`,
+    /// it must be synchronous and cheap, and must not create or destroy a session,
+    /// which would deadlock on the write lock.
+    pub fn for_each_session<F>(&self, mut f: F)
+    where F: FnMut(&Arc<Session>) {
+        for session in self.active_sessions.read().values() {
+            f(session);
+        }
+    }
 }
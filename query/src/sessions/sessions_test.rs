@@ -0,0 +1,306 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use common_base::tokio;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::sessions::SessionProtocol;
+use crate::sessions::ShutdownSummary;
+use crate::tests::SessionManagerBuilder;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_graceful_shutdown_idle_sessions() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let _session_1 = sessions.create_session(SessionProtocol::Internal)?;
+    let _session_2 = sessions.create_session(SessionProtocol::Internal)?;
+
+    let summary = sessions.graceful_shutdown(Duration::from_secs(5)).await;
+    assert_eq!(summary, ShutdownSummary {
+        finished_sessions: 2,
+        force_killed_sessions: 0,
+    });
+
+    // New sessions are rejected after shutdown.
+    let result = sessions.create_session(SessionProtocol::Internal);
+    assert!(result.is_err());
+    assert_eq!(result.err().unwrap().message(), "Aborting server.");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_graceful_shutdown_running_sessions() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let idle_session = sessions.create_session(SessionProtocol::Internal)?;
+    let running_session = sessions.create_session(SessionProtocol::Internal)?;
+
+    // Hold the context, so the query never finishes.
+    let _context = running_session.create_context().await?;
+    assert!(running_session.has_running_query());
+    assert!(!idle_session.has_running_query());
+
+    let summary = sessions.graceful_shutdown(Duration::from_millis(200)).await;
+    assert_eq!(summary, ShutdownSummary {
+        finished_sessions: 1,
+        force_killed_sessions: 1,
+    });
+
+    assert!(!running_session.has_running_query());
+    assert!(running_session.is_aborting());
+    assert!(idle_session.is_aborting());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cluster_discovery_cache() -> Result<()> {
+    // Discover every time.
+    {
+        let sessions = SessionManagerBuilder::create().build()?;
+        let session_1 = sessions.create_session(SessionProtocol::Internal)?;
+        let context_1 = session_1.create_context().await?;
+        let session_2 = sessions.create_session(SessionProtocol::Internal)?;
+        let context_2 = session_2.create_context().await?;
+        assert!(!Arc::ptr_eq(
+            &context_1.get_cluster(),
+            &context_2.get_cluster()
+        ));
+    }
+
+    // Reuse the discovered cluster within the TTL.
+    {
+        let sessions = SessionManagerBuilder::create()
+            .discovery_cache_ttl_secs(60)
+            .build()?;
+        let session_1 = sessions.create_session(SessionProtocol::Internal)?;
+        let context_1 = session_1.create_context().await?;
+        let session_2 = sessions.create_session(SessionProtocol::Internal)?;
+        let context_2 = session_2.create_context().await?;
+        assert!(Arc::ptr_eq(
+            &context_1.get_cluster(),
+            &context_2.get_cluster()
+        ));
+
+        sessions.invalidate_cluster_cache();
+        let session_3 = sessions.create_session(SessionProtocol::Internal)?;
+        let context_3 = session_3.create_context().await?;
+        assert!(!Arc::ptr_eq(
+            &context_1.get_cluster(),
+            &context_3.get_cluster()
+        ));
+        assert_eq!(
+            context_1.get_cluster().get_nodes().len(),
+            context_3.get_cluster().get_nodes().len()
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_list_sessions() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let idle_session = sessions.create_session(SessionProtocol::Internal)?;
+    let running_session = sessions.create_session(SessionProtocol::Internal)?;
+    running_session.set_current_database("db1".to_string());
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    // Listing sessions from within a session's own context does not deadlock.
+    let context = running_session.create_context().await?;
+    let mut infos = context.get_sessions_manager().list_sessions();
+    infos.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut expected = vec![
+        (idle_session.get_id(), "default".to_string(), false),
+        (running_session.get_id(), "db1".to_string(), true),
+    ];
+    expected.sort();
+
+    let got = infos
+        .iter()
+        .map(|x| (x.id.clone(), x.database.clone(), x.has_running_query))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, got);
+
+    for info in infos.iter() {
+        assert_eq!("InternalSession", info.typ);
+        assert_eq!(None, info.client_host);
+        assert!(info.created_at <= now && info.created_at + 60 > now);
+    }
+
+    // Serializable to feed a query result.
+    let json = serde_json::to_string(&infos[0])?;
+    assert!(json.contains("\"has_running_query\""), "{}", json);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_kill_query_by_id() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let session = sessions.create_session(SessionProtocol::Internal)?;
+    assert_eq!(None, session.get_current_query_id());
+
+    let context = session.create_context().await?;
+    assert_eq!(Some(context.get_id()), session.get_current_query_id());
+
+    // Not found.
+    {
+        let result = sessions.kill_query("no-such-query");
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().message(),
+            "Not found query id no-such-query"
+        );
+        assert!(session.has_running_query());
+    }
+
+    // Found, the query is killed and the session is kept.
+    {
+        sessions.kill_query(&context.get_id())?;
+        assert!(!session.has_running_query());
+        assert!(!session.is_aborting());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_kill_connection_by_id() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let session = sessions.create_session(SessionProtocol::Internal)?;
+    session.attach(None, Some(7), || {});
+
+    // Not found.
+    {
+        let result = sessions.kill_connection(8);
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message(), "Not found connection id 8");
+        assert!(!session.is_aborting());
+    }
+
+    // Found, the session is killed.
+    {
+        sessions.kill_connection(7)?;
+        assert!(session.is_aborting());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_can_accept_and_reject_connection() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().max_sessions(1).build()?;
+    assert!(sessions.can_accept());
+    assert_eq!(0, sessions.get_rejected_connections());
+
+    // At capacity.
+    let session = sessions.create_session(SessionProtocol::Internal)?;
+    assert!(!sessions.can_accept());
+
+    let error = sessions.reject_connection();
+    assert_eq!(error.code(), ErrorCode::TooManyUserConnections("").code());
+    assert_eq!(
+        error.message(),
+        "Too many connections, the max_active_sessions is 1"
+    );
+    assert_eq!(1, sessions.get_rejected_connections());
+
+    // Accept again once the session is released.
+    drop(session);
+    assert!(sessions.can_accept());
+    let _session = sessions.create_session(SessionProtocol::Internal)?;
+
+    // Reject everything while shutting down.
+    let _ = sessions.graceful_shutdown(Duration::from_secs(5)).await;
+    assert!(!sessions.can_accept());
+    let error = sessions.reject_connection();
+    assert_eq!(error.code(), ErrorCode::AbortedSession("").code());
+    assert_eq!(2, sessions.get_rejected_connections());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_for_each_session() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+
+    let mut count = 0;
+    sessions.for_each_session(|_| count += 1);
+    assert_eq!(0, count);
+
+    let _idle_session = sessions.create_session(SessionProtocol::Internal)?;
+    let running_session = sessions.create_session(SessionProtocol::Internal)?;
+    let _context = running_session.create_context().await?;
+
+    let (mut count, mut running) = (0, 0);
+    sessions.for_each_session(|session| {
+        count += 1;
+        if session.has_running_query() {
+            running += 1;
+        }
+    });
+    assert_eq!(2, count);
+    assert_eq!(1, running);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_apply_global_setting() -> Result<()> {
+    let sessions = SessionManagerBuilder::create().build()?;
+    let default_session = sessions.create_session(SessionProtocol::Internal)?;
+    let override_session = sessions.create_session(SessionProtocol::Internal)?;
+    override_session.get_settings().set_max_block_size(100)?;
+    // Set explicitly to the current default, it is still an override.
+    let same_session = sessions.create_session(SessionProtocol::Internal)?;
+    same_session.get_settings().set_max_block_size(10000)?;
+
+    sessions.apply_global_setting("max_block_size", String::from("2000"))?;
+
+    // The session using the default takes the new one, the overrides are kept.
+    assert_eq!(2000, default_session.get_settings().get_max_block_size()?);
+    assert_eq!(100, override_session.get_settings().get_max_block_size()?);
+    assert_eq!(10000, same_session.get_settings().get_max_block_size()?);
+
+    // The sessions created later start with it.
+    let new_session = sessions.create_session(SessionProtocol::Internal)?;
+    assert_eq!(2000, new_session.get_settings().get_max_block_size()?);
+
+    // An invalid value is applied to none of them.
+    let result = sessions.apply_global_setting("max_threads", String::from("0"));
+    assert_eq!(
+        result.unwrap_err().message(),
+        "Setting max_threads must be greater than 0"
+    );
+    assert!(default_session.get_settings().get_max_threads()? > 0);
+
+    let result = sessions.apply_global_setting("unknown_setting", String::from("1"));
+    assert_eq!(
+        result.unwrap_err().code(),
+        ErrorCode::UnknownVariable("").code()
+    );
+
+    Ok(())
+}
@@ -13,8 +13,12 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use common_base::tokio::sync::mpsc::unbounded_channel;
+use common_base::tokio::sync::mpsc::UnboundedReceiver;
+use common_base::tokio::sync::mpsc::UnboundedSender;
 use common_datavalues::DataValue;
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -31,7 +35,20 @@ impl Settings {
         ("max_threads", u64, 16, "The maximum number of threads to execute the request. By default, it is determined automatically."),
         ("flight_client_timeout", u64, 60, "Max duration the flight client request is allowed to take in seconds. By default, it is 60 seconds"),
         ("min_distributed_rows", u64, 100000000, "Minimum distributed read rows. In cluster mode, when read rows exceeds this value, the local table converted to distributed query."),
-        ("min_distributed_bytes", u64, 500 * 1024 * 1024, "Minimum distributed read bytes. In cluster mode, when read bytes exceeds this value, the local table converted to distributed query.")
+        ("min_distributed_bytes", u64, 500 * 1024 * 1024, "Minimum distributed read bytes. In cluster mode, when read bytes exceeds this value, the local table converted to distributed query."),
+        ("max_execution_time", u64, 0, "The maximum execution time of a query in seconds, the query is aborted once it exceeds. By default, 0 means no limit."),
+        ("block_size", u64, 0, "Coalesce the blocks read from sources until they reach this number of rows. By default, 0 means the blocks are forwarded as they are."),
+        ("sort_merge_fan_in", u64, 0, "The maximum number of sorted streams merged by one sort merge processor, the streams are merged in log(N) stages. By default, 0 means all streams are merged in one stage."),
+        ("max_cross_join_rows", u64, 0, "The maximum number of rows a cross join is allowed to produce, the query is aborted once it exceeds. By default, 0 means no limit."),
+        ("max_bytes_before_external_sort", u64, 0, "The sort spills sorted runs to the temp storage once its buffered blocks exceed this number of bytes, then merges the runs from disk. By default, 0 means never spill."),
+        ("max_bytes_before_external_group_by", u64, 0, "The group by spills its partial states to the temp storage by partitions once they exceed this number of bytes, then merges the partitions one by one. By default, 0 means never spill."),
+        ("readonly", u64, 0, "Only the queries reading data are allowed when it is 1, the statements changing the data or the metadata are rejected. By default, 0 means no restriction."),
+        ("max_source_rows_per_second", u64, 0, "The maximum number of rows a table source reads per second, to keep low priority queries from starving the others. By default, 0 means no limit."),
+        ("max_source_bytes_per_second", u64, 0, "The maximum number of bytes a table source reads per second, to keep low priority queries from starving the others. By default, 0 means no limit."),
+        ("pipeline_backpressure", u64, 0, "What a processor does when its downstream can not keep up: 0 waits, 1 drops the oldest buffered block so the result is approximate, 2 fails the query. By default, 0 never loses data."),
+        ("collect_block_min_max", u64, 0, "Attach the min and max of every column to the blocks read by table sources and output by projections, for the downstream operators and pruning. By default, 0 means they are not collected."),
+        ("source_readahead", u64, 0, "The number of blocks a table source reads ahead while the current one is processed downstream, to overlap the reads with the processing. 0 means no readahead. By default, 0."),
+        ("join_algorithm", u64, 0, "The algorithm of the joins on equal keys: 0 picks it automatically, 1 forces hash join, e.g., to work around a bad pick. By default, 0 means auto.")
     }
 
     pub fn try_create() -> Result<Arc<Settings>> {
@@ -40,11 +57,18 @@ impl Settings {
         });
 
         settings.initial_settings()?;
-        settings.set_max_threads(num_cpus::get() as u64)?;
+        settings
+            .inner
+            .try_update_default_u64("max_threads", num_cpus::get() as u64)?;
 
         Ok(settings)
     }
 
+    /// Subscribe the changes of settings, each update emits `(name, old_value, new_value)`.
+    pub fn subscribe(&self) -> UnboundedReceiver<SettingChange> {
+        self.inner.subscribe()
+    }
+
     pub fn iter(&self) -> SettingsIterator {
         SettingsIterator {
             settings: self.inner.get_settings(),
@@ -53,17 +77,123 @@ impl Settings {
     }
 }
 
+/// The change of a setting: (name, old_value, new_value).
+pub type SettingChange = (String, DataValue, DataValue);
+
 #[derive(Debug, Clone)]
 pub struct SettingsBase {
     // DataValue is of DataValue::Struct([name, value, default_value, description])
     settings: Arc<RwLock<HashMap<&'static str, DataValue>>>,
+    // The settings set explicitly, they keep their values when the default values change.
+    overridden: Arc<RwLock<HashSet<&'static str>>>,
+    subscribers: Arc<RwLock<Vec<UnboundedSender<SettingChange>>>>,
 }
 
 impl SettingsBase {
     pub fn create() -> Self {
         SettingsBase {
             settings: Arc::new(RwLock::new(HashMap::default())),
+            overridden: Arc::new(RwLock::new(HashSet::new())),
+            subscribers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    pub fn subscribe(&self) -> UnboundedReceiver<SettingChange> {
+        let (tx, rx) = unbounded_channel();
+        self.subscribers.write().push(tx);
+        rx
+    }
+
+    fn check_value(key: &str, val: &DataValue) -> Result<()> {
+        match (key, val) {
+            ("max_threads", DataValue::UInt64(Some(0))) => Err(ErrorCode::BadArguments(
+                "Setting max_threads must be greater than 0",
+            )),
+            ("sort_merge_fan_in", DataValue::UInt64(Some(1))) => Err(ErrorCode::BadArguments(
+                "Setting sort_merge_fan_in must be 0 or greater than 1",
+            )),
+            ("readonly", DataValue::UInt64(Some(v))) if *v > 1 => {
+                Err(ErrorCode::BadArguments("Setting readonly must be 0 or 1"))
+            }
+            ("pipeline_backpressure", DataValue::UInt64(Some(v))) if *v > 2 => Err(
+                ErrorCode::BadArguments("Setting pipeline_backpressure must be 0, 1 or 2"),
+            ),
+            ("collect_block_min_max", DataValue::UInt64(Some(v))) if *v > 1 => Err(
+                ErrorCode::BadArguments("Setting collect_block_min_max must be 0 or 1"),
+            ),
+            ("join_algorithm", DataValue::UInt64(Some(v))) if *v > 1 => Err(
+                ErrorCode::BadArguments("Setting join_algorithm must be 0(auto) or 1(hash)"),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    fn try_update_value(&self, key: &'static str, val: DataValue) -> Result<()> {
+        SettingsBase::check_value(key, &val)?;
+
+        let old_value = {
+            let mut settings = self.settings.write();
+            let setting_val = settings.get(key).ok_or_else(|| {
+                ErrorCode::UnknownVariable(format!("Unknown variable: {:?}", key))
+            })?;
+
+            match setting_val {
+                DataValue::Struct(values) => {
+                    let old_value = values[0].clone();
+                    let v =
+                        DataValue::Struct(vec![val.clone(), values[1].clone(), values[2].clone()]);
+                    settings.insert(key, v);
+                    self.overridden.write().insert(key);
+                    old_value
+                }
+                _ => return Ok(()),
+            }
+        };
+
+        // Remove the subscribers which have been closed.
+        let change = (key.to_string(), old_value, val);
+        let mut subscribers = self.subscribers.write();
+        subscribers.retain(|subscriber| subscriber.send(change.clone()).is_ok());
+        Ok(())
+    }
+
+    // Change the default value of a setting, the value follows it unless the setting has been set,
+    // even if it was set to the old default value.
+    fn try_update_default_value(&self, key: &'static str, val: DataValue) -> Result<()> {
+        SettingsBase::check_value(key, &val)?;
+
+        let old_value = {
+            let mut settings = self.settings.write();
+            let setting_val = settings.get(key).ok_or_else(|| {
+                ErrorCode::UnknownVariable(format!("Unknown variable: {:?}", key))
+            })?;
+
+            match setting_val {
+                DataValue::Struct(values) => {
+                    let overridden = self.overridden.read().contains(key);
+                    let value = match overridden {
+                        true => values[0].clone(),
+                        false => val.clone(),
+                    };
+                    let old_value = values[0].clone();
+                    let v = DataValue::Struct(vec![value, val.clone(), values[2].clone()]);
+                    settings.insert(key, v);
+
+                    match overridden {
+                        true => return Ok(()),
+                        false => old_value,
+                    }
+                }
+                _ => return Ok(()),
+            }
+        };
+
+        if old_value != val {
+            let change = (key.to_string(), old_value, val);
+            let mut subscribers = self.subscribers.write();
+            subscribers.retain(|subscriber| subscriber.send(change.clone()).is_ok());
         }
+        Ok(())
     }
 
     // TODO, to use macro generate this codes
@@ -81,20 +211,12 @@ impl SettingsBase {
 
     #[allow(unused)]
     pub fn try_update_u64(&self, key: &'static str, val: u64) -> Result<()> {
-        let mut settings = self.settings.write();
-        let setting_val = settings
-            .get(key)
-            .ok_or_else(|| ErrorCode::UnknownVariable(format!("Unknown variable: {:?}", key)))?;
+        self.try_update_value(key, DataValue::UInt64(Some(val)))
+    }
 
-        if let DataValue::Struct(values) = setting_val {
-            let v = DataValue::Struct(vec![
-                DataValue::UInt64(Some(val)),
-                values[1].clone(),
-                values[2].clone(),
-            ]);
-            settings.insert(key, v);
-        }
-        Ok(())
+    #[allow(unused)]
+    pub fn try_update_default_u64(&self, key: &'static str, val: u64) -> Result<()> {
+        self.try_update_default_value(key, DataValue::UInt64(Some(val)))
     }
 
     #[allow(unused)]
@@ -130,20 +252,12 @@ impl SettingsBase {
 
     #[allow(unused)]
     pub fn try_update_i64(&self, key: &'static str, val: i64) -> Result<()> {
-        let mut settings = self.settings.write();
-        let setting_val = settings
-            .get(key)
-            .ok_or_else(|| ErrorCode::UnknownVariable(format!("Unknown variable: {:?}", key)))?;
+        self.try_update_value(key, DataValue::Int64(Some(val)))
+    }
 
-        if let DataValue::Struct(values) = setting_val {
-            let v = DataValue::Struct(vec![
-                DataValue::Int64(Some(val)),
-                values[1].clone(),
-                values[2].clone(),
-            ]);
-            settings.insert(key, v);
-        }
-        Ok(())
+    #[allow(unused)]
+    pub fn try_update_default_i64(&self, key: &'static str, val: i64) -> Result<()> {
+        self.try_update_default_value(key, DataValue::Int64(Some(val)))
     }
 
     #[allow(unused)]
@@ -179,20 +293,12 @@ impl SettingsBase {
 
     #[allow(unused)]
     pub fn try_update_f64(&self, key: &'static str, val: f64) -> Result<()> {
-        let mut settings = self.settings.write();
-        let setting_val = settings
-            .get(key)
-            .ok_or_else(|| ErrorCode::UnknownVariable(format!("Unknown variable: {:?}", key)))?;
+        self.try_update_value(key, DataValue::Float64(Some(val)))
+    }
 
-        if let DataValue::Struct(values) = setting_val {
-            let v = DataValue::Struct(vec![
-                DataValue::Float64(Some(val)),
-                values[1].clone(),
-                values[2].clone(),
-            ]);
-            settings.insert(key, v);
-        }
-        Ok(())
+    #[allow(unused)]
+    pub fn try_update_default_f64(&self, key: &'static str, val: f64) -> Result<()> {
+        self.try_update_default_value(key, DataValue::Float64(Some(val)))
     }
 
     #[allow(unused)]
@@ -229,20 +335,12 @@ impl SettingsBase {
 
     #[allow(unused)]
     pub fn try_update_string(&self, key: &'static str, val: &str) -> Result<()> {
-        let mut settings = self.settings.write();
-        let setting_val = settings
-            .get(key)
-            .ok_or_else(|| ErrorCode::UnknownVariable(format!("Unknown variable: {:?}", key)))?;
+        self.try_update_value(key, DataValue::String(Some(val.as_bytes().to_vec())))
+    }
 
-        if let DataValue::Struct(values) = setting_val {
-            let v = DataValue::Struct(vec![
-                DataValue::String(Some(val.as_bytes().to_vec())),
-                values[1].clone(),
-                values[2].clone(),
-            ]);
-            settings.insert(key, v);
-        }
-        Ok(())
+    #[allow(unused)]
+    pub fn try_update_default_string(&self, key: &'static str, val: &str) -> Result<()> {
+        self.try_update_default_value(key, DataValue::String(Some(val.as_bytes().to_vec())))
     }
 
     #[allow(unused)]
@@ -0,0 +1,89 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::tokio;
+use common_datavalues::DataValue;
+use common_exception::Result;
+
+use crate::sessions::Settings;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_settings_subscribe() -> Result<()> {
+    let settings = Settings::try_create()?;
+    let mut receiver = settings.subscribe();
+
+    settings.set_max_threads(2)?;
+    settings.update_settings("max_threads", String::from("8"))?;
+
+    let (name, old_value, new_value) = receiver.recv().await.unwrap();
+    assert_eq!(name, "max_threads");
+    assert_eq!(old_value, DataValue::UInt64(Some(num_cpus::get() as u64)));
+    assert_eq!(new_value, DataValue::UInt64(Some(2)));
+
+    let (name, old_value, new_value) = receiver.recv().await.unwrap();
+    assert_eq!(name, "max_threads");
+    assert_eq!(old_value, DataValue::UInt64(Some(2)));
+    assert_eq!(new_value, DataValue::UInt64(Some(8)));
+
+    assert_eq!(settings.get_max_threads()?, 8);
+
+    // The closed subscriber does not block the update.
+    drop(receiver);
+    settings.set_max_block_size(1000)?;
+    assert_eq!(settings.get_max_block_size()?, 1000);
+
+    Ok(())
+}
+
+#[test]
+fn test_settings_validation() -> Result<()> {
+    let settings = Settings::try_create()?;
+    let mut receiver = settings.subscribe();
+
+    // Negative value.
+    {
+        let result = settings.update_settings("max_threads", String::from("-1"));
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().message(),
+            "Cannot set setting max_threads to \"-1\", cause: invalid digit found in string"
+        );
+    }
+
+    // Zero threads.
+    {
+        let result = settings.set_max_threads(0);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().message(),
+            "Setting max_threads must be greater than 0"
+        );
+    }
+
+    // Unknown setting.
+    {
+        let result = settings.update_settings("unknown_setting", String::from("1"));
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().message(),
+            "Unknown variable: \"unknown_setting\""
+        );
+    }
+
+    // Rejected values are not notified.
+    assert_eq!(settings.get_max_threads()?, num_cpus::get() as u64);
+    assert!(receiver.try_recv().is_err());
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+// Copyright 2020 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// The transaction state of a session.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransactionState {
+    /// No explicit transaction, each statement is auto committed.
+    None,
+    /// Inside a transaction started by `BEGIN`.
+    Active,
+    /// A statement of the active transaction failed, only `ROLLBACK` is accepted.
+    Failed,
+}
+
+impl Default for TransactionState {
+    fn default() -> Self {
+        TransactionState::None
+    }
+}
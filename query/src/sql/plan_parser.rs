@@ -75,6 +75,8 @@ use crate::sql::DfExplain;
 use crate::sql::DfHint;
 use crate::sql::DfKillStatement;
 use crate::sql::DfParser;
+use crate::sql::DfQueryWithSettings;
+use crate::sql::DfSetPersistent;
 use crate::sql::DfShowCreateTable;
 use crate::sql::DfShowDatabases;
 use crate::sql::DfShowTables;
@@ -121,6 +123,7 @@ impl PlanParser {
     pub fn statement_to_plan(&self, statement: &DfStatement) -> Result<PlanNode> {
         match statement {
             DfStatement::Statement(v) => self.sql_statement_to_plan(v),
+            DfStatement::QueryWithSettings(v) => self.sql_query_with_settings_to_plan(v),
             DfStatement::Explain(v) => self.sql_explain_to_plan(v),
             DfStatement::ShowDatabases(v) => self.sql_show_databases_to_plan(v),
             DfStatement::CreateDatabase(v) => self.sql_create_database_to_plan(v),
@@ -166,6 +169,7 @@ impl PlanParser {
             }
             DfStatement::KillQuery(v) => self.sql_kill_query_to_plan(v),
             DfStatement::KillConn(v) => self.sql_kill_connection_to_plan(v),
+            DfStatement::SetPersistent(v) => self.sql_set_persistent_to_plan(v),
         }
     }
 
@@ -176,7 +180,7 @@ impl PlanParser {
             Statement::Query(query) => self.query_to_plan(query),
             Statement::SetVariable {
                 variable, value, ..
-            } => self.set_variable_to_plan(variable, value),
+            } => self.set_variable_to_plan(variable, value, false),
 
             Statement::Insert {
                 table_name,
@@ -254,6 +258,7 @@ impl PlanParser {
         Ok(PlanNode::DropDatabase(DropDatabasePlan {
             if_exists: drop.if_exists,
             db: name,
+            cascade: drop.cascade,
         }))
     }
 
@@ -396,6 +401,7 @@ impl PlanParser {
             if_exists: drop.if_exists,
             db,
             table,
+            expected_version: None,
         }))
     }
 
@@ -728,7 +734,7 @@ impl PlanParser {
                                 .read_plan(
                                     Arc::new(io_ctx),
                                     Some(dummy_scan_plan.push_downs.clone()),
-                                    Some(self.ctx.get_settings().get_max_threads()? as usize),
+                                    Some(self.ctx.get_max_threads()? as usize),
                                 )
                                 .map(PlanNode::ReadSource)
                         }
@@ -808,7 +814,7 @@ impl PlanParser {
                 };
 
                 // TODO: Move ReadSourcePlan to SelectInterpreter
-                let partitions = self.ctx.get_settings().get_max_threads()? as usize;
+                let partitions = self.ctx.get_max_threads()? as usize;
                 scan.and_then(|scan| match scan {
                     PlanNode::Scan(ref scan) => {
                         // TODO(xp): is it possible to use get_cluster_table_io_context() here?
@@ -1187,6 +1193,7 @@ impl PlanParser {
         &self,
         variable: &sqlparser::ast::Ident,
         values: &[sqlparser::ast::SetVariableValue],
+        persistent: bool,
     ) -> Result<PlanNode> {
         let mut vars = vec![];
         for value in values {
@@ -1197,7 +1204,36 @@ impl PlanParser {
             };
             vars.push(VarValue { variable, value });
         }
-        Ok(PlanNode::SetVariable(SettingPlan { vars }))
+        Ok(PlanNode::SetVariable(SettingPlan { vars, persistent }))
+    }
+
+    /// Apply the settings of a query to its context only, the session settings are unchanged.
+    pub fn sql_query_with_settings_to_plan(&self, query: &DfQueryWithSettings) -> Result<PlanNode> {
+        for (variable, value) in &query.settings {
+            match variable.value.to_lowercase().as_str() {
+                "max_threads" => {
+                    let max_threads = value.to_string().parse::<u64>().map_err(|_| {
+                        ErrorCode::BadArguments(format!(
+                            "max_threads of a query must be a positive integer, got: {}",
+                            value
+                        ))
+                    })?;
+                    self.ctx.set_max_threads_hint(Some(max_threads))?;
+                }
+                _ => {
+                    return Err(ErrorCode::SyntaxException(format!(
+                        "Unsupported query setting: {}, only max_threads can be set for a query",
+                        variable
+                    )))
+                }
+            }
+        }
+
+        self.sql_statement_to_plan(&query.query)
+    }
+
+    pub fn sql_set_persistent_to_plan(&self, set: &DfSetPersistent) -> Result<PlanNode> {
+        self.set_variable_to_plan(&set.variable, &[set.value.clone()], true)
     }
 
     /// Apply a filter to the plan
@@ -42,13 +42,19 @@ fn test_plan_parser() -> Result<()> {
         Test {
             name: "drop-database-passed",
             sql: "DROP DATABASE db1",
-            expect: "Drop database db1, if_exists:false",
+            expect: "Drop database db1, if_exists:false, cascade:false",
             error: "",
         },
         Test {
             name: "drop-database-if-exists-passed",
             sql: "DROP DATABASE IF EXISTS db1",
-            expect: "Drop database db1, if_exists:true",
+            expect: "Drop database db1, if_exists:true, cascade:false",
+            error: "",
+        },
+        Test {
+            name: "drop-database-cascade-passed",
+            sql: "DROP DATABASE db1 CASCADE",
+            expect: "Drop database db1, if_exists:false, cascade:true",
             error: "",
         },
         Test {
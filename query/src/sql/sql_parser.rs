@@ -25,6 +25,7 @@ use sqlparser::ast::ColumnDef;
 use sqlparser::ast::ColumnOptionDef;
 use sqlparser::ast::Expr;
 use sqlparser::ast::Ident;
+use sqlparser::ast::SetVariableValue;
 use sqlparser::ast::SqlOption;
 use sqlparser::ast::TableConstraint;
 use sqlparser::ast::Value;
@@ -45,6 +46,8 @@ use crate::sql::DfDropTable;
 use crate::sql::DfExplain;
 use crate::sql::DfHint;
 use crate::sql::DfKillStatement;
+use crate::sql::DfQueryWithSettings;
+use crate::sql::DfSetPersistent;
 use crate::sql::DfShowCreateTable;
 use crate::sql::DfShowDatabases;
 use crate::sql::DfShowProcessList;
@@ -64,6 +67,7 @@ macro_rules! parser_err {
 /// SQL Parser
 pub struct DfParser<'a> {
     parser: Parser<'a>,
+    dialect: &'a dyn Dialect,
 }
 
 impl<'a> DfParser<'a> {
@@ -80,6 +84,7 @@ impl<'a> DfParser<'a> {
 
         Ok(DfParser {
             parser: Parser::new(tokens, dialect),
+            dialect,
         })
     }
 
@@ -203,6 +208,17 @@ impl<'a> DfParser<'a> {
                         self.parser.next_token();
                         self.parse_truncate()
                     }
+                    Keyword::SET => {
+                        self.parser.next_token();
+                        if self.consume_token("PERSISTENT") {
+                            self.parse_set_persistent()
+                        } else {
+                            // use the native parser
+                            self.parser.prev_token();
+                            Ok(DfStatement::Statement(self.parser.parse_statement()?))
+                        }
+                    }
+                    Keyword::SELECT | Keyword::WITH => self.parse_query(),
                     Keyword::NoKeyword => match w.value.to_uppercase().as_str() {
                         // Use database
                         "USE" => self.parse_use_database(),
@@ -222,6 +238,77 @@ impl<'a> DfParser<'a> {
         }
     }
 
+    /// Parse a query, with an optional trailing `SETTINGS name = value [, ...]` clause.
+    /// The clause is split off before the query is handed to the native parser.
+    fn parse_query(&mut self) -> Result<DfStatement, ParserError> {
+        let mut tokens = vec![];
+        loop {
+            match self.parser.next_token() {
+                Token::EOF | Token::SemiColon => {
+                    self.parser.prev_token();
+                    break;
+                }
+                token => tokens.push(token),
+            }
+        }
+
+        let mut depth = 0;
+        let mut settings_pos = None;
+        for (pos, token) in tokens.iter().enumerate() {
+            match token {
+                Token::LParen => depth += 1,
+                Token::RParen => depth -= 1,
+                Token::Word(w)
+                    if depth == 0
+                        && w.quote_style.is_none()
+                        && w.value.eq_ignore_ascii_case("SETTINGS")
+                        && matches!(tokens.get(pos + 1), Some(Token::Word(_)))
+                        && matches!(tokens.get(pos + 2), Some(Token::Eq)) =>
+                {
+                    settings_pos = Some(pos);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let settings_tokens = match settings_pos {
+            None => vec![],
+            Some(pos) => tokens.split_off(pos).split_off(1),
+        };
+
+        let mut parser = Parser::new(tokens, self.dialect);
+        let query = parser.parse_statement()?;
+        if parser.peek_token() != Token::EOF {
+            return self.expected("end of query", parser.peek_token());
+        }
+
+        if settings_pos.is_none() {
+            return Ok(DfStatement::Statement(query));
+        }
+
+        let mut parser = Parser::new(settings_tokens, self.dialect);
+        let mut settings = vec![];
+        loop {
+            let variable = parser.parse_identifier()?;
+            parser.expect_token(&Token::Eq)?;
+            settings.push((variable, parser.parse_value()?));
+
+            if !parser.consume_token(&Token::Comma) {
+                break;
+            }
+        }
+
+        if parser.peek_token() != Token::EOF {
+            return self.expected("end of settings", parser.peek_token());
+        }
+
+        Ok(DfStatement::QueryWithSettings(DfQueryWithSettings {
+            query,
+            settings,
+        }))
+    }
+
     /// Parse an SQL EXPLAIN statement.
     pub fn parse_explain(&mut self) -> Result<DfStatement, ParserError> {
         // Parser is at the token immediately after EXPLAIN
@@ -236,6 +323,10 @@ impl<'a> DfParser<'a> {
                     self.parser.next_token();
                     ExplainType::Graph
                 }
+                "ANALYZE" => {
+                    self.parser.next_token();
+                    ExplainType::Analyze
+                }
                 _ => ExplainType::Syntax,
             },
             _ => ExplainType::Syntax,
@@ -426,10 +517,12 @@ impl<'a> DfParser<'a> {
     fn parse_drop_database(&mut self) -> Result<DfStatement, ParserError> {
         let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
         let db_name = self.parser.parse_object_name()?;
+        let cascade = self.parser.parse_keyword(Keyword::CASCADE);
 
         let drop = DfDropDatabase {
             if_exists,
             name: db_name,
+            cascade,
         };
 
         Ok(DfStatement::DropDatabase(drop))
@@ -476,6 +569,25 @@ impl<'a> DfParser<'a> {
         }
     }
 
+    // Parse 'SET PERSISTENT variable = value'.
+    fn parse_set_persistent(&mut self) -> Result<DfStatement, ParserError> {
+        let variable = self.parser.parse_identifier()?;
+        if !self.parser.consume_token(&Token::Eq) && !self.parser.parse_keyword(Keyword::TO) {
+            return self.expected("equals sign or TO", self.parser.peek_token());
+        }
+
+        let value = match self.parser.peek_token() {
+            Token::Word(w) if w.quote_style.is_none() => {
+                SetVariableValue::Ident(self.parser.parse_identifier()?)
+            }
+            _ => SetVariableValue::Literal(self.parser.parse_value()?),
+        };
+        Ok(DfStatement::SetPersistent(DfSetPersistent {
+            variable,
+            value,
+        }))
+    }
+
     fn parse_database_engine(&mut self) -> Result<String, ParserError> {
         // TODO make ENGINE as a keyword
         if !self.consume_token("ENGINE") {
@@ -101,6 +101,7 @@ fn drop_database() -> Result<()> {
         let expected = DfStatement::DropDatabase(DfDropDatabase {
             if_exists: false,
             name: ObjectName(vec![Ident::new("db1")]),
+            cascade: false,
         });
         expect_parse_ok(sql, expected)?;
     }
@@ -109,6 +110,16 @@ fn drop_database() -> Result<()> {
         let expected = DfStatement::DropDatabase(DfDropDatabase {
             if_exists: true,
             name: ObjectName(vec![Ident::new("db1")]),
+            cascade: false,
+        });
+        expect_parse_ok(sql, expected)?;
+    }
+    {
+        let sql = "DROP DATABASE IF EXISTS db1 CASCADE";
+        let expected = DfStatement::DropDatabase(DfDropDatabase {
+            if_exists: true,
+            name: ObjectName(vec![Ident::new("db1")]),
+            cascade: true,
         });
         expect_parse_ok(sql, expected)?;
     }
@@ -285,6 +296,68 @@ fn use_database_test() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn set_persistent_test() -> Result<()> {
+    expect_parse_ok(
+        "SET PERSISTENT max_threads = 4",
+        DfStatement::SetPersistent(DfSetPersistent {
+            variable: Ident::new("max_threads"),
+            value: SetVariableValue::Literal(Value::Number("4".to_string(), false)),
+        }),
+    )?;
+    expect_parse_ok(
+        "set persistent sql_mode TO ansi",
+        DfStatement::SetPersistent(DfSetPersistent {
+            variable: Ident::new("sql_mode"),
+            value: SetVariableValue::Ident(Ident::new("ansi")),
+        }),
+    )?;
+
+    // Without PERSISTENT it is left to the native parser.
+    let (statements, _) = DfParser::parse_sql("SET max_threads = 4")?;
+    assert!(matches!(
+        statements[0],
+        DfStatement::Statement(Statement::SetVariable { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn query_with_settings_test() -> Result<()> {
+    let (statements, _) = DfParser::parse_sql(
+        "SELECT a FROM t WHERE a IN (SELECT b FROM t2) SETTINGS max_threads = 4;",
+    )?;
+    assert_eq!(1, statements.len());
+    match &statements[0] {
+        DfStatement::QueryWithSettings(v) => {
+            assert!(matches!(v.query, Statement::Query(_)));
+            assert_eq!(v.settings, vec![(
+                Ident::new("max_threads"),
+                Value::Number("4".to_string(), false)
+            )]);
+        }
+        other => panic!("Expected a query with settings, got: {:?}", other),
+    }
+
+    // The same query without settings is left to the native parser.
+    let (statements, _) = DfParser::parse_sql("SELECT a FROM t WHERE a IN (SELECT b FROM t2)")?;
+    assert!(matches!(
+        statements[0],
+        DfStatement::Statement(Statement::Query(_))
+    ));
+
+    // A column named settings is not a settings clause.
+    let (statements, _) = DfParser::parse_sql("SELECT settings FROM t")?;
+    assert!(matches!(
+        statements[0],
+        DfStatement::Statement(Statement::Query(_))
+    ));
+
+    assert!(DfParser::parse_sql("SELECT a FROM t SETTINGS max_threads = 4 a").is_err());
+    Ok(())
+}
+
 #[test]
 fn truncate_table() -> Result<()> {
     {
@@ -23,8 +23,10 @@ use sqlparser::ast::ColumnDef;
 use sqlparser::ast::Expr;
 use sqlparser::ast::Ident;
 use sqlparser::ast::ObjectName;
+use sqlparser::ast::SetVariableValue;
 use sqlparser::ast::SqlOption;
 use sqlparser::ast::Statement as SQLStatement;
+use sqlparser::ast::Value;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DfShowTables {
@@ -94,6 +96,7 @@ pub struct DfCreateDatabase {
 pub struct DfDropDatabase {
     pub if_exists: bool,
     pub name: ObjectName,
+    pub cascade: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -106,11 +109,26 @@ pub struct DfKillStatement {
     pub object_id: Ident,
 }
 
+/// `SET PERSISTENT variable = value`, the value is applied to the next sessions of the user too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DfSetPersistent {
+    pub variable: Ident,
+    pub value: SetVariableValue,
+}
+
+/// A query with settings that apply to itself only: `SELECT ... SETTINGS max_threads = 4`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DfQueryWithSettings {
+    pub query: SQLStatement,
+    pub settings: Vec<(Ident, Value)>,
+}
+
 /// Tokens parsed by `DFParser` are converted into these values.
 #[derive(Debug, Clone, PartialEq)]
 pub enum DfStatement {
     // ANSI SQL AST node
     Statement(SQLStatement),
+    QueryWithSettings(DfQueryWithSettings),
     Explain(DfExplain),
 
     // Databases.
@@ -129,6 +147,7 @@ pub enum DfStatement {
 
     // Settings.
     ShowSettings(DfShowSettings),
+    SetPersistent(DfSetPersistent),
 
     // ProcessList
     ShowProcessList(DfShowProcessList),
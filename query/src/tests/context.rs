@@ -22,11 +22,12 @@ use crate::configs::Config;
 use crate::sessions::DatabendQueryContext;
 use crate::sessions::DatabendQueryContextRef;
 use crate::sessions::DatabendQueryContextShared;
+use crate::sessions::SessionProtocol;
 use crate::tests::SessionManagerBuilder;
 
 pub fn try_create_context() -> Result<DatabendQueryContextRef> {
     let sessions = SessionManagerBuilder::create().build()?;
-    let dummy_session = sessions.create_session("TestSession")?;
+    let dummy_session = sessions.create_session(SessionProtocol::Internal)?;
 
     let context = DatabendQueryContext::from_shared(DatabendQueryContextShared::try_create(
         sessions.get_conf().clone(),
@@ -40,7 +41,7 @@ pub fn try_create_context() -> Result<DatabendQueryContextRef> {
 
 pub fn try_create_context_with_config(config: Config) -> Result<DatabendQueryContextRef> {
     let sessions = SessionManagerBuilder::create().build()?;
-    let dummy_session = sessions.create_session("TestSession")?;
+    let dummy_session = sessions.create_session(SessionProtocol::Internal)?;
 
     let context = DatabendQueryContext::from_shared(DatabendQueryContextShared::try_create(
         config,
@@ -84,7 +85,7 @@ impl ClusterDescriptor {
 
 pub fn try_create_cluster_context(desc: ClusterDescriptor) -> Result<DatabendQueryContextRef> {
     let sessions = SessionManagerBuilder::create().build()?;
-    let dummy_session = sessions.create_session("TestSession")?;
+    let dummy_session = sessions.create_session(SessionProtocol::Internal)?;
 
     let local_id = desc.local_node_id;
     let nodes = desc.cluster_nodes_list.clone();
@@ -58,7 +58,7 @@ impl NumberTestData {
         table.read_plan(
             Arc::new(io_ctx),
             None,
-            Some(self.ctx.get_settings().get_max_threads()? as usize),
+            Some(self.ctx.get_max_threads()? as usize),
         )
     }
 
@@ -54,6 +54,12 @@ impl SessionManagerBuilder {
         SessionManagerBuilder::inner_create(new_config)
     }
 
+    pub fn discovery_cache_ttl_secs(self, ttl_secs: u64) -> SessionManagerBuilder {
+        let mut new_config = self.config.clone();
+        new_config.query.discovery_cache_ttl_secs = ttl_secs;
+        SessionManagerBuilder::inner_create(new_config)
+    }
+
     pub fn rpc_tls_server_key(self, value: impl Into<String>) -> SessionManagerBuilder {
         let mut new_config = self.config.clone();
         new_config.query.rpc_tls_server_key = value.into();
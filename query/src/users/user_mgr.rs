@@ -19,6 +19,7 @@ use common_management::AuthType;
 use common_management::UserInfo;
 use common_management::UserMgr;
 use common_management::UserMgrApi;
+use common_management::UserSetting;
 use common_meta_api::KVApi;
 use sha2::Digest;
 
@@ -113,4 +114,17 @@ impl UserManager {
     pub fn drop_user(&self, user: &str) -> Result<()> {
         self.api_provider.drop_user(user.to_string(), None)
     }
+
+    // Get the settings persisted for a user.
+    pub fn get_user_settings(&self, user: &str) -> Result<Vec<UserSetting>> {
+        self.api_provider.get_user_settings(user.to_string())
+    }
+
+    // Persist a setting for a user.
+    pub fn set_user_setting(&self, user: &str, name: &str, value: &str) -> Result<()> {
+        let setting = UserSetting::new(name, value);
+        self.api_provider
+            .set_user_setting(user.to_string(), setting)
+            .map(|_| ())
+    }
 }
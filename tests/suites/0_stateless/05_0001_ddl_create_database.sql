@@ -1,4 +1,4 @@
-DROP DATABASE IF EXISTS db;
+DROP DATABASE IF EXISTS db CASCADE;
 
 CREATE DATABASE db ENGINE = default;
 CREATE TABLE db.t(c1 int) ENGINE = Null;
@@ -7,6 +7,7 @@ SELECT COUNT(1) from system.tables where name = 't' and database = 'db';
 CREATE DATABASE IF NOT EXISTS db ENGINE = default;
 CREATE DATABASE db ENGINE = default; -- {ErrorCode 4001}
 
+DROP TABLE db.t;
 DROP DATABASE IF EXISTS db;
 
 CREATE DATABASE db ENGINE = NotExists; -- {ErrorCode 8001}